@@ -57,5 +57,41 @@ fn bench_poll(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_poll);
+fn bench_instrument(c: &mut Criterion) {
+    for (name, capacity) in [("instrument", 0), ("instrument_pooled", 1024)] {
+        c.bench_function(name, move |b| {
+            b.iter_custom(|iters| {
+                let monitor = TaskMonitor::builder().with_state_pool(capacity).build();
+                let num_cpus = num_cpus::get();
+                let start = Arc::new(Barrier::new(num_cpus + 1));
+                let stop = Arc::new(Barrier::new(num_cpus + 1));
+
+                let mut workers: Vec<_> = iter::repeat((monitor, start.clone(), stop.clone()))
+                    .take(num_cpus)
+                    .map(|(monitor, start, stop)| {
+                        thread::spawn(move || {
+                            start.wait();
+                            let start_time = Instant::now();
+                            for _i in 0..iters {
+                                drop(black_box(monitor.instrument(TestFuture)));
+                            }
+                            let stop_time = Instant::now();
+                            stop.wait();
+                            stop_time - start_time
+                        })
+                    })
+                    .collect();
+
+                start.wait();
+                stop.wait();
+
+                let elapsed: Duration = workers.drain(..).map(|w| w.join().unwrap()).sum();
+
+                elapsed / (num_cpus as u32)
+            })
+        });
+    }
+}
+
+criterion_group!(benches, bench_poll, bench_instrument);
 criterion_main!(benches);
@@ -0,0 +1,171 @@
+use crate::TaskMetrics;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Detects bursts in consecutive sampling intervals of [`TaskMetrics`]; e.g., thundering herds
+/// of spawned tasks.
+///
+/// A detector [records][BurstDetector::record] each interval, and compares two measures of it
+/// against their baselines, the means over the
+/// [preceding intervals][BurstDetector::with_baseline] (by default, 10):
+///
+/// - **[`Instrumentation`][BurstKind::Instrumentation]**
+///   The number of tasks [instrumented][TaskMetrics::instrumented_count] within the interval.
+/// - **[`FirstPollBacklog`][BurstKind::FirstPollBacklog]**
+///   The growth of the backlog of tasks awaiting their first poll within the interval; i.e., the
+///   number of tasks instrumented, less the number [first polled][TaskMetrics::first_poll_count].
+///
+/// A measure bursts if it exceeds its baseline by more than the
+/// [factor][BurstDetector::with_factor] (by default, 3), and is at least the
+/// [minimum][BurstDetector::with_min_count] (by default, 10), so that a quiet monitor does not
+/// burst on a handful of tasks. Measures are only compared once the baseline is full. The
+/// intervals should be of equal length; e.g., those of a [`Reporter`][crate::Reporter].
+///
+/// ##### Examples
+/// ```
+/// use tokio_metrics::{BurstDetector, BurstKind, TaskMetrics};
+///
+/// let mut detector = BurstDetector::new().with_baseline(5).with_factor(4.0);
+///
+/// let mut interval = TaskMetrics::default();
+/// interval.instrumented_count = 20;
+/// interval.first_poll_count = 20;
+/// for _ in 0..5 {
+///     assert!(detector.record(&interval).is_empty());
+/// }
+///
+/// // a thundering herd, most of which has yet to be polled
+/// interval.instrumented_count = 500;
+/// interval.first_poll_count = 100;
+/// let bursts = detector.record(&interval);
+/// assert_eq!(bursts.len(), 2);
+/// assert_eq!(bursts[0].kind, BurstKind::Instrumentation);
+/// assert_eq!(bursts[0].count, 500);
+/// assert_eq!(bursts[0].baseline, 20.0);
+/// assert_eq!(bursts[1].kind, BurstKind::FirstPollBacklog);
+/// assert_eq!(bursts[1].count, 400);
+/// assert_eq!(
+///     bursts[0].to_string(),
+///     "500 tasks instrumented, against a baseline of 20.0"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct BurstDetector {
+    baseline: usize,
+    factor: f64,
+    min_count: u64,
+    /// The measures of the most recent sampling intervals, oldest first.
+    history: VecDeque<[u64; 2]>,
+}
+
+/// A measure of a sampling interval that burst beyond its baseline, as detected by a
+/// [`BurstDetector`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Burst {
+    /// The measure that burst.
+    pub kind: BurstKind,
+
+    /// The measure of the interval.
+    pub count: u64,
+
+    /// The mean of the measure over the preceding intervals.
+    pub baseline: f64,
+}
+
+/// The measures of a sampling interval compared by a [`BurstDetector`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BurstKind {
+    /// The number of tasks instrumented.
+    Instrumentation,
+
+    /// The growth of the backlog of tasks awaiting their first poll.
+    FirstPollBacklog,
+}
+
+impl BurstDetector {
+    /// Constructs a detector with the default baseline, factor and minimum.
+    pub fn new() -> BurstDetector {
+        BurstDetector {
+            baseline: 10,
+            factor: 3.0,
+            min_count: 10,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Sets the number of preceding sampling intervals over which baselines are averaged.
+    ///
+    /// ##### Panics
+    /// This method panics if `intervals` is zero.
+    pub fn with_baseline(mut self, intervals: usize) -> BurstDetector {
+        assert!(
+            intervals > 0,
+            "the baseline must span at least one interval"
+        );
+        self.baseline = intervals;
+        self
+    }
+
+    /// Sets the multiple of its baseline that a measure must exceed to burst.
+    pub fn with_factor(mut self, factor: f64) -> BurstDetector {
+        self.factor = factor;
+        self
+    }
+
+    /// Sets the measure below which an interval never bursts.
+    pub fn with_min_count(mut self, min_count: u64) -> BurstDetector {
+        self.min_count = min_count;
+        self
+    }
+
+    /// Records the next sampling `interval`, and produces a [`Burst`] for each of its measures
+    /// that burst.
+    pub fn record(&mut self, interval: &TaskMetrics) -> Vec<Burst> {
+        let measures = [
+            interval.instrumented_count,
+            interval
+                .instrumented_count
+                .saturating_sub(interval.first_poll_count),
+        ];
+
+        let mut bursts = Vec::new();
+        if self.history.len() == self.baseline {
+            let kinds = [BurstKind::Instrumentation, BurstKind::FirstPollBacklog];
+            for (i, (&kind, &count)) in kinds.iter().zip(&measures).enumerate() {
+                let total: u64 = self.history.iter().map(|measures| measures[i]).sum();
+                let baseline = total as f64 / self.baseline as f64;
+                if count >= self.min_count && count as f64 > baseline * self.factor {
+                    bursts.push(Burst {
+                        kind,
+                        count,
+                        baseline,
+                    });
+                }
+            }
+        }
+
+        self.history.push_back(measures);
+        while self.history.len() > self.baseline {
+            self.history.pop_front();
+        }
+        bursts
+    }
+}
+
+impl Default for BurstDetector {
+    fn default() -> BurstDetector {
+        BurstDetector::new()
+    }
+}
+
+impl fmt::Display for Burst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            BurstKind::Instrumentation => write!(f, "{} tasks instrumented", self.count)?,
+            BurstKind::FirstPollBacklog => write!(f, "first-poll backlog grew by {}", self.count)?,
+        }
+        write!(f, ", against a baseline of {:.1}", self.baseline)
+    }
+}
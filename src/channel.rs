@@ -0,0 +1,379 @@
+use crate::{MetricValue, MetricsSink};
+use std::sync::atomic::{AtomicU64, Ordering::SeqCst};
+use std::sync::Arc;
+use tokio::sync::{broadcast, watch};
+use tokio::time::{Duration, Instant};
+
+/// Monitors the receivers of [`broadcast`] and [`watch`] channels.
+///
+/// Receivers [instrumented][ChannelMonitor::instrument_broadcast] by a [`ChannelMonitor`] count
+/// the messages they receive, the messages they miss by lagging behind their senders, and the
+/// time they spend waiting for messages; these are reported as [`ChannelMetrics`], cumulatively
+/// or in sampling intervals, like the [`TaskMetrics`][crate::TaskMetrics] of a
+/// [`TaskMonitor`][crate::TaskMonitor].
+///
+/// ##### Examples
+/// ```
+/// use tokio::sync::broadcast;
+/// use tokio_metrics::ChannelMonitor;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let monitor = ChannelMonitor::new();
+///     let (tx, rx) = broadcast::channel(2);
+///     let mut rx = monitor.instrument_broadcast(rx);
+///
+///     // overflow the channel, so the receiver misses the oldest message
+///     for i in 0..3 {
+///         tx.send(i).unwrap();
+///     }
+///
+///     assert!(rx.recv().await.is_err());
+///     assert_eq!(rx.recv().await.unwrap(), 1);
+///     assert_eq!(rx.recv().await.unwrap(), 2);
+///
+///     let metrics = monitor.cumulative();
+///     assert_eq!(metrics.received_count, 2);
+///     assert_eq!(metrics.lagged_count, 1);
+///     assert_eq!(metrics.total_missed_count, 1);
+///     assert_eq!(metrics.total_wait_count, 3);
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ChannelMonitor {
+    metrics: Arc<RawChannelMetrics>,
+}
+
+/// Key metrics of the channel receivers [instrumented][ChannelMonitor::instrument_broadcast] by
+/// a [`ChannelMonitor`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChannelMetrics {
+    /// The number of receivers instrumented.
+    pub instrumented_count: u64,
+
+    /// The total number of messages received; for [`watch`] receivers, the number of changes
+    /// observed.
+    pub received_count: u64,
+
+    /// The total number of times that [`broadcast`] receivers lagged behind their senders, such
+    /// that messages were overwritten before they could be received.
+    pub lagged_count: u64,
+
+    /// The total number of messages that [`broadcast`] receivers missed by lagging behind their
+    /// senders.
+    ///
+    /// A rising number of missed messages suggests that receivers are not polled often enough to
+    /// keep up with their senders; e.g., because the tasks that own them are starved, or the
+    /// channel capacity is too small for bursts of messages.
+    pub total_missed_count: u64,
+
+    /// The total number of times receivers waited for a message or change; i.e., the number of
+    /// completed calls of [`InstrumentedBroadcastReceiver::recv`] and
+    /// [`InstrumentedWatchReceiver::changed`].
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_wait_duration`][ChannelMetrics::mean_wait_duration]**   
+    ///   The mean duration of waits.
+    pub total_wait_count: u64,
+
+    /// The total duration receivers spent waiting for a message or change.
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_wait_duration`][ChannelMetrics::mean_wait_duration]**   
+    ///   The mean duration of waits.
+    pub total_wait_duration: Duration,
+}
+
+/// A [`broadcast::Receiver`] instrumented with [`ChannelMonitor::instrument_broadcast`].
+#[derive(Debug)]
+pub struct InstrumentedBroadcastReceiver<T> {
+    receiver: broadcast::Receiver<T>,
+    metrics: Arc<RawChannelMetrics>,
+}
+
+/// A [`watch::Receiver`] instrumented with [`ChannelMonitor::instrument_watch`].
+#[derive(Debug)]
+pub struct InstrumentedWatchReceiver<T> {
+    receiver: watch::Receiver<T>,
+    metrics: Arc<RawChannelMetrics>,
+}
+
+#[derive(Debug, Default)]
+struct RawChannelMetrics {
+    /// Total number of instrumented receivers.
+    instrumented_count: AtomicU64,
+
+    /// Total number of messages received.
+    received_count: AtomicU64,
+
+    /// Total number of times receivers lagged.
+    lagged_count: AtomicU64,
+
+    /// Total number of messages missed by lagging receivers.
+    total_missed_count: AtomicU64,
+
+    /// Total number of waits for a message or change.
+    total_wait_count: AtomicU64,
+
+    /// Total amount of time spent waiting for a message or change.
+    total_wait_duration_ns: AtomicU64,
+}
+
+impl ChannelMonitor {
+    /// Constructs a new channel monitor.
+    pub fn new() -> ChannelMonitor {
+        ChannelMonitor::default()
+    }
+
+    /// Produces an instrumented façade around a given [`broadcast::Receiver`]. Its metrics are
+    /// recorded by this monitor.
+    pub fn instrument_broadcast<T: Clone>(
+        &self,
+        receiver: broadcast::Receiver<T>,
+    ) -> InstrumentedBroadcastReceiver<T> {
+        self.metrics.instrumented_count.fetch_add(1, SeqCst);
+        InstrumentedBroadcastReceiver {
+            receiver,
+            metrics: self.metrics.clone(),
+        }
+    }
+
+    /// Produces an instrumented façade around a given [`watch::Receiver`]. Its metrics are
+    /// recorded by this monitor.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio::sync::watch;
+    /// use tokio_metrics::ChannelMonitor;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = ChannelMonitor::new();
+    ///     let (tx, rx) = watch::channel(0);
+    ///     let mut rx = monitor.instrument_watch(rx);
+    ///
+    ///     tokio::spawn(async move {
+    ///         tokio::time::sleep(Duration::from_millis(10)).await;
+    ///         tx.send(1).unwrap();
+    ///     });
+    ///
+    ///     rx.changed().await.unwrap();
+    ///     assert_eq!(*rx.borrow(), 1);
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.received_count, 1);
+    ///     assert!(metrics.total_wait_duration >= Duration::from_millis(10));
+    /// }
+    /// ```
+    pub fn instrument_watch<T>(
+        &self,
+        receiver: watch::Receiver<T>,
+    ) -> InstrumentedWatchReceiver<T> {
+        self.metrics.instrumented_count.fetch_add(1, SeqCst);
+        InstrumentedWatchReceiver {
+            receiver,
+            metrics: self.metrics.clone(),
+        }
+    }
+
+    /// Produces [`ChannelMetrics`] for the receivers instrumented by this monitor, collected
+    /// since its construction.
+    pub fn cumulative(&self) -> ChannelMetrics {
+        self.metrics.metrics()
+    }
+
+    /// Produces an unending iterator of metric sampling intervals, like
+    /// [`TaskMonitor::intervals`][crate::TaskMonitor::intervals].
+    pub fn intervals(&self) -> impl Iterator<Item = ChannelMetrics> {
+        let latest = self.metrics.clone();
+        let mut previous: Option<ChannelMetrics> = None;
+
+        std::iter::from_fn(move || {
+            let latest: ChannelMetrics = latest.metrics();
+            let next = match previous {
+                Some(previous) => latest - previous,
+                None => latest,
+            };
+            previous = Some(latest);
+            Some(next)
+        })
+    }
+}
+
+impl RawChannelMetrics {
+    fn metrics(&self) -> ChannelMetrics {
+        ChannelMetrics {
+            instrumented_count: self.instrumented_count.load(SeqCst),
+            received_count: self.received_count.load(SeqCst),
+            lagged_count: self.lagged_count.load(SeqCst),
+            total_missed_count: self.total_missed_count.load(SeqCst),
+            total_wait_count: self.total_wait_count.load(SeqCst),
+            total_wait_duration: Duration::from_nanos(self.total_wait_duration_ns.load(SeqCst)),
+        }
+    }
+
+    fn record_wait(&self, started_at: Instant) {
+        self.total_wait_count.fetch_add(1, SeqCst);
+        self.total_wait_duration_ns
+            .fetch_add(nanos(started_at.elapsed()), SeqCst);
+    }
+
+    fn record_broadcast<T>(&self, result: &Result<T, broadcast::error::RecvError>) {
+        match result {
+            Ok(_) => {
+                self.received_count.fetch_add(1, SeqCst);
+            }
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                self.lagged_count.fetch_add(1, SeqCst);
+                self.total_missed_count.fetch_add(*missed, SeqCst);
+            }
+            Err(broadcast::error::RecvError::Closed) => {}
+        }
+    }
+}
+
+impl ChannelMetrics {
+    /// The mean duration receivers spent waiting for a message or change.
+    ///
+    /// ##### Definition
+    /// This metric is derived from
+    /// [`total_wait_duration`][ChannelMetrics::total_wait_duration] ÷
+    /// [`total_wait_count`][ChannelMetrics::total_wait_count].
+    pub fn mean_wait_duration(&self) -> Duration {
+        match nanos(self.total_wait_duration).checked_div(self.total_wait_count) {
+            Some(mean) => Duration::from_nanos(mean),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Records each of these metrics into a given [`MetricsSink`], qualified by `labels`.
+    ///
+    /// Each metric is recorded under the name of its field, prefixed with `channel_`; e.g.,
+    /// `channel_received_count`. Derived metrics are not recorded.
+    pub fn record_to(&self, sink: &dyn MetricsSink, labels: &[(&str, &str)]) {
+        use MetricValue::{Count, Duration};
+
+        sink.record(
+            "channel_instrumented_count",
+            Count(self.instrumented_count),
+            labels,
+        );
+        sink.record("channel_received_count", Count(self.received_count), labels);
+        sink.record("channel_lagged_count", Count(self.lagged_count), labels);
+        sink.record(
+            "channel_total_missed_count",
+            Count(self.total_missed_count),
+            labels,
+        );
+        sink.record(
+            "channel_total_wait_count",
+            Count(self.total_wait_count),
+            labels,
+        );
+        sink.record(
+            "channel_total_wait_duration",
+            Duration(self.total_wait_duration),
+            labels,
+        );
+    }
+}
+
+impl std::ops::Sub for ChannelMetrics {
+    type Output = ChannelMetrics;
+
+    fn sub(self, rhs: ChannelMetrics) -> ChannelMetrics {
+        ChannelMetrics {
+            instrumented_count: self.instrumented_count.wrapping_sub(rhs.instrumented_count),
+            received_count: self.received_count.wrapping_sub(rhs.received_count),
+            lagged_count: self.lagged_count.wrapping_sub(rhs.lagged_count),
+            total_missed_count: self.total_missed_count.wrapping_sub(rhs.total_missed_count),
+            total_wait_count: self.total_wait_count.wrapping_sub(rhs.total_wait_count),
+            total_wait_duration: Duration::from_nanos(
+                nanos(self.total_wait_duration).wrapping_sub(nanos(rhs.total_wait_duration)),
+            ),
+        }
+    }
+}
+
+impl<T: Clone> InstrumentedBroadcastReceiver<T> {
+    /// Receives the next message, like [`broadcast::Receiver::recv`], recording the time spent
+    /// waiting for it.
+    pub async fn recv(&mut self) -> Result<T, broadcast::error::RecvError> {
+        let started_at = Instant::now();
+        let result = self.receiver.recv().await;
+        self.metrics.record_wait(started_at);
+        self.metrics.record_broadcast(&result);
+        result
+    }
+
+    /// Attempts to receive the next message without waiting, like
+    /// [`broadcast::Receiver::try_recv`].
+    pub fn try_recv(&mut self) -> Result<T, broadcast::error::TryRecvError> {
+        let result = self.receiver.try_recv();
+        match &result {
+            Ok(_) => {
+                self.metrics.received_count.fetch_add(1, SeqCst);
+            }
+            Err(broadcast::error::TryRecvError::Lagged(missed)) => {
+                self.metrics.lagged_count.fetch_add(1, SeqCst);
+                self.metrics.total_missed_count.fetch_add(*missed, SeqCst);
+            }
+            Err(_) => {}
+        }
+        result
+    }
+}
+
+impl<T> InstrumentedBroadcastReceiver<T> {
+    /// Produces a reference to the wrapped receiver.
+    pub fn get_ref(&self) -> &broadcast::Receiver<T> {
+        &self.receiver
+    }
+
+    /// Consumes this façade, producing the wrapped receiver.
+    pub fn into_inner(self) -> broadcast::Receiver<T> {
+        self.receiver
+    }
+}
+
+impl<T> InstrumentedWatchReceiver<T> {
+    /// Waits for a change, like [`watch::Receiver::changed`], recording the time spent waiting
+    /// for it.
+    pub async fn changed(&mut self) -> Result<(), watch::error::RecvError> {
+        let started_at = Instant::now();
+        let result = self.receiver.changed().await;
+        self.metrics.record_wait(started_at);
+        if result.is_ok() {
+            self.metrics.received_count.fetch_add(1, SeqCst);
+        }
+        result
+    }
+
+    /// Borrows the most recent value, like [`watch::Receiver::borrow`].
+    pub fn borrow(&self) -> watch::Ref<'_, T> {
+        self.receiver.borrow()
+    }
+
+    /// Borrows the most recent value, marking it as seen, like
+    /// [`watch::Receiver::borrow_and_update`].
+    pub fn borrow_and_update(&mut self) -> watch::Ref<'_, T> {
+        self.receiver.borrow_and_update()
+    }
+
+    /// Produces a reference to the wrapped receiver.
+    pub fn get_ref(&self) -> &watch::Receiver<T> {
+        &self.receiver
+    }
+
+    /// Consumes this façade, producing the wrapped receiver.
+    pub fn into_inner(self) -> watch::Receiver<T> {
+        self.receiver
+    }
+}
+
+#[inline(always)]
+fn nanos(d: Duration) -> u64 {
+    d.as_nanos().try_into().unwrap_or(u64::MAX)
+}
@@ -0,0 +1,128 @@
+use crate::{Instrumented, TaskMonitor};
+use hyper::service::Service;
+use std::task::{Context, Poll};
+
+/// Instruments the futures produced by a service, such as a [`hyper::Client`] or a connector,
+/// with a [`TaskMonitor`].
+///
+/// Wrapping a client instruments each outbound request, from the moment it is issued until its
+/// response head is received. Of its [`TaskMetrics`][crate::TaskMetrics], the
+/// [poll duration][crate::TaskMetrics::total_poll_duration] is the time spent doing work on behalf
+/// of requests, the [scheduled duration][crate::TaskMetrics::total_scheduled_duration] is the
+/// time requests spent waiting for a worker thread after becoming ready to make progress, and the
+/// [idle duration][crate::TaskMetrics::total_idle_duration] is, predominantly, the time spent
+/// waiting on the wire. Wrapping a connector likewise instruments the establishment of each
+/// connection; see also [`InstrumentedExecutor`] to instrument the tasks that drive connections.
+///
+/// **This functionality requires the crate feature `hyper`.**
+///
+/// [`hyper::Client`]: https://docs.rs/hyper/0.14/hyper/client/struct.Client.html
+///
+/// ##### Examples
+/// ```no_run
+/// use hyper::client::HttpConnector;
+/// use hyper::service::Service;
+/// use hyper::{Body, Client, Request};
+/// use tokio_metrics::{InstrumentedExecutor, InstrumentedService, TaskMonitor};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), hyper::Error> {
+///     let requests = TaskMonitor::new();
+///     let connects = TaskMonitor::new();
+///     let connections = TaskMonitor::new();
+///
+///     let connector = InstrumentedService::new(connects.clone(), HttpConnector::new());
+///     let client = Client::builder()
+///         .executor(InstrumentedExecutor::new(connections.clone()))
+///         .build::<_, Body>(connector);
+///     let mut client = InstrumentedService::new(requests.clone(), client);
+///
+///     let request = Request::get("http://example.com").body(Body::empty()).unwrap();
+///     let response = client.call(request).await?;
+///     println!("{}", response.status());
+///
+///     let metrics = requests.cumulative();
+///     println!("poll: {:?}", metrics.total_poll_duration);
+///     println!("scheduled: {:?}", metrics.total_scheduled_duration);
+///     println!("wire: {:?}", metrics.total_idle_duration);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct InstrumentedService<S> {
+    monitor: TaskMonitor,
+    inner: S,
+}
+
+/// A [`hyper::rt::Executor`] that instruments the tasks it spawns onto the current tokio runtime
+/// with a [`TaskMonitor`].
+///
+/// A [`hyper::Client`] configured with this executor instruments the background tasks that drive
+/// its connections; see [`InstrumentedService`] for an example.
+///
+/// **This functionality requires the crate features `hyper` and `rt`.**
+///
+/// [`hyper::Client`]: https://docs.rs/hyper/0.14/hyper/client/struct.Client.html
+#[cfg(feature = "rt")]
+#[derive(Clone)]
+pub struct InstrumentedExecutor {
+    monitor: TaskMonitor,
+}
+
+impl<S> InstrumentedService<S> {
+    /// Constructs a service whose futures are instrumented by `monitor`.
+    pub fn new(monitor: TaskMonitor, inner: S) -> InstrumentedService<S> {
+        InstrumentedService { monitor, inner }
+    }
+
+    /// Produces the monitor of this service.
+    pub fn monitor(&self) -> &TaskMonitor {
+        &self.monitor
+    }
+
+    /// Produces a reference to the wrapped service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Consumes this service, producing the wrapped service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, R> Service<R> for InstrumentedService<S>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Instrumented<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Instrumented<S::Future> {
+        self.monitor.instrument(self.inner.call(request))
+    }
+}
+
+#[cfg(feature = "rt")]
+impl InstrumentedExecutor {
+    /// Constructs an executor whose tasks are instrumented by `monitor`.
+    pub fn new(monitor: TaskMonitor) -> InstrumentedExecutor {
+        InstrumentedExecutor { monitor }
+    }
+}
+
+#[cfg(feature = "rt")]
+impl<F> hyper::rt::Executor<F> for InstrumentedExecutor
+where
+    F: std::future::Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    fn execute(&self, task: F) {
+        self.monitor.spawn(task);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{ConfigError, DDSketch, ExponentialHistogram, LatencySketch, Reporter};
+use crate::{TDigest, TaskMonitorBuilder};
+use serde::Deserialize;
+use std::path::Path;
+use tokio::time::Duration;
+
+/// The configuration of a [`Reporter`], as parsed by [`Reporter::from_json`].
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ReporterConfig {
+    period_ms: Option<u64>,
+    exporter: Option<String>,
+    endpoint: Option<String>,
+    slow_poll_threshold_us: Option<u64>,
+    blocking_poll_threshold_us: Option<u64>,
+    poll_buckets_us: Option<Vec<u64>>,
+    slow_poll_calibration: Option<CalibrationConfig>,
+    latency_sketch: Option<SketchConfig>,
+    #[serde(default)]
+    monitors: Vec<MonitorConfig>,
+}
+
+/// A monitor to construct and register; unset options are inherited from the reporter.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MonitorConfig {
+    name: String,
+    slow_poll_threshold_us: Option<u64>,
+    blocking_poll_threshold_us: Option<u64>,
+    poll_buckets_us: Option<Vec<u64>>,
+    slow_poll_calibration: Option<CalibrationConfig>,
+    latency_sketch: Option<SketchConfig>,
+}
+
+/// The [calibration][TaskMonitorBuilder::with_slow_poll_calibration] of the slow-poll threshold.
+#[derive(Deserialize, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+struct CalibrationConfig {
+    warm_up_polls: usize,
+    quantile: f64,
+}
+
+/// The [latency sketch][TaskMonitorBuilder::with_latency_sketch] of a monitor, by `kind`.
+#[derive(Deserialize, Clone, Copy)]
+#[serde(tag = "kind", deny_unknown_fields)]
+enum SketchConfig {
+    #[serde(rename = "ddsketch")]
+    DDSketch { relative_accuracy: f64 },
+    #[serde(rename = "tdigest")]
+    TDigest { compression: f64 },
+    #[serde(rename = "exponential_histogram")]
+    ExponentialHistogram { max_size: usize },
+    #[cfg(feature = "hdrhistogram")]
+    #[serde(rename = "hdrhistogram")]
+    HdrHistogram { significant_figures: u8 },
+}
+
+impl SketchConfig {
+    /// Constructs the configured sketch, if its parameters are valid.
+    fn build(self) -> Result<LatencySketch, ConfigError> {
+        let invalid = |message: &str| Err(ConfigError::new("latency_sketch", message));
+        Ok(match self {
+            SketchConfig::DDSketch { relative_accuracy } => {
+                if !(relative_accuracy > 0.0 && relative_accuracy < 1.0) {
+                    return invalid("the relative accuracy must be strictly between 0 and 1");
+                }
+                DDSketch::new(relative_accuracy).into()
+            }
+            SketchConfig::TDigest { compression } => {
+                if compression.is_nan() || compression <= 0.0 {
+                    return invalid("the compression must be positive");
+                }
+                TDigest::new(compression).into()
+            }
+            SketchConfig::ExponentialHistogram { max_size } => {
+                if max_size < 2 {
+                    return invalid("the maximum size must be at least 2");
+                }
+                ExponentialHistogram::new(max_size).into()
+            }
+            #[cfg(feature = "hdrhistogram")]
+            SketchConfig::HdrHistogram {
+                significant_figures,
+            } => {
+                if significant_figures > 5 {
+                    return invalid("the significant figures must be at most 5");
+                }
+                crate::HdrHistogram::new(significant_figures).into()
+            }
+        })
+    }
+}
+
+/// Applies the given thresholds, buckets, calibration and sketch to `builder`.
+fn configure(
+    mut builder: TaskMonitorBuilder,
+    slow_poll_threshold_us: Option<u64>,
+    blocking_poll_threshold_us: Option<u64>,
+    poll_buckets_us: Option<&[u64]>,
+    slow_poll_calibration: Option<CalibrationConfig>,
+    latency_sketch: Option<SketchConfig>,
+) -> Result<TaskMonitorBuilder, ConfigError> {
+    if let Some(threshold) = slow_poll_threshold_us {
+        builder = builder.with_slow_poll_threshold(Duration::from_micros(threshold));
+    }
+    if let Some(threshold) = blocking_poll_threshold_us {
+        builder = builder.with_blocking_poll_threshold(Duration::from_micros(threshold));
+    }
+    if let Some(thresholds) = poll_buckets_us {
+        builder = builder.with_poll_buckets(thresholds.iter().copied().map(Duration::from_micros));
+    }
+    if let Some(CalibrationConfig {
+        warm_up_polls,
+        quantile,
+    }) = slow_poll_calibration
+    {
+        if !(0.0..=1.0).contains(&quantile) {
+            return Err(ConfigError::new(
+                "slow_poll_calibration",
+                "the quantile must be between 0 and 1",
+            ));
+        }
+        builder = builder.with_slow_poll_calibration(warm_up_polls, quantile);
+    }
+    if let Some(sketch) = latency_sketch {
+        builder = builder.with_latency_sketch(sketch.build()?);
+    }
+    Ok(builder)
+}
+
+impl Reporter {
+    /// Constructs a reporter, and its monitors, from a JSON configuration.
+    ///
+    /// Every key is optional:
+    ///
+    /// - **`period_ms`**
+    ///   The period between reports, in milliseconds.
+    /// - **`exporter`** and **`endpoint`**
+    ///   Where metrics are reported; any of the exporters, and their endpoints, accepted by the
+    ///   `TOKIO_METRICS_EXPORTER` and `TOKIO_METRICS_ENDPOINT` variables of
+    ///   [`Reporter::from_env`].
+    /// - **`slow_poll_threshold_us`**, **`blocking_poll_threshold_us`** and **`poll_buckets_us`**
+    ///   The [slow-poll threshold][TaskMonitorBuilder::with_slow_poll_threshold],
+    ///   [blocking-poll threshold][TaskMonitorBuilder::with_blocking_poll_threshold] and
+    ///   [poll buckets][TaskMonitorBuilder::with_poll_buckets] of the monitors constructed by
+    ///   [`Reporter::monitor`], in microseconds.
+    /// - **`slow_poll_calibration`**
+    ///   The [calibration][TaskMonitorBuilder::with_slow_poll_calibration] of the slow-poll
+    ///   threshold of those monitors, as an object with the keys `warm_up_polls` and `quantile`.
+    /// - **`latency_sketch`**
+    ///   The [latency sketch][TaskMonitorBuilder::with_latency_sketch] of those monitors, as an
+    ///   object whose `kind` selects the sketch, and whose other key is the parameter of its
+    ///   constructor: `ddsketch` (`relative_accuracy`), `tdigest` (`compression`),
+    ///   `exponential_histogram` (`max_size`), or, with the crate feature `hdrhistogram`,
+    ///   `hdrhistogram` (`significant_figures`).
+    /// - **`monitors`**
+    ///   The monitors to construct and register up front: each has a `name` and, optionally,
+    ///   thresholds, buckets, calibration and a sketch overriding those above.
+    ///
+    /// The other options of [`TaskMonitorBuilder`] (e.g., callbacks and observers) cannot be
+    /// expressed in JSON; configure them with [`Reporter::with_monitor_builder`] instead.
+    ///
+    /// **This functionality requires the crate feature `config`.**
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::Reporter;
+    ///
+    /// let mut reporter = Reporter::from_json(r#"{
+    ///     "period_ms": 5000,
+    ///     "exporter": "stderr",
+    ///     "slow_poll_threshold_us": 100,
+    ///     "monitors": [
+    ///         { "name": "requests", "poll_buckets_us": [10, 100, 1000] },
+    ///         {
+    ///             "name": "background",
+    ///             "slow_poll_threshold_us": 10000,
+    ///             "latency_sketch": { "kind": "ddsketch", "relative_accuracy": 0.01 }
+    ///         }
+    ///     ]
+    /// }"#).unwrap();
+    ///
+    /// assert_eq!(reporter.period(), Duration::from_secs(5));
+    /// assert_eq!(reporter.monitors().count(), 2);
+    ///
+    /// let requests = reporter.monitor("requests");
+    /// assert_eq!(requests.slow_poll_threshold(), Duration::from_micros(100));
+    /// assert_eq!(requests.poll_buckets().len(), 4);
+    ///
+    /// let background = reporter.monitor("background");
+    /// assert_eq!(background.slow_poll_threshold(), Duration::from_millis(10));
+    /// assert!(background.take_latency_sketches().is_some());
+    /// ```
+    pub fn from_json(json: &str) -> Result<Reporter, ConfigError> {
+        let config: ReporterConfig =
+            serde_json::from_str(json).map_err(|e| ConfigError::new("json", e))?;
+
+        let mut reporter = Reporter::new();
+        if let Some(period) = config.period_ms {
+            if period == 0 {
+                return Err(ConfigError::new("period_ms", "the period must be non-zero"));
+            }
+            reporter = reporter.with_period(Duration::from_millis(period));
+        }
+
+        let builder = configure(
+            TaskMonitorBuilder::new(),
+            config.slow_poll_threshold_us,
+            config.blocking_poll_threshold_us,
+            config.poll_buckets_us.as_deref(),
+            config.slow_poll_calibration,
+            config.latency_sketch,
+        )?;
+        for monitor in &config.monitors {
+            let monitor_builder = configure(
+                builder.clone(),
+                monitor.slow_poll_threshold_us,
+                monitor.blocking_poll_threshold_us,
+                monitor.poll_buckets_us.as_deref(),
+                monitor.slow_poll_calibration,
+                monitor.latency_sketch,
+            )?;
+            reporter.register(monitor.name.clone(), monitor_builder.build());
+        }
+        reporter = reporter.with_monitor_builder(builder);
+
+        let keys = ("exporter", "endpoint");
+        let (exporter, endpoint) = (config.exporter.as_deref(), config.endpoint.as_deref());
+        reporter.with_named_exporter(exporter, endpoint, keys)
+    }
+
+    /// Constructs a reporter, and its monitors, from the JSON configuration file at `path`.
+    ///
+    /// See [`Reporter::from_json`].
+    ///
+    /// **This functionality requires the crate feature `config`.**
+    pub fn from_config_file(path: impl AsRef<Path>) -> Result<Reporter, ConfigError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| ConfigError::new(path.display().to_string(), e))?;
+        Reporter::from_json(&json)
+    }
+}
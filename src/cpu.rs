@@ -0,0 +1,19 @@
+#[cfg(feature = "rt")]
+use tokio::time::Duration;
+
+#[cfg(not(feature = "rt"))]
+use std::time::Duration;
+
+/// Produces the CPU time consumed by the current thread, if the platform supports measuring it.
+pub(crate) fn thread_cpu_time() -> Option<Duration> {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `time` is a valid, writable `timespec`.
+    let ret = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) };
+    if ret != 0 {
+        return None;
+    }
+    Some(Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+}
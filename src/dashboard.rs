@@ -0,0 +1,138 @@
+use crate::reporter::{Export, Round};
+use crate::{Reporter, ReporterTask, TaskMetrics};
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use tokio::time::Duration;
+
+/// Clears the terminal, and moves the cursor to its top-left corner.
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+impl Reporter {
+    /// Spawns a task onto the current tokio runtime that renders a live, `top`-like dashboard of
+    /// the monitors registered with this reporter to the terminal (standard output), once per
+    /// period, until it is [shut down][ReporterTask::shutdown] or aborted.
+    ///
+    /// For each monitor, the dashboard shows the rates of instrumentation, polls and slow polls
+    /// in the last period, the ratio of slow polls, and the mean poll duration, scheduling delay
+    /// and first-poll delay. Monitors registered after this call are not shown.
+    ///
+    /// This is intended for quick, interactive debugging on a host without external tooling; it
+    /// requires only a terminal that understands ANSI escape codes.
+    ///
+    /// **This functionality requires the crate feature `tui`.**
+    ///
+    /// ##### Panics
+    /// This method panics if called outside of a tokio runtime.
+    ///
+    /// ##### Examples
+    /// ```no_run
+    /// use tokio_metrics::Reporter;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut reporter = Reporter::new();
+    ///     let requests = reporter.monitor("requests");
+    ///     let background = reporter.monitor("background");
+    ///     reporter.spawn_dashboard();
+    ///
+    ///     loop {
+    ///         requests.instrument(tokio::task::yield_now()).await;
+    ///         background.instrument(tokio::task::yield_now()).await;
+    ///     }
+    /// }
+    /// ```
+    pub fn spawn_dashboard(&self) -> ReporterTask {
+        self.spawn_exporter(Dashboard {
+            period: self.period(),
+        })
+    }
+}
+
+/// The exporter of [`Reporter::spawn_dashboard`].
+struct Dashboard {
+    period: Duration,
+}
+
+impl Export for Dashboard {
+    fn export(&mut self, round: &Round<'_>) -> io::Result<()> {
+        let rows: Vec<_> = round
+            .monitors
+            .iter()
+            .map(|(name, _, sample)| (*name, sample.metrics))
+            .collect();
+        let mut stdout = io::stdout();
+        stdout.write_all(render(&rows, self.period).as_bytes())?;
+        stdout.flush()
+    }
+}
+
+/// Renders a frame of the dashboard, for the metrics of a sampling interval of length `period`.
+fn render(rows: &[(&str, TaskMetrics)], period: Duration) -> String {
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max("MONITOR".len());
+    let per_second = |count: u64| count as f64 / period.as_secs_f64();
+
+    let mut frame = String::from(CLEAR);
+    let _ = writeln!(
+        frame,
+        "tokio-metrics: {} monitors, every {:?}\n",
+        rows.len(),
+        period
+    );
+    let _ = writeln!(
+        frame,
+        "{:<width$} {:>10} {:>10} {:>10} {:>7} {:>10} {:>10} {:>10}",
+        "MONITOR",
+        "TASKS/s",
+        "POLLS/s",
+        "SLOW/s",
+        "SLOW%",
+        "POLL",
+        "SCHEDULED",
+        "FIRST POLL",
+        width = width
+    );
+    for (name, metrics) in rows {
+        let _ = writeln!(
+            frame,
+            "{:<width$} {:>10.1} {:>10.1} {:>10.1} {:>6.1}% {:>10} {:>10} {:>10}",
+            name,
+            per_second(metrics.instrumented_count),
+            per_second(metrics.total_poll_count),
+            per_second(metrics.total_slow_poll_count),
+            metrics.slow_poll_ratio() * 100.0,
+            format_duration(metrics.mean_poll_duration()),
+            format_duration(metrics.mean_scheduled_duration()),
+            format_duration(metrics.mean_first_poll_delay()),
+            width = width
+        );
+    }
+    frame
+}
+
+/// Formats `duration` to three significant figures, in the largest unit in which it is at
+/// least one.
+fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos() as f64;
+    let (value, unit) = if nanos >= 1e9 {
+        (nanos / 1e9, "s")
+    } else if nanos >= 1e6 {
+        (nanos / 1e6, "ms")
+    } else if nanos >= 1e3 {
+        (nanos / 1e3, "µs")
+    } else {
+        (nanos, "ns")
+    };
+    let precision = if value >= 100.0 {
+        0
+    } else if value >= 10.0 {
+        1
+    } else {
+        2
+    };
+    format!("{:.*}{}", precision, value, unit)
+}
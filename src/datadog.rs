@@ -0,0 +1,196 @@
+use crate::reporter::{Export, Round};
+use crate::{LatencySketch, MetricValue, MetricsSink, Reporter, ReporterTask};
+use std::fmt::Write as _;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::Mutex;
+
+/// Sends metrics to a Datadog agent over DogStatsD.
+///
+/// As a [`MetricsSink`], each metric is sent as a datagram, tagged with its labels and any
+/// additional [tags][DogStatsd::with_tag], and named with the [prefix][DogStatsd::with_prefix],
+/// if any. Counts and durations (in seconds) are sent as counters, and gauges, ratios and
+/// maximum durations as gauges. The first failure to send since the last
+/// [flush][MetricsSink::flush] is produced by the flush.
+///
+/// [Latency sketches][crate::TaskMonitorBuilder::with_latency_sketch] are sent as
+/// [distributions][DogStatsd::distribution], from which Datadog computes global percentiles
+/// across hosts. Each representative latency of the sketch is sent once, sampled at the inverse
+/// of its count, so that the agent reconstructs the distribution without receiving each latency.
+///
+/// The API key and site are configured on the agent; this exporter does not submit metrics to
+/// the Datadog API directly.
+///
+/// **This functionality requires the crate feature `datadog`.**
+///
+/// ##### Examples
+/// ```
+/// use std::net::UdpSocket;
+/// use tokio_metrics::{DogStatsd, MetricValue, MetricsSink};
+///
+/// fn main() -> std::io::Result<()> {
+///     // a stand-in for the agent
+///     let agent = UdpSocket::bind("127.0.0.1:0")?;
+///
+///     let dogstatsd = DogStatsd::connect(agent.local_addr()?)?
+///         .with_prefix("tokio")
+///         .with_tag("env:prod");
+///     dogstatsd.record("total_poll_count", MetricValue::Count(3), &[("monitor", "requests")]);
+///
+///     let mut datagram = [0; 512];
+///     let len = agent.recv(&mut datagram)?;
+///     assert_eq!(
+///         &datagram[..len],
+///         b"tokio.total_poll_count:3|c|#monitor:requests,env:prod"
+///     );
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DogStatsd {
+    socket: UdpSocket,
+    prefix: String,
+    tags: Vec<String>,
+    /// The first send error since the last flush, surfaced by [`MetricsSink::flush`].
+    error: Mutex<Option<io::Error>>,
+}
+
+impl DogStatsd {
+    /// Connects to the agent listening at `addr`; e.g., `127.0.0.1:8125`.
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<DogStatsd> {
+        let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "no address to connect to")
+        })?;
+        let local: SocketAddr = if addr.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0u16; 8], 0).into()
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(addr)?;
+        Ok(DogStatsd {
+            socket,
+            prefix: String::new(),
+            tags: Vec::new(),
+            error: Mutex::new(None),
+        })
+    }
+
+    /// Prefixes the names of metrics with `prefix`, and a period.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> DogStatsd {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Tags every metric with `tag`; e.g., `env:prod`.
+    pub fn with_tag(mut self, tag: impl Into<String>) -> DogStatsd {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Sends the latencies of `sketch` as the distribution `name`, in seconds, tagged with
+    /// `labels`.
+    pub fn distribution(&self, name: &str, sketch: &LatencySketch, labels: &[(&str, &str)]) {
+        for (latency, count) in sketch.weighted_latencies() {
+            let value = format!("{}|d|@{}", latency.as_secs_f64(), 1.0 / count as f64);
+            self.send(name, &value, labels);
+        }
+    }
+
+    /// Sends the metric `name` of `value` (e.g., `3|c`), tagged with `labels`.
+    fn send(&self, name: &str, value: &str, labels: &[(&str, &str)]) {
+        let mut datagram = String::new();
+        if !self.prefix.is_empty() {
+            datagram.push_str(&self.prefix);
+            datagram.push('.');
+        }
+        let _ = write!(datagram, "{}:{}", name, value);
+        let tags = labels
+            .iter()
+            .map(|(key, value)| format!("{}:{}", key, value))
+            .chain(self.tags.iter().cloned());
+        for (i, tag) in tags.enumerate() {
+            datagram.push_str(if i == 0 { "|#" } else { "," });
+            datagram.push_str(&tag);
+        }
+        if let Err(error) = self.socket.send(datagram.as_bytes()) {
+            self.error.lock().unwrap().get_or_insert(error);
+        }
+    }
+}
+
+impl MetricsSink for DogStatsd {
+    fn record(&self, name: &'static str, value: MetricValue, labels: &[(&str, &str)]) {
+        let value = match value {
+            MetricValue::Count(count) => format!("{}|c", count),
+            MetricValue::Gauge(gauge) => format!("{}|g", gauge),
+            MetricValue::Duration(duration) => format!("{}|c", duration.as_secs_f64()),
+            MetricValue::Ratio(ratio) => format!("{}|g", ratio),
+            MetricValue::MaxDuration(duration) => format!("{}|g", duration.as_secs_f64()),
+        };
+        self.send(name, &value, labels);
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        match self.error.lock().unwrap().take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Reporter {
+    /// Spawns a task onto the current tokio runtime that sends the metrics of each sampling
+    /// interval of the monitors registered with this reporter to `dogstatsd`, once per period,
+    /// until it is [shut down][ReporterTask::shutdown] or aborted. Monitors registered after
+    /// this call are not sent.
+    ///
+    /// The latency sketches of monitors for which they are enabled are
+    /// [taken][crate::TaskMonitor::take_latency_sketches] each period, and sent as the distributions
+    /// `poll_duration` and `scheduled_duration`.
+    ///
+    /// **This functionality requires the crate feature `datadog`.**
+    ///
+    /// ##### Panics
+    /// This method panics if called outside of a tokio runtime.
+    ///
+    /// ##### Examples
+    /// ```no_run
+    /// use tokio_metrics::{DDSketch, DogStatsd, Reporter, TaskMonitor};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut reporter = Reporter::new().with_monitor_builder(
+    ///         TaskMonitor::builder().with_latency_sketch(DDSketch::new(0.01)),
+    ///     );
+    ///     let monitor = reporter.monitor("requests");
+    ///     let dogstatsd = DogStatsd::connect("127.0.0.1:8125")?.with_prefix("tokio");
+    ///     let reporting = reporter.spawn_dogstatsd(dogstatsd);
+    ///
+    ///     for _ in 0..1000 {
+    ///         monitor.instrument(tokio::task::yield_now()).await;
+    ///     }
+    ///
+    ///     // send the metrics of the partial interval before exiting
+    ///     reporting.shutdown().await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn spawn_dogstatsd(&self, dogstatsd: DogStatsd) -> ReporterTask {
+        self.spawn_exporter(dogstatsd)
+    }
+}
+
+impl Export for DogStatsd {
+    fn export(&mut self, round: &Round<'_>) -> io::Result<()> {
+        for (name, monitor, sample) in &round.monitors {
+            let labels = [("monitor", *name)];
+            sample.metrics.record_to(&*self, &labels);
+            if let Some((polls, scheduled)) = monitor.take_latency_sketches() {
+                self.distribution("poll_duration", &polls, &labels);
+                self.distribution("scheduled_duration", &scheduled, &labels);
+            }
+        }
+        self.flush()
+    }
+}
@@ -0,0 +1,188 @@
+use crate::reporter::{Export, Round};
+use crate::{MetricValue, Reporter, ReporterTask, TaskMetrics};
+use serde_json::{json, Map, Value};
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Exports [`TaskMetrics`] in the CloudWatch Embedded Metric Format: structured log lines from
+/// which CloudWatch extracts metrics, with no agent or API calls; e.g., from the standard output
+/// of Lambda functions and ECS tasks.
+///
+/// Each document carries the metrics of one monitor, dimensioned by its name (as `monitor`) and
+/// any additional [dimensions][EmfExporter::with_dimension]. Counts are reported in the unit
+/// `Count`, durations in `Seconds`, and ratios in `None`.
+///
+/// Documents are written to standard output by default, or to any
+/// [writer][EmfExporter::with_writer]; e.g., one that forwards them to a Firehose delivery
+/// stream. Long-running services [spawn][Reporter::spawn_emf] an exporter to write the metrics of
+/// each sampling interval; Lambda functions, whose runtimes are frozen between invocations, may
+/// instead [encode][EmfExporter::encode] and print a document at the end of each invocation.
+///
+/// **This functionality requires the crate feature `emf`.**
+///
+/// ##### Examples
+/// ```
+/// use tokio_metrics::{EmfExporter, TaskMonitor};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let monitor = TaskMonitor::new();
+///     monitor.instrument(async {}).await;
+///
+///     let exporter = EmfExporter::new("MyService").with_dimension("function", "checkout");
+///     let document = exporter.encode("requests", &monitor.cumulative());
+///
+///     assert!(document.contains(r#""Namespace":"MyService""#));
+///     assert!(document.contains(r#""Dimensions":[["monitor","function"]]"#));
+///     assert!(document.contains(r#"{"Name":"total_poll_count","Unit":"Count"}"#));
+///     assert!(document.contains(r#""total_poll_count":1"#));
+///     println!("{}", document);
+/// }
+/// ```
+pub struct EmfExporter {
+    namespace: String,
+    dimensions: Vec<(String, String)>,
+    writer: Box<dyn Write + Send>,
+}
+
+impl EmfExporter {
+    /// Constructs an exporter of metrics into the CloudWatch `namespace`, which writes to
+    /// standard output.
+    pub fn new(namespace: impl Into<String>) -> EmfExporter {
+        EmfExporter {
+            namespace: namespace.into(),
+            dimensions: Vec::new(),
+            writer: Box::new(io::stdout()),
+        }
+    }
+
+    /// Adds a dimension to the exported metrics, in addition to `monitor`; e.g., the name of a
+    /// service or function.
+    pub fn with_dimension(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> EmfExporter {
+        self.dimensions.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the writer to which documents are written, one per line.
+    pub fn with_writer<W>(mut self, writer: W) -> EmfExporter
+    where
+        W: Write + Send + 'static,
+    {
+        self.writer = Box::new(writer);
+        self
+    }
+
+    /// Encodes the `metrics` of the monitor named `monitor` as a document, timestamped now.
+    pub fn encode(&self, monitor: &str, metrics: &TaskMetrics) -> String {
+        let values = RefCell::new(Vec::new());
+        metrics.record_to(
+            &|name: &'static str, value: MetricValue, _: &[(&str, &str)]| {
+                values.borrow_mut().push((name, value))
+            },
+            &[],
+        );
+
+        let mut document = Map::new();
+        let mut definitions = Vec::new();
+        for (name, value) in values.into_inner() {
+            let (value, unit) = match value {
+                MetricValue::Count(count) | MetricValue::Gauge(count) => (json!(count), "Count"),
+                MetricValue::Duration(duration) | MetricValue::MaxDuration(duration) => {
+                    (json!(duration.as_secs_f64()), "Seconds")
+                }
+                MetricValue::Ratio(ratio) => (json!(ratio), "None"),
+            };
+            definitions.push(json!({ "Name": name, "Unit": unit }));
+            document.insert(name.to_owned(), value);
+        }
+
+        let mut dimensions = vec![Value::from("monitor")];
+        document.insert("monitor".to_owned(), json!(monitor));
+        for (name, value) in &self.dimensions {
+            dimensions.push(json!(name));
+            document.insert(name.clone(), json!(value));
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        document.insert(
+            "_aws".to_owned(),
+            json!({
+                "Timestamp": timestamp,
+                "CloudWatchMetrics": [{
+                    "Namespace": self.namespace,
+                    "Dimensions": [dimensions],
+                    "Metrics": definitions,
+                }],
+            }),
+        );
+        Value::Object(document).to_string()
+    }
+
+    /// Writes the `metrics` of the monitor named `monitor` as a line.
+    pub fn write(&mut self, monitor: &str, metrics: &TaskMetrics) -> io::Result<()> {
+        let document = self.encode(monitor, metrics);
+        writeln!(self.writer, "{}", document)?;
+        self.writer.flush()
+    }
+}
+
+impl fmt::Debug for EmfExporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmfExporter")
+            .field("namespace", &self.namespace)
+            .field("dimensions", &self.dimensions)
+            .finish()
+    }
+}
+
+impl Reporter {
+    /// Spawns a task onto the current tokio runtime that writes the metrics of each sampling
+    /// interval of the monitors registered with this reporter with `exporter`, once per period,
+    /// until it is [shut down][ReporterTask::shutdown] or aborted. Monitors registered after
+    /// this call are not exported. Failed writes are handed to the
+    /// [error handler][Reporter::with_error_handler].
+    ///
+    /// **This functionality requires the crate feature `emf`.**
+    ///
+    /// ##### Panics
+    /// This method panics if called outside of a tokio runtime.
+    ///
+    /// ##### Examples
+    /// ```no_run
+    /// use tokio_metrics::{EmfExporter, Reporter};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut reporter = Reporter::new();
+    ///     let monitor = reporter.monitor("requests");
+    ///     let emf = reporter.spawn_emf(EmfExporter::new("MyService"));
+    ///
+    ///     for _ in 0..1000 {
+    ///         monitor.instrument(tokio::task::yield_now()).await;
+    ///     }
+    ///
+    ///     // write the metrics of the partial interval before exiting
+    ///     emf.shutdown().await;
+    /// }
+    /// ```
+    pub fn spawn_emf(&self, exporter: EmfExporter) -> ReporterTask {
+        self.spawn_exporter(exporter)
+    }
+}
+
+impl Export for EmfExporter {
+    fn export(&mut self, round: &Round<'_>) -> io::Result<()> {
+        for (name, _, sample) in &round.monitors {
+            self.write(name, &sample.metrics)?;
+        }
+        Ok(())
+    }
+}
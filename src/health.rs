@@ -0,0 +1,265 @@
+use crate::{Burst, BurstDetector, TaskMetrics};
+use std::fmt;
+
+#[cfg(feature = "rt")]
+use tokio::time::Duration;
+
+#[cfg(not(feature = "rt"))]
+use std::time::Duration;
+
+/// Evaluates recent sampling intervals of [`TaskMetrics`] against heuristics of ill health.
+///
+/// A check [evaluates][HealthCheck::evaluate] consecutive intervals, oldest first, and produces
+/// a [`HealthReport`] of its [findings][HealthFinding]:
+///
+/// - **[`SlowPolls`][HealthFinding::SlowPolls]**
+///   The [slow-poll ratio][TaskMetrics::slow_poll_ratio] of the latest interval exceeds the
+///   [maximum][HealthCheck::with_max_slow_poll_ratio] (by default, 0.1).
+/// - **[`SchedulingDelayCreeping`][HealthFinding::SchedulingDelayCreeping]**
+///   The [mean scheduling delay][TaskMetrics::mean_scheduled_duration] rose in every interval,
+///   and that of the latest interval is at least the
+///   [floor][HealthCheck::with_scheduling_delay_floor] (by default, 1ms).
+/// - **[`FirstPollBacklogGrowing`][HealthFinding::FirstPollBacklogGrowing]**
+///   More tasks were instrumented than were first polled in every interval, and the backlog of
+///   tasks awaiting their first poll grew by more than the
+///   [maximum][HealthCheck::with_max_first_poll_backlog_growth] (by default, 10) in total.
+/// - **[`Burst`][HealthFinding::Burst]**
+///   If [enabled][HealthCheck::with_burst_factor], the instrumentation rate or first-poll
+///   backlog of the latest interval burst beyond its baseline over the preceding intervals, as
+///   detected by a [`BurstDetector`].
+///
+/// The trends are only evaluated over at least two intervals. Readiness probes can evaluate a
+/// short window of recent intervals, and report [`HealthReport::is_healthy`].
+///
+/// ##### Examples
+/// ```
+/// use std::time::Duration;
+/// use tokio_metrics::{HealthCheck, HealthFinding, TaskMetrics};
+///
+/// let intervals: Vec<TaskMetrics> = [2, 4, 8]
+///     .iter()
+///     .map(|&delay_ms| {
+///         let mut interval = TaskMetrics::default();
+///         interval.total_poll_count = 100;
+///         interval.total_slow_poll_count = 1;
+///         interval.total_scheduled_count = 100;
+///         interval.total_scheduled_duration = Duration::from_millis(delay_ms * 100);
+///         interval
+///     })
+///     .collect();
+///
+/// let report = HealthCheck::new().evaluate(&intervals);
+/// assert!(!report.is_healthy());
+/// assert!(matches!(
+///     report.findings()[0],
+///     HealthFinding::SchedulingDelayCreeping { .. }
+/// ));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HealthCheck {
+    max_slow_poll_ratio: f64,
+    scheduling_delay_floor: Duration,
+    max_first_poll_backlog_growth: u64,
+    burst_factor: Option<f64>,
+}
+
+/// The findings of a [`HealthCheck`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HealthReport {
+    findings: Vec<HealthFinding>,
+}
+
+/// A heuristic of ill health, found by a [`HealthCheck`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum HealthFinding {
+    /// The slow-poll ratio of the latest interval exceeds the maximum.
+    SlowPolls {
+        /// The slow-poll ratio of the latest interval.
+        ratio: f64,
+        /// The maximum slow-poll ratio.
+        max: f64,
+    },
+
+    /// The mean scheduling delay rose in every interval.
+    SchedulingDelayCreeping {
+        /// The mean scheduling delay of the oldest interval.
+        from: Duration,
+        /// The mean scheduling delay of the latest interval.
+        to: Duration,
+    },
+
+    /// The backlog of tasks awaiting their first poll grew in every interval.
+    FirstPollBacklogGrowing {
+        /// The total growth of the backlog.
+        growth: u64,
+    },
+
+    /// A measure of the latest interval burst beyond its baseline over the preceding intervals.
+    Burst(Burst),
+}
+
+impl HealthCheck {
+    /// Constructs a check with the default thresholds.
+    pub fn new() -> HealthCheck {
+        HealthCheck {
+            max_slow_poll_ratio: 0.1,
+            scheduling_delay_floor: Duration::from_millis(1),
+            max_first_poll_backlog_growth: 10,
+            burst_factor: None,
+        }
+    }
+
+    /// Sets the maximum healthy [slow-poll ratio][TaskMetrics::slow_poll_ratio].
+    pub fn with_max_slow_poll_ratio(mut self, ratio: f64) -> HealthCheck {
+        self.max_slow_poll_ratio = ratio;
+        self
+    }
+
+    /// Sets the mean scheduling delay below which a rising delay is ignored as noise.
+    pub fn with_scheduling_delay_floor(mut self, floor: Duration) -> HealthCheck {
+        self.scheduling_delay_floor = floor;
+        self
+    }
+
+    /// Sets the maximum healthy growth of the backlog of tasks awaiting their first poll.
+    pub fn with_max_first_poll_backlog_growth(mut self, growth: u64) -> HealthCheck {
+        self.max_first_poll_backlog_growth = growth;
+        self
+    }
+
+    /// Enables burst detection: a finding if a measure of the latest interval exceeds its
+    /// baseline over the preceding intervals by more than `factor`. See [`BurstDetector`].
+    ///
+    /// ##### Examples
+    /// ```
+    /// use tokio_metrics::{HealthCheck, HealthFinding, TaskMetrics};
+    ///
+    /// let intervals: Vec<TaskMetrics> = [20, 20, 20, 500]
+    ///     .iter()
+    ///     .map(|&count| {
+    ///         let mut interval = TaskMetrics::default();
+    ///         interval.instrumented_count = count;
+    ///         interval.first_poll_count = count;
+    ///         interval
+    ///     })
+    ///     .collect();
+    ///
+    /// let report = HealthCheck::new().with_burst_factor(5.0).evaluate(&intervals);
+    /// assert!(matches!(report.findings(), [HealthFinding::Burst(_)]));
+    /// ```
+    pub fn with_burst_factor(mut self, factor: f64) -> HealthCheck {
+        self.burst_factor = Some(factor);
+        self
+    }
+
+    /// Evaluates consecutive sampling `intervals`, oldest first.
+    pub fn evaluate(&self, intervals: &[TaskMetrics]) -> HealthReport {
+        let mut findings = Vec::new();
+        let latest = match intervals.last() {
+            Some(latest) => latest,
+            None => return HealthReport { findings },
+        };
+
+        let ratio = latest.slow_poll_ratio();
+        if ratio > self.max_slow_poll_ratio {
+            findings.push(HealthFinding::SlowPolls {
+                ratio,
+                max: self.max_slow_poll_ratio,
+            });
+        }
+
+        if intervals.len() < 2 {
+            return HealthReport { findings };
+        }
+
+        let delays: Vec<Duration> = intervals
+            .iter()
+            .map(TaskMetrics::mean_scheduled_duration)
+            .collect();
+        let creeping = delays.windows(2).all(|pair| pair[1] > pair[0]);
+        let to = delays[delays.len() - 1];
+        if creeping && to >= self.scheduling_delay_floor {
+            findings.push(HealthFinding::SchedulingDelayCreeping {
+                from: delays[0],
+                to,
+            });
+        }
+
+        let growing = intervals
+            .iter()
+            .all(|interval| interval.instrumented_count > interval.first_poll_count);
+        if growing {
+            let growth = intervals
+                .iter()
+                .map(|interval| interval.instrumented_count - interval.first_poll_count)
+                .fold(0u64, u64::saturating_add);
+            if growth > self.max_first_poll_backlog_growth {
+                findings.push(HealthFinding::FirstPollBacklogGrowing { growth });
+            }
+        }
+
+        if let Some(factor) = self.burst_factor {
+            let mut detector = BurstDetector::new()
+                .with_baseline(intervals.len() - 1)
+                .with_factor(factor);
+            let mut bursts = Vec::new();
+            for interval in intervals {
+                bursts = detector.record(interval);
+            }
+            findings.extend(bursts.into_iter().map(HealthFinding::Burst));
+        }
+
+        HealthReport { findings }
+    }
+}
+
+impl Default for HealthCheck {
+    fn default() -> HealthCheck {
+        HealthCheck::new()
+    }
+}
+
+impl HealthReport {
+    /// Produces `true` if there are no findings.
+    pub fn is_healthy(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Produces the findings of the check.
+    pub fn findings(&self) -> &[HealthFinding] {
+        &self.findings
+    }
+}
+
+impl fmt::Display for HealthReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_healthy() {
+            return f.write_str("healthy");
+        }
+        for (index, finding) in self.findings.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}", finding)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for HealthFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthFinding::SlowPolls { ratio, max } => {
+                write!(f, "slow-poll ratio {:.3} exceeds {:.3}", ratio, max)
+            }
+            HealthFinding::SchedulingDelayCreeping { from, to } => {
+                write!(f, "scheduling delay rose from {:?} to {:?}", from, to)
+            }
+            HealthFinding::FirstPollBacklogGrowing { growth } => {
+                write!(f, "first-poll backlog grew by {}", growth)
+            }
+            HealthFinding::Burst(burst) => write!(f, "burst: {}", burst),
+        }
+    }
+}
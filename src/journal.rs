@@ -0,0 +1,192 @@
+use crate::reporter::{Export, Round};
+use crate::{MetricValue, Reporter, ReporterTask, TaskMetrics};
+use std::cell::RefCell;
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::path::PathBuf;
+
+/// Writes [`TaskMetrics`] to the systemd journal as structured entries, so that hosts with
+/// journald-based log pipelines can query task metrics without a metrics stack; e.g.,
+/// `journalctl TOKIO_METRICS_MONITOR=requests -o json`.
+///
+/// Each entry carries the metrics of one monitor, as fields named by the uppercased names of the
+/// metrics (e.g., `TOTAL_POLL_COUNT`); durations are written in seconds. The name of the monitor
+/// is written as `TOKIO_METRICS_MONITOR`, and a summary as `MESSAGE`.
+///
+/// Entries are sent with the journal's native protocol, as `sd_journal_send` does, without
+/// linking `libsystemd`; values spanning several lines are framed by their length.
+///
+/// **This functionality requires the crate feature `journald`, and Linux.**
+///
+/// ##### Examples
+/// ```
+/// use std::os::unix::net::UnixDatagram;
+/// use tokio_metrics::{JournalExporter, TaskMonitor};
+///
+/// #[tokio::main]
+/// async fn main() -> std::io::Result<()> {
+///     // a stand-in for journald
+///     let path = std::env::temp_dir().join(format!("tokio-metrics-{}.journal", std::process::id()));
+///     let journal = UnixDatagram::bind(&path)?;
+///
+///     let monitor = TaskMonitor::new();
+///     monitor.instrument(async {}).await;
+///
+///     let exporter = JournalExporter::new()?
+///         .with_path(&path)
+///         .with_field("SYSLOG_IDENTIFIER", "checkout")
+///         .with_field("DEPLOYMENT", "canary\nus-east-1");
+///     exporter.send("requests", &monitor.cumulative())?;
+///
+///     let mut entry = vec![0; 64 * 1024];
+///     let len = journal.recv(&mut entry)?;
+///     let entry = String::from_utf8_lossy(&entry[..len]);
+///     assert!(entry.contains("\nTOKIO_METRICS_MONITOR=requests\n"));
+///     assert!(entry.contains("\nSYSLOG_IDENTIFIER=checkout\n"));
+///     assert!(entry.contains("\nTOTAL_POLL_COUNT=1\n"));
+///     // the multi-line value is preceded by its length, in place of `=`
+///     assert!(entry.contains("\nDEPLOYMENT\n\u{10}\0\0\0\0\0\0\0canary\nus-east-1\n"));
+///
+///     std::fs::remove_file(&path)
+/// }
+/// ```
+#[derive(Debug)]
+pub struct JournalExporter {
+    socket: UnixDatagram,
+    path: PathBuf,
+    fields: Vec<(String, String)>,
+}
+
+impl JournalExporter {
+    /// The path of the socket on which journald listens for native entries.
+    pub const DEFAULT_PATH: &'static str = "/run/systemd/journal/socket";
+
+    /// Constructs an exporter to the journal at [`JournalExporter::DEFAULT_PATH`].
+    pub fn new() -> io::Result<JournalExporter> {
+        Ok(JournalExporter {
+            socket: UnixDatagram::unbound()?,
+            path: PathBuf::from(JournalExporter::DEFAULT_PATH),
+            fields: Vec::new(),
+        })
+    }
+
+    /// Sets the path of the socket to which entries are sent.
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> JournalExporter {
+        self.path = path.into();
+        self
+    }
+
+    /// Adds a field to every entry; e.g., `SYSLOG_IDENTIFIER`. Field names consist of
+    /// uppercase letters, digits and underscores.
+    pub fn with_field(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> JournalExporter {
+        self.fields.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sends the `metrics` of the monitor named `monitor` as an entry.
+    pub fn send(&self, monitor: &str, metrics: &TaskMetrics) -> io::Result<()> {
+        let values = RefCell::new(Vec::new());
+        metrics.record_to(
+            &|name: &'static str, value: MetricValue, _: &[(&str, &str)]| {
+                values.borrow_mut().push((name, value))
+            },
+            &[],
+        );
+
+        let mut entry = Vec::new();
+        field(
+            &mut entry,
+            "MESSAGE",
+            &format!(
+                "tokio-metrics: {}: {} polls, {} slow",
+                monitor, metrics.total_poll_count, metrics.total_slow_poll_count
+            ),
+        );
+        field(&mut entry, "PRIORITY", "6");
+        field(&mut entry, "TOKIO_METRICS_MONITOR", monitor);
+        for (name, value) in &self.fields {
+            field(&mut entry, name, value);
+        }
+        for (name, value) in values.into_inner() {
+            let value = match value {
+                MetricValue::Count(count) | MetricValue::Gauge(count) => count.to_string(),
+                MetricValue::Duration(duration) | MetricValue::MaxDuration(duration) => {
+                    duration.as_secs_f64().to_string()
+                }
+                MetricValue::Ratio(ratio) => ratio.to_string(),
+            };
+            field(&mut entry, &name.to_ascii_uppercase(), &value);
+        }
+        self.socket.send_to(&entry, &self.path)?;
+        Ok(())
+    }
+}
+
+impl Reporter {
+    /// Spawns a task onto the current tokio runtime that writes the metrics of each sampling
+    /// interval of the monitors registered with this reporter to the journal with `exporter`,
+    /// once per period, until it is [shut down][ReporterTask::shutdown] or aborted. Monitors
+    /// registered after this call are not written.
+    ///
+    /// Failures to send are handed to the [error handler][Reporter::with_error_handler], as
+    /// [`ReportError::Sink`][crate::ReportError::Sink], and delay the next entries by the
+    /// [backoff][Reporter::with_backoff], if configured.
+    ///
+    /// **This functionality requires the crate feature `journald`, and Linux.**
+    ///
+    /// ##### Panics
+    /// This method panics if called outside of a tokio runtime.
+    ///
+    /// ##### Examples
+    /// ```no_run
+    /// use tokio_metrics::{JournalExporter, Reporter};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut reporter = Reporter::new().with_error_handler(|error| {
+    ///         eprintln!("failed to write to the journal: {}", error);
+    ///     });
+    ///     let monitor = reporter.monitor("requests");
+    ///     let journal = reporter.spawn_journal(JournalExporter::new()?);
+    ///
+    ///     for _ in 0..1000 {
+    ///         monitor.instrument(tokio::task::yield_now()).await;
+    ///     }
+    ///
+    ///     // write the metrics of the partial interval before exiting
+    ///     journal.shutdown().await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn spawn_journal(&self, exporter: JournalExporter) -> ReporterTask {
+        self.spawn_exporter(exporter)
+    }
+}
+
+impl Export for JournalExporter {
+    fn export(&mut self, round: &Round<'_>) -> io::Result<()> {
+        for (name, _, sample) in &round.monitors {
+            self.send(name, &sample.metrics)?;
+        }
+        Ok(())
+    }
+}
+
+/// Appends the field `name` of `value` to `entry`, in the journal's native format.
+fn field(entry: &mut Vec<u8>, name: &str, value: &str) {
+    entry.extend_from_slice(name.as_bytes());
+    if value.contains('\n') {
+        // as `sd_journal_send` does, multi-line values are framed by their length, as a
+        // little-endian 64-bit integer, rather than terminated by a newline
+        entry.push(b'\n');
+        entry.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        entry.push(b'=');
+    }
+    entry.extend_from_slice(value.as_bytes());
+    entry.push(b'\n');
+}
@@ -114,8 +114,143 @@ cfg_rt! {
     pub use runtime::{
         RuntimeMetrics,
         RuntimeMonitor,
+        WorkerMetrics,
     };
 }
 
 mod task;
-pub use task::{Instrumented, TaskMetrics, TaskMonitor};
+#[cfg(feature = "rt")]
+pub use task::InstrumentedWithTimeout;
+#[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+pub use task::StallReport;
+pub use task::{
+    BranchPolls, DumpOrder, Instrumented, InstrumentedBranch, InstrumentedResult,
+    InstrumentedWithSummary, IntervalSample, LiveTask, LiveTaskState, PollBucket, PollExemplar,
+    ScopeGuard, SlowPoll, SlowPollTier, Snapshot, StallKind, StalledTask, TaskDump, TaskId,
+    TaskMetrics, TaskMetricsRates, TaskMonitor, TaskMonitorBuilder, TaskSummary, WeakTaskMonitor,
+    WorkerPolls,
+};
+
+#[cfg(feature = "hyper")]
+mod client;
+#[cfg(all(feature = "hyper", feature = "rt"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "hyper", feature = "rt"))))]
+pub use client::InstrumentedExecutor;
+#[cfg(feature = "hyper")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hyper")))]
+pub use client::InstrumentedService;
+
+#[cfg(feature = "rt")]
+mod channel;
+#[cfg(feature = "rt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rt")))]
+pub use channel::{
+    ChannelMetrics, ChannelMonitor, InstrumentedBroadcastReceiver, InstrumentedWatchReceiver,
+};
+
+#[cfg(feature = "rt")]
+mod lock;
+#[cfg(feature = "rt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rt")))]
+pub use lock::{
+    InstrumentedMutex, InstrumentedRwLock, InstrumentedSemaphore, LockMetrics, LockMonitor,
+    WaitBucket,
+};
+
+#[cfg(feature = "rt")]
+mod reporter;
+#[cfg(feature = "rt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rt")))]
+pub use reporter::{ConfigError, ReportError, Reporter, ReporterTask};
+
+#[cfg(feature = "config")]
+mod config;
+
+#[cfg(feature = "tui")]
+mod dashboard;
+
+#[cfg(feature = "server")]
+mod server;
+#[cfg(feature = "server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+pub use server::MetricsServer;
+
+#[cfg(feature = "datadog")]
+mod datadog;
+#[cfg(feature = "datadog")]
+#[cfg_attr(docsrs, doc(cfg(feature = "datadog")))]
+pub use datadog::DogStatsd;
+
+#[cfg(feature = "publish")]
+mod publish;
+#[cfg(all(unix, feature = "publish"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "publish"))))]
+pub use publish::UnixSocketPublisher;
+#[cfg(feature = "publish")]
+#[cfg_attr(docsrs, doc(cfg(feature = "publish")))]
+pub use publish::{SnapshotFormat, SnapshotPublisher};
+
+#[cfg(all(target_os = "linux", feature = "journald"))]
+mod journal;
+#[cfg(all(target_os = "linux", feature = "journald"))]
+#[cfg_attr(docsrs, doc(cfg(all(target_os = "linux", feature = "journald"))))]
+pub use journal::JournalExporter;
+
+#[cfg(feature = "emf")]
+mod emf;
+#[cfg(feature = "emf")]
+#[cfg_attr(docsrs, doc(cfg(feature = "emf")))]
+pub use emf::EmfExporter;
+
+#[cfg(feature = "pushgateway")]
+mod push;
+#[cfg(feature = "pushgateway")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pushgateway")))]
+pub use push::Pushgateway;
+
+mod health;
+pub use health::{HealthCheck, HealthFinding, HealthReport};
+
+mod allocation;
+pub use allocation::TrackingAllocator;
+
+#[cfg(all(feature = "cpu-time", unix))]
+mod cpu;
+
+mod local;
+pub use local::{LocalInstrumented, LocalTaskMonitor};
+
+mod perf;
+pub use perf::PerfCounters;
+
+mod observer;
+pub use observer::TaskObserver;
+
+#[cfg(feature = "tracing")]
+mod outlier;
+
+#[cfg(feature = "tracing")]
+mod span;
+#[cfg(feature = "tracing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+pub use span::{SpanKey, SpanMonitor};
+
+mod sketch;
+#[cfg(feature = "hdrhistogram")]
+pub use sketch::HdrHistogram;
+pub use sketch::{DDSketch, ExponentialHistogram, LatencySketch, TDigest};
+
+#[cfg(feature = "usdt")]
+mod usdt;
+
+mod slo;
+pub use slo::{BurnRateAlert, BurnRateTracker, Slo};
+
+mod burst;
+pub use burst::{Burst, BurstDetector, BurstKind};
+
+mod sink;
+pub use sink::{MetricDescriptor, MetricKind, MetricValue, MetricsSink, NamespacedSink};
+
+mod trace;
+pub use trace::TraceRecorder;
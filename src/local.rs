@@ -0,0 +1,356 @@
+use crate::task::{InstrumentedWaker, OnWake};
+use crate::TaskMetrics;
+use pin_project_lite::pin_project;
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering::SeqCst};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+#[cfg(feature = "rt")]
+use tokio::time::{Duration, Instant};
+
+#[cfg(not(feature = "rt"))]
+use std::time::{Duration, Instant};
+
+/// Monitors key metrics of tokio tasks that are polled on a single thread; e.g., on a
+/// `current_thread` runtime, or in a [`LocalSet`][tokio::task::LocalSet].
+///
+/// A [`LocalTaskMonitor`] is the unsynchronized counterpart of
+/// [`TaskMonitor`][crate::TaskMonitor]: its metrics are recorded in plain [`Cell`]s, shared by
+/// [`Rc`], rather than in atomics shared by [`Arc`]. Neither the monitor nor the tasks it
+/// instruments are [`Send`]; in exchange, recording a metric costs no more than an ordinary
+/// addition. Only the waker of each task, which must be [`Send`], is synchronized.
+///
+/// A [`LocalTaskMonitor`] records only a subset of the [`TaskMetrics`] of a
+/// [`TaskMonitor`][crate::TaskMonitor]; namely:
+/// - [`instrumented_count`][TaskMetrics::instrumented_count] and
+///   [`dropped_count`][TaskMetrics::dropped_count],
+/// - [`first_poll_count`][TaskMetrics::first_poll_count] and
+///   [`total_first_poll_delay`][TaskMetrics::total_first_poll_delay],
+/// - [`total_idled_count`][TaskMetrics::total_idled_count] and
+///   [`total_idle_duration`][TaskMetrics::total_idle_duration],
+/// - [`total_scheduled_count`][TaskMetrics::total_scheduled_count] and
+///   [`total_scheduled_duration`][TaskMetrics::total_scheduled_duration],
+/// - [`total_poll_count`][TaskMetrics::total_poll_count] and
+///   [`total_poll_duration`][TaskMetrics::total_poll_duration],
+/// - [`total_fast_poll_count`][TaskMetrics::total_fast_poll_count] and
+///   [`total_fast_poll_duration`][TaskMetrics::total_fast_poll_duration],
+/// - [`total_slow_poll_count`][TaskMetrics::total_slow_poll_count] and
+///   [`total_slow_poll_duration`][TaskMetrics::total_slow_poll_duration], and
+/// - [`total_budget_yield_count`][TaskMetrics::total_budget_yield_count].
+///
+/// Every other field of the [`TaskMetrics`] it produces remains zero; so do the derived metrics
+/// of those fields.
+///
+/// ##### Examples
+/// ```
+/// use tokio_metrics::LocalTaskMonitor;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let monitor = LocalTaskMonitor::new();
+///     let local = tokio::task::LocalSet::new();
+///
+///     local.run_until(async {
+///         let task = monitor.instrument(async {
+///             // `Rc` is not `Send`
+///             let counter = std::rc::Rc::new(0);
+///             tokio::task::yield_now().await;
+///             *counter
+///         });
+///         tokio::task::spawn_local(task).await.unwrap();
+///     }).await;
+///
+///     let metrics = monitor.cumulative();
+///     assert_eq!(metrics.instrumented_count, 1);
+///     assert_eq!(metrics.total_poll_count, 2);
+///     assert_eq!(metrics.total_scheduled_count, 1);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct LocalTaskMonitor {
+    metrics: Rc<LocalRawMetrics>,
+}
+
+pin_project! {
+    /// An async task that has been instrumented with [`LocalTaskMonitor::instrument`].
+    pub struct LocalInstrumented<T> {
+        // The task being instrumented
+        #[pin]
+        task: T,
+
+        // True when the task is polled for the first time
+        did_poll_once: bool,
+
+        // The instant, tracked as nanoseconds since `instrumented_at`, at which the future finished
+        // its last poll.
+        idled_at: u64,
+
+        // Where metrics should be recorded
+        metrics: Rc<LocalRawMetrics>,
+
+        // The state shared with the waker of the task
+        state: Arc<WakeState>,
+
+        // The instrumented waker with which the task was last polled
+        waker: Option<Arc<InstrumentedWaker<WakeState>>>,
+    }
+
+    impl<T> PinnedDrop for LocalInstrumented<T> {
+        fn drop(this: Pin<&mut Self>) {
+            inc(&this.metrics.dropped_count, 1);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct LocalRawMetrics {
+    /// A task poll takes longer than this, it is considered a slow poll.
+    slow_poll_threshold: Duration,
+
+    /// Total number of instrumented tasks.
+    instrumented_count: Cell<u64>,
+
+    /// Total number of instrumented tasks polled at least once.
+    first_poll_count: Cell<u64>,
+
+    /// Total number of times tasks entered the `idle` state.
+    total_idled_count: Cell<u64>,
+
+    /// Total number of times tasks were scheduled.
+    total_scheduled_count: Cell<u64>,
+
+    /// Total number of times tasks were polled fast
+    total_fast_poll_count: Cell<u64>,
+
+    /// Total number of times tasks were polled slow
+    total_slow_poll_count: Cell<u64>,
+
+    /// Total number of times tasks were woken during a poll that returned `Pending`
+    total_budget_yield_count: Cell<u64>,
+
+    /// Total number of times tasks were dropped
+    dropped_count: Cell<u64>,
+
+    /// Total amount of time until the first poll
+    total_first_poll_delay_ns: Cell<u64>,
+
+    /// Total amount of time tasks spent in the `idle` state.
+    total_idle_duration_ns: Cell<u64>,
+
+    /// Total amount of time tasks spent in the waking state.
+    total_scheduled_duration_ns: Cell<u64>,
+
+    /// Total amount of time tasks spent being polled below the slow cut off.
+    total_fast_poll_duration_ns: Cell<u64>,
+
+    /// Total amount of time tasks spent being polled above the slow cut off.
+    total_slow_poll_duration_ns: Cell<u64>,
+}
+
+/// The state of a task shared with its waker, which may be invoked from any thread.
+struct WakeState {
+    /// Instant at which the task was instrumented.
+    instrumented_at: Instant,
+
+    /// The instant, tracked as nanoseconds since `instrumented_at`, at which the future
+    /// was last woken.
+    woke_at: AtomicU64,
+}
+
+impl LocalTaskMonitor {
+    /// Constructs a new local task monitor, which considers polls at least
+    /// [`TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD`][crate::TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD]
+    /// long to be slow.
+    pub fn new() -> LocalTaskMonitor {
+        LocalTaskMonitor::with_slow_poll_threshold(crate::TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD)
+    }
+
+    /// Constructs a new local task monitor with a given threshold at which polls are considered
+    /// 'slow'.
+    pub fn with_slow_poll_threshold(slow_poll_cut_off: Duration) -> LocalTaskMonitor {
+        LocalTaskMonitor {
+            metrics: Rc::new(LocalRawMetrics {
+                slow_poll_threshold: slow_poll_cut_off,
+                ..LocalRawMetrics::default()
+            }),
+        }
+    }
+
+    /// Produces the duration greater-than-or-equal-to at which polls are categorized as slow.
+    pub fn slow_poll_threshold(&self) -> Duration {
+        self.metrics.slow_poll_threshold
+    }
+
+    /// Produces an instrumented façade around a given async task. Its metrics are recorded by
+    /// this monitor.
+    pub fn instrument<F: Future>(&self, task: F) -> LocalInstrumented<F> {
+        inc(&self.metrics.instrumented_count, 1);
+        LocalInstrumented {
+            task,
+            did_poll_once: false,
+            idled_at: 0,
+            metrics: self.metrics.clone(),
+            state: Arc::new(WakeState {
+                instrumented_at: Instant::now(),
+                woke_at: AtomicU64::new(0),
+            }),
+            waker: None,
+        }
+    }
+
+    /// Produces [`TaskMetrics`] for the tasks instrumented by this monitor, collected since its
+    /// construction.
+    pub fn cumulative(&self) -> TaskMetrics {
+        self.metrics.metrics()
+    }
+
+    /// Produces an unending iterator of metric sampling intervals, like
+    /// [`TaskMonitor::intervals`][crate::TaskMonitor::intervals].
+    pub fn intervals(&self) -> impl Iterator<Item = TaskMetrics> {
+        let latest = self.metrics.clone();
+        let mut previous: Option<TaskMetrics> = None;
+
+        std::iter::from_fn(move || {
+            let latest: TaskMetrics = latest.metrics();
+            let next = match previous {
+                Some(previous) => latest - previous,
+                None => latest,
+            };
+            previous = Some(latest);
+            Some(next)
+        })
+    }
+}
+
+impl Default for LocalTaskMonitor {
+    fn default() -> LocalTaskMonitor {
+        LocalTaskMonitor::new()
+    }
+}
+
+impl LocalRawMetrics {
+    fn metrics(&self) -> TaskMetrics {
+        let total_fast_poll_count = self.total_fast_poll_count.get();
+        let total_slow_poll_count = self.total_slow_poll_count.get();
+
+        let total_fast_poll_duration = Duration::from_nanos(self.total_fast_poll_duration_ns.get());
+        let total_slow_poll_duration = Duration::from_nanos(self.total_slow_poll_duration_ns.get());
+
+        TaskMetrics {
+            instrumented_count: self.instrumented_count.get(),
+            dropped_count: self.dropped_count.get(),
+
+            total_poll_count: total_fast_poll_count + total_slow_poll_count,
+            total_poll_duration: total_fast_poll_duration + total_slow_poll_duration,
+
+            first_poll_count: self.first_poll_count.get(),
+            total_idled_count: self.total_idled_count.get(),
+            total_scheduled_count: self.total_scheduled_count.get(),
+            total_fast_poll_count,
+            total_slow_poll_count,
+            total_budget_yield_count: self.total_budget_yield_count.get(),
+            total_first_poll_delay: Duration::from_nanos(self.total_first_poll_delay_ns.get()),
+            total_idle_duration: Duration::from_nanos(self.total_idle_duration_ns.get()),
+            total_scheduled_duration: Duration::from_nanos(self.total_scheduled_duration_ns.get()),
+            total_fast_poll_duration,
+            total_slow_poll_duration,
+            ..TaskMetrics::default()
+        }
+    }
+}
+
+impl<T: Future> Future for LocalInstrumented<T> {
+    type Output = T::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let poll_start = Instant::now();
+        let this = self.project();
+        let state = this.state;
+        let metrics = this.metrics;
+        let instrumented_at = state.instrumented_at;
+
+        /* accounting for time-to-first-poll and tasks-count */
+        if !*this.did_poll_once {
+            *this.did_poll_once = true;
+            inc(&metrics.first_poll_count, 1);
+            inc(
+                &metrics.total_first_poll_delay_ns,
+                nanos(poll_start - instrumented_at),
+            );
+        }
+
+        /* accounting for time-idled and time-scheduled */
+        let woke_at = state.woke_at.swap(0, SeqCst);
+        let idled_at = this.idled_at;
+
+        if *idled_at < woke_at {
+            inc(&metrics.total_idled_count, 1);
+            inc(&metrics.total_idle_duration_ns, woke_at - *idled_at);
+        }
+
+        if woke_at > 0 {
+            inc(&metrics.total_scheduled_count, 1);
+            let woke_instant = instrumented_at + Duration::from_nanos(woke_at);
+            inc(
+                &metrics.total_scheduled_duration_ns,
+                nanos(poll_start.saturating_duration_since(woke_instant)),
+            );
+        }
+
+        // Get the instrumented waker, which forwards wakes to the original
+        let waker = InstrumentedWaker::get(this.waker, state, cx.waker()).as_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        // Poll the task
+        let inner_poll_start = Instant::now();
+        let ret = Future::poll(this.task, &mut cx);
+        let inner_poll_end = Instant::now();
+
+        /* idle time starts now */
+        *idled_at = nanos(inner_poll_end - instrumented_at);
+
+        /* accounting for poll time */
+        let inner_poll_duration = inner_poll_end - inner_poll_start;
+        let (count_bucket, duration_bucket) = // was this a slow or fast poll?
+            if inner_poll_duration >= metrics.slow_poll_threshold {
+                (&metrics.total_slow_poll_count, &metrics.total_slow_poll_duration_ns)
+            } else {
+                (&metrics.total_fast_poll_count, &metrics.total_fast_poll_duration_ns)
+            };
+        inc(count_bucket, 1);
+        inc(duration_bucket, nanos(inner_poll_duration));
+
+        /* accounting for forced yields */
+        if ret.is_pending() && state.woke_at.load(SeqCst) != 0 {
+            inc(&metrics.total_budget_yield_count, 1);
+        }
+
+        ret
+    }
+}
+
+impl OnWake for WakeState {
+    fn on_wake(&self) {
+        let woke_at: u64 = match self.instrumented_at.elapsed().as_nanos().try_into() {
+            Ok(woke_at) => woke_at,
+            Err(_) => return,
+        };
+
+        // We don't actually care about the result
+        let _ = self.woke_at.compare_exchange(0, woke_at, SeqCst, SeqCst);
+    }
+}
+
+#[inline(always)]
+fn inc(cell: &Cell<u64>, n: u64) {
+    cell.set(cell.get().wrapping_add(n));
+}
+
+#[inline(always)]
+fn nanos(d: Duration) -> u64 {
+    d.as_nanos().try_into().unwrap_or(u64::MAX)
+}
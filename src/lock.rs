@@ -0,0 +1,772 @@
+use crate::{MetricValue, MetricsSink};
+use std::sync::atomic::{AtomicU64, Ordering::SeqCst};
+use std::sync::Arc;
+use tokio::sync::{
+    AcquireError, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard, Semaphore,
+    SemaphorePermit, TryLockError,
+};
+use tokio::time::{Duration, Instant};
+
+/// Monitors the acquisition of [`Semaphore`] permits, and of [`Mutex`] and [`RwLock`] locks.
+///
+/// Waits for locks frequently masquerade as slow tasks: a task blocked on a contended lock is
+/// idle, not slow, yet its requests take longer all the same. Semaphores and locks
+/// [instrumented][LockMonitor::instrument_mutex] by a [`LockMonitor`] count their acquisitions,
+/// how many of them were contended, and the time spent waiting for contended acquisitions; these
+/// are reported as [`LockMetrics`], cumulatively or in sampling intervals, like the
+/// [`TaskMetrics`][crate::TaskMetrics] of a [`TaskMonitor`][crate::TaskMonitor]. The distribution
+/// of wait times is reported by [`LockMonitor::wait_buckets`].
+///
+/// An acquisition is contended if it cannot be satisfied immediately.
+///
+/// ##### Examples
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use tokio::sync::Mutex;
+/// use tokio_metrics::LockMonitor;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let monitor = LockMonitor::new();
+///     let counter = Arc::new(monitor.instrument_mutex(Mutex::new(0)));
+///
+///     let guard = counter.lock().await;
+///     let waiter = tokio::spawn({
+///         let counter = counter.clone();
+///         async move { *counter.lock().await += 1 }
+///     });
+///     tokio::time::sleep(Duration::from_millis(10)).await;
+///     drop(guard);
+///     waiter.await.unwrap();
+///
+///     let metrics = monitor.cumulative();
+///     assert_eq!(metrics.acquired_count, 2);
+///     assert_eq!(metrics.contended_count, 1);
+///     assert!(metrics.total_wait_duration >= Duration::from_millis(10));
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct LockMonitor {
+    metrics: Arc<RawLockMetrics>,
+}
+
+/// Key metrics of the semaphores and locks [instrumented][LockMonitor::instrument_mutex] by a
+/// [`LockMonitor`].
+///
+/// The acquisitions of [`RwLock`]s are counted both in the totals and, separately for reads and
+/// writes, in the `read_*` and `write_*` metrics.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LockMetrics {
+    /// The number of semaphores and locks instrumented.
+    pub instrumented_count: u64,
+
+    /// The total number of acquisitions of permits or locks.
+    ///
+    /// ##### Derived metrics
+    /// - **[`contended_ratio`][LockMetrics::contended_ratio]**   
+    ///   The ratio of acquisitions that were contended.
+    pub acquired_count: u64,
+
+    /// The total number of acquisitions that could not be satisfied immediately.
+    ///
+    /// ##### Derived metrics
+    /// - **[`contended_ratio`][LockMetrics::contended_ratio]**   
+    ///   The ratio of acquisitions that were contended.
+    /// - **[`mean_wait_duration`][LockMetrics::mean_wait_duration]**   
+    ///   The mean duration of contended acquisitions.
+    pub contended_count: u64,
+
+    /// The total duration spent waiting for contended acquisitions.
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_wait_duration`][LockMetrics::mean_wait_duration]**   
+    ///   The mean duration of contended acquisitions.
+    pub total_wait_duration: Duration,
+
+    /// The total number of read locks acquired on [`RwLock`]s.
+    pub read_acquired_count: u64,
+
+    /// The total number of read lock acquisitions that could not be satisfied immediately;
+    /// i.e., that waited for a writer.
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_read_wait_duration`][LockMetrics::mean_read_wait_duration]**   
+    ///   The mean duration of contended read lock acquisitions.
+    pub read_contended_count: u64,
+
+    /// The total duration spent waiting for contended read lock acquisitions.
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_read_wait_duration`][LockMetrics::mean_read_wait_duration]**   
+    ///   The mean duration of contended read lock acquisitions.
+    pub total_read_wait_duration: Duration,
+
+    /// The total number of write locks acquired on [`RwLock`]s.
+    pub write_acquired_count: u64,
+
+    /// The total number of write lock acquisitions that could not be satisfied immediately;
+    /// i.e., that waited for readers or another writer.
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_write_wait_duration`][LockMetrics::mean_write_wait_duration]**   
+    ///   The mean duration of contended write lock acquisitions.
+    pub write_contended_count: u64,
+
+    /// The total duration spent waiting for contended write lock acquisitions.
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_write_wait_duration`][LockMetrics::mean_write_wait_duration]**   
+    ///   The mean duration of contended write lock acquisitions.
+    pub total_write_wait_duration: Duration,
+}
+
+/// The acquisitions whose wait fell into a bucket, as reported by [`LockMonitor::wait_buckets`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WaitBucket {
+    /// The inclusive lower bound of the waits in this bucket.
+    pub lower_bound: Duration,
+
+    /// The exclusive upper bound of the waits in this bucket, or `None` if this is the last
+    /// bucket.
+    pub upper_bound: Option<Duration>,
+
+    /// The number of acquisitions in this bucket.
+    pub count: u64,
+
+    /// The total duration of the waits in this bucket.
+    pub total_duration: Duration,
+}
+
+/// A [`Semaphore`] instrumented with [`LockMonitor::instrument_semaphore`].
+#[derive(Debug)]
+pub struct InstrumentedSemaphore {
+    semaphore: Semaphore,
+    metrics: Arc<RawLockMetrics>,
+}
+
+/// A [`Mutex`] instrumented with [`LockMonitor::instrument_mutex`].
+#[derive(Debug)]
+pub struct InstrumentedMutex<T: ?Sized> {
+    metrics: Arc<RawLockMetrics>,
+    mutex: Mutex<T>,
+}
+
+/// An [`RwLock`] instrumented with [`LockMonitor::instrument_rwlock`].
+#[derive(Debug)]
+pub struct InstrumentedRwLock<T: ?Sized> {
+    metrics: Arc<RawLockMetrics>,
+    rwlock: RwLock<T>,
+}
+
+/// The kind of an acquisition.
+#[derive(Clone, Copy)]
+enum Acquisition {
+    /// Of a semaphore permit, or of a mutex.
+    Exclusive,
+
+    /// Of a read lock.
+    Read,
+
+    /// Of a write lock.
+    Write,
+}
+
+#[derive(Debug, Default)]
+struct RawLockMetrics {
+    /// The upper bounds of each wait bucket but the last, in ascending order.
+    wait_bucket_thresholds: Vec<Duration>,
+
+    /// The counters of each wait bucket; one more than the thresholds, or none if no thresholds
+    /// are configured.
+    wait_buckets: Vec<WaitBucketCounters>,
+
+    /// Total number of instrumented semaphores and locks.
+    instrumented_count: AtomicU64,
+
+    /// Total number of acquisitions.
+    acquired_count: AtomicU64,
+
+    /// Total number of contended acquisitions.
+    contended_count: AtomicU64,
+
+    /// Total amount of time spent waiting for contended acquisitions.
+    total_wait_duration_ns: AtomicU64,
+
+    /// Total number of read lock acquisitions.
+    read_acquired_count: AtomicU64,
+
+    /// Total number of contended read lock acquisitions.
+    read_contended_count: AtomicU64,
+
+    /// Total amount of time spent waiting for contended read lock acquisitions.
+    total_read_wait_duration_ns: AtomicU64,
+
+    /// Total number of write lock acquisitions.
+    write_acquired_count: AtomicU64,
+
+    /// Total number of contended write lock acquisitions.
+    write_contended_count: AtomicU64,
+
+    /// Total amount of time spent waiting for contended write lock acquisitions.
+    total_write_wait_duration_ns: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct WaitBucketCounters {
+    count: AtomicU64,
+    duration_ns: AtomicU64,
+}
+
+impl LockMonitor {
+    /// Constructs a new lock monitor.
+    pub fn new() -> LockMonitor {
+        LockMonitor::default()
+    }
+
+    /// Constructs a new lock monitor that also counts acquisitions into buckets by the duration
+    /// of their waits; see [`LockMonitor::wait_buckets`].
+    ///
+    /// Each threshold is the exclusive upper bound of a bucket, and the inclusive lower bound of
+    /// the next; uncontended acquisitions fall into the first bucket.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio::sync::Semaphore;
+    /// use tokio_metrics::LockMonitor;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = LockMonitor::with_wait_buckets([
+    ///         Duration::from_micros(100),
+    ///         Duration::from_millis(10),
+    ///     ]);
+    ///     let semaphore = monitor.instrument_semaphore(Semaphore::new(1));
+    ///
+    ///     drop(semaphore.acquire().await.unwrap());
+    ///
+    ///     let buckets = monitor.wait_buckets();
+    ///     assert_eq!(buckets.len(), 3);
+    ///     assert_eq!(buckets[0].count, 1);
+    /// }
+    /// ```
+    pub fn with_wait_buckets<I>(thresholds: I) -> LockMonitor
+    where
+        I: IntoIterator<Item = Duration>,
+    {
+        let mut thresholds: Vec<Duration> = thresholds.into_iter().collect();
+        thresholds.sort();
+        thresholds.dedup();
+        let wait_buckets = if thresholds.is_empty() {
+            Vec::new()
+        } else {
+            (0..=thresholds.len())
+                .map(|_| WaitBucketCounters::default())
+                .collect()
+        };
+        LockMonitor {
+            metrics: Arc::new(RawLockMetrics {
+                wait_bucket_thresholds: thresholds,
+                wait_buckets,
+                ..RawLockMetrics::default()
+            }),
+        }
+    }
+
+    /// Produces an instrumented façade around a given [`Semaphore`]. Its metrics are recorded by
+    /// this monitor.
+    pub fn instrument_semaphore(&self, semaphore: Semaphore) -> InstrumentedSemaphore {
+        self.metrics.instrumented_count.fetch_add(1, SeqCst);
+        InstrumentedSemaphore {
+            semaphore,
+            metrics: self.metrics.clone(),
+        }
+    }
+
+    /// Produces an instrumented façade around a given [`Mutex`]. Its metrics are recorded by this
+    /// monitor.
+    pub fn instrument_mutex<T>(&self, mutex: Mutex<T>) -> InstrumentedMutex<T> {
+        self.metrics.instrumented_count.fetch_add(1, SeqCst);
+        InstrumentedMutex {
+            metrics: self.metrics.clone(),
+            mutex,
+        }
+    }
+
+    /// Produces an instrumented façade around a given [`RwLock`]. Its metrics are recorded by
+    /// this monitor.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use tokio::sync::RwLock;
+    /// use tokio_metrics::LockMonitor;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = LockMonitor::new();
+    ///     let config = Arc::new(monitor.instrument_rwlock(RwLock::new(0)));
+    ///
+    ///     // readers do not contend with one another...
+    ///     let first = config.read().await;
+    ///     let second = config.read().await;
+    ///
+    ///     // ...but a writer contends with readers
+    ///     let writer = tokio::spawn({
+    ///         let config = config.clone();
+    ///         async move { *config.write().await += 1 }
+    ///     });
+    ///     tokio::time::sleep(Duration::from_millis(10)).await;
+    ///     drop((first, second));
+    ///     writer.await.unwrap();
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.read_acquired_count, 2);
+    ///     assert_eq!(metrics.read_contended_count, 0);
+    ///     assert_eq!(metrics.write_acquired_count, 1);
+    ///     assert_eq!(metrics.write_contended_count, 1);
+    ///     assert!(metrics.total_write_wait_duration >= Duration::from_millis(10));
+    /// }
+    /// ```
+    pub fn instrument_rwlock<T>(&self, rwlock: RwLock<T>) -> InstrumentedRwLock<T> {
+        self.metrics.instrumented_count.fetch_add(1, SeqCst);
+        InstrumentedRwLock {
+            metrics: self.metrics.clone(),
+            rwlock,
+        }
+    }
+
+    /// Produces [`LockMetrics`] for the semaphores and locks instrumented by this monitor,
+    /// collected since its construction.
+    pub fn cumulative(&self) -> LockMetrics {
+        self.metrics.metrics()
+    }
+
+    /// Produces an unending iterator of metric sampling intervals, like
+    /// [`TaskMonitor::intervals`][crate::TaskMonitor::intervals].
+    pub fn intervals(&self) -> impl Iterator<Item = LockMetrics> {
+        let latest = self.metrics.clone();
+        let mut previous: Option<LockMetrics> = None;
+
+        std::iter::from_fn(move || {
+            let latest: LockMetrics = latest.metrics();
+            let next = match previous {
+                Some(previous) => latest - previous,
+                None => latest,
+            };
+            previous = Some(latest);
+            Some(next)
+        })
+    }
+
+    /// Produces the [`WaitBucket`]s of this monitor, collected since its construction; or
+    /// nothing, unless it was constructed with [`LockMonitor::with_wait_buckets`].
+    pub fn wait_buckets(&self) -> Vec<WaitBucket> {
+        self.metrics.wait_buckets()
+    }
+
+    /// Produces an unending iterator of the [`WaitBucket`]s of sampling intervals, like
+    /// [`LockMonitor::intervals`].
+    pub fn wait_bucket_intervals(&self) -> impl Iterator<Item = Vec<WaitBucket>> {
+        let latest = self.metrics.clone();
+        let mut previous: Option<Vec<WaitBucket>> = None;
+
+        std::iter::from_fn(move || {
+            let latest = latest.wait_buckets();
+            let next = match &previous {
+                Some(previous) => latest
+                    .iter()
+                    .zip(previous)
+                    .map(|(latest, previous)| WaitBucket {
+                        count: latest.count.wrapping_sub(previous.count),
+                        total_duration: sub(latest.total_duration, previous.total_duration),
+                        ..*latest
+                    })
+                    .collect(),
+                None => latest.clone(),
+            };
+            previous = Some(latest);
+            Some(next)
+        })
+    }
+}
+
+impl RawLockMetrics {
+    fn metrics(&self) -> LockMetrics {
+        LockMetrics {
+            instrumented_count: self.instrumented_count.load(SeqCst),
+            acquired_count: self.acquired_count.load(SeqCst),
+            contended_count: self.contended_count.load(SeqCst),
+            total_wait_duration: Duration::from_nanos(self.total_wait_duration_ns.load(SeqCst)),
+            read_acquired_count: self.read_acquired_count.load(SeqCst),
+            read_contended_count: self.read_contended_count.load(SeqCst),
+            total_read_wait_duration: Duration::from_nanos(
+                self.total_read_wait_duration_ns.load(SeqCst),
+            ),
+            write_acquired_count: self.write_acquired_count.load(SeqCst),
+            write_contended_count: self.write_contended_count.load(SeqCst),
+            total_write_wait_duration: Duration::from_nanos(
+                self.total_write_wait_duration_ns.load(SeqCst),
+            ),
+        }
+    }
+
+    fn wait_buckets(&self) -> Vec<WaitBucket> {
+        let thresholds = &self.wait_bucket_thresholds;
+        self.wait_buckets
+            .iter()
+            .enumerate()
+            .map(|(i, bucket)| WaitBucket {
+                lower_bound: i.checked_sub(1).map_or(Duration::ZERO, |i| thresholds[i]),
+                upper_bound: thresholds.get(i).copied(),
+                count: bucket.count.load(SeqCst),
+                total_duration: Duration::from_nanos(bucket.duration_ns.load(SeqCst)),
+            })
+            .collect()
+    }
+
+    /// Records an acquisition, which waited since `started_at` if it was contended.
+    fn record_acquisition(&self, acquisition: Acquisition, started_at: Option<Instant>) {
+        let (acquired_count, contended_count, total_wait_duration_ns) = match acquisition {
+            Acquisition::Exclusive => (None, None, None),
+            Acquisition::Read => (
+                Some(&self.read_acquired_count),
+                Some(&self.read_contended_count),
+                Some(&self.total_read_wait_duration_ns),
+            ),
+            Acquisition::Write => (
+                Some(&self.write_acquired_count),
+                Some(&self.write_contended_count),
+                Some(&self.total_write_wait_duration_ns),
+            ),
+        };
+        let inc = |counter: Option<&AtomicU64>, n: u64| {
+            if let Some(counter) = counter {
+                counter.fetch_add(n, SeqCst);
+            }
+        };
+
+        self.acquired_count.fetch_add(1, SeqCst);
+        inc(acquired_count, 1);
+        let wait = match started_at {
+            Some(started_at) => {
+                let wait = started_at.elapsed();
+                self.contended_count.fetch_add(1, SeqCst);
+                self.total_wait_duration_ns.fetch_add(nanos(wait), SeqCst);
+                inc(contended_count, 1);
+                inc(total_wait_duration_ns, nanos(wait));
+                wait
+            }
+            None => Duration::ZERO,
+        };
+        if !self.wait_buckets.is_empty() {
+            let i = self
+                .wait_bucket_thresholds
+                .partition_point(|&threshold| threshold <= wait);
+            let bucket = &self.wait_buckets[i];
+            bucket.count.fetch_add(1, SeqCst);
+            bucket.duration_ns.fetch_add(nanos(wait), SeqCst);
+        }
+    }
+}
+
+impl LockMetrics {
+    /// The ratio of acquisitions that were contended.
+    ///
+    /// ##### Definition
+    /// This metric is derived from [`contended_count`][LockMetrics::contended_count] ÷
+    /// [`acquired_count`][LockMetrics::acquired_count].
+    pub fn contended_ratio(&self) -> f64 {
+        self.contended_count as f64 / self.acquired_count as f64
+    }
+
+    /// The mean duration of contended acquisitions.
+    ///
+    /// ##### Definition
+    /// This metric is derived from [`total_wait_duration`][LockMetrics::total_wait_duration] ÷
+    /// [`contended_count`][LockMetrics::contended_count].
+    pub fn mean_wait_duration(&self) -> Duration {
+        match nanos(self.total_wait_duration).checked_div(self.contended_count) {
+            Some(mean) => Duration::from_nanos(mean),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// The mean duration of contended read lock acquisitions.
+    ///
+    /// ##### Definition
+    /// This metric is derived from
+    /// [`total_read_wait_duration`][LockMetrics::total_read_wait_duration] ÷
+    /// [`read_contended_count`][LockMetrics::read_contended_count].
+    pub fn mean_read_wait_duration(&self) -> Duration {
+        match nanos(self.total_read_wait_duration).checked_div(self.read_contended_count) {
+            Some(mean) => Duration::from_nanos(mean),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// The mean duration of contended write lock acquisitions.
+    ///
+    /// ##### Definition
+    /// This metric is derived from
+    /// [`total_write_wait_duration`][LockMetrics::total_write_wait_duration] ÷
+    /// [`write_contended_count`][LockMetrics::write_contended_count].
+    pub fn mean_write_wait_duration(&self) -> Duration {
+        match nanos(self.total_write_wait_duration).checked_div(self.write_contended_count) {
+            Some(mean) => Duration::from_nanos(mean),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Records each of these metrics into a given [`MetricsSink`], qualified by `labels`.
+    ///
+    /// Each metric is recorded under the name of its field, prefixed with `lock_`; e.g.,
+    /// `lock_contended_count`. Derived metrics are not recorded.
+    pub fn record_to(&self, sink: &dyn MetricsSink, labels: &[(&str, &str)]) {
+        use MetricValue::{Count, Duration};
+
+        sink.record(
+            "lock_instrumented_count",
+            Count(self.instrumented_count),
+            labels,
+        );
+        sink.record("lock_acquired_count", Count(self.acquired_count), labels);
+        sink.record("lock_contended_count", Count(self.contended_count), labels);
+        sink.record(
+            "lock_total_wait_duration",
+            Duration(self.total_wait_duration),
+            labels,
+        );
+        sink.record(
+            "lock_read_acquired_count",
+            Count(self.read_acquired_count),
+            labels,
+        );
+        sink.record(
+            "lock_read_contended_count",
+            Count(self.read_contended_count),
+            labels,
+        );
+        sink.record(
+            "lock_total_read_wait_duration",
+            Duration(self.total_read_wait_duration),
+            labels,
+        );
+        sink.record(
+            "lock_write_acquired_count",
+            Count(self.write_acquired_count),
+            labels,
+        );
+        sink.record(
+            "lock_write_contended_count",
+            Count(self.write_contended_count),
+            labels,
+        );
+        sink.record(
+            "lock_total_write_wait_duration",
+            Duration(self.total_write_wait_duration),
+            labels,
+        );
+    }
+}
+
+impl std::ops::Sub for LockMetrics {
+    type Output = LockMetrics;
+
+    fn sub(self, rhs: LockMetrics) -> LockMetrics {
+        LockMetrics {
+            instrumented_count: self.instrumented_count.wrapping_sub(rhs.instrumented_count),
+            acquired_count: self.acquired_count.wrapping_sub(rhs.acquired_count),
+            contended_count: self.contended_count.wrapping_sub(rhs.contended_count),
+            total_wait_duration: sub(self.total_wait_duration, rhs.total_wait_duration),
+            read_acquired_count: self
+                .read_acquired_count
+                .wrapping_sub(rhs.read_acquired_count),
+            read_contended_count: self
+                .read_contended_count
+                .wrapping_sub(rhs.read_contended_count),
+            total_read_wait_duration: sub(
+                self.total_read_wait_duration,
+                rhs.total_read_wait_duration,
+            ),
+            write_acquired_count: self
+                .write_acquired_count
+                .wrapping_sub(rhs.write_acquired_count),
+            write_contended_count: self
+                .write_contended_count
+                .wrapping_sub(rhs.write_contended_count),
+            total_write_wait_duration: sub(
+                self.total_write_wait_duration,
+                rhs.total_write_wait_duration,
+            ),
+        }
+    }
+}
+
+impl InstrumentedSemaphore {
+    /// Acquires a permit, like [`Semaphore::acquire`], recording whether the acquisition was
+    /// contended and, if so, the time spent waiting for it.
+    pub async fn acquire(&self) -> Result<SemaphorePermit<'_>, AcquireError> {
+        self.acquire_many(1).await
+    }
+
+    /// Acquires `n` permits, like [`Semaphore::acquire_many`], recording whether the acquisition
+    /// was contended and, if so, the time spent waiting for it.
+    pub async fn acquire_many(&self, n: u32) -> Result<SemaphorePermit<'_>, AcquireError> {
+        if let Ok(permit) = self.semaphore.try_acquire_many(n) {
+            self.metrics
+                .record_acquisition(Acquisition::Exclusive, None);
+            return Ok(permit);
+        }
+        let started_at = Instant::now();
+        let permit = self.semaphore.acquire_many(n).await?;
+        self.metrics
+            .record_acquisition(Acquisition::Exclusive, Some(started_at));
+        Ok(permit)
+    }
+
+    /// Produces the number of permits currently available.
+    pub fn available_permits(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+
+    /// Adds `n` permits to the semaphore.
+    pub fn add_permits(&self, n: usize) {
+        self.semaphore.add_permits(n)
+    }
+
+    /// Produces a reference to the wrapped semaphore. Permits acquired from it directly are not
+    /// recorded.
+    pub fn get_ref(&self) -> &Semaphore {
+        &self.semaphore
+    }
+
+    /// Consumes this façade, producing the wrapped semaphore.
+    pub fn into_inner(self) -> Semaphore {
+        self.semaphore
+    }
+}
+
+impl<T: ?Sized> InstrumentedMutex<T> {
+    /// Locks the mutex, like [`Mutex::lock`], recording whether the acquisition was contended
+    /// and, if so, the time spent waiting for it.
+    pub async fn lock(&self) -> MutexGuard<'_, T> {
+        if let Ok(guard) = self.mutex.try_lock() {
+            self.metrics
+                .record_acquisition(Acquisition::Exclusive, None);
+            return guard;
+        }
+        let started_at = Instant::now();
+        let guard = self.mutex.lock().await;
+        self.metrics
+            .record_acquisition(Acquisition::Exclusive, Some(started_at));
+        guard
+    }
+
+    /// Attempts to lock the mutex without waiting, like [`Mutex::try_lock`]. Only successful
+    /// attempts are recorded.
+    pub fn try_lock(&self) -> Result<MutexGuard<'_, T>, TryLockError> {
+        let guard = self.mutex.try_lock()?;
+        self.metrics
+            .record_acquisition(Acquisition::Exclusive, None);
+        Ok(guard)
+    }
+
+    /// Produces a mutable reference to the protected data. No locking is needed, since this
+    /// façade is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.mutex.get_mut()
+    }
+
+    /// Produces a reference to the wrapped mutex. Locks acquired from it directly are not
+    /// recorded.
+    pub fn get_ref(&self) -> &Mutex<T> {
+        &self.mutex
+    }
+}
+
+impl<T> InstrumentedMutex<T> {
+    /// Consumes this façade, producing the wrapped mutex.
+    pub fn into_inner(self) -> Mutex<T> {
+        self.mutex
+    }
+}
+
+impl<T: ?Sized> InstrumentedRwLock<T> {
+    /// Locks the lock with shared read access, like [`RwLock::read`], recording whether the
+    /// acquisition was contended and, if so, the time spent waiting for it.
+    pub async fn read(&self) -> RwLockReadGuard<'_, T> {
+        if let Ok(guard) = self.rwlock.try_read() {
+            self.metrics.record_acquisition(Acquisition::Read, None);
+            return guard;
+        }
+        let started_at = Instant::now();
+        let guard = self.rwlock.read().await;
+        self.metrics
+            .record_acquisition(Acquisition::Read, Some(started_at));
+        guard
+    }
+
+    /// Locks the lock with exclusive write access, like [`RwLock::write`], recording whether the
+    /// acquisition was contended and, if so, the time spent waiting for it.
+    pub async fn write(&self) -> RwLockWriteGuard<'_, T> {
+        if let Ok(guard) = self.rwlock.try_write() {
+            self.metrics.record_acquisition(Acquisition::Write, None);
+            return guard;
+        }
+        let started_at = Instant::now();
+        let guard = self.rwlock.write().await;
+        self.metrics
+            .record_acquisition(Acquisition::Write, Some(started_at));
+        guard
+    }
+
+    /// Attempts to lock the lock with shared read access without waiting, like
+    /// [`RwLock::try_read`]. Only successful attempts are recorded.
+    pub fn try_read(&self) -> Result<RwLockReadGuard<'_, T>, TryLockError> {
+        let guard = self.rwlock.try_read()?;
+        self.metrics.record_acquisition(Acquisition::Read, None);
+        Ok(guard)
+    }
+
+    /// Attempts to lock the lock with exclusive write access without waiting, like
+    /// [`RwLock::try_write`]. Only successful attempts are recorded.
+    pub fn try_write(&self) -> Result<RwLockWriteGuard<'_, T>, TryLockError> {
+        let guard = self.rwlock.try_write()?;
+        self.metrics.record_acquisition(Acquisition::Write, None);
+        Ok(guard)
+    }
+
+    /// Produces a mutable reference to the protected data. No locking is needed, since this
+    /// façade is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.rwlock.get_mut()
+    }
+
+    /// Produces a reference to the wrapped lock. Locks acquired from it directly are not
+    /// recorded.
+    pub fn get_ref(&self) -> &RwLock<T> {
+        &self.rwlock
+    }
+}
+
+impl<T> InstrumentedRwLock<T> {
+    /// Consumes this façade, producing the wrapped lock.
+    pub fn into_inner(self) -> RwLock<T> {
+        self.rwlock
+    }
+}
+
+#[inline(always)]
+fn nanos(d: Duration) -> u64 {
+    d.as_nanos().try_into().unwrap_or(u64::MAX)
+}
+
+#[inline(always)]
+fn sub(a: Duration, b: Duration) -> Duration {
+    Duration::from_nanos(nanos(a).wrapping_sub(nanos(b)))
+}
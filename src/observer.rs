@@ -0,0 +1,101 @@
+use crate::TaskId;
+
+#[cfg(feature = "rt")]
+use tokio::time::Duration;
+
+#[cfg(not(feature = "rt"))]
+use std::time::Duration;
+
+/// Receives the raw events underlying [`TaskMetrics`][crate::TaskMetrics].
+///
+/// A [`TaskObserver`] [attached][crate::TaskMonitorBuilder::with_observer] to a
+/// [`TaskMonitor`][crate::TaskMonitor] is notified of each event in the lifecycle of the tasks
+/// that monitor instruments, as it occurs. Observers can build custom aggregations (e.g.,
+/// per-tenant accounting) on top of the same instrumentation that powers
+/// [`TaskMetrics`][crate::TaskMetrics].
+///
+/// Every method has a default, no-op implementation. Methods are invoked synchronously, on the
+/// thread on which the event occurred — usually a runtime worker thread — and so they should
+/// return promptly.
+///
+/// ##### Examples
+/// ```
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use tokio_metrics::{TaskId, TaskMonitor, TaskObserver};
+///
+/// /// Tracks the duration of the longest poll.
+/// #[derive(Default)]
+/// struct LongestPoll(AtomicU64);
+///
+/// impl TaskObserver for LongestPoll {
+///     fn on_poll(&self, _task: TaskId, duration: Duration, _ready: bool) {
+///         self.0.fetch_max(duration.as_nanos() as u64, Ordering::Relaxed);
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let longest_poll = Arc::new(LongestPoll::default());
+///     let monitor = TaskMonitor::builder()
+///         .with_observer(longest_poll.clone())
+///         .build();
+///
+///     monitor.instrument(async {
+///         std::thread::sleep(Duration::from_millis(10));
+///     }).await;
+///
+///     assert!(longest_poll.0.load(Ordering::Relaxed) >= 10_000_000);
+/// }
+/// ```
+pub trait TaskObserver: Send + Sync {
+    /// Invoked when a task is instrumented.
+    fn on_instrument(&self, task: TaskId) {
+        let _ = task;
+    }
+
+    /// Invoked when a task is polled for the first time, with the duration elapsed since it was
+    /// instrumented.
+    fn on_first_poll(&self, task: TaskId, first_poll_delay: Duration) {
+        let _ = (task, first_poll_delay);
+    }
+
+    /// Invoked when a poll of a task completes, with the duration of the poll and whether the
+    /// task completed.
+    fn on_poll(&self, task: TaskId, duration: Duration, ready: bool) {
+        let _ = (task, duration, ready);
+    }
+
+    /// Invoked when a task is awoken.
+    fn on_wake(&self, task: TaskId) {
+        let _ = task;
+    }
+
+    /// Invoked when a task is dropped.
+    fn on_drop(&self, task: TaskId) {
+        let _ = task;
+    }
+}
+
+impl<T: TaskObserver + ?Sized> TaskObserver for std::sync::Arc<T> {
+    fn on_instrument(&self, task: TaskId) {
+        (**self).on_instrument(task)
+    }
+
+    fn on_first_poll(&self, task: TaskId, first_poll_delay: Duration) {
+        (**self).on_first_poll(task, first_poll_delay)
+    }
+
+    fn on_poll(&self, task: TaskId, duration: Duration, ready: bool) {
+        (**self).on_poll(task, duration, ready)
+    }
+
+    fn on_wake(&self, task: TaskId) {
+        (**self).on_wake(task)
+    }
+
+    fn on_drop(&self, task: TaskId) {
+        (**self).on_drop(task)
+    }
+}
@@ -0,0 +1,99 @@
+use crate::TaskId;
+use std::sync::atomic::{AtomicU64, Ordering::SeqCst};
+
+#[cfg(feature = "rt")]
+use tokio::time::{Duration, Instant};
+
+#[cfg(not(feature = "rt"))]
+use std::time::{Duration, Instant};
+
+/// The duration of each window within which the number of logged outliers is limited.
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Logs, via `tracing`, polls and schedule delays that exceed a high watermark, at most a given
+/// number of times per minute.
+pub(crate) struct OutlierLogger {
+    /// Events at least this long are logged.
+    high_watermark: Duration,
+
+    /// The maximum number of events logged per window.
+    max_per_window: u64,
+
+    /// The instant from which windows are measured.
+    created_at: Instant,
+
+    /// The index of the current window.
+    window: AtomicU64,
+
+    /// The number of events observed within the current window, logged or not.
+    observed: AtomicU64,
+}
+
+impl OutlierLogger {
+    pub(crate) fn new(high_watermark: Duration, max_per_minute: u32) -> OutlierLogger {
+        OutlierLogger {
+            high_watermark,
+            max_per_window: max_per_minute.into(),
+            created_at: Instant::now(),
+            window: AtomicU64::new(0),
+            observed: AtomicU64::new(0),
+        }
+    }
+
+    /// Logs a poll of the task `id` that completed at `now`, if it is an outlier.
+    pub(crate) fn poll(
+        &self,
+        id: TaskId,
+        name: Option<&'static str>,
+        duration: Duration,
+        now: Instant,
+    ) {
+        if duration >= self.high_watermark && self.permit(now) {
+            tracing::warn!(
+                target: "tokio_metrics::outliers",
+                task_id = id.as_u64(),
+                task_name = name.unwrap_or_default(),
+                ?duration,
+                "task poll took {:?}",
+                duration,
+            );
+        }
+    }
+
+    /// Logs the schedule delay of the task `id`, polled at `now`, if it is an outlier.
+    pub(crate) fn scheduled(
+        &self,
+        id: TaskId,
+        name: Option<&'static str>,
+        duration: Duration,
+        now: Instant,
+    ) {
+        if duration >= self.high_watermark && self.permit(now) {
+            tracing::warn!(
+                target: "tokio_metrics::outliers",
+                task_id = id.as_u64(),
+                task_name = name.unwrap_or_default(),
+                ?duration,
+                "task waited {:?} to be polled after being woken",
+                duration,
+            );
+        }
+    }
+
+    /// Determines whether an outlier observed at `now` may be logged without exceeding the limit
+    /// of its window.
+    fn permit(&self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.created_at);
+        let window = elapsed.as_secs() / WINDOW.as_secs();
+        let current = self.window.load(SeqCst);
+        if window > current
+            && self
+                .window
+                .compare_exchange(current, window, SeqCst, SeqCst)
+                .is_ok()
+        {
+            self.observed.store(0, SeqCst);
+        }
+        self.observed.fetch_add(1, SeqCst) < self.max_per_window
+    }
+}
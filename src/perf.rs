@@ -0,0 +1,153 @@
+/// Hardware performance counters sampled around a poll, as reported by [`SlowPoll`][crate::SlowPoll].
+///
+/// **This functionality requires the crate feature `perf`, and Linux.**
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PerfCounters {
+    /// The number of CPU cycles consumed by the poll.
+    pub cycles: u64,
+
+    /// The number of instructions retired by the poll.
+    pub instructions: u64,
+
+    /// The number of cache misses incurred by the poll.
+    pub cache_misses: u64,
+}
+
+impl std::ops::Sub for PerfCounters {
+    type Output = PerfCounters;
+
+    fn sub(self, rhs: PerfCounters) -> PerfCounters {
+        PerfCounters {
+            cycles: self.cycles.wrapping_sub(rhs.cycles),
+            instructions: self.instructions.wrapping_sub(rhs.instructions),
+            cache_misses: self.cache_misses.wrapping_sub(rhs.cache_misses),
+        }
+    }
+}
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+pub(crate) use sys::sample;
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+mod sys {
+    use super::PerfCounters;
+    use std::cell::RefCell;
+    use std::os::raw::{c_int, c_long};
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+    const PERF_FORMAT_GROUP: u64 = 1 << 3;
+    const PERF_FLAG_FD_CLOEXEC: c_long = 1 << 3;
+    const EXCLUDE_KERNEL: u64 = 1 << 5;
+    const EXCLUDE_HV: u64 = 1 << 6;
+
+    /// The first published revision of `struct perf_event_attr`, which every kernel supporting perf
+    /// events accepts.
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        type_: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    /// A group of counters of the current thread, read together.
+    struct PerfGroup {
+        /// The file descriptors of the counters; the first is the group leader.
+        fds: Vec<c_int>,
+    }
+
+    thread_local! {
+        /// The counters of the current thread; `None` if they could not be opened.
+        static GROUP: RefCell<Option<Option<PerfGroup>>> = RefCell::new(None);
+    }
+
+    /// Reads the counters of the current thread, opening them if necessary. Produces `None` if perf
+    /// events are unavailable; e.g., if forbidden by `kernel.perf_event_paranoid`.
+    pub(crate) fn sample() -> Option<PerfCounters> {
+        GROUP
+            .try_with(|group| {
+                group
+                    .borrow_mut()
+                    .get_or_insert_with(PerfGroup::open)
+                    .as_ref()
+                    .and_then(PerfGroup::read)
+            })
+            .ok()
+            .flatten()
+    }
+
+    impl PerfGroup {
+        fn open() -> Option<PerfGroup> {
+            let mut group = PerfGroup { fds: Vec::new() };
+            for config in [
+                PERF_COUNT_HW_CPU_CYCLES,
+                PERF_COUNT_HW_INSTRUCTIONS,
+                PERF_COUNT_HW_CACHE_MISSES,
+            ] {
+                let attr = PerfEventAttr {
+                    type_: PERF_TYPE_HARDWARE,
+                    size: std::mem::size_of::<PerfEventAttr>() as u32,
+                    config,
+                    read_format: PERF_FORMAT_GROUP,
+                    flags: EXCLUDE_KERNEL | EXCLUDE_HV,
+                    ..PerfEventAttr::default()
+                };
+                let leader = group.fds.first().copied().unwrap_or(-1);
+                // SAFETY: `attr` is a valid `perf_event_attr` of the size it declares; the call
+                // measures the current thread (pid 0) on any CPU (-1).
+                let fd = unsafe {
+                    libc::syscall(
+                        libc::SYS_perf_event_open,
+                        &attr as *const PerfEventAttr,
+                        0 as libc::pid_t,
+                        -1 as c_int,
+                        leader,
+                        PERF_FLAG_FD_CLOEXEC,
+                    )
+                };
+                if fd < 0 {
+                    // dropping the group closes the counters opened so far
+                    return None;
+                }
+                group.fds.push(fd as c_int);
+            }
+            Some(group)
+        }
+
+        fn read(&self) -> Option<PerfCounters> {
+            // the number of counters, followed by their values
+            let mut values = [0u64; 4];
+            let len = std::mem::size_of_val(&values);
+            // SAFETY: `values` is a valid, writable buffer of `len` bytes.
+            let read = unsafe { libc::read(self.fds[0], values.as_mut_ptr().cast(), len) };
+            if read != len as isize || values[0] != 3 {
+                return None;
+            }
+            Some(PerfCounters {
+                cycles: values[1],
+                instructions: values[2],
+                cache_misses: values[3],
+            })
+        }
+    }
+
+    impl Drop for PerfGroup {
+        fn drop(&mut self) {
+            for &fd in &self.fds {
+                // SAFETY: `fd` was opened by this group, and is closed exactly once.
+                unsafe { libc::close(fd) };
+            }
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use crate::reporter::{Export, Round};
+use crate::{IntervalSample, MetricValue, Reporter, ReporterTask};
+use serde_json::{json, Map};
+use std::cell::RefCell;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(unix)]
+use std::{io::Write, os::unix::net::UnixStream, path::PathBuf, time::Duration};
+
+/// The formats in which [`Reporter::spawn_publisher`] serializes interval snapshots.
+///
+/// A snapshot carries the name of a monitor, the wall-clock bounds of a sampling interval, and
+/// the [`TaskMetrics`][crate::TaskMetrics] of that interval.
+///
+/// **This functionality requires the crate feature `publish`.**
+///
+/// ##### Examples
+/// ```
+/// use tokio_metrics::{SnapshotFormat, TaskMetrics, TaskMonitor};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let monitor = TaskMonitor::new();
+///     let mut samples = monitor.sampled_intervals();
+///     monitor.instrument(async {}).await;
+///     let sample = samples.next().unwrap();
+///
+///     let json = SnapshotFormat::Json.encode("requests", &sample);
+///     let json = String::from_utf8(json).unwrap();
+///     assert!(json.starts_with(r#"{"ended_at_ms":"#));
+///     assert!(json.contains(r#""monitor":"requests""#));
+///     assert!(json.contains(r#""total_poll_count":1"#));
+///
+///     let binary = SnapshotFormat::Binary.encode("requests", &sample);
+///     let header = 1 + 2 + "requests".len() + 8 + 8 + 2;
+///     assert_eq!(binary.len(), header + 9 * TaskMetrics::descriptors().len());
+///     assert!(binary.len() < json.len());
+/// }
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    /// A JSON object, with the fields `monitor`, `started_at_ms` and `ended_at_ms` (in
+    /// milliseconds since the Unix epoch), and `metrics`, which maps the name of each metric to
+    /// its value; durations are in seconds.
+    Json,
+
+    /// A compact, big-endian binary encoding:
+    ///
+    /// - the version of the encoding, `1`, as a byte;
+    /// - the length of the name of the monitor, as a `u16`, followed by the name in UTF-8;
+    /// - `started_at_ms` and `ended_at_ms`, as `u64`s;
+    /// - the number of metrics, as a `u16`, followed by each metric, in the order of
+    ///   [`TaskMetrics::descriptors`][crate::TaskMetrics::descriptors], as a tag byte and a
+    ///   value: `0` for a count and `1` for a gauge, as a `u64`; `2` for a duration, as a `u64`
+    ///   of nanoseconds; `3` for a ratio, as an `f64`; and `4` for a maximum duration, as a `u64`
+    ///   of nanoseconds.
+    ///
+    /// The names of the metrics are omitted; consumers pair values with the descriptors of the
+    /// version of this crate that produced them.
+    Binary,
+}
+
+/// Publishes serialized interval snapshots; e.g., to a Kafka topic.
+///
+/// Implemented for closures of the same signature, which may, e.g., hand each payload to the
+/// producer of whatever Kafka client the application already uses.
+///
+/// **This functionality requires the crate feature `publish`.**
+pub trait SnapshotPublisher {
+    /// Publishes the serialized snapshot `payload`, keyed by the name of its monitor, so that
+    /// the snapshots of a monitor are ordered within a partition.
+    fn publish(&mut self, key: &str, payload: Vec<u8>);
+}
+
+impl<F> SnapshotPublisher for F
+where
+    F: FnMut(&str, Vec<u8>),
+{
+    fn publish(&mut self, key: &str, payload: Vec<u8>) {
+        self(key, payload)
+    }
+}
+
+/// The exporter of [`Reporter::spawn_publisher`].
+struct Publisher<P> {
+    format: SnapshotFormat,
+    publisher: P,
+}
+
+/// A [`SnapshotPublisher`] that streams snapshots over a Unix domain socket; e.g., to a local
+/// sidecar or agent, without HTTP or a metrics library in the main process.
+///
+/// Each snapshot is framed by its length, in bytes, as a big-endian `u32`. The publisher
+/// connects to the listener at its path on the first publication, and reconnects on the next
+/// publication after the connection fails; snapshots published while the listener is
+/// unavailable are dropped. The socket's I/O blocks: [`Reporter::spawn_publisher`] publishes on
+/// the runtime's blocking threads, off of its workers, and writes time out after a second, so a
+/// stalled consumer does not hold up the reporter indefinitely.
+///
+/// **This functionality requires the crate feature `publish`, and a Unix platform.**
+///
+/// ##### Examples
+/// ```
+/// use std::io::Read;
+/// use std::os::unix::net::UnixListener;
+/// use std::time::Duration;
+/// use tokio_metrics::{Reporter, SnapshotFormat, UnixSocketPublisher};
+///
+/// #[tokio::main]
+/// async fn main() -> std::io::Result<()> {
+///     // a stand-in for the sidecar
+///     let path = std::env::temp_dir().join(format!("tokio-metrics-{}.sock", std::process::id()));
+///     let listener = UnixListener::bind(&path)?;
+///
+///     let mut reporter = Reporter::new().with_period(Duration::from_millis(10));
+///     let monitor = reporter.monitor("requests");
+///     let publisher = UnixSocketPublisher::new(&path);
+///     let handle = reporter.spawn_publisher(SnapshotFormat::Binary, publisher);
+///     monitor.instrument(async {}).await;
+///
+///     let (mut stream, _) = listener.accept()?;
+///     let mut len = [0; 4];
+///     stream.read_exact(&mut len)?;
+///     let mut snapshot = vec![0; u32::from_be_bytes(len) as usize];
+///     stream.read_exact(&mut snapshot)?;
+///     assert_eq!(&snapshot[3..11], b"requests");
+///
+///     handle.abort();
+///     std::fs::remove_file(&path)
+/// }
+/// ```
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct UnixSocketPublisher {
+    path: PathBuf,
+    stream: Option<UnixStream>,
+}
+
+#[cfg(unix)]
+impl UnixSocketPublisher {
+    /// How long to wait for the consumer to accept a snapshot.
+    const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Constructs a publisher to the listener at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> UnixSocketPublisher {
+        UnixSocketPublisher {
+            path: path.into(),
+            stream: None,
+        }
+    }
+
+    fn write_frame(&mut self, payload: &[u8]) -> io::Result<()> {
+        let stream = match &mut self.stream {
+            Some(stream) => stream,
+            None => {
+                let stream = UnixStream::connect(&self.path)?;
+                stream.set_write_timeout(Some(Self::WRITE_TIMEOUT))?;
+                self.stream.insert(stream)
+            }
+        };
+        let len = u32::try_from(payload.len())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        stream.write_all(&len.to_be_bytes())?;
+        stream.write_all(payload)
+    }
+}
+
+#[cfg(unix)]
+impl SnapshotPublisher for UnixSocketPublisher {
+    fn publish(&mut self, _key: &str, payload: Vec<u8>) {
+        if self.write_frame(&payload).is_err() {
+            // a partially-written frame corrupts the stream; reconnect on the next publication
+            self.stream = None;
+        }
+    }
+}
+
+impl SnapshotFormat {
+    /// Serializes `sample`, the sampling interval of the monitor named `monitor`.
+    pub fn encode(&self, monitor: &str, sample: &IntervalSample) -> Vec<u8> {
+        let values = RefCell::new(Vec::new());
+        sample.metrics.record_to(
+            &|name: &'static str, value: MetricValue, _: &[(&str, &str)]| {
+                values.borrow_mut().push((name, value))
+            },
+            &[],
+        );
+        let values = values.into_inner();
+        let started_at_ms = millis_since_epoch(sample.started_at);
+        let ended_at_ms = millis_since_epoch(sample.ended_at);
+
+        match self {
+            SnapshotFormat::Json => {
+                let metrics: Map<_, _> = values
+                    .into_iter()
+                    .map(|(name, value)| {
+                        let value = match value {
+                            MetricValue::Count(count) | MetricValue::Gauge(count) => json!(count),
+                            MetricValue::Duration(duration)
+                            | MetricValue::MaxDuration(duration) => json!(duration.as_secs_f64()),
+                            MetricValue::Ratio(ratio) => json!(ratio),
+                        };
+                        (name.to_owned(), value)
+                    })
+                    .collect();
+                json!({
+                    "monitor": monitor,
+                    "started_at_ms": started_at_ms,
+                    "ended_at_ms": ended_at_ms,
+                    "metrics": metrics,
+                })
+                .to_string()
+                .into_bytes()
+            }
+            SnapshotFormat::Binary => {
+                let monitor = &monitor.as_bytes()[..monitor.len().min(u16::MAX as usize)];
+                let mut payload = Vec::with_capacity(21 + monitor.len() + 9 * values.len());
+                payload.push(1);
+                payload.extend_from_slice(&(monitor.len() as u16).to_be_bytes());
+                payload.extend_from_slice(monitor);
+                payload.extend_from_slice(&started_at_ms.to_be_bytes());
+                payload.extend_from_slice(&ended_at_ms.to_be_bytes());
+                payload.extend_from_slice(&(values.len() as u16).to_be_bytes());
+                for (_, value) in values {
+                    let (tag, bits) = match value {
+                        MetricValue::Count(count) => (0u8, count),
+                        MetricValue::Gauge(gauge) => (1, gauge),
+                        MetricValue::Duration(duration) => {
+                            (2, duration.as_nanos().try_into().unwrap_or(u64::MAX))
+                        }
+                        MetricValue::Ratio(ratio) => (3, ratio.to_bits()),
+                        MetricValue::MaxDuration(duration) => {
+                            (4, duration.as_nanos().try_into().unwrap_or(u64::MAX))
+                        }
+                    };
+                    payload.push(tag);
+                    payload.extend_from_slice(&bits.to_be_bytes());
+                }
+                payload
+            }
+        }
+    }
+}
+
+impl Reporter {
+    /// Spawns a task onto the current tokio runtime that serializes a snapshot of each sampling
+    /// interval of the monitors registered with this reporter in `format`, and hands it to
+    /// `publisher`, once per period, until it is [shut down][ReporterTask::shutdown] or aborted.
+    /// Monitors registered after this call are not published.
+    ///
+    /// The publisher is invoked on the runtime's blocking threads, so it may block; e.g., on a
+    /// full producer queue.
+    ///
+    /// **This functionality requires the crate feature `publish`.**
+    ///
+    /// ##### Panics
+    /// This method panics if called outside of a tokio runtime.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::sync::mpsc;
+    /// use std::time::Duration;
+    /// use tokio_metrics::{Reporter, SnapshotFormat};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut reporter = Reporter::new().with_period(Duration::from_millis(10));
+    ///     let monitor = reporter.monitor("requests");
+    ///
+    ///     // e.g., a channel to a thread that produces to the `task-metrics` topic
+    ///     let (producer, topic) = mpsc::channel();
+    ///     let handle = reporter.spawn_publisher(SnapshotFormat::Json, move |key: &str, payload| {
+    ///         let _ = producer.send((key.to_owned(), payload));
+    ///     });
+    ///
+    ///     monitor.instrument(async {}).await;
+    ///     tokio::time::sleep(Duration::from_millis(50)).await;
+    ///     handle.shutdown().await;
+    ///
+    ///     let (key, payload) = topic.recv().unwrap();
+    ///     assert_eq!(key, "requests");
+    ///     assert!(String::from_utf8(payload).unwrap().contains("total_poll_count"));
+    /// }
+    /// ```
+    pub fn spawn_publisher<P>(&self, format: SnapshotFormat, publisher: P) -> ReporterTask
+    where
+        P: SnapshotPublisher + Send + 'static,
+    {
+        self.spawn_exporter(Publisher { format, publisher })
+    }
+}
+
+impl<P> Export for Publisher<P>
+where
+    P: SnapshotPublisher + Send + 'static,
+{
+    fn export(&mut self, round: &Round<'_>) -> io::Result<()> {
+        for (name, _, sample) in &round.monitors {
+            self.publisher
+                .publish(name, self.format.encode(name, sample));
+        }
+        Ok(())
+    }
+}
+
+/// Produces the milliseconds between the Unix epoch and `time`.
+fn millis_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
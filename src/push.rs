@@ -0,0 +1,245 @@
+use crate::reporter::{Export, Round};
+use crate::server::{encode, Format, READ_TIMEOUT};
+use crate::{Reporter, ReporterTask, TaskMonitor};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+
+/// A Prometheus Pushgateway, to which the cumulative metrics of a reporter's monitors are pushed;
+/// for short-lived batch jobs that do not live long enough to be scraped.
+///
+/// Metrics are pushed to the group identified by the job and the grouping key, in the format
+/// served by [`Reporter::serve`], replacing the metrics previously pushed to that group. A
+/// reporter's metrics may be [pushed once][Pushgateway::push], or
+/// [once per period][Reporter::spawn_pushes].
+///
+/// Only plain HTTP is supported.
+///
+/// **This functionality requires the crate feature `pushgateway`.**
+///
+/// ##### Examples
+/// ```
+/// use std::io::{BufRead, BufReader, Read, Write};
+/// use std::net::TcpListener;
+/// use tokio_metrics::{Pushgateway, Reporter};
+///
+/// #[tokio::main]
+/// async fn main() -> std::io::Result<()> {
+///     // a stand-in for the pushgateway, which accepts one push
+///     let listener = TcpListener::bind("127.0.0.1:0")?;
+///     let addr = listener.local_addr()?;
+///     let gateway = std::thread::spawn(move || {
+///         let (stream, _) = listener.accept().unwrap();
+///         let mut reader = BufReader::new(&stream);
+///         let mut request_line = String::new();
+///         reader.read_line(&mut request_line).unwrap();
+///         // read the rest of the request, lest the connection be reset on closing
+///         let mut content_length = 0;
+///         loop {
+///             let mut header = String::new();
+///             reader.read_line(&mut header).unwrap();
+///             match header.trim_end().split_once(": ") {
+///                 Some(("Content-Length", len)) => content_length = len.parse().unwrap(),
+///                 Some(_) => {}
+///                 None => break,
+///             }
+///         }
+///         reader.read_exact(&mut vec![0; content_length]).unwrap();
+///         (&stream).write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").unwrap();
+///         request_line
+///     });
+///
+///     let mut reporter = Reporter::new();
+///     let monitor = reporter.monitor("requests");
+///     monitor.instrument(async {}).await;
+///
+///     Pushgateway::new(addr.to_string(), "nightly-backup")
+///         .with_grouping_key("instance", "db-1")
+///         .with_grouping_key("path", "/var/lib/db")
+///         .with_basic_auth("batch", "secret")
+///         .push(&reporter)?;
+///
+///     assert_eq!(
+///         gateway.join().unwrap(),
+///         "PUT /metrics/job/nightly-backup/instance/db-1/path@base64/L3Zhci9saWIvZGI= HTTP/1.1\r\n"
+///     );
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Pushgateway {
+    addr: String,
+    path: String,
+    authorization: Option<String>,
+}
+
+impl Pushgateway {
+    /// Constructs a pushgateway listening at `addr` (e.g., `localhost:9091`, or
+    /// `http://localhost:9091`), to which metrics are pushed under `job`.
+    pub fn new(addr: impl Into<String>, job: &str) -> Pushgateway {
+        let addr = addr.into();
+        let addr = addr.strip_prefix("http://").unwrap_or(&addr);
+        Pushgateway {
+            addr: addr.trim_end_matches('/').to_owned(),
+            path: format!("/metrics{}", segment("job", job)),
+            authorization: None,
+        }
+    }
+
+    /// Adds a label to the grouping key of the pushed metrics; e.g., `instance`.
+    pub fn with_grouping_key(mut self, name: &str, value: &str) -> Pushgateway {
+        self.path.push_str(&segment(name, value));
+        self
+    }
+
+    /// Authenticates pushes with HTTP basic authentication.
+    pub fn with_basic_auth(mut self, username: &str, password: &str) -> Pushgateway {
+        let credentials = format!("{}:{}", username, password);
+        self.authorization = Some(format!(
+            "Basic {}",
+            base64(credentials.as_bytes(), STANDARD)
+        ));
+        self
+    }
+
+    /// Pushes the cumulative metrics of the monitors registered with `reporter`, blocking the
+    /// current thread until the pushgateway responds; e.g., at the end of a batch job.
+    pub fn push(&self, reporter: &Reporter) -> io::Result<()> {
+        let monitors: Vec<(String, TaskMonitor)> = reporter
+            .monitors()
+            .map(|(name, monitor)| (name.to_owned(), monitor.clone()))
+            .collect();
+        self.push_monitors(&monitors)
+    }
+
+    fn push_monitors(&self, monitors: &[(String, TaskMonitor)]) -> io::Result<()> {
+        let body = encode(monitors, Format::Prometheus);
+        let mut stream = TcpStream::connect(&self.addr)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let authorization = match &self.authorization {
+            Some(authorization) => format!("Authorization: {}\r\n", authorization),
+            None => String::new(),
+        };
+        write!(
+            stream,
+            "PUT {} HTTP/1.1\r\n\
+            Host: {}\r\n\
+            Content-Type: text/plain; version=0.0.4\r\n\
+            Content-Length: {}\r\n\
+            {}\
+            Connection: close\r\n\r\n{}",
+            self.path,
+            self.addr,
+            body.len(),
+            authorization,
+            body
+        )?;
+        stream.flush()?;
+
+        let mut status_line = String::new();
+        BufReader::new(stream).read_line(&mut status_line)?;
+        match status_line.split_whitespace().nth(1) {
+            Some(status) if status.starts_with('2') => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("the pushgateway responded `{}`", status_line.trim()),
+            )),
+        }
+    }
+}
+
+impl Reporter {
+    /// Spawns a task onto the current tokio runtime that pushes the cumulative metrics of the
+    /// monitors registered with this reporter to `gateway` once per period, until it is
+    /// [shut down][ReporterTask::shutdown] or aborted, pushing once more on shutdown. Monitors
+    /// registered after this call are not pushed.
+    ///
+    /// A failed push is handed to the [error handler][Reporter::with_error_handler], and retried
+    /// at the next period.
+    ///
+    /// **This functionality requires the crate feature `pushgateway`.**
+    ///
+    /// ##### Panics
+    /// This method panics if called outside of a tokio runtime.
+    ///
+    /// ##### Examples
+    /// ```no_run
+    /// use tokio_metrics::{Pushgateway, Reporter};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut reporter = Reporter::new();
+    ///     let monitor = reporter.monitor("backup");
+    ///     let pushes = reporter.spawn_pushes(Pushgateway::new("localhost:9091", "backup"));
+    ///
+    ///     monitor.instrument(async { /* ... */ }).await;
+    ///
+    ///     // push the final metrics before exiting
+    ///     pushes.shutdown().await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn spawn_pushes(&self, gateway: Pushgateway) -> ReporterTask {
+        self.spawn_exporter(gateway)
+    }
+}
+
+impl Export for Pushgateway {
+    fn export(&mut self, round: &Round<'_>) -> io::Result<()> {
+        let monitors: Vec<(String, TaskMonitor)> = round
+            .monitors
+            .iter()
+            .map(|(name, monitor, _)| ((*name).to_owned(), (*monitor).clone()))
+            .collect();
+        self.push_monitors(&monitors)
+    }
+}
+
+impl fmt::Debug for Pushgateway {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the credentials are deliberately omitted
+        f.debug_struct("Pushgateway")
+            .field("addr", &self.addr)
+            .field("path", &self.path)
+            .field("basic_auth", &self.authorization.is_some())
+            .finish()
+    }
+}
+
+/// The standard and URL-safe base64 alphabets.
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Produces the path segment of a label of the grouping key; values that are not URL-safe are
+/// base64-encoded, as the pushgateway permits.
+fn segment(name: &str, value: &str) -> String {
+    let url_safe = value
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b"-_.~".contains(&b));
+    if url_safe && !value.is_empty() {
+        format!("/{}/{}", name, value)
+    } else if value.is_empty() {
+        format!("/{}@base64/=", name)
+    } else {
+        format!("/{}@base64/{}", name, base64(value.as_bytes(), URL_SAFE))
+    }
+}
+
+/// Encodes `bytes` in base64, with padding, in the given `alphabet`.
+fn base64(bytes: &[u8], alphabet: &[u8; 64]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(alphabet[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
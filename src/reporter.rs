@@ -0,0 +1,1004 @@
+use crate::{
+    IntervalSample, MetricValue, MetricsSink, TaskMetrics, TaskMonitor, TaskMonitorBuilder,
+};
+#[cfg(tokio_unstable)]
+use crate::{RuntimeMetrics, RuntimeMonitor};
+use futures_util::future::{self, Either};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+
+/// Periodically records the [`TaskMetrics`][crate::TaskMetrics] of a set of named
+/// [`TaskMonitor`]s into a [`MetricsSink`].
+///
+/// Each monitor is [registered][Reporter::register] under a name, with which its metrics are
+/// labeled (as `monitor`). Once [spawned][Reporter::spawn], the reporter records the metrics of
+/// each sampling interval of every monitor, once per [period][Reporter::with_period].
+///
+/// With `--cfg tokio_unstable`, runtime monitors may also be
+/// [registered][Reporter::register_runtime]; their metrics are labeled with their names (as
+/// `runtime`), and recorded in the same period as those of the task monitors.
+///
+/// A reporter may also be configured [from the environment][Reporter::from_env], so that
+/// deployments can toggle or retarget reporting without code changes.
+///
+/// **This functionality requires the crate feature `rt`.**
+///
+/// ##### Examples
+/// ```
+/// use std::time::Duration;
+/// use tokio_metrics::{MetricValue, Reporter};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut reporter = Reporter::new()
+///         .with_period(Duration::from_millis(10))
+///         .with_sink(|name: &'static str, value: MetricValue, labels: &[(&str, &str)]| {
+///             if name == "instrumented_count" {
+///                 println!("{:?} {:?}", labels, value);
+///             }
+///         });
+///
+///     let monitor = reporter.monitor("requests");
+///     let handle = reporter.spawn();
+///
+///     monitor.instrument(async {}).await;
+///     tokio::time::sleep(Duration::from_millis(50)).await;
+///     handle.shutdown().await;
+/// }
+/// ```
+pub struct Reporter {
+    period: Duration,
+    builder: TaskMonitorBuilder,
+    monitors: Vec<(String, TaskMonitor)>,
+    sink: Option<Box<dyn MetricsSink + Send + Sync>>,
+    exporter: Option<NamedExporter>,
+    aligned: bool,
+    flush_on_shutdown: bool,
+    pub(crate) on_error: Option<ErrorHandler>,
+    max_backoff: Option<Duration>,
+    #[cfg(tokio_unstable)]
+    runtimes: Vec<(String, RuntimeMonitor)>,
+}
+
+pub(crate) type ErrorHandler = Arc<dyn Fn(&ReportError) + Send + Sync>;
+
+/// An exporter configured by name (e.g., by `TOKIO_METRICS_EXPORTER`), which spawns the
+/// reporter when it is [spawned][Reporter::spawn].
+type NamedExporter = Box<dyn FnOnce(Reporter) -> ReporterTask + Send + Sync>;
+
+/// A reporter spawned by [`Reporter::spawn`], or by a spawning method of an exporter; e.g.,
+/// `Reporter::spawn_journal`.
+///
+/// Dropping the handle detaches the reporter, which then reports until its runtime shuts down.
+#[derive(Debug)]
+pub struct ReporterTask {
+    pub(crate) task: JoinHandle<()>,
+    pub(crate) shutdown: Arc<Notify>,
+}
+
+/// An error encountered by a spawned [`Reporter`] in reporting, as handed to its
+/// [error handler][Reporter::with_error_handler].
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum ReportError {
+    /// The sink failed to [flush][MetricsSink::flush], an exporter failed to send a report, or
+    /// the [metrics server][Reporter::serve] failed to respond to a scrape.
+    Sink(io::Error),
+
+    /// The sink or exporter panicked, with the given message.
+    Panicked(String),
+}
+
+/// An exporter driven by a spawned [`Reporter`], to which it hands the metrics of each sampling
+/// interval of its monitors.
+pub(crate) trait Export: Send + 'static {
+    /// Exports the metrics of a sampling interval of the reporter's monitors.
+    fn export(&mut self, round: &Round<'_>) -> io::Result<()>;
+}
+
+/// The metrics of a sampling interval of the monitors of a spawned [`Reporter`].
+pub(crate) struct Round<'a> {
+    /// The name, monitor and interval of each task monitor with an interval to report.
+    pub(crate) monitors: Vec<(&'a str, &'a TaskMonitor, IntervalSample)>,
+    /// The name and interval of each runtime monitor with an interval to report.
+    #[cfg(tokio_unstable)]
+    pub(crate) runtimes: Vec<(&'a str, RuntimeMetrics)>,
+}
+
+/// A sampler of the intervals of a monitor.
+type Intervals<T> = Box<dyn Iterator<Item = T> + Send>;
+
+/// The state of a spawned [`Reporter`], handed between its task and the blocking threads on
+/// which it reports.
+struct Driver<E> {
+    exporter: E,
+    monitors: Vec<(String, TaskMonitor, Intervals<TaskMetrics>)>,
+    #[cfg(tokio_unstable)]
+    runtimes: Vec<(String, Intervals<RuntimeMetrics>)>,
+    /// The wall-clock time and instant at which the current interval started.
+    started_at: SystemTime,
+    started: Instant,
+}
+
+/// The exporter of [`Reporter::spawn`], which records into the reporter's sink.
+struct SinkExporter {
+    sink: Box<dyn MetricsSink + Send + Sync>,
+    /// The monitors of which to record the final report, when dropped, if configured.
+    final_report: Option<FinalReport>,
+}
+
+/// The exporter of a reporter whose metrics are served by a [`MetricsServer`][crate::MetricsServer]
+/// configured by name; it exports nothing itself, and shuts the server down with the reporter.
+#[cfg(feature = "server")]
+struct Served(Option<crate::MetricsServer>);
+
+/// The monitors of which a spawned [`Reporter`] records a
+/// [final report][Reporter::with_flush_on_shutdown].
+struct FinalReport {
+    monitors: Vec<(String, TaskMonitor)>,
+    on_error: Option<ErrorHandler>,
+    #[cfg(tokio_unstable)]
+    runtimes: Vec<(String, RuntimeMonitor)>,
+}
+
+/// An error in the configuration of a [`Reporter`]; e.g., a malformed environment variable.
+#[derive(Debug)]
+pub struct ConfigError {
+    key: String,
+    message: String,
+}
+
+/// A [`MetricsSink`] that writes each metric as a line of text: its name, its labels, and its
+/// value; durations are written in seconds.
+pub(crate) struct TextSink {
+    writer: Mutex<Box<dyn Write + Send>>,
+    /// The first write error since the last flush, surfaced by [`MetricsSink::flush`].
+    error: Mutex<Option<io::Error>>,
+}
+
+impl Reporter {
+    /// The default period between reports.
+    pub const DEFAULT_PERIOD: Duration = Duration::from_secs(1);
+
+    /// Constructs a reporter with the [default period][Reporter::DEFAULT_PERIOD], no monitors,
+    /// and no sink.
+    pub fn new() -> Reporter {
+        Reporter {
+            period: Self::DEFAULT_PERIOD,
+            builder: TaskMonitor::builder(),
+            monitors: Vec::new(),
+            sink: None,
+            exporter: None,
+            aligned: false,
+            flush_on_shutdown: false,
+            on_error: None,
+            max_backoff: None,
+            #[cfg(tokio_unstable)]
+            runtimes: Vec::new(),
+        }
+    }
+
+    /// Constructs a reporter configured by environment variables. Unset variables keep the
+    /// configuration of [`Reporter::new`].
+    ///
+    /// - **`TOKIO_METRICS_PERIOD_MS`**
+    ///   The period between reports, in milliseconds.
+    /// - **`TOKIO_METRICS_EXPORTER`**
+    ///   Where metrics are reported: `none` (the default); `stdout`, `stderr` or `file`, as lines
+    ///   of text; or, if the crate feature enabling it is enabled, one of the exporters below.
+    /// - **`TOKIO_METRICS_ENDPOINT`**
+    ///   The address of the exporter, if it has one; see below.
+    ///
+    /// The endpoint of each exporter is:
+    ///
+    /// - **`file`**
+    ///   The path of the file to which lines are appended; required.
+    /// - **`dogstatsd`** (crate feature `datadog`)
+    ///   The address of the agent, as for [`DogStatsd::connect`][crate::DogStatsd::connect];
+    ///   `127.0.0.1:8125` by default.
+    /// - **`emf`** (crate feature `emf`)
+    ///   The CloudWatch namespace of the metrics, which are written to standard output; required.
+    /// - **`journald`** (crate feature `journald`, on Linux)
+    ///   The path of the journal's socket; `/run/systemd/journal/socket` by default.
+    /// - **`pushgateway`** (crate feature `pushgateway`)
+    ///   The address of the pushgateway, to which metrics are pushed under the job
+    ///   `tokio_metrics`; required.
+    /// - **`prometheus`** (crate feature `server`)
+    ///   The address on which the metrics are [served][crate::Reporter::serve] once the reporter
+    ///   is spawned; required. A failure to bind is handed to the
+    ///   [error handler][Reporter::with_error_handler].
+    /// - **`unix-socket`** (crate feature `publish`, on Unix)
+    ///   The path of the socket to which JSON snapshots are published; required.
+    /// - **`TOKIO_METRICS_SLOW_POLL_THRESHOLD_US`**
+    ///   The [slow-poll threshold][TaskMonitorBuilder::with_slow_poll_threshold] of the monitors
+    ///   constructed by [`Reporter::monitor`], in microseconds.
+    /// - **`TOKIO_METRICS_BLOCKING_POLL_THRESHOLD_US`**
+    ///   The [blocking-poll threshold][TaskMonitorBuilder::with_blocking_poll_threshold] of the
+    ///   monitors constructed by [`Reporter::monitor`], in microseconds.
+    ///
+    /// Produces an error if a variable is malformed, if the exporter is unknown or its crate
+    /// feature is disabled, or if the exporter cannot open its endpoint.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::Reporter;
+    ///
+    /// std::env::set_var("TOKIO_METRICS_PERIOD_MS", "250");
+    /// std::env::set_var("TOKIO_METRICS_EXPORTER", "stdout");
+    /// std::env::set_var("TOKIO_METRICS_SLOW_POLL_THRESHOLD_US", "100");
+    ///
+    /// let mut reporter = Reporter::from_env().unwrap();
+    /// assert_eq!(reporter.period(), Duration::from_millis(250));
+    ///
+    /// let monitor = reporter.monitor("requests");
+    /// assert_eq!(monitor.slow_poll_threshold(), Duration::from_micros(100));
+    ///
+    /// std::env::set_var("TOKIO_METRICS_EXPORTER", "carrier-pigeon");
+    /// assert!(Reporter::from_env().is_err());
+    /// ```
+    pub fn from_env() -> Result<Reporter, ConfigError> {
+        Reporter::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Constructs a reporter configured by the variables produced by `lookup`.
+    fn from_lookup<L>(lookup: L) -> Result<Reporter, ConfigError>
+    where
+        L: Fn(&str) -> Option<String>,
+    {
+        let micros = |key: &str| -> Result<Option<Duration>, ConfigError> {
+            lookup(key)
+                .map(|value| parse_u64(key, &value).map(Duration::from_micros))
+                .transpose()
+        };
+
+        let mut reporter = Reporter::new();
+        if let Some(value) = lookup("TOKIO_METRICS_PERIOD_MS") {
+            let period = parse_u64("TOKIO_METRICS_PERIOD_MS", &value)?;
+            if period == 0 {
+                return Err(ConfigError::new(
+                    "TOKIO_METRICS_PERIOD_MS",
+                    "the period must be non-zero",
+                ));
+            }
+            reporter.period = Duration::from_millis(period);
+        }
+        if let Some(threshold) = micros("TOKIO_METRICS_SLOW_POLL_THRESHOLD_US")? {
+            reporter.builder = reporter.builder.with_slow_poll_threshold(threshold);
+        }
+        if let Some(threshold) = micros("TOKIO_METRICS_BLOCKING_POLL_THRESHOLD_US")? {
+            reporter.builder = reporter.builder.with_blocking_poll_threshold(threshold);
+        }
+
+        let exporter = lookup("TOKIO_METRICS_EXPORTER");
+        let endpoint = lookup("TOKIO_METRICS_ENDPOINT");
+        let keys = ("TOKIO_METRICS_EXPORTER", "TOKIO_METRICS_ENDPOINT");
+        reporter.with_named_exporter(exporter.as_deref(), endpoint.as_deref(), keys)
+    }
+
+    /// Configures the exporter named `exporter`, at `endpoint`, as described by
+    /// [`Reporter::from_env`]; errors are attributed to the settings named by `keys`.
+    pub(crate) fn with_named_exporter(
+        mut self,
+        exporter: Option<&str>,
+        endpoint: Option<&str>,
+        keys: (&str, &str),
+    ) -> Result<Reporter, ConfigError> {
+        let (exporter_key, endpoint_key) = keys;
+        let required = |exporter: &str| {
+            endpoint.ok_or_else(|| {
+                let message = format!("the `{}` exporter requires an endpoint", exporter);
+                ConfigError::new(endpoint_key, message)
+            })
+        };
+        let spawn: NamedExporter = match exporter.unwrap_or("none") {
+            "none" => return Ok(self),
+            "stdout" => TextSink::new(io::stdout()).spawner(),
+            "stderr" => TextSink::new(io::stderr()).spawner(),
+            "file" => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(required("file")?)
+                    .map_err(|e| ConfigError::new(endpoint_key, e))?;
+                TextSink::new(file).spawner()
+            }
+            #[cfg(feature = "datadog")]
+            "dogstatsd" => {
+                let dogstatsd = crate::DogStatsd::connect(endpoint.unwrap_or("127.0.0.1:8125"))
+                    .map_err(|e| ConfigError::new(endpoint_key, e))?;
+                Box::new(move |reporter: Reporter| reporter.spawn_dogstatsd(dogstatsd))
+            }
+            #[cfg(feature = "emf")]
+            "emf" => {
+                let namespace = required("emf")?.to_owned();
+                Box::new(move |reporter: Reporter| {
+                    reporter.spawn_emf(crate::EmfExporter::new(namespace))
+                })
+            }
+            #[cfg(all(target_os = "linux", feature = "journald"))]
+            "journald" => {
+                let path = endpoint.unwrap_or(crate::JournalExporter::DEFAULT_PATH);
+                let exporter = crate::JournalExporter::new()
+                    .map_err(|e| ConfigError::new(endpoint_key, e))?
+                    .with_path(path);
+                Box::new(move |reporter: Reporter| reporter.spawn_journal(exporter))
+            }
+            #[cfg(feature = "pushgateway")]
+            "pushgateway" => {
+                let gateway = crate::Pushgateway::new(required("pushgateway")?, "tokio_metrics");
+                Box::new(move |reporter: Reporter| reporter.spawn_pushes(gateway))
+            }
+            #[cfg(feature = "server")]
+            "prometheus" => {
+                let addr = required("prometheus")?.to_owned();
+                Box::new(move |reporter: Reporter| match reporter.serve(addr) {
+                    Ok(server) => reporter.spawn_exporter(Served(Some(server))),
+                    Err(error) => {
+                        if let Some(on_error) = &reporter.on_error {
+                            on_error(&ReportError::Sink(error));
+                        }
+                        ReporterTask::completed()
+                    }
+                })
+            }
+            #[cfg(all(unix, feature = "publish"))]
+            "unix-socket" => {
+                let publisher = crate::UnixSocketPublisher::new(required("unix-socket")?);
+                Box::new(move |reporter: Reporter| {
+                    reporter.spawn_publisher(crate::SnapshotFormat::Json, publisher)
+                })
+            }
+            other => {
+                return Err(ConfigError::new(
+                    exporter_key,
+                    format!("unknown exporter `{}`", other),
+                ))
+            }
+        };
+        self.exporter = Some(spawn);
+        Ok(self)
+    }
+
+    /// Sets the period between reports.
+    ///
+    /// ##### Panics
+    /// This method panics if `period` is zero.
+    pub fn with_period(mut self, period: Duration) -> Reporter {
+        assert!(!period.is_zero(), "the period must be non-zero");
+        self.period = period;
+        self
+    }
+
+    /// Sets whether sampling intervals are aligned to multiples of the period on the wall clock;
+    /// e.g., with a period of one minute, every minute on the minute.
+    ///
+    /// Aligned samples from many processes line up when aggregated; e.g., in a time-series
+    /// database. The first, partial interval, between the reporter being spawned and the first
+    /// boundary, is not reported.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::Reporter;
+    ///
+    /// let reporter = Reporter::new()
+    ///     .with_period(Duration::from_secs(60))
+    ///     .with_aligned_sampling(true);
+    /// ```
+    pub fn with_aligned_sampling(mut self, aligned: bool) -> Reporter {
+        self.aligned = aligned;
+        self
+    }
+
+    /// Sets whether a final report is recorded when the spawned reporter shuts down; i.e., when
+    /// its task is aborted, or its runtime is shut down (e.g., when `main` returns).
+    ///
+    /// The final report carries the cumulative metrics of each registered monitor, since its
+    /// construction, labeled with `snapshot="final"` in addition to the name of the monitor; so
+    /// short-lived jobs do not lose the tail of their metrics between the last interval and
+    /// their exit. The sink is [flushed][MetricsSink::flush] after the final report, and a
+    /// failure to flush is handed to the [error handler][Reporter::with_error_handler].
+    /// Processes that exit without shutting down the runtime (e.g., with
+    /// [`std::process::exit`]) record no final report.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    /// use tokio_metrics::{MetricValue, Reporter};
+    ///
+    /// let recorded = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = recorded.clone();
+    ///
+    /// let runtime = tokio::runtime::Runtime::new().unwrap();
+    /// runtime.block_on(async {
+    ///     let mut reporter = Reporter::new()
+    ///         .with_period(Duration::from_secs(60))
+    ///         .with_flush_on_shutdown(true)
+    ///         .with_sink(move |name: &'static str, value: MetricValue, labels: &[(&str, &str)]| {
+    ///             if name == "total_poll_count" {
+    ///                 sink.lock().unwrap().push((value, labels.len()));
+    ///             }
+    ///         });
+    ///     let monitor = reporter.monitor("job");
+    ///     reporter.spawn();
+    ///
+    ///     // the job completes well within the first period
+    ///     monitor.instrument(async {}).await;
+    /// });
+    /// drop(runtime);
+    ///
+    /// // the final report carries the tail of the job's metrics
+    /// let recorded = recorded.lock().unwrap();
+    /// assert_eq!(*recorded, [(MetricValue::Count(1), 2)]);
+    /// ```
+    pub fn with_flush_on_shutdown(mut self, flush_on_shutdown: bool) -> Reporter {
+        self.flush_on_shutdown = flush_on_shutdown;
+        self
+    }
+
+    /// Sets the handler of the errors encountered by the spawned reporter in reporting; e.g., to
+    /// log them. Without a handler, errors are ignored. The handler also receives the errors of
+    /// the [metrics server][Reporter::serve] in responding to scrapes.
+    ///
+    /// A report fails if the sink fails to [flush][MetricsSink::flush], or an exporter (e.g., to a
+    /// pushgateway) fails to export, or either panics; the reporter survives
+    /// any of these, and reports again at the next period (or, if configured, after
+    /// [backing off][Reporter::with_backoff]).
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use tokio_metrics::{MetricValue, Reporter, ReportError};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let failures = Arc::new(AtomicUsize::new(0));
+    ///     let handled = failures.clone();
+    ///     let mut reporter = Reporter::new()
+    ///         .with_period(Duration::from_millis(10))
+    ///         .with_sink(|_: &'static str, _: MetricValue, _: &[(&str, &str)]| {
+    ///             panic!("the backend is down");
+    ///         })
+    ///         .with_error_handler(move |error: &ReportError| {
+    ///             assert!(matches!(error, ReportError::Panicked(_)));
+    ///             handled.fetch_add(1, Ordering::SeqCst);
+    ///         });
+    ///     let monitor = reporter.monitor("requests");
+    ///     let handle = reporter.spawn();
+    ///
+    ///     monitor.instrument(async {}).await;
+    ///     tokio::time::sleep(Duration::from_millis(50)).await;
+    ///
+    ///     // the reporter survives its sink panicking, and reports once more on shutdown
+    ///     handle.shutdown().await;
+    ///     assert!(failures.load(Ordering::SeqCst) >= 1);
+    /// }
+    /// ```
+    pub fn with_error_handler<F>(mut self, handler: F) -> Reporter
+    where
+        F: Fn(&ReportError) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Arc::new(handler));
+        self
+    }
+
+    /// Sets the spawned reporter to back off after failed reports: the delay before the next
+    /// report doubles with each consecutive failure, from the period up to `max_backoff`, and is
+    /// reset by a successful report. Sampling intervals are not lost while backing off; the next
+    /// report covers the whole of the delay.
+    ///
+    /// Without backoff, the reporter reports once per period regardless of failures. While
+    /// backing off, reports are not [aligned][Reporter::with_aligned_sampling].
+    pub fn with_backoff(mut self, max_backoff: Duration) -> Reporter {
+        self.max_backoff = Some(max_backoff);
+        self
+    }
+
+    /// Sets the builder with which [`Reporter::monitor`] constructs monitors.
+    pub fn with_monitor_builder(mut self, builder: TaskMonitorBuilder) -> Reporter {
+        self.builder = builder;
+        self
+    }
+
+    /// Sets the sink into which metrics are recorded, replacing any exporter configured by the
+    /// environment.
+    pub fn with_sink<S>(mut self, sink: S) -> Reporter
+    where
+        S: MetricsSink + Send + Sync + 'static,
+    {
+        self.sink = Some(Box::new(sink));
+        self.exporter = None;
+        self
+    }
+
+    /// Produces the period between reports.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Produces the monitor registered under `name`. If there is none, constructs one with the
+    /// configured [builder][Reporter::with_monitor_builder], and [registers][Reporter::register]
+    /// it under `name`.
+    pub fn monitor(&mut self, name: impl Into<String>) -> TaskMonitor {
+        let name = name.into();
+        if let Some((_, monitor)) = self.monitors.iter().find(|(other, _)| *other == name) {
+            return monitor.clone();
+        }
+        let monitor = self.builder.clone().build();
+        self.register(name, monitor.clone());
+        monitor
+    }
+
+    /// Registers `monitor`, whose metrics are reported labeled with `name`.
+    pub fn register(&mut self, name: impl Into<String>, monitor: TaskMonitor) {
+        self.monitors.push((name.into(), monitor));
+    }
+
+    /// Produces the registered monitors, and their names.
+    pub fn monitors(&self) -> impl Iterator<Item = (&str, &TaskMonitor)> {
+        self.monitors
+            .iter()
+            .map(|(name, monitor)| (name.as_str(), monitor))
+    }
+
+    /// Registers the runtime `monitor`, whose metrics are reported labeled with `name` (as
+    /// `runtime`), on the same schedule as the task monitors; e.g., so that a spike in the slow
+    /// polls of a task monitor can be read against the busy ratio of its runtime in the same
+    /// interval.
+    ///
+    /// **This functionality requires `--cfg tokio_unstable`.**
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    /// use tokio_metrics::{MetricValue, Reporter, RuntimeMonitor};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let recorded = Arc::new(Mutex::new(Vec::new()));
+    ///     let sink = recorded.clone();
+    ///     let mut reporter = Reporter::new()
+    ///         .with_period(Duration::from_millis(10))
+    ///         .with_sink(move |_: &'static str, _: MetricValue, labels: &[(&str, &str)]| {
+    ///             let (key, value) = labels[0];
+    ///             sink.lock().unwrap().push((key.to_owned(), value.to_owned()));
+    ///         });
+    ///     let monitor = reporter.monitor("requests");
+    ///     let runtime = RuntimeMonitor::new(&tokio::runtime::Handle::current());
+    ///     reporter.register_runtime("main", runtime);
+    ///     let handle = reporter.spawn();
+    ///
+    ///     monitor.instrument(async {}).await;
+    ///     tokio::time::sleep(Duration::from_millis(50)).await;
+    ///     handle.abort();
+    ///
+    ///     let recorded = recorded.lock().unwrap();
+    ///     assert!(recorded.contains(&("monitor".to_owned(), "requests".to_owned())));
+    ///     assert!(recorded.contains(&("runtime".to_owned(), "main".to_owned())));
+    /// }
+    /// ```
+    #[cfg(tokio_unstable)]
+    #[cfg_attr(docsrs, doc(cfg(tokio_unstable)))]
+    pub fn register_runtime(&mut self, name: impl Into<String>, monitor: RuntimeMonitor) {
+        self.runtimes.push((name.into(), monitor));
+    }
+
+    /// Produces the registered runtime monitors, and their names.
+    ///
+    /// **This functionality requires `--cfg tokio_unstable`.**
+    #[cfg(tokio_unstable)]
+    #[cfg_attr(docsrs, doc(cfg(tokio_unstable)))]
+    pub fn runtimes(&self) -> impl Iterator<Item = (&str, &RuntimeMonitor)> {
+        self.runtimes
+            .iter()
+            .map(|(name, monitor)| (name.as_str(), monitor))
+    }
+
+    /// Spawns a task onto the current tokio runtime that reports the metrics of the registered
+    /// monitors (task and runtime monitors alike) once per period, until it is
+    /// [shut down][ReporterTask::shutdown] or aborted, and then records a
+    /// [final report][Reporter::with_flush_on_shutdown] if configured. If an exporter was
+    /// configured [by the environment][Reporter::from_env] instead of a sink (e.g.,
+    /// `dogstatsd`), the reporter is spawned with it. If neither is configured, the task
+    /// completes immediately.
+    ///
+    /// ##### Panics
+    /// This method panics if called outside of a tokio runtime.
+    pub fn spawn(mut self) -> ReporterTask {
+        if let Some(spawn) = self.exporter.take() {
+            return spawn(self);
+        }
+        let sink = match self.sink.take() {
+            Some(sink) => sink,
+            None => return ReporterTask::completed(),
+        };
+        let final_report = if self.flush_on_shutdown {
+            Some(FinalReport {
+                monitors: self.monitors.clone(),
+                on_error: self.on_error.clone(),
+                #[cfg(tokio_unstable)]
+                runtimes: self.runtimes.clone(),
+            })
+        } else {
+            None
+        };
+        self.spawn_exporter(SinkExporter { sink, final_report })
+    }
+
+    /// Spawns a task onto the current tokio runtime that hands the metrics of each sampling
+    /// interval of the registered monitors to `exporter`, once per period, until it is
+    /// [shut down][ReporterTask::shutdown] or aborted; the loop shared by [`Reporter::spawn`]
+    /// and the spawning methods of the exporters.
+    ///
+    /// Rounds are run on the runtime's blocking threads, as exporters may block on I/O. A round
+    /// that fails, or panics, is handed to the [error handler][Reporter::with_error_handler];
+    /// the reporter survives it, and reports again at the next period, or after
+    /// [backing off][Reporter::with_backoff].
+    pub(crate) fn spawn_exporter<E: Export>(&self, exporter: E) -> ReporterTask {
+        let mut driver = Driver {
+            exporter,
+            monitors: self
+                .monitors
+                .iter()
+                .map(|(name, monitor)| {
+                    let intervals: Intervals<TaskMetrics> = Box::new(monitor.intervals());
+                    (name.clone(), monitor.clone(), intervals)
+                })
+                .collect(),
+            #[cfg(tokio_unstable)]
+            runtimes: self
+                .runtimes
+                .iter()
+                .map(|(name, monitor)| {
+                    let intervals: Intervals<RuntimeMetrics> = Box::new(monitor.intervals());
+                    (name.clone(), intervals)
+                })
+                .collect(),
+            started_at: SystemTime::now(),
+            started: Instant::now(),
+        };
+        let period = self.period;
+        let aligned = self.aligned;
+        let max_backoff = self.max_backoff;
+        let on_error = self.on_error.clone();
+        let shutdown = Arc::new(Notify::new());
+        let signal = shutdown.clone();
+        let task = tokio::spawn(async move {
+            if aligned {
+                tokio::time::sleep(until_boundary(period)).await;
+                // discard the partial interval preceding the first boundary
+                driver.discard();
+            }
+            let mut failures = 0;
+            loop {
+                let period = match max_backoff {
+                    Some(max_backoff) if failures > 0 => backoff(period, failures).min(max_backoff),
+                    _ if aligned => until_boundary(period),
+                    _ => period,
+                };
+                let sleep = Box::pin(tokio::time::sleep(period));
+                let notified = Box::pin(signal.notified());
+                let shutting_down =
+                    matches!(future::select(sleep, notified).await, Either::Right(_));
+
+                let round = tokio::task::spawn_blocking(move || {
+                    let result = driver.report();
+                    (driver, result)
+                });
+                let result = match round.await {
+                    Ok((returned, result)) => {
+                        driver = returned;
+                        result
+                    }
+                    // the runtime is shutting down, and has dropped the driver
+                    Err(_) => return,
+                };
+                match result {
+                    Err(error) => {
+                        failures += 1;
+                        if let Some(on_error) = &on_error {
+                            on_error(&error);
+                        }
+                    }
+                    Ok(()) => failures = 0,
+                }
+
+                if shutting_down {
+                    break;
+                }
+            }
+        });
+        ReporterTask { task, shutdown }
+    }
+}
+
+impl<E: Export> Driver<E> {
+    /// Hands the metrics of the interval since the last round to the exporter.
+    fn report(&mut self) -> Result<(), ReportError> {
+        let (ended_at, ended) = (SystemTime::now(), Instant::now());
+        let started_at = mem::replace(&mut self.started_at, ended_at);
+        let elapsed = ended.saturating_duration_since(mem::replace(&mut self.started, ended));
+        let Driver {
+            exporter,
+            monitors,
+            #[cfg(tokio_unstable)]
+            runtimes,
+            ..
+        } = self;
+        let round = Round {
+            monitors: monitors
+                .iter_mut()
+                .filter_map(|(name, monitor, intervals)| {
+                    let sample = IntervalSample {
+                        metrics: intervals.next()?,
+                        started_at,
+                        ended_at,
+                        elapsed,
+                    };
+                    Some((name.as_str(), &*monitor, sample))
+                })
+                .collect(),
+            #[cfg(tokio_unstable)]
+            runtimes: runtimes
+                .iter_mut()
+                .filter_map(|(name, intervals)| Some((name.as_str(), intervals.next()?)))
+                .collect(),
+        };
+        match panic::catch_unwind(AssertUnwindSafe(|| exporter.export(&round))) {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(error)) => Err(ReportError::Sink(error)),
+            Err(panic) => Err(ReportError::Panicked(panic_message(&*panic))),
+        }
+    }
+
+    /// Discards the interval since the last round.
+    fn discard(&mut self) {
+        for (_, _, intervals) in &mut self.monitors {
+            intervals.next();
+        }
+        #[cfg(tokio_unstable)]
+        for (_, intervals) in &mut self.runtimes {
+            intervals.next();
+        }
+        self.started_at = SystemTime::now();
+        self.started = Instant::now();
+    }
+}
+
+impl Export for SinkExporter {
+    fn export(&mut self, round: &Round<'_>) -> io::Result<()> {
+        for (name, _, sample) in &round.monitors {
+            sample.metrics.record_to(&*self.sink, &[("monitor", name)]);
+        }
+        #[cfg(tokio_unstable)]
+        for (name, metrics) in &round.runtimes {
+            metrics.record_to(&*self.sink, &[("runtime", name)]);
+        }
+        self.sink.flush()
+    }
+}
+
+#[cfg(feature = "server")]
+impl Export for Served {
+    fn export(&mut self, _: &Round<'_>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "server")]
+impl Drop for Served {
+    fn drop(&mut self) {
+        if let Some(server) = self.0.take() {
+            server.shutdown();
+        }
+    }
+}
+
+impl Default for Reporter {
+    fn default() -> Reporter {
+        Reporter::new()
+    }
+}
+
+impl fmt::Debug for Reporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let monitors: Vec<&str> = self.monitors().map(|(name, _)| name).collect();
+        let mut debug = f.debug_struct("Reporter");
+        debug
+            .field("period", &self.period)
+            .field("monitors", &monitors)
+            .field("sink", &self.sink.is_some())
+            .field("exporter", &self.exporter.is_some())
+            .field("aligned", &self.aligned)
+            .field("flush_on_shutdown", &self.flush_on_shutdown)
+            .field("error_handler", &self.on_error.is_some())
+            .field("max_backoff", &self.max_backoff);
+        #[cfg(tokio_unstable)]
+        {
+            let runtimes: Vec<&str> = self.runtimes().map(|(name, _)| name).collect();
+            debug.field("runtimes", &runtimes);
+        }
+        debug.finish()
+    }
+}
+
+impl ReporterTask {
+    /// Produces the handle of a reporter that has nothing to report, and so completes
+    /// immediately.
+    fn completed() -> ReporterTask {
+        ReporterTask {
+            task: tokio::spawn(async {}),
+            shutdown: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Stops the reporter: reports the metrics of the partial interval since the last report,
+    /// records the [final report][Reporter::with_flush_on_shutdown] if configured, and waits for
+    /// the reporter to finish.
+    pub async fn shutdown(self) {
+        self.shutdown.notify_one();
+        // the reporter isolates the panics of its sink, so it can only fail by being aborted
+        let _ = self.task.await;
+    }
+
+    /// Aborts the reporter, without reporting the partial interval since the last report; the
+    /// [final report][Reporter::with_flush_on_shutdown] is still recorded if configured.
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportError::Sink(error) => write!(f, "the sink failed: {}", error),
+            ReportError::Panicked(message) => write!(f, "the reporter panicked: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ReportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReportError::Sink(error) => Some(error),
+            ReportError::Panicked(_) => None,
+        }
+    }
+}
+
+impl Drop for SinkExporter {
+    fn drop(&mut self) {
+        let final_report = match &self.final_report {
+            Some(final_report) => final_report,
+            None => return,
+        };
+        for (name, monitor) in &final_report.monitors {
+            monitor
+                .cumulative()
+                .record_to(&*self.sink, &[("monitor", name), ("snapshot", "final")]);
+        }
+        #[cfg(tokio_unstable)]
+        for (name, monitor) in &final_report.runtimes {
+            monitor
+                .cumulative()
+                .record_to(&*self.sink, &[("runtime", name), ("snapshot", "final")]);
+        }
+        if let Err(error) = self.sink.flush() {
+            if let Some(on_error) = &final_report.on_error {
+                on_error(&ReportError::Sink(error));
+            }
+        }
+    }
+}
+
+impl ConfigError {
+    pub(crate) fn new(key: impl Into<String>, message: impl fmt::Display) -> ConfigError {
+        ConfigError {
+            key: key.into(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Produces the key of the malformed configuration; e.g., the name of an environment
+    /// variable.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid value for `{}`: {}", self.key, self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl TextSink {
+    /// Constructs a sink that writes lines of text to `writer`.
+    fn new<W: Write + Send + 'static>(writer: W) -> TextSink {
+        TextSink {
+            writer: Mutex::new(Box::new(writer)),
+            error: Mutex::new(None),
+        }
+    }
+
+    /// Produces an exporter that spawns the reporter with this as its sink.
+    fn spawner(self) -> NamedExporter {
+        Box::new(move |reporter: Reporter| reporter.with_sink(self).spawn())
+    }
+}
+
+impl MetricsSink for TextSink {
+    fn record(&self, name: &'static str, value: MetricValue, labels: &[(&str, &str)]) {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(key, value)| format!("{}={:?}", key, value))
+            .collect();
+        let value = match value {
+            MetricValue::Count(count) | MetricValue::Gauge(count) => count.to_string(),
+            MetricValue::Duration(duration) | MetricValue::MaxDuration(duration) => {
+                duration.as_secs_f64().to_string()
+            }
+            MetricValue::Ratio(ratio) => ratio.to_string(),
+        };
+        let mut writer = self.writer.lock().unwrap();
+        if let Err(error) = writeln!(writer, "{}{{{}}} {}", name, labels.join(","), value) {
+            self.error.lock().unwrap().get_or_insert(error);
+        }
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        if let Some(error) = self.error.lock().unwrap().take() {
+            return Err(error);
+        }
+        self.writer.lock().unwrap().flush()
+    }
+}
+
+/// Produces the delay before reporting after `failures` consecutive failed reports: `period`,
+/// doubled for each failure.
+fn backoff(period: Duration, failures: u32) -> Duration {
+    period
+        .checked_mul(1 << failures.min(31))
+        .unwrap_or(Duration::MAX)
+}
+
+/// Produces the message of the panic with the given `payload`.
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "<non-string panic payload>".to_owned()
+    }
+}
+
+/// Produces the duration until the next multiple of `period` on the wall clock.
+fn until_boundary(period: Duration) -> Duration {
+    let period = period.as_nanos();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    Duration::from_nanos((period - now % period) as u64)
+}
+
+/// Parses the value of `key` as an unsigned integer.
+fn parse_u64(key: &str, value: &str) -> Result<u64, ConfigError> {
+    value.trim().parse().map_err(|e| ConfigError::new(key, e))
+}
@@ -1,3 +1,4 @@
+use crate::{MetricValue, MetricsSink, PollBucket};
 use tokio::runtime;
 use std::time::{Duration, Instant};
 
@@ -44,9 +45,16 @@ use std::time::{Duration, Instant};
 ///     }
 /// }
 /// ```
+#[derive(Clone)]
 pub struct RuntimeMonitor {
     /// Handle to the runtime
     runtime: runtime::RuntimeMetrics,
+
+    /// The thresholds onto which the runtime's poll-time histogram is mapped, if configured
+    poll_bucket_thresholds: Option<Vec<Duration>>,
+
+    /// When the monitor was constructed
+    started_at: Instant,
 }
 
 #[cfg(any(docsrs, all(tokio_unstable, feature = "rt")))]
@@ -383,6 +391,92 @@ pub struct RuntimeMetrics {
     /// ```
     pub num_remote_schedules: u64,
 
+    /// The number of times tasks were forced to yield after exhausting their cooperative budget.
+    ///
+    /// The forced yield count increases by one each time the scheduler preempts a task that
+    /// has performed so many operations on tokio resources (e.g., receiving from channels, or
+    /// reading from sockets) in a single poll that its [budget][tokio::task::unconstrained] is
+    /// exhausted. Read against the poll counts of task monitors, this indicates how many of
+    /// their polls ended at the scheduler's behest, rather than the task's.
+    ///
+    /// ##### Definition
+    /// This metric is derived from [`tokio::runtime::RuntimeMetrics::budget_forced_yield_count`].
+    ///
+    /// ##### Examples
+    /// In the below example, a task that drains a full channel exhausts its budget:
+    /// ```
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let handle = tokio::runtime::Handle::current();
+    ///     let monitor = tokio_metrics::RuntimeMonitor::new(&handle);
+    ///     let mut intervals = monitor.intervals();
+    ///     let mut next_interval = || intervals.next().unwrap();
+    ///
+    ///     let interval = next_interval(); // end of first sampling interval
+    ///     assert_eq!(interval.budget_forced_yield_count, 0);
+    ///
+    ///     let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    ///     for _ in 0..1000 {
+    ///         tx.send(()).unwrap();
+    ///     }
+    ///     drop(tx);
+    ///     tokio::spawn(async move {
+    ///         while rx.recv().await.is_some() {}
+    ///     }).await.unwrap();
+    ///
+    ///     let interval = next_interval(); // end of second sampling interval
+    ///     assert!(interval.budget_forced_yield_count >= 1);
+    ///
+    ///     let interval = next_interval(); // end of third sampling interval
+    ///     assert_eq!(interval.budget_forced_yield_count, 0);
+    /// }
+    /// ```
+    pub budget_forced_yield_count: u64,
+
+    /// The number of ready events processed by the runtime's I/O driver.
+    ///
+    /// The I/O driver ready count increases by one for each readiness event (e.g., a socket
+    /// becoming readable) that the reactor receives from the operating system, and dispatches to
+    /// the tasks awaiting it. Graphed next to scheduling latencies, this indicates whether a
+    /// spike in latency follows a spike in I/O.
+    ///
+    /// This metric is always zero if the runtime's I/O driver is not
+    /// [enabled][tokio::runtime::Builder::enable_io].
+    ///
+    /// **This metric requires the crate feature `net`.**
+    ///
+    /// ##### Definition
+    /// This metric is derived from [`tokio::runtime::RuntimeMetrics::io_driver_ready_count`].
+    ///
+    /// ##### Examples
+    /// In the below example, ready events are induced by connecting to a TCP listener:
+    /// ```
+    /// use tokio::net::{TcpListener, TcpStream};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let handle = tokio::runtime::Handle::current();
+    ///     let monitor = tokio_metrics::RuntimeMonitor::new(&handle);
+    ///     let mut intervals = monitor.intervals();
+    ///     let mut next_interval = || intervals.next().unwrap();
+    ///
+    ///     let interval = next_interval(); // end of first sampling interval
+    ///     assert_eq!(interval.io_driver_ready_count, 0);
+    ///
+    ///     let listener = TcpListener::bind("127.0.0.1:0").await?;
+    ///     let addr = listener.local_addr()?;
+    ///     let (accepted, connected) = tokio::join!(listener.accept(), TcpStream::connect(addr));
+    ///     let _ = (accepted?, connected?);
+    ///
+    ///     let interval = next_interval(); // end of second sampling interval
+    ///     assert!(interval.io_driver_ready_count >= 1);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "net")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "net")))]
+    pub io_driver_ready_count: u64,
+
     /// The number of tasks scheduled from worker threads.
     ///
     /// The local schedule count increases by one each time a task is woken from **inside** of the
@@ -833,6 +927,15 @@ pub struct RuntimeMetrics {
     /// ```
     pub injection_queue_depth: usize,
 
+    /// The number of tasks in the runtime's injection queue at the start of the interval.
+    ///
+    /// For [cumulative][RuntimeMonitor::cumulative] snapshots, this metric is zero.
+    ///
+    /// ##### See also
+    /// - [`RuntimeMetrics::injection_queue_depth`]
+    /// - [`RuntimeMetrics::injection_queue_growth`]
+    pub injection_queue_depth_at_start: usize,
+
     /// The total number of tasks currently scheduled in workers' local queues.
     ///
     /// Tasks that are spawned or notified from within a runtime thread are scheduled using that
@@ -943,7 +1046,54 @@ pub struct RuntimeMetrics {
     pub elapsed: Duration,
 }
 
+#[cfg(any(docsrs, all(tokio_unstable, feature = "rt")))]
+#[cfg_attr(docsrs, doc(cfg(all(tokio_unstable, feature = "rt"))))]
+/// Key metrics of a single worker thread, as reported by [`RuntimeMonitor::worker_intervals`].
+#[non_exhaustive]
+#[derive(Default, Debug, Clone, Copy)]
+pub struct WorkerMetrics {
+    /// The index of the worker thread, in `0..workers_count`.
+    pub worker: usize,
+
+    /// The amount of time the worker thread has been busy since the runtime was started.
+    ///
+    /// ##### Definition
+    /// This metric is equal to [`tokio::runtime::RuntimeMetrics::worker_total_busy_duration`].
+    pub total_busy_duration: Duration,
+
+    /// The amount of time the worker thread was busy within the interval; i.e., the delta of
+    /// [`WorkerMetrics::total_busy_duration`].
+    ///
+    /// ##### See also
+    /// - [`WorkerMetrics::busy_ratio`]
+    pub busy_duration: Duration,
+
+    /// The number of times the worker thread parked within the interval.
+    ///
+    /// ##### Definition
+    /// This metric is derived from the delta of
+    /// [`tokio::runtime::RuntimeMetrics::worker_park_count`].
+    pub park_count: u64,
+
+    /// The number of times the worker thread unparked but performed no work before parking
+    /// again, within the interval; e.g., because of a spurious wake up, or a timer that fired
+    /// without waking a task.
+    ///
+    /// ##### Definition
+    /// This metric is derived from the delta of
+    /// [`tokio::runtime::RuntimeMetrics::worker_noop_count`].
+    ///
+    /// ##### See also
+    /// - [`RuntimeMetrics::total_noop_count`]
+    /// - [`WorkerMetrics::noop_ratio`]
+    pub noop_count: u64,
+
+    /// Total amount of time elapsed within the interval.
+    pub elapsed: Duration,
+}
+
 /// Snapshot of per-worker metrics
+#[derive(Default)]
 struct Worker {
     worker: usize,
     total_park_count: u64,
@@ -961,71 +1111,351 @@ impl RuntimeMonitor {
 
         RuntimeMonitor {
             runtime,
+            poll_bucket_thresholds: None,
+            started_at: Instant::now(),
         }
     }
 
+    /// Produces the [`RuntimeMetrics`] of the runtime since it was started; e.g., for pollers
+    /// that compute intervals themselves, as the difference of two cumulative snapshots.
+    ///
+    /// The counts and durations are those of the runtime since it was started, and
+    /// [`elapsed`][RuntimeMetrics::elapsed] is the time elapsed since the construction of this
+    /// monitor. The maxima and minima across workers are of those cumulative values.
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let handle = tokio::runtime::Handle::current();
+    ///     let monitor = tokio_metrics::RuntimeMonitor::new(&handle);
+    ///
+    ///     let previous = monitor.cumulative();
+    ///     tokio::spawn(async {}).await.unwrap();
+    ///     let latest = monitor.cumulative();
+    ///
+    ///     let interval = latest - previous;
+    ///     assert_eq!(interval.total_polls_count, 1);
+    ///     assert!(interval.elapsed <= latest.elapsed);
+    /// }
+    /// ```
+    pub fn cumulative(&self) -> RuntimeMetrics {
+        Intervals::new(&self.runtime, self.started_at, true).probe()
+    }
+
+    /// Maps the buckets of the runtime's poll-time histogram onto latency buckets delimited by
+    /// the given `thresholds`, as reported by [`RuntimeMonitor::poll_bucket_intervals`].
+    ///
+    /// `n` thresholds delimit `n + 1` buckets, as with
+    /// [`TaskMonitorBuilder::with_poll_buckets`][crate::TaskMonitorBuilder::with_poll_buckets];
+    /// aligning the thresholds of a runtime and its task monitors puts their latency views on
+    /// the same scale. Each bucket of the runtime's histogram is counted, as a whole, in the
+    /// bucket containing its lower bound, so thresholds are effectively rounded down to the
+    /// histogram's resolution. The thresholds need not be given in order.
+    ///
+    /// If no thresholds are configured, the buckets are those of the runtime's histogram.
+    pub fn with_poll_buckets<I>(mut self, thresholds: I) -> RuntimeMonitor
+    where
+        I: IntoIterator<Item = Duration>,
+    {
+        let mut thresholds: Vec<Duration> = thresholds.into_iter().collect();
+        thresholds.sort();
+        thresholds.dedup();
+        self.poll_bucket_thresholds = Some(thresholds);
+        self
+    }
+
+    /// Produces an unending iterator of the [`PollBucket`]s of each worker thread over sampling
+    /// intervals, from the runtime's poll-time histogram, like [`RuntimeMonitor::intervals`].
+    ///
+    /// Each item holds the buckets of each worker, indexed by worker. The histogram must be
+    /// enabled when building the runtime, with
+    /// [`enable_metrics_poll_count_histogram`][tokio::runtime::Builder::enable_metrics_poll_count_histogram];
+    /// otherwise, the workers have no buckets. The runtime only counts polls, so the
+    /// `total_duration` of each bucket is zero, and it has no `exemplar`.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// fn main() {
+    ///     let runtime = tokio::runtime::Builder::new_current_thread()
+    ///         .enable_all()
+    ///         .enable_metrics_poll_count_histogram()
+    ///         .metrics_poll_count_histogram_resolution(Duration::from_millis(1))
+    ///         .build()
+    ///         .unwrap();
+    ///
+    ///     runtime.block_on(async {
+    ///         let handle = tokio::runtime::Handle::current();
+    ///         let monitor = tokio_metrics::RuntimeMonitor::new(&handle)
+    ///             .with_poll_buckets([Duration::from_millis(5)]);
+    ///         let mut intervals = monitor.poll_bucket_intervals();
+    ///
+    ///         tokio::spawn(async {
+    ///             std::thread::sleep(Duration::from_millis(10)); // a slow poll
+    ///         }).await.unwrap();
+    ///
+    ///         let workers = intervals.next().unwrap();
+    ///         assert_eq!(workers.len(), 1);
+    ///
+    ///         // [0, 5ms)
+    ///         assert_eq!(workers[0][0].upper_bound, Some(Duration::from_millis(5)));
+    ///         assert_eq!(workers[0][0].count, 0);
+    ///
+    ///         // [5ms, ∞)
+    ///         assert_eq!(workers[0][1].lower_bound, Duration::from_millis(5));
+    ///         assert_eq!(workers[0][1].count, 1);
+    ///     });
+    /// }
+    /// ```
+    pub fn poll_bucket_intervals(&self) -> impl Iterator<Item = Vec<Vec<PollBucket>>> {
+        let runtime = self.runtime.clone();
+        let (mapping, buckets) = self.poll_bucket_mapping();
+
+        let num_buckets = mapping.len();
+
+        let counts = move |runtime: &runtime::RuntimeMetrics| -> Vec<Vec<u64>> {
+            (0..runtime.num_workers())
+                .map(|worker| {
+                    (0..num_buckets)
+                        .map(|bucket| runtime.poll_count_histogram_bucket_count(worker, bucket))
+                        .collect()
+                })
+                .collect()
+        };
+        let mut previous = counts(&runtime);
+
+        std::iter::from_fn(move || {
+            let latest = counts(&runtime);
+            let next = latest
+                .iter()
+                .zip(&previous)
+                .map(|(latest, previous)| {
+                    let mut worker = buckets.clone();
+                    for ((&bucket, latest), previous) in mapping.iter().zip(latest).zip(previous) {
+                        worker[bucket].count += latest.wrapping_sub(*previous);
+                    }
+                    worker
+                })
+                .collect();
+            previous = latest;
+            Some(next)
+        })
+    }
+
+    /// Produces an unending iterator of the [`WorkerMetrics`] of each worker thread over
+    /// sampling intervals, like [`RuntimeMonitor::intervals`]; e.g., to chart the CPU time
+    /// attributable to each worker without OS-level tooling.
+    ///
+    /// Each item holds the metrics of each worker, indexed by worker. Workers publish their busy
+    /// durations to the runtime periodically, rather than after every poll, so busy time may be
+    /// attributed to the interval after the one in which it was spent.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+    /// async fn main() {
+    ///     let handle = tokio::runtime::Handle::current();
+    ///     let monitor = tokio_metrics::RuntimeMonitor::new(&handle);
+    ///     let mut intervals = monitor.worker_intervals();
+    ///
+    ///     tokio::spawn(async {
+    ///         std::thread::sleep(Duration::from_millis(10)); // keep a worker busy
+    ///     }).await.unwrap();
+    ///
+    ///     let workers = intervals.next().unwrap();
+    ///     assert_eq!(workers.len(), 2);
+    ///     assert_eq!(workers[1].worker, 1);
+    ///     for worker in &workers {
+    ///         assert!(worker.total_busy_duration >= worker.busy_duration);
+    ///     }
+    /// }
+    /// ```
+    pub fn worker_intervals(&self) -> impl Iterator<Item = Vec<WorkerMetrics>> {
+        let runtime = self.runtime.clone();
+        let mut started_at = Instant::now();
+        // the busy duration, park count and no-op count of each worker
+        let totals = |runtime: &runtime::RuntimeMetrics, worker| {
+            (
+                runtime.worker_total_busy_duration(worker),
+                runtime.worker_park_count(worker),
+                runtime.worker_noop_count(worker),
+            )
+        };
+        let mut previous: Vec<_> = (0..runtime.num_workers())
+            .map(|worker| totals(&runtime, worker))
+            .collect();
+
+        std::iter::from_fn(move || {
+            let now = Instant::now();
+            let elapsed = now - started_at;
+            started_at = now;
+
+            let next = previous
+                .iter_mut()
+                .enumerate()
+                .map(|(worker, previous)| {
+                    let latest = totals(&runtime, worker);
+                    let metrics = WorkerMetrics {
+                        worker,
+                        total_busy_duration: latest.0,
+                        busy_duration: latest.0.saturating_sub(previous.0),
+                        park_count: latest.1.wrapping_sub(previous.1),
+                        noop_count: latest.2.wrapping_sub(previous.2),
+                        elapsed,
+                    };
+                    *previous = latest;
+                    metrics
+                })
+                .collect();
+            Some(next)
+        })
+    }
+
+    /// Maps each bucket of the runtime's poll-time histogram onto the index of the bucket
+    /// containing its lower bound, and produces those (empty) buckets.
+    fn poll_bucket_mapping(&self) -> (Vec<usize>, Vec<PollBucket>) {
+        let rt = &self.runtime;
+        if !rt.poll_count_histogram_enabled() {
+            return (Vec::new(), Vec::new());
+        }
+
+        let lower_bounds: Vec<Duration> = (0..rt.poll_count_histogram_num_buckets())
+            .map(|bucket| rt.poll_count_histogram_bucket_range(bucket).start)
+            .collect();
+        let thresholds = match &self.poll_bucket_thresholds {
+            Some(thresholds) => thresholds.clone(),
+            None => lower_bounds.iter().skip(1).copied().collect(),
+        };
+
+        let mapping = lower_bounds
+            .iter()
+            .map(|&lower_bound| thresholds.partition_point(|&threshold| threshold <= lower_bound))
+            .collect();
+        let buckets = (0..=thresholds.len())
+            .map(|i| PollBucket {
+                lower_bound: if i == 0 { Duration::ZERO } else { thresholds[i - 1] },
+                upper_bound: thresholds.get(i).copied(),
+                ..Default::default()
+            })
+            .collect();
+        (mapping, buckets)
+    }
+
     pub fn intervals(&self) -> impl Iterator<Item = RuntimeMetrics> {
-        struct Iter {
-            runtime: runtime::RuntimeMetrics,
-            started_at: Instant,
-            workers: Vec<Worker>,
+        Intervals::new(&self.runtime, Instant::now(), false)
+    }
+}
+
+/// Samples the intervals of a runtime's metrics
+struct Intervals {
+    runtime: runtime::RuntimeMetrics,
+    started_at: Instant,
+    workers: Vec<Worker>,
 
-            // Number of tasks scheduled from *outside* of the runtime
-            num_remote_schedules: u64,
+    // Number of tasks scheduled from *outside* of the runtime
+    num_remote_schedules: u64,
+
+    // Number of times tasks were forced to yield by the cooperative budget
+    budget_forced_yield_count: u64,
+
+    // Number of tasks in the injection queue
+    injection_queue_depth: usize,
+
+    // Number of ready events processed by the I/O driver
+    #[cfg(feature = "net")]
+    io_driver_ready_count: u64,
+}
+
+impl Intervals {
+    /// Begins sampling at `started_at`, from the current values of the runtime's counters, or,
+    /// if `since_start`, from zero.
+    fn new(runtime: &runtime::RuntimeMetrics, started_at: Instant, since_start: bool) -> Intervals {
+        if since_start {
+            return Intervals {
+                runtime: runtime.clone(),
+                started_at,
+                workers: (0..runtime.num_workers())
+                    .map(|worker| Worker {
+                        worker,
+                        ..Default::default()
+                    })
+                    .collect(),
+                num_remote_schedules: 0,
+                budget_forced_yield_count: 0,
+                injection_queue_depth: 0,
+                #[cfg(feature = "net")]
+                io_driver_ready_count: 0,
+            };
         }
 
-        impl Iter {
-            fn probe(&mut self) -> RuntimeMetrics {
-                let now = Instant::now();
-
-                let num_remote_schedules = self.runtime.remote_schedule_count();
-
-                let mut metrics = RuntimeMetrics {
-                    workers_count: self.runtime.num_workers(),
-                    elapsed: now - self.started_at,
-                    injection_queue_depth: self.runtime.injection_queue_depth(),
-                    num_remote_schedules: num_remote_schedules - self.num_remote_schedules,
-                    min_park_count: u64::MAX,
-                    min_noop_count: u64::MAX,
-                    min_steal_count: u64::MAX,
-                    min_local_schedule_count: u64::MAX,
-                    min_overflow_count: u64::MAX,
-                    min_polls_count: u64::MAX,
-                    min_busy_duration: Duration::from_secs(1000000000),
-                    min_local_queue_depth: usize::MAX,
-                    .. Default::default()
-                };
-
-                self.num_remote_schedules = num_remote_schedules;
-                self.started_at = now;
-
-                for worker in &mut self.workers {
-                    worker.probe(&self.runtime, &mut metrics);
-                }
-        
-                metrics
-            }
+        Intervals {
+            runtime: runtime.clone(),
+            started_at,
+            workers: (0..runtime.num_workers())
+                .map(|worker| Worker::new(worker, runtime))
+                .collect(),
+            num_remote_schedules: runtime.remote_schedule_count(),
+            budget_forced_yield_count: runtime.budget_forced_yield_count(),
+            injection_queue_depth: runtime.injection_queue_depth(),
+            #[cfg(feature = "net")]
+            io_driver_ready_count: runtime.io_driver_ready_count(),
         }
+    }
 
-        impl Iterator for Iter {
-            type Item = RuntimeMetrics;
+    fn probe(&mut self) -> RuntimeMetrics {
+        let now = Instant::now();
 
-            fn next(&mut self) -> Option<RuntimeMetrics> {
-                Some(self.probe())
-            }
-        }
+        let num_remote_schedules = self.runtime.remote_schedule_count();
+        let budget_forced_yield_count = self.runtime.budget_forced_yield_count();
+        let injection_queue_depth = self.runtime.injection_queue_depth();
 
-        let started_at = Instant::now();
+        let mut metrics = RuntimeMetrics {
+            workers_count: self.runtime.num_workers(),
+            elapsed: now - self.started_at,
+            injection_queue_depth,
+            injection_queue_depth_at_start: self.injection_queue_depth,
+            num_remote_schedules: num_remote_schedules - self.num_remote_schedules,
+            budget_forced_yield_count: budget_forced_yield_count - self.budget_forced_yield_count,
+            min_park_count: u64::MAX,
+            min_noop_count: u64::MAX,
+            min_steal_count: u64::MAX,
+            min_local_schedule_count: u64::MAX,
+            min_overflow_count: u64::MAX,
+            min_polls_count: u64::MAX,
+            min_busy_duration: Duration::from_secs(1000000000),
+            min_local_queue_depth: usize::MAX,
+            .. Default::default()
+        };
 
-        let workers = (0..self.runtime.num_workers())
-            .map(|worker| Worker::new(worker, &self.runtime)).collect();
+        self.num_remote_schedules = num_remote_schedules;
+        self.budget_forced_yield_count = budget_forced_yield_count;
+        self.injection_queue_depth = injection_queue_depth;
 
-        Iter {
-            runtime: self.runtime.clone(),
-            started_at,
-            workers,
-            num_remote_schedules: self.runtime.remote_schedule_count(),
+        #[cfg(feature = "net")]
+        {
+            let io_driver_ready_count = self.runtime.io_driver_ready_count();
+            metrics.io_driver_ready_count = io_driver_ready_count - self.io_driver_ready_count;
+            self.io_driver_ready_count = io_driver_ready_count;
+        }
+        self.started_at = now;
+
+        for worker in &mut self.workers {
+            worker.probe(&self.runtime, &mut metrics);
         }
+
+        metrics
+    }
+}
+
+impl Iterator for Intervals {
+    type Item = RuntimeMetrics;
+
+    fn next(&mut self) -> Option<RuntimeMetrics> {
+        Some(self.probe())
     }
 }
 
@@ -1086,8 +1516,46 @@ impl Worker {
 }
 
 impl RuntimeMetrics {
+    /// The mean number of tasks polled by worker threads between parking, within the interval.
+    ///
+    /// This metric indicates whether workers are doing useful batches of work between parks, or
+    /// thrashing between parking and unparking. A ratio near or below one means that workers
+    /// often park after polling a single task; e.g., because work arrives in a trickle, or
+    /// because there are more workers than work. Parks that performed no work (see
+    /// [`RuntimeMetrics::total_noop_count`]) are excluded, so that false-positive wake ups do
+    /// not dilute the ratio.
+    ///
+    /// ##### Definition
+    /// This metric is derived from [`total_polls_count`][RuntimeMetrics::total_polls_count] ÷
+    /// ([`total_park_count`][RuntimeMetrics::total_park_count] -
+    /// [`total_noop_count`][RuntimeMetrics::total_noop_count]), across all worker threads. If
+    /// no worker parked after performing work, it is `0.0`.
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let handle = tokio::runtime::Handle::current();
+    ///     let monitor = tokio_metrics::RuntimeMonitor::new(&handle);
+    ///     let mut intervals = monitor.intervals();
+    ///     let mut next_interval = || intervals.next().unwrap();
+    ///
+    ///     let interval = next_interval(); // end of interval 1
+    ///     assert_eq!(interval.mean_polls_per_park(), 0.0);
+    ///
+    ///     // a batch of tasks, polled before the worker next parks
+    ///     let tasks: Vec<_> = (0..10).map(|_| tokio::spawn(async {})).collect();
+    ///     for task in tasks {
+    ///         task.await.unwrap();
+    ///     }
+    ///     tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+    ///
+    ///     let interval = next_interval(); // end of interval 2
+    ///     assert!(interval.mean_polls_per_park() >= 1.0);
+    /// }
+    /// ```
     pub fn mean_polls_per_park(&self) -> f64 {
-        let total_park_count = self.total_park_count - self.total_noop_count;
+        let total_park_count = self.total_park_count.saturating_sub(self.total_noop_count);
         if total_park_count == 0 {
             0.0
         } else {
@@ -1095,8 +1563,314 @@ impl RuntimeMetrics {
         }
     }
 
+    /// The ratio of the parks of worker threads that performed no work to all of their parks
+    /// within the interval; a high ratio suggests spurious wake ups, or excessive timer churn.
+    ///
+    /// ##### Definition
+    /// This metric is derived from [`RuntimeMetrics::total_noop_count`] ÷
+    /// [`RuntimeMetrics::total_park_count`]. If no worker parked, it is `0.0`.
+    ///
+    /// ##### See also
+    /// - [`WorkerMetrics::noop_ratio`]
+    pub fn noop_ratio(&self) -> f64 {
+        if self.total_park_count == 0 {
+            0.0
+        } else {
+            self.total_noop_count as f64 / self.total_park_count as f64
+        }
+    }
+
     pub fn busy_ratio(&self) -> f64 {
         self.total_busy_duration.as_nanos() as f64 /
             self.elapsed.as_nanos() as f64
     }
+
+    /// The mean ratio of the time worker threads were busy to the time elapsed within the
+    /// interval; i.e., the utilization of the runtime, from `0.0` (idle) to `1.0` (every worker
+    /// busy throughout).
+    ///
+    /// Unlike [`RuntimeMetrics::busy_ratio`], which sums the busy time of all workers, this
+    /// metric is comparable across runtimes with different numbers of workers.
+    ///
+    /// ##### Definition
+    /// This metric is derived from [`total_busy_duration`][RuntimeMetrics::total_busy_duration] ÷
+    /// ([`elapsed`][RuntimeMetrics::elapsed] × [`workers_count`][RuntimeMetrics::workers_count]).
+    /// If no time elapsed, it is `0.0`.
+    pub fn workers_busy_ratio(&self) -> f64 {
+        let capacity = self.elapsed.as_nanos() as f64 * self.workers_count as f64;
+        if capacity == 0.0 {
+            0.0
+        } else {
+            self.total_busy_duration.as_nanos() as f64 / capacity
+        }
+    }
+
+    /// The number of tasks by which the runtime's injection queue grew within the interval;
+    /// negative if it shrank. Sustained growth means that tasks are scheduled from outside of
+    /// the runtime faster than workers take them up.
+    ///
+    /// ##### Definition
+    /// This metric is derived from
+    /// [`injection_queue_depth`][RuntimeMetrics::injection_queue_depth] -
+    /// [`injection_queue_depth_at_start`][RuntimeMetrics::injection_queue_depth_at_start].
+    pub fn injection_queue_growth(&self) -> i64 {
+        self.injection_queue_depth as i64 - self.injection_queue_depth_at_start as i64
+    }
+
+    /// A composite score of the saturation of the runtime within the interval, from `0.0`
+    /// (idle) to `1.0` (saturated); e.g., for autoscalers and health checks that consume one
+    /// number.
+    ///
+    /// The score is the greater of two measures, each clamped to `1.0`:
+    ///
+    /// - **Utilization**
+    ///   The [`workers_busy_ratio`][RuntimeMetrics::workers_busy_ratio].
+    /// - **Backlog**
+    ///   The number of tasks queued at the end of the interval (in the injection queue and the
+    ///   local queues of workers), relative to the number of tasks polled within it; i.e., the
+    ///   fraction of an interval's throughput that is waiting. A backlog that workers polled
+    ///   nothing of saturates the score.
+    ///
+    /// Busy workers that keep up with their queues score their utilization; workers that fall
+    /// behind saturate the score, however busy they are.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::RuntimeMetrics;
+    ///
+    /// let mut interval = RuntimeMetrics::default();
+    /// interval.workers_count = 4;
+    /// interval.elapsed = Duration::from_secs(1);
+    /// interval.total_busy_duration = Duration::from_secs(2);
+    /// interval.total_polls_count = 1000;
+    /// assert_eq!(interval.saturation_score(), 0.5);
+    ///
+    /// // workers fall behind
+    /// interval.injection_queue_depth = 2000;
+    /// assert_eq!(interval.saturation_score(), 1.0);
+    /// ```
+    pub fn saturation_score(&self) -> f64 {
+        let utilization = self.workers_busy_ratio().min(1.0);
+        let queued = (self.injection_queue_depth + self.total_local_queue_depth) as f64;
+        let backlog = if queued == 0.0 {
+            0.0
+        } else if self.total_polls_count == 0 {
+            1.0
+        } else {
+            (queued / self.total_polls_count as f64).min(1.0)
+        };
+        utilization.max(backlog)
+    }
+
+    /// Records each of these metrics into a given [`MetricsSink`], qualified by `labels`.
+    ///
+    /// Each metric is recorded under the name of its field; e.g., `total_polls_count`. Derived
+    /// metrics are not recorded.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use tokio_metrics::{MetricValue, RuntimeMonitor};
+    ///
+    /// #[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+    /// async fn main() {
+    ///     let handle = tokio::runtime::Handle::current();
+    ///     let monitor = RuntimeMonitor::new(&handle);
+    ///     let mut intervals = monitor.intervals();
+    ///
+    ///     let sink = |name: &'static str, value: MetricValue, _: &[(&str, &str)]| {
+    ///         if name == "workers_count" {
+    ///             assert_eq!(value, MetricValue::Gauge(2));
+    ///         }
+    ///     };
+    ///
+    ///     intervals.next().unwrap().record_to(&sink, &[]);
+    /// }
+    /// ```
+    pub fn record_to(&self, sink: &dyn MetricsSink, labels: &[(&str, &str)]) {
+        use MetricValue::{Count, Duration, Gauge};
+
+        sink.record("workers_count", Gauge(self.workers_count as u64), labels);
+        sink.record("total_park_count", Count(self.total_park_count), labels);
+        sink.record("max_park_count", Count(self.max_park_count), labels);
+        sink.record("min_park_count", Count(self.min_park_count), labels);
+        sink.record("total_noop_count", Count(self.total_noop_count), labels);
+        sink.record("max_noop_count", Count(self.max_noop_count), labels);
+        sink.record("min_noop_count", Count(self.min_noop_count), labels);
+        sink.record("total_steal_count", Count(self.total_steal_count), labels);
+        sink.record("max_steal_count", Count(self.max_steal_count), labels);
+        sink.record("min_steal_count", Count(self.min_steal_count), labels);
+        sink.record("num_remote_schedules", Count(self.num_remote_schedules), labels);
+        sink.record("budget_forced_yield_count", Count(self.budget_forced_yield_count), labels);
+        #[cfg(feature = "net")]
+        sink.record("io_driver_ready_count", Count(self.io_driver_ready_count), labels);
+        sink.record("total_local_schedule_count", Count(self.total_local_schedule_count), labels);
+        sink.record("max_local_schedule_count", Count(self.max_local_schedule_count), labels);
+        sink.record("min_local_schedule_count", Count(self.min_local_schedule_count), labels);
+        sink.record("total_overflow_count", Count(self.total_overflow_count), labels);
+        sink.record("max_overflow_count", Count(self.max_overflow_count), labels);
+        sink.record("min_overflow_count", Count(self.min_overflow_count), labels);
+        sink.record("total_polls_count", Count(self.total_polls_count), labels);
+        sink.record("max_polls_count", Count(self.max_polls_count), labels);
+        sink.record("min_polls_count", Count(self.min_polls_count), labels);
+        sink.record("total_busy_duration", Duration(self.total_busy_duration), labels);
+        sink.record("max_busy_duration", Duration(self.max_busy_duration), labels);
+        sink.record("min_busy_duration", Duration(self.min_busy_duration), labels);
+        sink.record("injection_queue_depth", Gauge(self.injection_queue_depth as u64), labels);
+        sink.record(
+            "injection_queue_depth_at_start",
+            Gauge(self.injection_queue_depth_at_start as u64),
+            labels,
+        );
+        sink.record("total_local_queue_depth", Gauge(self.total_local_queue_depth as u64), labels);
+        sink.record("max_local_queue_depth", Gauge(self.max_local_queue_depth as u64), labels);
+        sink.record("min_local_queue_depth", Gauge(self.min_local_queue_depth as u64), labels);
+        sink.record("elapsed", Duration(self.elapsed), labels);
+    }
+}
+
+impl WorkerMetrics {
+    /// The ratio of the time the worker thread was busy to the time elapsed within the
+    /// interval.
+    ///
+    /// ##### Definition
+    /// This metric is derived from [`WorkerMetrics::busy_duration`] ÷
+    /// [`WorkerMetrics::elapsed`]. If no time elapsed, it is `0.0`.
+    pub fn busy_ratio(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            0.0
+        } else {
+            self.busy_duration.as_nanos() as f64 / self.elapsed.as_nanos() as f64
+        }
+    }
+
+    /// The ratio of the parks of the worker thread that performed no work to all of its parks
+    /// within the interval; a high ratio suggests spurious wake ups, or excessive timer churn.
+    ///
+    /// ##### Definition
+    /// This metric is derived from [`WorkerMetrics::noop_count`] ÷
+    /// [`WorkerMetrics::park_count`]. If the worker did not park, it is `0.0`.
+    pub fn noop_ratio(&self) -> f64 {
+        if self.park_count == 0 {
+            0.0
+        } else {
+            self.noop_count as f64 / self.park_count as f64
+        }
+    }
+}
+
+/// Computes the interval between two snapshots of the same runtime; e.g., two
+/// [cumulative][RuntimeMonitor::cumulative] snapshots. The counts, durations and elapsed time are
+/// differenced; the maxima and minima across workers, and the gauges, are those of `self`, except
+/// that the injection queue depth at the start of the interval is that of `rhs`.
+impl std::ops::Sub for RuntimeMetrics {
+    type Output = RuntimeMetrics;
+
+    fn sub(self, rhs: RuntimeMetrics) -> RuntimeMetrics {
+        RuntimeMetrics {
+            total_park_count: self.total_park_count.wrapping_sub(rhs.total_park_count),
+            total_noop_count: self.total_noop_count.wrapping_sub(rhs.total_noop_count),
+            total_steal_count: self.total_steal_count.wrapping_sub(rhs.total_steal_count),
+            num_remote_schedules: self
+                .num_remote_schedules
+                .wrapping_sub(rhs.num_remote_schedules),
+            budget_forced_yield_count: self
+                .budget_forced_yield_count
+                .wrapping_sub(rhs.budget_forced_yield_count),
+            #[cfg(feature = "net")]
+            io_driver_ready_count: self
+                .io_driver_ready_count
+                .wrapping_sub(rhs.io_driver_ready_count),
+            total_local_schedule_count: self
+                .total_local_schedule_count
+                .wrapping_sub(rhs.total_local_schedule_count),
+            total_overflow_count: self
+                .total_overflow_count
+                .wrapping_sub(rhs.total_overflow_count),
+            total_polls_count: self.total_polls_count.wrapping_sub(rhs.total_polls_count),
+            total_busy_duration: self
+                .total_busy_duration
+                .saturating_sub(rhs.total_busy_duration),
+            injection_queue_depth_at_start: rhs.injection_queue_depth,
+            elapsed: self.elapsed.saturating_sub(rhs.elapsed),
+            ..self
+        }
+    }
+}
+
+/// Aggregates the snapshots of two runtimes over the same interval; e.g., across the runtimes of
+/// a process, or of a fleet. The counts, durations and gauges are summed; the maxima and minima
+/// across workers are those across the workers of both runtimes; and the elapsed time is the
+/// longer of the two.
+///
+/// ##### Examples
+/// ```
+/// let io = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let compute = tokio::runtime::Builder::new_multi_thread()
+///     .worker_threads(2)
+///     .build()
+///     .unwrap();
+///
+/// let io = tokio_metrics::RuntimeMonitor::new(io.handle());
+/// let compute = tokio_metrics::RuntimeMonitor::new(compute.handle());
+///
+/// let process = io.cumulative() + compute.cumulative();
+/// assert_eq!(process.workers_count, 3);
+/// ```
+impl std::ops::Add for RuntimeMetrics {
+    type Output = RuntimeMetrics;
+
+    fn add(self, rhs: RuntimeMetrics) -> RuntimeMetrics {
+        RuntimeMetrics {
+            workers_count: self.workers_count + rhs.workers_count,
+            total_park_count: self.total_park_count.wrapping_add(rhs.total_park_count),
+            max_park_count: self.max_park_count.max(rhs.max_park_count),
+            min_park_count: self.min_park_count.min(rhs.min_park_count),
+            total_noop_count: self.total_noop_count.wrapping_add(rhs.total_noop_count),
+            max_noop_count: self.max_noop_count.max(rhs.max_noop_count),
+            min_noop_count: self.min_noop_count.min(rhs.min_noop_count),
+            total_steal_count: self.total_steal_count.wrapping_add(rhs.total_steal_count),
+            max_steal_count: self.max_steal_count.max(rhs.max_steal_count),
+            min_steal_count: self.min_steal_count.min(rhs.min_steal_count),
+            num_remote_schedules: self
+                .num_remote_schedules
+                .wrapping_add(rhs.num_remote_schedules),
+            budget_forced_yield_count: self
+                .budget_forced_yield_count
+                .wrapping_add(rhs.budget_forced_yield_count),
+            #[cfg(feature = "net")]
+            io_driver_ready_count: self
+                .io_driver_ready_count
+                .wrapping_add(rhs.io_driver_ready_count),
+            total_local_schedule_count: self
+                .total_local_schedule_count
+                .wrapping_add(rhs.total_local_schedule_count),
+            max_local_schedule_count: self
+                .max_local_schedule_count
+                .max(rhs.max_local_schedule_count),
+            min_local_schedule_count: self
+                .min_local_schedule_count
+                .min(rhs.min_local_schedule_count),
+            total_overflow_count: self
+                .total_overflow_count
+                .wrapping_add(rhs.total_overflow_count),
+            max_overflow_count: self.max_overflow_count.max(rhs.max_overflow_count),
+            min_overflow_count: self.min_overflow_count.min(rhs.min_overflow_count),
+            total_polls_count: self.total_polls_count.wrapping_add(rhs.total_polls_count),
+            max_polls_count: self.max_polls_count.max(rhs.max_polls_count),
+            min_polls_count: self.min_polls_count.min(rhs.min_polls_count),
+            total_busy_duration: self
+                .total_busy_duration
+                .saturating_add(rhs.total_busy_duration),
+            max_busy_duration: self.max_busy_duration.max(rhs.max_busy_duration),
+            min_busy_duration: self.min_busy_duration.min(rhs.min_busy_duration),
+            injection_queue_depth: self.injection_queue_depth + rhs.injection_queue_depth,
+            injection_queue_depth_at_start: self.injection_queue_depth_at_start
+                + rhs.injection_queue_depth_at_start,
+            total_local_queue_depth: self.total_local_queue_depth + rhs.total_local_queue_depth,
+            max_local_queue_depth: self.max_local_queue_depth.max(rhs.max_local_queue_depth),
+            min_local_queue_depth: self.min_local_queue_depth.min(rhs.min_local_queue_depth),
+            elapsed: self.elapsed.max(rhs.elapsed),
+        }
+    }
 }
@@ -0,0 +1,293 @@
+use crate::{MetricKind, MetricValue, ReportError, Reporter, TaskMetrics, TaskMonitor};
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// A minimal HTTP server, started by [`Reporter::serve`], that serves the cumulative metrics of
+/// a reporter's monitors at `/metrics`.
+///
+/// The server runs on a thread of its own, independently of any tokio runtime, until it is
+/// [shut down][MetricsServer::shutdown]; dropping it leaves it running.
+///
+/// **This functionality requires the crate feature `server`.**
+#[derive(Debug)]
+pub struct MetricsServer {
+    local_addr: SocketAddr,
+    shutdown: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+/// How long to wait for a client to send its request.
+pub(crate) const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for a client to accept the response; since connections are served one at a
+/// time, a client that stops reading would otherwise stall all others, and shutdown.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl Reporter {
+    /// Serves the cumulative metrics of the monitors registered with this reporter at
+    /// `http://{addr}/metrics`, in the Prometheus text exposition format.
+    ///
+    /// Each metric is labeled by the name of its monitor (as `monitor`); durations are served in
+    /// seconds. The [poll buckets][TaskMonitor::poll_buckets] of monitors for which they are
+    /// configured are served as a histogram, `poll_duration_seconds`. Monitors registered after
+    /// this call are not served.
+    ///
+    /// Clients that accept `application/openmetrics-text` are served the OpenMetrics text format
+    /// instead, in which each bucket of the histogram is annotated with its
+    /// [exemplar][crate::PollBucket::exemplar], if any: the duration, time and span id of its
+    /// most recent slow poll.
+    ///
+    /// This is intended for batch jobs and sidecars that do not otherwise embed a web server; it
+    /// depends on no HTTP framework, and serves one connection at a time.
+    ///
+    /// **This functionality requires the crate feature `server`.**
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::io::{Read, Write};
+    /// use std::net::TcpStream;
+    /// use tokio_metrics::Reporter;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut reporter = Reporter::new();
+    ///     let monitor = reporter.monitor("requests");
+    ///     let server = reporter.serve("127.0.0.1:0")?;
+    ///
+    ///     monitor.instrument(async {}).await;
+    ///
+    ///     let mut stream = TcpStream::connect(server.local_addr())?;
+    ///     stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")?;
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response)?;
+    ///
+    ///     assert!(response.starts_with("HTTP/1.1 200 OK"));
+    ///     assert!(response.contains("# TYPE total_poll_count counter"));
+    ///     assert!(response.contains("total_poll_count{monitor=\"requests\"} 1"));
+    ///
+    ///     server.shutdown();
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn serve(&self, addr: impl ToSocketAddrs) -> io::Result<MetricsServer> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let monitors: Vec<(String, TaskMonitor)> = self
+            .monitors()
+            .map(|(name, monitor)| (name.to_owned(), monitor.clone()))
+            .collect();
+        let on_error = self.on_error.clone();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let shutdown = shutdown.clone();
+            std::thread::Builder::new()
+                .name("tokio-metrics-server".into())
+                .spawn(move || {
+                    for stream in listener.incoming() {
+                        if shutdown.load(SeqCst) {
+                            break;
+                        }
+                        let responded = stream.and_then(|stream| respond(stream, &monitors));
+                        if let (Err(error), Some(on_error)) = (responded, &on_error) {
+                            on_error(&ReportError::Sink(error));
+                        }
+                    }
+                })?
+        };
+        Ok(MetricsServer {
+            local_addr,
+            shutdown,
+            thread,
+        })
+    }
+}
+
+impl MetricsServer {
+    /// Produces the address on which this server listens.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stops this server, and waits for it to finish serving its current connection.
+    pub fn shutdown(self) {
+        self.shutdown.store(true, SeqCst);
+        // wake the listener, which is blocked accepting connections
+        let _ = TcpStream::connect(self.local_addr);
+        let _ = self.thread.join();
+    }
+}
+
+/// Responds to the request on `stream`.
+fn respond(stream: TcpStream, monitors: &[(String, TaskMonitor)]) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // of the headers, only `Accept` is of interest
+    let mut format = Format::Prometheus;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        let mut parts = header.splitn(2, ':');
+        if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+            if name.trim().eq_ignore_ascii_case("accept")
+                && value.contains("application/openmetrics-text")
+            {
+                format = Format::OpenMetrics;
+            }
+        }
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", encode(monitors, format)),
+        (Some("GET"), Some(_)) => ("404 Not Found", String::new()),
+        _ => ("405 Method Not Allowed", String::new()),
+    };
+    let content_type = match format {
+        Format::Prometheus => "text/plain; version=0.0.4",
+        Format::OpenMetrics => "application/openmetrics-text; version=1.0.0; charset=utf-8",
+    };
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\n\
+        Content-Type: {}\r\n\
+        Content-Length: {}\r\n\
+        Connection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// The exposition formats in which metrics are served.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    /// The Prometheus text exposition format.
+    Prometheus,
+
+    /// The OpenMetrics text format, which supports exemplars.
+    OpenMetrics,
+}
+
+/// Encodes the cumulative metrics of `monitors` in the given `format`.
+pub(crate) fn encode(monitors: &[(String, TaskMonitor)], format: Format) -> String {
+    // the values of each monitor, in the order of the descriptors
+    let values: Vec<Vec<MetricValue>> = monitors
+        .iter()
+        .map(|(_, monitor)| {
+            let values = std::cell::RefCell::new(Vec::new());
+            monitor.cumulative().record_to(
+                &|_: &'static str, value: MetricValue, _: &[(&str, &str)]| {
+                    values.borrow_mut().push(value)
+                },
+                &[],
+            );
+            values.into_inner()
+        })
+        .collect();
+
+    let mut body = String::new();
+    for (index, descriptor) in TaskMetrics::descriptors().iter().enumerate() {
+        let kind = match descriptor.kind {
+            MetricKind::Count | MetricKind::Duration => "counter",
+            _ => "gauge",
+        };
+        // the samples of OpenMetrics counters are suffixed with `_total`
+        let suffix = match (format, kind) {
+            (Format::OpenMetrics, "counter") => "_total",
+            _ => "",
+        };
+        let _ = writeln!(body, "# HELP {} {}", descriptor.name, descriptor.help);
+        let _ = writeln!(body, "# TYPE {} {}", descriptor.name, kind);
+        for ((name, _), values) in monitors.iter().zip(&values) {
+            let value = match values[index] {
+                MetricValue::Count(count) | MetricValue::Gauge(count) => count as f64,
+                MetricValue::Duration(duration) | MetricValue::MaxDuration(duration) => {
+                    duration.as_secs_f64()
+                }
+                MetricValue::Ratio(ratio) => ratio,
+            };
+            let _ = writeln!(
+                body,
+                "{}{}{{monitor={:?}}} {}",
+                descriptor.name, suffix, name, value
+            );
+        }
+    }
+    encode_poll_durations(&mut body, monitors, format);
+    if format == Format::OpenMetrics {
+        body.push_str("# EOF\n");
+    }
+    body
+}
+
+/// Encodes the [poll buckets][TaskMonitor::poll_buckets] of `monitors` as a histogram of poll
+/// durations; in OpenMetrics, each bucket is annotated with its exemplar, if any.
+fn encode_poll_durations(body: &mut String, monitors: &[(String, TaskMonitor)], format: Format) {
+    const NAME: &str = "poll_duration_seconds";
+    let monitors: Vec<_> = monitors
+        .iter()
+        .map(|(name, monitor)| (name, monitor.poll_buckets()))
+        .filter(|(_, buckets)| !buckets.is_empty())
+        .collect();
+    if monitors.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(body, "# HELP {} The durations of polls.", NAME);
+    let _ = writeln!(body, "# TYPE {} histogram", NAME);
+    for (name, buckets) in monitors {
+        let (mut count, mut sum) = (0, Duration::ZERO);
+        for bucket in buckets {
+            count += bucket.count;
+            sum += bucket.total_duration;
+            let le = match bucket.upper_bound {
+                Some(upper_bound) => upper_bound.as_secs_f64().to_string(),
+                None => "+Inf".to_string(),
+            };
+            let _ = write!(
+                body,
+                "{}_bucket{{monitor={:?},le={:?}}} {}",
+                NAME, name, le, count
+            );
+            match (format, bucket.exemplar) {
+                (Format::OpenMetrics, Some(exemplar)) => {
+                    let labels = match exemplar.span_id {
+                        Some(span_id) => format!("span_id=\"{:016x}\"", span_id),
+                        None => String::new(),
+                    };
+                    let timestamp = exemplar
+                        .completed_at
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default();
+                    let _ = writeln!(
+                        body,
+                        " # {{{}}} {} {}",
+                        labels,
+                        exemplar.duration.as_secs_f64(),
+                        timestamp.as_secs_f64()
+                    );
+                }
+                _ => body.push('\n'),
+            }
+        }
+        let _ = writeln!(body, "{}_count{{monitor={:?}}} {}", NAME, name, count);
+        let _ = writeln!(
+            body,
+            "{}_sum{{monitor={:?}}} {}",
+            NAME,
+            name,
+            sum.as_secs_f64()
+        );
+    }
+}
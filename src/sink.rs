@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use std::io;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering::AcqRel, Ordering::Acquire};
+use std::sync::RwLock;
+
+#[cfg(feature = "rt")]
+use tokio::time::Duration;
+
+#[cfg(not(feature = "rt"))]
+use std::time::Duration;
+
+/// A destination for metrics; e.g., a telemetry backend.
+///
+/// Implement this trait to export metrics to a backend for which no first-party exporter exists.
+/// Metrics are handed to a sink one at a time by, e.g., [`TaskMetrics::record_to`][crate::TaskMetrics::record_to].
+///
+/// This trait is object-safe; sinks may be used as `&dyn MetricsSink`, and boxed or shared sinks
+/// (`Box<dyn MetricsSink + Send + Sync>` and `Arc<dyn MetricsSink + Send + Sync>`) are sinks in
+/// turn, so that, e.g., a sink chosen at runtime may be handed to
+/// [`Reporter::with_sink`][crate::Reporter::with_sink]. It is also implemented for closures
+/// accepting the same arguments as [`MetricsSink::record`].
+///
+/// ##### Examples
+/// ```
+/// use std::sync::Mutex;
+/// use tokio_metrics::{MetricValue, MetricsSink, TaskMonitor};
+///
+/// /// Accumulates metrics into lines of text.
+/// #[derive(Default)]
+/// struct LineSink(Mutex<Vec<String>>);
+///
+/// impl MetricsSink for LineSink {
+///     fn record(&self, name: &'static str, value: MetricValue, labels: &[(&str, &str)]) {
+///         let labels: Vec<String> = labels.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+///         let line = format!("{}{{{}}} {:?}", name, labels.join(","), value);
+///         self.0.lock().unwrap().push(line);
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let monitor = TaskMonitor::new();
+///     monitor.instrument(async {}).await;
+///
+///     let sink = LineSink::default();
+///     monitor.cumulative().record_to(&sink, &[("endpoint", "root")]);
+///
+///     let lines = sink.0.into_inner().unwrap();
+///     assert!(lines.contains(&"instrumented_count{endpoint=root} Count(1)".to_string()));
+/// }
+/// ```
+pub trait MetricsSink {
+    /// Records the value of the metric `name`, qualified by the given `labels`.
+    fn record(&self, name: &'static str, value: MetricValue, labels: &[(&str, &str)]);
+
+    /// Flushes the metrics recorded since the last flush (e.g., from a buffer to a file or
+    /// socket), and produces any error encountered in recording them.
+    ///
+    /// A [`Reporter`][crate::Reporter] flushes its sink after each report, and handles errors as
+    /// [configured][crate::Reporter::with_error_handler]. The default implementation does
+    /// nothing.
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The value of a metric, as recorded by a [`MetricsSink`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricValue {
+    /// A count of events; e.g., [`total_poll_count`][crate::TaskMetrics::total_poll_count].
+    Count(u64),
+
+    /// An instantaneous measurement; e.g., the depth of a queue.
+    Gauge(u64),
+
+    /// An amount of time; e.g., [`total_poll_duration`][crate::TaskMetrics::total_poll_duration].
+    Duration(Duration),
+
+    /// A proportion; e.g., [`slow_poll_ratio`][crate::TaskMetrics::slow_poll_ratio].
+    Ratio(f64),
+
+    /// The greatest amount of time taken by a single event; e.g.,
+    /// [`max_scheduled_duration`][crate::TaskMetrics::max_scheduled_duration].
+    MaxDuration(Duration),
+}
+
+/// The kind of a metric; i.e., the variant of [`MetricValue`] with which it is recorded.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetricKind {
+    /// A count of events, recorded as [`MetricValue::Count`].
+    Count,
+
+    /// An instantaneous measurement, recorded as [`MetricValue::Gauge`].
+    Gauge,
+
+    /// An amount of time, recorded as [`MetricValue::Duration`].
+    Duration,
+
+    /// A proportion, recorded as [`MetricValue::Ratio`].
+    Ratio,
+
+    /// The greatest amount of time taken by a single event, recorded as
+    /// [`MetricValue::MaxDuration`].
+    MaxDuration,
+}
+
+/// Describes a metric, as produced by [`TaskMetrics::descriptors`][crate::TaskMetrics::descriptors].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MetricDescriptor {
+    /// The name under which the metric is recorded; e.g., `total_poll_count`.
+    pub name: &'static str,
+
+    /// The kind of the metric.
+    pub kind: MetricKind,
+
+    /// A one-sentence description of the metric, suitable for, e.g., the `HELP` line of a
+    /// Prometheus exposition.
+    pub help: &'static str,
+}
+
+/// A [`MetricsSink`] that qualifies the metrics it records with a namespace and static labels,
+/// before forwarding them to another sink.
+///
+/// Configure the prefix (e.g., `tokio_task_`) and labels (e.g., `service="api"`) of an exporter
+/// once, rather than renaming and relabeling its metrics downstream. Static labels precede the
+/// labels with which each metric is recorded.
+///
+/// The prefixed name of each metric is allocated the first time the metric is recorded with a
+/// given prefix, by any sink, and lives for the remainder of the program; it is shared by every
+/// sink with the same prefix, so constructing sinks repeatedly does not allocate anew.
+///
+/// ##### Examples
+/// ```
+/// use std::sync::Mutex;
+/// use tokio_metrics::{MetricValue, NamespacedSink, TaskMonitor};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let lines = Mutex::new(Vec::new());
+///     let sink = NamespacedSink::new(
+///         |name: &'static str, _: MetricValue, labels: &[(&str, &str)]| {
+///             let labels: Vec<String> = labels.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+///             lines.lock().unwrap().push(format!("{}{{{}}}", name, labels.join(",")));
+///         },
+///         "tokio_task_",
+///     )
+///     .with_label("service", "api");
+///
+///     let monitor = TaskMonitor::new();
+///     monitor.instrument(async {}).await;
+///     monitor.cumulative().record_to(&sink, &[("endpoint", "root")]);
+///
+///     let lines = lines.lock().unwrap();
+///     assert!(lines.contains(&"tokio_task_total_poll_count{service=api,endpoint=root}".to_string()));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct NamespacedSink<S> {
+    inner: S,
+    prefix: &'static str,
+    labels: Vec<(String, String)>,
+}
+
+/// The prefixed names of the metrics recorded by [`NamespacedSink`]s so far, by prefix and name.
+type PrefixedNames = RwLock<HashMap<(&'static str, &'static str), &'static str>>;
+
+impl<S: MetricsSink> NamespacedSink<S> {
+    /// Constructs a sink that prefixes the name of each metric with `prefix`, before forwarding
+    /// it to `inner`.
+    pub fn new(inner: S, prefix: &'static str) -> NamespacedSink<S> {
+        NamespacedSink {
+            inner,
+            prefix,
+            labels: Vec::new(),
+        }
+    }
+
+    /// Qualifies every metric recorded by this sink with the label `key`=`value`.
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.push((key.into(), value.into()));
+        self
+    }
+
+    /// Produces a reference to the wrapped sink.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Consumes this sink, producing the wrapped sink.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn prefixed(&self, name: &'static str) -> &'static str {
+        if self.prefix.is_empty() {
+            return name;
+        }
+        let names = prefixed_names();
+        let key = (self.prefix, name);
+        if let Some(prefixed) = names.read().unwrap().get(&key) {
+            return prefixed;
+        }
+        let mut names = names.write().unwrap();
+        names
+            .entry(key)
+            .or_insert_with(|| Box::leak(format!("{}{}", self.prefix, name).into_boxed_str()))
+    }
+}
+
+/// Produces the prefixed names shared by all [`NamespacedSink`]s, allocating them on first use.
+fn prefixed_names() -> &'static PrefixedNames {
+    static NAMES: AtomicPtr<PrefixedNames> = AtomicPtr::new(ptr::null_mut());
+
+    let names = NAMES.load(Acquire);
+    if !names.is_null() {
+        // SAFETY: once published, the names are never freed
+        return unsafe { &*names };
+    }
+    let allocated = Box::into_raw(Box::new(PrefixedNames::default()));
+    match NAMES.compare_exchange(ptr::null_mut(), allocated, AcqRel, Acquire) {
+        // SAFETY: the names were just published, and are never freed
+        Ok(_) => unsafe { &*allocated },
+        Err(published) => {
+            // another thread published its names first
+            // SAFETY: `allocated` was never published, so this is its only owner
+            drop(unsafe { Box::from_raw(allocated) });
+            // SAFETY: once published, the names are never freed
+            unsafe { &*published }
+        }
+    }
+}
+
+impl<S: MetricsSink> MetricsSink for NamespacedSink<S> {
+    fn record(&self, name: &'static str, value: MetricValue, labels: &[(&str, &str)]) {
+        let name = self.prefixed(name);
+        if self.labels.is_empty() {
+            return self.inner.record(name, value, labels);
+        }
+        let labels: Vec<(&str, &str)> = self
+            .labels
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .chain(labels.iter().copied())
+            .collect();
+        self.inner.record(name, value, &labels);
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl MetricValue {
+    /// Produces the kind of this value.
+    pub fn kind(&self) -> MetricKind {
+        match self {
+            MetricValue::Count(_) => MetricKind::Count,
+            MetricValue::Gauge(_) => MetricKind::Gauge,
+            MetricValue::Duration(_) => MetricKind::Duration,
+            MetricValue::Ratio(_) => MetricKind::Ratio,
+            MetricValue::MaxDuration(_) => MetricKind::MaxDuration,
+        }
+    }
+}
+
+impl<F> MetricsSink for F
+where
+    F: Fn(&'static str, MetricValue, &[(&str, &str)]),
+{
+    fn record(&self, name: &'static str, value: MetricValue, labels: &[(&str, &str)]) {
+        self(name, value, labels)
+    }
+}
+
+impl MetricsSink for Box<dyn MetricsSink + Send + Sync> {
+    fn record(&self, name: &'static str, value: MetricValue, labels: &[(&str, &str)]) {
+        (**self).record(name, value, labels)
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        (**self).flush()
+    }
+}
+
+impl MetricsSink for std::sync::Arc<dyn MetricsSink + Send + Sync> {
+    fn record(&self, name: &'static str, value: MetricValue, labels: &[(&str, &str)]) {
+        (**self).record(name, value, labels)
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        (**self).flush()
+    }
+}
@@ -0,0 +1,860 @@
+use std::cmp::Ordering;
+use std::f64::consts::PI;
+
+#[cfg(feature = "rt")]
+use tokio::time::Duration;
+
+#[cfg(not(feature = "rt"))]
+use std::time::Duration;
+
+/// A mergeable summary of a distribution of latencies, from which quantiles can be estimated, as
+/// recorded by [`TaskMonitorBuilder::with_latency_sketch`][crate::TaskMonitorBuilder::with_latency_sketch].
+///
+/// Sketches of the same kind and parameters may be [merged][LatencySketch::merge]; e.g., across
+/// monitors, or across sampling intervals by a central aggregator.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum LatencySketch {
+    /// A [`DDSketch`].
+    DDSketch(DDSketch),
+
+    /// A [`TDigest`].
+    TDigest(TDigest),
+
+    /// An [`ExponentialHistogram`].
+    ExponentialHistogram(ExponentialHistogram),
+
+    /// An [`HdrHistogram`].
+    #[cfg(feature = "hdrhistogram")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "hdrhistogram")))]
+    HdrHistogram(HdrHistogram),
+}
+
+/// A quantile sketch with relative-error guarantees: every quantile it estimates is within a
+/// configured relative accuracy of the true quantile.
+///
+/// Latencies are counted in logarithmically-sized bins; the sketch's size grows with the
+/// logarithm of the range of latencies recorded, not with their number. Sketches with the same
+/// relative accuracy merge exactly.
+///
+/// ##### Examples
+/// ```
+/// use std::time::Duration;
+/// use tokio_metrics::DDSketch;
+///
+/// let mut sketch = DDSketch::new(0.01);
+/// for micros in 1..=1000 {
+///     sketch.record(Duration::from_micros(micros));
+/// }
+///
+/// let mut other = DDSketch::new(0.01);
+/// other.record(Duration::from_secs(1));
+/// sketch.merge(&other);
+///
+/// assert_eq!(sketch.count(), 1001);
+/// let median = sketch.quantile(0.5).unwrap().as_secs_f64();
+/// assert!((median - 500e-6).abs() <= 0.01 * 500e-6);
+/// let max = sketch.quantile(1.0).unwrap().as_secs_f64();
+/// assert!((max - 1.0).abs() <= 0.01);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DDSketch {
+    relative_accuracy: f64,
+    /// The natural logarithm of the ratio between the bounds of each bin.
+    ln_gamma: f64,
+    /// The count of each bin, starting with the bin of index `offset`.
+    bins: Vec<u64>,
+    offset: i32,
+    /// The count of latencies less than a nanosecond.
+    zero_count: u64,
+    count: u64,
+}
+
+/// A t-digest: a quantile sketch that clusters latencies into centroids, which are smallest
+/// near the extremes of the distribution, so that its tail quantiles are the most accurate.
+///
+/// The number of centroids is bounded by the compression; greater compressions are more
+/// accurate, but larger. Digests of any compression merge; the result is compressed by that of
+/// the digest merged into.
+///
+/// ##### Examples
+/// ```
+/// use std::time::Duration;
+/// use tokio_metrics::TDigest;
+///
+/// let mut digest = TDigest::new(100.0);
+/// for micros in 1..=1000 {
+///     digest.record(Duration::from_micros(micros));
+/// }
+///
+/// let mut other = TDigest::new(100.0);
+/// for micros in 1001..=2000 {
+///     other.record(Duration::from_micros(micros));
+/// }
+/// digest.merge(&other);
+///
+/// assert_eq!(digest.count(), 2000);
+/// assert!(digest.centroids().len() <= 100);
+/// let p99 = digest.quantile(0.99).unwrap().as_secs_f64();
+/// assert!((p99 - 1980e-6).abs() <= 0.001 * 1980e-6);
+/// assert_eq!(digest.quantile(1.0), Some(Duration::from_micros(2000)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TDigest {
+    compression: f64,
+    /// The compressed centroids, in ascending order of mean.
+    centroids: Vec<Centroid>,
+    /// The centroids recorded or merged since the last compression, in no particular order.
+    unmerged: Vec<Centroid>,
+    count: u64,
+    /// The least and greatest latencies recorded, in nanoseconds.
+    min: f64,
+    max: f64,
+}
+
+/// A histogram of exponentially-sized buckets, as in the OpenTelemetry data model, which an
+/// exporter can emit as a native exponential histogram.
+///
+/// Latencies are recorded in seconds. The bucket of index `i` counts latencies greater than
+/// `base^i` and at most `base^(i+1)`, where `base = 2^(2^-scale)`. The scale starts at
+/// [`MAX_SCALE`][ExponentialHistogram::MAX_SCALE], and is reduced as needed to fit the range of
+/// latencies recorded into the maximum number of buckets; each reduction merges pairs of
+/// neighboring buckets, halving the histogram's resolution. Histograms merge at the lesser of
+/// their scales.
+///
+/// ##### Examples
+/// ```
+/// use std::time::Duration;
+/// use tokio_metrics::ExponentialHistogram;
+///
+/// let mut histogram = ExponentialHistogram::new(160);
+/// for micros in 1..=1_000_000 {
+///     histogram.record(Duration::from_micros(micros));
+/// }
+///
+/// // 20 doublings of range fit in 160 buckets at 8 buckets per doubling
+/// assert_eq!(histogram.scale(), 3);
+/// assert!(histogram.bucket_counts().len() <= 160);
+/// assert_eq!(histogram.count(), 1_000_000);
+///
+/// let median = histogram.quantile(0.5).unwrap().as_secs_f64();
+/// assert!((median - 0.5).abs() <= 0.05 * 0.5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExponentialHistogram {
+    max_size: usize,
+    scale: i32,
+    /// The count of each bucket, starting with the bucket of index `offset`.
+    counts: Vec<u64>,
+    offset: i32,
+    /// The count of latencies of zero.
+    zero_count: u64,
+    count: u64,
+    /// The sum of the latencies recorded, in seconds.
+    sum: f64,
+}
+
+/// A high-dynamic-range histogram, backed by the [`hdrhistogram`] crate, whose quantiles are
+/// accurate to a configured number of significant decimal digits across any range of latencies.
+///
+/// Latencies are recorded in nanoseconds; the histogram resizes itself to cover the greatest
+/// latency recorded. Histograms with the same precision merge exactly.
+///
+/// **This functionality requires the crate feature `hdrhistogram`.**
+///
+/// ##### Examples
+/// ```
+/// use std::time::Duration;
+/// use tokio_metrics::HdrHistogram;
+///
+/// let mut histogram = HdrHistogram::new(3);
+/// for micros in 1..=1000 {
+///     histogram.record(Duration::from_micros(micros));
+/// }
+///
+/// let mut other = HdrHistogram::new(3);
+/// other.record(Duration::from_secs(1));
+/// histogram.merge(&other);
+///
+/// assert_eq!(histogram.count(), 1001);
+/// let median = histogram.quantile(0.5).unwrap().as_secs_f64();
+/// assert!((median - 501e-6).abs() <= 0.001 * 501e-6);
+/// let max = histogram.quantile(1.0).unwrap().as_secs_f64();
+/// assert!((max - 1.0).abs() <= 0.001);
+/// ```
+#[cfg(feature = "hdrhistogram")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hdrhistogram")))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HdrHistogram {
+    histogram: hdrhistogram::Histogram<u64>,
+}
+
+/// A cluster of latencies of a [`TDigest`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    /// The mean of the latencies, in nanoseconds.
+    mean: f64,
+    weight: u64,
+}
+
+impl LatencySketch {
+    /// Records a latency.
+    pub fn record(&mut self, latency: Duration) {
+        match self {
+            LatencySketch::DDSketch(sketch) => sketch.record(latency),
+            LatencySketch::TDigest(digest) => digest.record(latency),
+            LatencySketch::ExponentialHistogram(histogram) => histogram.record(latency),
+            #[cfg(feature = "hdrhistogram")]
+            LatencySketch::HdrHistogram(histogram) => histogram.record(latency),
+        }
+    }
+
+    /// Merges `other` into this sketch.
+    ///
+    /// ##### Panics
+    /// This method panics if the sketches are of different kinds or parameters.
+    pub fn merge(&mut self, other: &LatencySketch) {
+        match (self, other) {
+            (LatencySketch::DDSketch(sketch), LatencySketch::DDSketch(other)) => {
+                sketch.merge(other)
+            }
+            (LatencySketch::TDigest(digest), LatencySketch::TDigest(other)) => digest.merge(other),
+            (
+                LatencySketch::ExponentialHistogram(histogram),
+                LatencySketch::ExponentialHistogram(other),
+            ) => histogram.merge(other),
+            #[cfg(feature = "hdrhistogram")]
+            (LatencySketch::HdrHistogram(histogram), LatencySketch::HdrHistogram(other)) => {
+                histogram.merge(other)
+            }
+            _ => panic!("only sketches of the same kind can be merged"),
+        }
+    }
+
+    /// Estimates the `q`-quantile of the recorded latencies, for `q` between 0 and 1; e.g., the
+    /// median for 0.5. Produces `None` if no latencies are recorded.
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        match self {
+            LatencySketch::DDSketch(sketch) => sketch.quantile(q),
+            LatencySketch::TDigest(digest) => digest.quantile(q),
+            LatencySketch::ExponentialHistogram(histogram) => histogram.quantile(q),
+            #[cfg(feature = "hdrhistogram")]
+            LatencySketch::HdrHistogram(histogram) => histogram.quantile(q),
+        }
+    }
+
+    /// Produces the number of latencies recorded.
+    pub fn count(&self) -> u64 {
+        match self {
+            LatencySketch::DDSketch(sketch) => sketch.count(),
+            LatencySketch::TDigest(digest) => digest.count(),
+            LatencySketch::ExponentialHistogram(histogram) => histogram.count(),
+            #[cfg(feature = "hdrhistogram")]
+            LatencySketch::HdrHistogram(histogram) => histogram.count(),
+        }
+    }
+
+    /// Produces the recorded latencies as representative latencies and their counts, in
+    /// ascending order of latency.
+    #[cfg(feature = "datadog")]
+    pub(crate) fn weighted_latencies(&self) -> Vec<(Duration, u64)> {
+        match self {
+            LatencySketch::DDSketch(sketch) => std::iter::once((Duration::ZERO, sketch.zero_count))
+                .chain(
+                    sketch
+                        .bins()
+                        .map(|(index, count)| (sketch.representative(index), count)),
+                )
+                .filter(|&(_, count)| count > 0)
+                .collect(),
+            LatencySketch::TDigest(digest) => digest.centroids(),
+            LatencySketch::ExponentialHistogram(histogram) => {
+                std::iter::once((Duration::ZERO, histogram.zero_count))
+                    .chain(
+                        (histogram.offset..)
+                            .zip(histogram.counts.iter().copied())
+                            .map(|(index, count)| (histogram.representative(index), count)),
+                    )
+                    .filter(|&(_, count)| count > 0)
+                    .collect()
+            }
+            #[cfg(feature = "hdrhistogram")]
+            LatencySketch::HdrHistogram(histogram) => histogram
+                .histogram
+                .iter_recorded()
+                .map(|value| {
+                    (
+                        Duration::from_nanos(value.value_iterated_to()),
+                        value.count_at_value(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Produces an empty sketch of the same kind and parameters.
+    pub(crate) fn empty(&self) -> LatencySketch {
+        match self {
+            LatencySketch::DDSketch(sketch) => {
+                LatencySketch::DDSketch(DDSketch::new(sketch.relative_accuracy))
+            }
+            LatencySketch::TDigest(digest) => {
+                LatencySketch::TDigest(TDigest::new(digest.compression))
+            }
+            LatencySketch::ExponentialHistogram(histogram) => {
+                LatencySketch::ExponentialHistogram(ExponentialHistogram::new(histogram.max_size))
+            }
+            #[cfg(feature = "hdrhistogram")]
+            LatencySketch::HdrHistogram(histogram) => {
+                LatencySketch::HdrHistogram(HdrHistogram::new(histogram.significant_figures()))
+            }
+        }
+    }
+}
+
+impl From<DDSketch> for LatencySketch {
+    fn from(sketch: DDSketch) -> LatencySketch {
+        LatencySketch::DDSketch(sketch)
+    }
+}
+
+impl From<TDigest> for LatencySketch {
+    fn from(digest: TDigest) -> LatencySketch {
+        LatencySketch::TDigest(digest)
+    }
+}
+
+impl From<ExponentialHistogram> for LatencySketch {
+    fn from(histogram: ExponentialHistogram) -> LatencySketch {
+        LatencySketch::ExponentialHistogram(histogram)
+    }
+}
+
+#[cfg(feature = "hdrhistogram")]
+impl From<HdrHistogram> for LatencySketch {
+    fn from(histogram: HdrHistogram) -> LatencySketch {
+        LatencySketch::HdrHistogram(histogram)
+    }
+}
+
+impl DDSketch {
+    /// Constructs an empty sketch whose quantiles are accurate to within `relative_accuracy`;
+    /// e.g., 0.01 for 1%.
+    ///
+    /// ##### Panics
+    /// This method panics if `relative_accuracy` is not strictly between 0 and 1.
+    pub fn new(relative_accuracy: f64) -> DDSketch {
+        assert!(
+            relative_accuracy > 0.0 && relative_accuracy < 1.0,
+            "the relative accuracy must be strictly between 0 and 1"
+        );
+        let gamma = (1.0 + relative_accuracy) / (1.0 - relative_accuracy);
+        DDSketch {
+            relative_accuracy,
+            ln_gamma: gamma.ln(),
+            bins: Vec::new(),
+            offset: 0,
+            zero_count: 0,
+            count: 0,
+        }
+    }
+
+    /// Produces the relative accuracy of this sketch.
+    pub fn relative_accuracy(&self) -> f64 {
+        self.relative_accuracy
+    }
+
+    /// Produces the number of latencies recorded.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Records a latency.
+    pub fn record(&mut self, latency: Duration) {
+        self.count += 1;
+        let nanos = latency.as_nanos() as f64;
+        if nanos < 1.0 {
+            self.zero_count += 1;
+            return;
+        }
+        let index = (nanos.ln() / self.ln_gamma).ceil() as i32;
+        *self.bin_mut(index) += 1;
+    }
+
+    /// Merges `other` into this sketch.
+    ///
+    /// ##### Panics
+    /// This method panics if the sketches have different relative accuracies.
+    pub fn merge(&mut self, other: &DDSketch) {
+        assert!(
+            self.relative_accuracy == other.relative_accuracy,
+            "only sketches of the same relative accuracy can be merged"
+        );
+        self.count += other.count;
+        self.zero_count += other.zero_count;
+        for (index, count) in other.bins() {
+            if count > 0 {
+                *self.bin_mut(index) += count;
+            }
+        }
+    }
+
+    /// Estimates the `q`-quantile of the recorded latencies, for `q` between 0 and 1; e.g., the
+    /// median for 0.5. Produces `None` if no latencies are recorded.
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (q.clamp(0.0, 1.0) * (self.count - 1) as f64) as u64;
+        if rank < self.zero_count {
+            return Some(Duration::ZERO);
+        }
+        let mut seen = self.zero_count;
+        for (index, count) in self.bins() {
+            seen += count;
+            if seen > rank {
+                return Some(self.representative(index));
+            }
+        }
+        None
+    }
+
+    /// Produces the estimate of the latencies of the bin of `index` that minimizes the relative
+    /// error.
+    fn representative(&self, index: i32) -> Duration {
+        let gamma = self.ln_gamma.exp();
+        let nanos = 2.0 * (self.ln_gamma * index as f64).exp() / (1.0 + gamma);
+        Duration::from_nanos(nanos.round() as u64)
+    }
+
+    /// Produces the number of latencies recorded of less than a nanosecond.
+    pub fn zero_count(&self) -> u64 {
+        self.zero_count
+    }
+
+    /// Produces the index and count of each bin, in ascending order of index, for shipping the
+    /// sketch to an aggregator. The bin of index `i` counts latencies, in nanoseconds, greater
+    /// than `γ^(i-1)` and at most `γ^i`, where `γ = (1 + α) / (1 - α)` for relative accuracy `α`.
+    pub fn bins(&self) -> impl Iterator<Item = (i32, u64)> + '_ {
+        let offset = self.offset;
+        (offset..).zip(self.bins.iter().copied())
+    }
+
+    /// Produces the bin of `index`, growing the bins to include it.
+    fn bin_mut(&mut self, index: i32) -> &mut u64 {
+        if self.bins.is_empty() {
+            self.offset = index;
+        }
+        if index < self.offset {
+            let grow = (self.offset - index) as usize;
+            self.bins.splice(0..0, std::iter::repeat(0).take(grow));
+            self.offset = index;
+        }
+        let i = (index - self.offset) as usize;
+        if i >= self.bins.len() {
+            self.bins.resize(i + 1, 0);
+        }
+        &mut self.bins[i]
+    }
+}
+
+impl TDigest {
+    /// Constructs an empty digest of the given `compression`; e.g., 100, which bounds the digest
+    /// to at most 100 centroids.
+    ///
+    /// ##### Panics
+    /// This method panics if `compression` is not positive.
+    pub fn new(compression: f64) -> TDigest {
+        assert!(compression > 0.0, "the compression must be positive");
+        TDigest {
+            compression,
+            centroids: Vec::new(),
+            unmerged: Vec::new(),
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Produces the compression of this digest.
+    pub fn compression(&self) -> f64 {
+        self.compression
+    }
+
+    /// Produces the number of latencies recorded.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Records a latency.
+    pub fn record(&mut self, latency: Duration) {
+        let nanos = latency.as_nanos() as f64;
+        self.insert(Centroid {
+            mean: nanos,
+            weight: 1,
+        });
+        self.min = self.min.min(nanos);
+        self.max = self.max.max(nanos);
+    }
+
+    /// Merges `other` into this digest.
+    pub fn merge(&mut self, other: &TDigest) {
+        for &centroid in other.centroids.iter().chain(&other.unmerged) {
+            self.insert(centroid);
+        }
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    /// Estimates the `q`-quantile of the recorded latencies, for `q` between 0 and 1; e.g., the
+    /// median for 0.5. Produces `None` if no latencies are recorded.
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        let centroids = self.compressed();
+        let first = centroids.first()?;
+        let last = centroids[centroids.len() - 1];
+        let to_duration = |nanos: f64| Duration::from_nanos(nanos.round() as u64);
+
+        // each centroid is centered at the midpoint of its weight; latencies are interpolated
+        // between the centers of neighboring centroids, and the extremes
+        let rank = q.clamp(0.0, 1.0) * self.count as f64;
+        let mut center = first.weight as f64 / 2.0;
+        if rank <= center {
+            return Some(to_duration(
+                self.min + (first.mean - self.min) * rank / center,
+            ));
+        }
+        for pair in centroids.windows(2) {
+            let next = center + (pair[0].weight + pair[1].weight) as f64 / 2.0;
+            if rank <= next {
+                let fraction = (rank - center) / (next - center);
+                return Some(to_duration(
+                    pair[0].mean + (pair[1].mean - pair[0].mean) * fraction,
+                ));
+            }
+            center = next;
+        }
+        let remaining = self.count as f64 - center;
+        let fraction = if remaining > 0.0 {
+            (rank - center) / remaining
+        } else {
+            1.0
+        };
+        Some(to_duration(last.mean + (self.max - last.mean) * fraction))
+    }
+
+    /// Produces the mean and weight of each centroid, in ascending order of mean, for shipping
+    /// the digest to an aggregator.
+    pub fn centroids(&self) -> Vec<(Duration, u64)> {
+        self.compressed()
+            .iter()
+            .map(|centroid| {
+                let mean = Duration::from_nanos(centroid.mean.round() as u64);
+                (mean, centroid.weight)
+            })
+            .collect()
+    }
+
+    /// Inserts a centroid, compressing the digest once enough centroids are unmerged.
+    fn insert(&mut self, centroid: Centroid) {
+        self.count += centroid.weight;
+        self.unmerged.push(centroid);
+        if self.unmerged.len() as f64 >= 5.0 * self.compression {
+            self.centroids = self.compressed();
+            self.unmerged.clear();
+        }
+    }
+
+    /// Produces the centroids of this digest, with the unmerged centroids merged in.
+    fn compressed(&self) -> Vec<Centroid> {
+        if self.unmerged.is_empty() {
+            return self.centroids.clone();
+        }
+        let mut sorted: Vec<Centroid> = self
+            .centroids
+            .iter()
+            .chain(&self.unmerged)
+            .copied()
+            .collect();
+        sorted.sort_by(|a, b| a.mean.partial_cmp(&b.mean).unwrap_or(Ordering::Equal));
+
+        // the scale function bounds the weight of each centroid by its quantile; centroids near
+        // the extremes are kept small
+        let total = self.count as f64;
+        let scale = |q: f64| self.compression / (2.0 * PI) * (2.0 * q - 1.0).asin();
+        let inverse = |k: f64| {
+            let angle = (2.0 * PI * k / self.compression).min(PI / 2.0);
+            (angle.sin() + 1.0) / 2.0
+        };
+
+        let mut compressed = Vec::with_capacity(self.compression.ceil() as usize);
+        let mut sorted = sorted.into_iter();
+        let mut current = match sorted.next() {
+            Some(first) => first,
+            None => return compressed,
+        };
+        let mut weight_so_far = 0.0;
+        let mut limit = inverse(scale(0.0) + 1.0);
+        for next in sorted {
+            let q = (weight_so_far + (current.weight + next.weight) as f64) / total;
+            if q <= limit {
+                let weight = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight as f64 / weight as f64;
+                current.weight = weight;
+            } else {
+                weight_so_far += current.weight as f64;
+                compressed.push(current);
+                limit = inverse(scale(weight_so_far / total) + 1.0);
+                current = next;
+            }
+        }
+        compressed.push(current);
+        compressed
+    }
+}
+
+impl ExponentialHistogram {
+    /// The greatest scale of the OpenTelemetry data model, at which histograms start.
+    pub const MAX_SCALE: i32 = 20;
+
+    /// Constructs an empty histogram of at most `max_size` buckets; e.g., 160, the default of
+    /// OpenTelemetry SDKs.
+    ///
+    /// ##### Panics
+    /// This method panics if `max_size` is less than 2.
+    pub fn new(max_size: usize) -> ExponentialHistogram {
+        assert!(max_size >= 2, "the maximum size must be at least 2");
+        ExponentialHistogram {
+            max_size,
+            scale: ExponentialHistogram::MAX_SCALE,
+            counts: Vec::new(),
+            offset: 0,
+            zero_count: 0,
+            count: 0,
+            sum: 0.0,
+        }
+    }
+
+    /// Produces the maximum number of buckets of this histogram.
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Produces the current scale of this histogram.
+    pub fn scale(&self) -> i32 {
+        self.scale
+    }
+
+    /// Produces the index of the first of the [bucket counts][ExponentialHistogram::bucket_counts].
+    pub fn offset(&self) -> i32 {
+        self.offset
+    }
+
+    /// Produces the count of each bucket, starting with the bucket of index
+    /// [`offset`][ExponentialHistogram::offset].
+    pub fn bucket_counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Produces the number of latencies recorded of zero.
+    pub fn zero_count(&self) -> u64 {
+        self.zero_count
+    }
+
+    /// Produces the number of latencies recorded.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Produces the sum of the latencies recorded.
+    pub fn sum(&self) -> Duration {
+        Duration::from_secs_f64(self.sum)
+    }
+
+    /// Records a latency.
+    pub fn record(&mut self, latency: Duration) {
+        self.count += 1;
+        let seconds = latency.as_secs_f64();
+        self.sum += seconds;
+        if seconds == 0.0 {
+            self.zero_count += 1;
+            return;
+        }
+        let index = self.index(seconds);
+        self.increment(index, 1);
+    }
+
+    /// Merges `other` into this histogram.
+    pub fn merge(&mut self, other: &ExponentialHistogram) {
+        if other.scale < self.scale {
+            self.downscale(self.scale - other.scale);
+        }
+        self.count += other.count;
+        self.zero_count += other.zero_count;
+        self.sum += other.sum;
+        for (index, count) in (other.offset..).zip(other.counts.iter().copied()) {
+            if count > 0 {
+                // this histogram may be downscaled further by any increment
+                let index = index >> (other.scale - self.scale);
+                self.increment(index, count);
+            }
+        }
+    }
+
+    /// Estimates the `q`-quantile of the recorded latencies, for `q` between 0 and 1; e.g., the
+    /// median for 0.5. Produces `None` if no latencies are recorded.
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (q.clamp(0.0, 1.0) * (self.count - 1) as f64) as u64;
+        if rank < self.zero_count {
+            return Some(Duration::ZERO);
+        }
+        let mut seen = self.zero_count;
+        for (index, count) in (self.offset..).zip(self.counts.iter().copied()) {
+            seen += count;
+            if seen > rank {
+                return Some(self.representative(index));
+            }
+        }
+        None
+    }
+
+    /// Produces the estimate of the latencies of the bucket of `index` that minimizes the
+    /// relative error.
+    fn representative(&self, index: i32) -> Duration {
+        let base = self.base();
+        let lower = base.powi(index);
+        Duration::from_secs_f64(2.0 * lower * base / (1.0 + base))
+    }
+
+    /// Produces the ratio between the bounds of each bucket.
+    fn base(&self) -> f64 {
+        2f64.powf(2f64.powi(-self.scale))
+    }
+
+    /// Produces the index of the bucket of a positive latency of `seconds`.
+    fn index(&self, seconds: f64) -> i32 {
+        (seconds.log2() * 2f64.powi(self.scale)).ceil() as i32 - 1
+    }
+
+    /// Adds `count` to the bucket of `index` at the current scale, downscaling the histogram
+    /// as needed to fit it.
+    fn increment(&mut self, mut index: i32, count: u64) {
+        if self.counts.is_empty() {
+            self.offset = index;
+            self.counts.push(0);
+        }
+        let mut change = 0;
+        loop {
+            let low = (self.offset >> change).min(index >> change);
+            let high =
+                ((self.offset + self.counts.len() as i32 - 1) >> change).max(index >> change);
+            if ((high - low) as usize) < self.max_size {
+                break;
+            }
+            change += 1;
+        }
+        if change > 0 {
+            self.downscale(change);
+            index >>= change;
+        }
+        if index < self.offset {
+            let grow = (self.offset - index) as usize;
+            self.counts.splice(0..0, std::iter::repeat(0).take(grow));
+            self.offset = index;
+        }
+        let i = (index - self.offset) as usize;
+        if i >= self.counts.len() {
+            self.counts.resize(i + 1, 0);
+        }
+        self.counts[i] += count;
+    }
+
+    /// Reduces the scale by `change`, merging each `2^change` neighboring buckets.
+    fn downscale(&mut self, change: i32) {
+        self.scale -= change;
+        if self.counts.is_empty() {
+            return;
+        }
+        let offset = self.offset >> change;
+        let mut counts = vec![
+            0;
+            ((self.offset + self.counts.len() as i32 - 1) >> change) as usize
+                - offset as usize
+                + 1
+        ];
+        for (index, count) in (self.offset..).zip(self.counts.iter().copied()) {
+            counts[((index >> change) - offset) as usize] += count;
+        }
+        self.counts = counts;
+        self.offset = offset;
+    }
+}
+
+#[cfg(feature = "hdrhistogram")]
+impl HdrHistogram {
+    /// Constructs an empty histogram whose quantiles are accurate to `significant_figures`
+    /// significant decimal digits; e.g., 3 for 0.1%.
+    ///
+    /// ##### Panics
+    /// This method panics if `significant_figures` is greater than 5.
+    pub fn new(significant_figures: u8) -> HdrHistogram {
+        assert!(
+            significant_figures <= 5,
+            "the significant figures must be at most 5"
+        );
+        let histogram = hdrhistogram::Histogram::new(significant_figures)
+            .expect("an auto-resizing histogram of at most 5 significant figures is valid");
+        HdrHistogram { histogram }
+    }
+
+    /// Produces the number of significant decimal digits of this histogram.
+    pub fn significant_figures(&self) -> u8 {
+        self.histogram.sigfig()
+    }
+
+    /// Produces the number of latencies recorded.
+    pub fn count(&self) -> u64 {
+        self.histogram.len()
+    }
+
+    /// Records a latency.
+    pub fn record(&mut self, latency: Duration) {
+        let nanos = latency.as_nanos().min(u64::MAX as u128) as u64;
+        // recording resizes the histogram to fit, up to the greatest trackable latency
+        if self.histogram.record(nanos).is_err() {
+            self.histogram.saturating_record(nanos);
+        }
+    }
+
+    /// Merges `other` into this histogram.
+    ///
+    /// ##### Panics
+    /// This method panics if the histograms have different significant figures.
+    pub fn merge(&mut self, other: &HdrHistogram) {
+        assert!(
+            self.significant_figures() == other.significant_figures(),
+            "only histograms of the same significant figures can be merged"
+        );
+        self.histogram
+            .add(&other.histogram)
+            .expect("an auto-resizing histogram accepts the values of any other");
+    }
+
+    /// Estimates the `q`-quantile of the recorded latencies, for `q` between 0 and 1; e.g., the
+    /// median for 0.5. Produces `None` if no latencies are recorded.
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        if self.histogram.is_empty() {
+            return None;
+        }
+        let nanos = self.histogram.value_at_quantile(q.clamp(0.0, 1.0));
+        Some(Duration::from_nanos(nanos))
+    }
+
+    /// Produces the underlying [`hdrhistogram::Histogram`] of latencies, in nanoseconds; e.g.,
+    /// for serializing it in the HdrHistogram interchange format.
+    pub fn histogram(&self) -> &hdrhistogram::Histogram<u64> {
+        &self.histogram
+    }
+}
@@ -0,0 +1,249 @@
+use crate::TaskMetrics;
+use std::collections::VecDeque;
+
+/// A service-level objective over [`TaskMetrics`]; e.g., "99% of polls are fast".
+///
+/// An objective is defined by an indicator, which counts the good events, and the total events,
+/// of a sampling interval, and by the proportion of events that must be good. The remainder is
+/// the error budget; the rate at which it is consumed, relative to the rate that would exhaust
+/// it exactly, is the [burn rate][Slo::burn_rate]. [`BurnRateTracker`] alerts when the budget
+/// burns too fast.
+///
+/// ##### Examples
+/// ```
+/// use tokio_metrics::{Slo, TaskMetrics};
+///
+/// // 99% of polls are fast
+/// let slo = Slo::fast_polls(0.99);
+///
+/// let mut interval = TaskMetrics::default();
+/// interval.total_poll_count = 1000;
+/// interval.total_fast_poll_count = 980;
+/// interval.total_slow_poll_count = 20;
+///
+/// // 2% of polls were slow, consuming the 1% budget twice as fast as is sustainable
+/// assert!((slo.burn_rate(&[interval]) - 2.0).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Slo {
+    objective: f64,
+    indicator: Indicator,
+}
+
+/// Counts the good events, and the total events, of a sampling interval.
+type Indicator = fn(&TaskMetrics) -> (u64, u64);
+
+/// Tracks the burn rate of an [`Slo`] over multiple windows of recent sampling intervals, and
+/// alerts when the budget burns too fast.
+///
+/// Each window pairs a long window, which establishes that a significant portion of the budget
+/// has been consumed, with a short window, which establishes that it is still being consumed;
+/// a window alerts if the burn rates over both exceed its maximum. Windows are measured in
+/// sampling intervals; e.g., with one-minute intervals, a window of 60 intervals spans an hour.
+///
+/// ##### Examples
+/// ```
+/// use tokio_metrics::{BurnRateTracker, Slo, TaskMetrics};
+///
+/// // alert if the budget burns more than 10x too fast over the last 12 intervals, and the last
+/// // interval
+/// let mut tracker = BurnRateTracker::new(Slo::fast_polls(0.99)).with_window(12, 1, 10.0);
+///
+/// let mut interval = TaskMetrics::default();
+/// interval.total_poll_count = 100;
+/// interval.total_fast_poll_count = 100;
+/// for _ in 0..12 {
+///     assert!(tracker.record(interval).is_empty());
+/// }
+///
+/// interval.total_fast_poll_count = 50;
+/// interval.total_slow_poll_count = 50;
+/// for _ in 0..2 {
+///     assert!(tracker.record(interval).is_empty());
+/// }
+///
+/// // 3 of 12 intervals had 50% slow polls: a burn rate of 12.5 over the long window
+/// let alerts = tracker.record(interval);
+/// assert_eq!(alerts.len(), 1);
+/// assert!((alerts[0].long_burn_rate - 12.5).abs() < 1e-9);
+/// assert!((alerts[0].short_burn_rate - 50.0).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BurnRateTracker {
+    slo: Slo,
+    windows: Vec<BurnRateWindow>,
+    /// The most recent sampling intervals, oldest first.
+    history: VecDeque<TaskMetrics>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BurnRateWindow {
+    long: usize,
+    short: usize,
+    max_burn_rate: f64,
+}
+
+/// A window of a [`BurnRateTracker`] whose burn rates exceeded its maximum.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BurnRateAlert {
+    /// The length of the long window, in sampling intervals.
+    pub long: usize,
+
+    /// The length of the short window, in sampling intervals.
+    pub short: usize,
+
+    /// The burn rate over the long window.
+    pub long_burn_rate: f64,
+
+    /// The burn rate over the short window.
+    pub short_burn_rate: f64,
+
+    /// The maximum burn rate of the window.
+    pub max_burn_rate: f64,
+}
+
+impl Slo {
+    /// Constructs an objective that the proportion of good events counted by `indicator` is at
+    /// least `objective`; `indicator` produces the good events, and the total events, of a
+    /// sampling interval.
+    ///
+    /// Indicators are limited to what [`TaskMetrics`] counts. For instance, polls faster than an
+    /// arbitrary threshold are counted by configuring it as the
+    /// [slow-poll threshold][crate::TaskMonitorBuilder::with_slow_poll_threshold] of a monitor;
+    /// see [`Slo::fast_polls`].
+    ///
+    /// ##### Panics
+    /// This method panics if `objective` is not strictly between 0 and 1.
+    pub fn new(objective: f64, indicator: fn(&TaskMetrics) -> (u64, u64)) -> Slo {
+        assert!(
+            objective > 0.0 && objective < 1.0,
+            "the objective must be strictly between 0 and 1"
+        );
+        Slo {
+            objective,
+            indicator,
+        }
+    }
+
+    /// Constructs an objective that the proportion of polls that are
+    /// [fast][TaskMetrics::total_fast_poll_count] is at least `objective`; e.g., with a
+    /// slow-poll threshold of 1ms, `Slo::fast_polls(0.99)` is "99% of polls take less than 1ms".
+    ///
+    /// ##### Panics
+    /// This method panics if `objective` is not strictly between 0 and 1.
+    pub fn fast_polls(objective: f64) -> Slo {
+        Slo::new(objective, |metrics| {
+            (metrics.total_fast_poll_count, metrics.total_poll_count)
+        })
+    }
+
+    /// Produces the proportion of events that must be good.
+    pub fn objective(&self) -> f64 {
+        self.objective
+    }
+
+    /// Produces the error budget; i.e., the proportion of events that may be bad.
+    pub fn error_budget(&self) -> f64 {
+        1.0 - self.objective
+    }
+
+    /// Produces the burn rate of the error budget over the given sampling `intervals`; i.e., the
+    /// proportion of bad events, divided by the error budget.
+    ///
+    /// A burn rate of 1 consumes the budget exactly; a burn rate of 0 means no events were bad,
+    /// or no events occurred.
+    pub fn burn_rate(&self, intervals: &[TaskMetrics]) -> f64 {
+        self.burn_rate_of(intervals.iter())
+    }
+
+    fn burn_rate_of<'a, I>(&self, intervals: I) -> f64
+    where
+        I: Iterator<Item = &'a TaskMetrics>,
+    {
+        let (good, total) = intervals
+            .map(self.indicator)
+            .fold((0u64, 0u64), |(good, total), (g, t)| {
+                (good.saturating_add(g), total.saturating_add(t))
+            });
+        if total == 0 {
+            return 0.0;
+        }
+        let bad = total.saturating_sub(good);
+        (bad as f64 / total as f64) / self.error_budget()
+    }
+}
+
+impl BurnRateTracker {
+    /// Constructs a tracker of `slo`, without windows.
+    pub fn new(slo: Slo) -> BurnRateTracker {
+        BurnRateTracker {
+            slo,
+            windows: Vec::new(),
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Adds a window that alerts if the burn rates over both the last `long` and the last
+    /// `short` sampling intervals exceed `max_burn_rate`.
+    ///
+    /// ##### Panics
+    /// This method panics if `short` is zero, or exceeds `long`.
+    pub fn with_window(mut self, long: usize, short: usize, max_burn_rate: f64) -> BurnRateTracker {
+        assert!(
+            short > 0 && short <= long,
+            "the short window must be non-empty, and no longer than the long window"
+        );
+        self.windows.push(BurnRateWindow {
+            long,
+            short,
+            max_burn_rate,
+        });
+        self
+    }
+
+    /// Records the next sampling `interval`, and produces an alert for each window whose burn
+    /// rates exceed its maximum. A window is only evaluated once its long window is full.
+    pub fn record(&mut self, interval: TaskMetrics) -> Vec<BurnRateAlert> {
+        let capacity = self
+            .windows
+            .iter()
+            .map(|window| window.long)
+            .max()
+            .unwrap_or(0);
+        self.history.push_back(interval);
+        while self.history.len() > capacity {
+            self.history.pop_front();
+        }
+
+        let len = self.history.len();
+        self.windows
+            .iter()
+            .filter(|window| len >= window.long)
+            .filter_map(|window| {
+                let long_burn_rate = self
+                    .slo
+                    .burn_rate_of(self.history.iter().skip(len - window.long));
+                let short_burn_rate = self
+                    .slo
+                    .burn_rate_of(self.history.iter().skip(len - window.short));
+                if long_burn_rate > window.max_burn_rate && short_burn_rate > window.max_burn_rate {
+                    Some(BurnRateAlert {
+                        long: window.long,
+                        short: window.short,
+                        long_burn_rate,
+                        short_burn_rate,
+                        max_burn_rate: window.max_burn_rate,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Produces the objective tracked.
+    pub fn slo(&self) -> &Slo {
+        &self.slo
+    }
+}
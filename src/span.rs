@@ -0,0 +1,194 @@
+use crate::{Instrumented, TaskMetrics, TaskMonitor, TaskMonitorBuilder};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// Aggregates [`TaskMetrics`] separately for each `tracing` span in which tasks are instrumented.
+///
+/// A [`SpanMonitor`] maintains a [`TaskMonitor`] for each distinct [`SpanKey`] — the name and
+/// target of the [current span][tracing::Span::current] at the moment a task is
+/// [instrumented][SpanMonitor::instrument]. Breakdowns of task metrics thereby align with
+/// existing `tracing` instrumentation; e.g., tasks instrumented within an `info_span!("request")`
+/// are aggregated apart from those instrumented within an `info_span!("background_flush")`.
+///
+/// Tasks instrumented outside of any (enabled) span are aggregated under
+/// [`SpanKey::default()`][SpanKey::default]. Spans are only enabled while a `tracing` subscriber
+/// is installed.
+///
+/// **This functionality requires the crate feature `tracing`.**
+///
+/// ##### Examples
+/// ```no_run
+/// use std::time::Duration;
+/// use tokio_metrics::SpanMonitor;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let monitor = SpanMonitor::new();
+///
+///     // print task metrics for each span every 500ms
+///     {
+///         let monitor = monitor.clone();
+///         tokio::spawn(async move {
+///             for interval in monitor.intervals() {
+///                 for (span, metrics) in interval {
+///                     println!("{}::{} {:?}", span.target, span.name, metrics);
+///                 }
+///                 tokio::time::sleep(Duration::from_millis(500)).await;
+///             }
+///         });
+///     }
+///
+///     let task = tracing::info_span!("request").in_scope(|| {
+///         monitor.instrument(async {
+///             tokio::time::sleep(Duration::from_millis(100)).await;
+///         })
+///     });
+///     task.await;
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SpanMonitor {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    /// Constructs the monitor of each newly-encountered span.
+    builder: TaskMonitorBuilder,
+
+    /// The monitor of each span encountered so far.
+    monitors: Mutex<HashMap<SpanKey, TaskMonitor>>,
+}
+
+/// Identifies the `tracing` span in which tasks were instrumented by a [`SpanMonitor`].
+///
+/// **This functionality requires the crate feature `tracing`.**
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SpanKey {
+    /// The target of the span; e.g., the module path in which it was created. Empty for tasks
+    /// instrumented outside of any span.
+    pub target: &'static str,
+
+    /// The name of the span. Empty for tasks instrumented outside of any span.
+    pub name: &'static str,
+}
+
+impl SpanMonitor {
+    /// Constructs a new span monitor, which constructs the [`TaskMonitor`] of each span with the
+    /// default configuration.
+    pub fn new() -> SpanMonitor {
+        SpanMonitor::with_monitor_builder(TaskMonitor::builder())
+    }
+
+    /// Constructs a new span monitor, which constructs the [`TaskMonitor`] of each span from a
+    /// clone of `builder`.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::{SpanMonitor, TaskMonitor};
+    ///
+    /// let monitor = SpanMonitor::with_monitor_builder(
+    ///     TaskMonitor::builder().with_slow_poll_threshold(Duration::from_millis(1)),
+    /// );
+    /// ```
+    pub fn with_monitor_builder(builder: TaskMonitorBuilder) -> SpanMonitor {
+        SpanMonitor {
+            inner: Arc::new(Inner {
+                builder,
+                monitors: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Produces an instrumented façade around a given async task, recording its metrics with the
+    /// monitor of the [current span][tracing::Span::current].
+    ///
+    /// The span is determined once, when this method is called; see
+    /// [`TaskMonitor::instrument`].
+    #[track_caller]
+    pub fn instrument<F: Future>(&self, task: F) -> Instrumented<F> {
+        self.monitor(SpanKey::current()).instrument(task)
+    }
+
+    /// Produces the [`TaskMonitor`] of the span identified by `key`, constructing it if no task
+    /// has yet been instrumented in that span.
+    pub fn monitor(&self, key: SpanKey) -> TaskMonitor {
+        self.inner
+            .monitors
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| self.inner.builder.clone().build())
+            .clone()
+    }
+
+    /// Produces [`TaskMetrics`] for each span, collected since tasks were first instrumented in
+    /// that span.
+    ///
+    /// See [`TaskMonitor::cumulative`].
+    pub fn cumulative(&self) -> HashMap<SpanKey, TaskMetrics> {
+        self.inner
+            .monitors
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(key, monitor)| (*key, monitor.cumulative()))
+            .collect()
+    }
+
+    /// Produces an unending iterator of [`TaskMetrics`] for each span, for sampling intervals.
+    ///
+    /// Each sampling interval is defined by the time elapsed between advancements of the iterator,
+    /// like [`TaskMonitor::intervals`]. The first interval of a span that is encountered after
+    /// the iterator is constructed begins when its monitor is constructed.
+    pub fn intervals(&self) -> impl Iterator<Item = HashMap<SpanKey, TaskMetrics>> {
+        let inner = self.inner.clone();
+        let mut intervals: HashMap<SpanKey, Box<dyn Iterator<Item = TaskMetrics> + Send>> =
+            HashMap::new();
+
+        std::iter::from_fn(move || {
+            let monitors = inner.monitors.lock().unwrap();
+            let interval = monitors
+                .iter()
+                .map(|(key, monitor)| {
+                    let metrics = intervals
+                        .entry(*key)
+                        .or_insert_with(|| Box::new(monitor.intervals()))
+                        .next()
+                        .expect("task monitor intervals are unending");
+                    (*key, metrics)
+                })
+                .collect();
+            Some(interval)
+        })
+    }
+}
+
+impl Default for SpanMonitor {
+    fn default() -> SpanMonitor {
+        SpanMonitor::new()
+    }
+}
+
+impl std::fmt::Debug for SpanMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpanMonitor")
+            .field("spans", &self.inner.monitors.lock().unwrap().keys())
+            .finish()
+    }
+}
+
+impl SpanKey {
+    /// Produces the key of the [current span][tracing::Span::current].
+    pub fn current() -> SpanKey {
+        match tracing::Span::current().metadata() {
+            Some(metadata) => SpanKey {
+                target: metadata.target(),
+                name: metadata.name(),
+            },
+            None => SpanKey::default(),
+        }
+    }
+}
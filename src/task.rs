@@ -1,10 +1,30 @@
-use futures_util::task::{ArcWake, AtomicWaker};
+#[cfg(feature = "tracing")]
+use crate::outlier::OutlierLogger;
+use crate::trace::current_thread_id;
+use crate::{
+    LatencySketch, MetricDescriptor, MetricKind, MetricValue, MetricsSink, PerfCounters,
+    TaskObserver, TraceRecorder,
+};
+use futures_util::future::{BoxFuture, LocalBoxFuture};
+use futures_util::task::WakerRef;
 use pin_project_lite::pin_project;
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use std::future::Future;
+use std::mem::ManuallyDrop;
+use std::panic::Location;
 use std::pin::Pin;
-use std::sync::atomic::{AtomicU64, Ordering::SeqCst};
-use std::sync::Arc;
-use std::task::{Context, Poll};
+use std::ptr;
+use std::sync::atomic::{
+    AtomicBool, AtomicU64,
+    Ordering::{Relaxed, SeqCst},
+};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use std::thread::ThreadId;
+use std::time::SystemTime;
 
 #[cfg(any(feature = "rt"))]
 use tokio::time::{Duration, Instant};
@@ -503,12 +523,104 @@ pub struct TaskMonitor {
     metrics: Arc<RawMetrics>,
 }
 
+/// A weak handle to a [`TaskMonitor`], which does not keep its metrics alive.
+///
+/// Produced by [`TaskMonitor::downgrade`]. Long-lived registries and caches can hold weak
+/// handles to monitors without preventing their metrics from being freed once the subsystems
+/// that own them shut down. The metrics of a monitor are freed once every [`TaskMonitor`] handle,
+/// every task it instrumented, and every iterator produced by [`TaskMonitor::intervals`] is
+/// dropped.
+///
+/// ##### Examples
+/// ```
+/// use tokio_metrics::TaskMonitor;
+///
+/// let monitor = TaskMonitor::new();
+/// let weak = monitor.downgrade();
+/// assert!(weak.upgrade().is_some());
+///
+/// drop(monitor);
+/// assert!(weak.upgrade().is_none());
+/// ```
+#[derive(Clone)]
+pub struct WeakTaskMonitor {
+    metrics: Weak<RawMetrics>,
+}
+
+/// A guard that measures a synchronous region of code, from its construction by
+/// [`TaskMonitor::scope`] until it is dropped.
+///
+/// The region is recorded in the [`total_inline_count`][TaskMetrics::total_inline_count] and
+/// [`total_inline_duration`][TaskMetrics::total_inline_duration] metrics of the monitor.
+#[must_use = "the region is measured until the guard is dropped"]
+pub struct ScopeGuard<'a> {
+    monitor: &'a TaskMonitor,
+    started_at: Instant,
+}
+
+/// Configures and constructs a [`TaskMonitor`].
+///
+/// Produced by [`TaskMonitor::builder`].
+#[derive(Clone)]
+pub struct TaskMonitorBuilder {
+    slow_poll_threshold: Duration,
+    calibration: Option<(usize, f64)>,
+    blocking_poll_threshold: Option<Duration>,
+    on_blocking_poll: Option<BlockingPollCallback>,
+    stalled_task_threshold: Option<Duration>,
+    incomplete_task_threshold: Option<Duration>,
+    #[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+    on_stall_dump: Option<StallDumpCallback>,
+    slowest_polls_capacity: usize,
+    trace_recorder: Option<TraceRecorder>,
+    observer: Option<Arc<dyn TaskObserver>>,
+    on_completion: Option<CompletionCallback>,
+    timing: bool,
+    track_first_poll: bool,
+    track_scheduled: bool,
+    track_idle: bool,
+    capture_locations: bool,
+    checkpoints: Option<(Duration, usize)>,
+    track_watermarks: bool,
+    batching: Option<Batching>,
+    poll_bucket_thresholds: Vec<Duration>,
+    slow_poll_tiers: Vec<(&'static str, Duration, Option<SlowPollCallback>)>,
+    latency_sketch: Option<LatencySketch>,
+    #[cfg(feature = "tracing")]
+    outlier_logging: Option<(Duration, u32)>,
+    attribute_workers: bool,
+    track_live_tasks: bool,
+    state_pool_capacity: usize,
+    measure_cpu_time: bool,
+    track_allocations: bool,
+    sample_perf: bool,
+    #[cfg(feature = "usdt")]
+    usdt_long_schedule_threshold: Option<Duration>,
+}
+
+/// A callback invoked with the duration of a poll that exceeded the blocking-poll threshold.
+type BlockingPollCallback = Arc<dyn Fn(Duration) + Send + Sync>;
+
+/// A callback invoked with the duration of a poll that reached a slow-poll tier.
+type SlowPollCallback = Arc<dyn Fn(Duration) + Send + Sync>;
+
+/// A callback invoked with the summary of each task that completes.
+type CompletionCallback = Arc<dyn Fn(&TaskSummary) + Send + Sync>;
+
+/// A callback invoked with the stalled tasks of a monitor, and a dump of their runtime.
+#[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+type StallDumpCallback = Arc<dyn Fn(StallReport) + Send + Sync>;
+
+/// Classifies the results of tasks as successful if they are `Ok`.
+type IsOk<T, E> = fn(&Result<T, E>) -> bool;
+
 pin_project! {
     /// An async task that has been instrumented with [`TaskMonitor::instrument`].
     pub struct Instrumented<T> {
-        // The task being instrumented
+        // The task being instrumented. It is dropped manually, so that the duration of its
+        // destructor can be measured.
         #[pin]
-        task: T,
+        task: ManuallyDrop<T>,
 
         // True when the task is polled for the first time
         did_poll_once: bool,
@@ -517,17 +629,119 @@ pin_project! {
         // its last poll.
         idled_at: u64,
 
-        // State shared between the task and its instrumented waker.
-        state: Arc<State>,
+        // The number of times the task has been polled.
+        poll_count: u64,
+
+        // The metrics of this task alone, if they are reported; i.e., to a completion callback, or
+        // by `InstrumentedWithSummary`. Boxed, so as not to enlarge every instrumented task.
+        summary: Option<Box<TaskSummary>>,
+
+        // The instant at which the task was spawned, if it was spawned via a spawn wrapper.
+        spawned_at: Option<Instant>,
+
+        // True once the task has completed.
+        completed: bool,
+
+        // True while the task is being polled; if the task is dropped while this is set, its poll
+        // panicked.
+        polling: bool,
+
+        // State shared between the task and its instrumented wakers. It is dropped manually, so
+        // that it may be returned to the pool of its monitor.
+        state: ManuallyDrop<Arc<State>>,
+
+        // The instrumented waker with which the task was last polled, if wakes are tracked; it is
+        // reused for as long as the task is polled with an equivalent waker.
+        waker: Option<Arc<InstrumentedWaker<State>>>,
     }
 
     impl<T> PinnedDrop for Instrumented<T> {
         fn drop(this: Pin<&mut Self>) {
-            this.state.metrics.dropped_count.fetch_add(1, SeqCst);
+            let this = this.project();
+            this.state.untrack();
+
+            let drop_start = Instant::now();
+            this.state.metrics.checkpoint(drop_start);
+            // SAFETY: `task` is never accessed again after being dropped here, and dropping it in
+            // place upholds the pinning guarantees.
+            unsafe { ManuallyDrop::drop(this.task.get_unchecked_mut()) };
+            let drop_ns = drop_start
+                .elapsed()
+                .as_nanos()
+                .try_into()
+                .unwrap_or(u64::MAX);
+            this.state.record_drop(drop_ns);
+
+            /* accounting for tasks dropped before completing */
+            if !*this.completed {
+                let metrics = &this.state.metrics;
+                let spawned = this.spawned_at.is_some();
+                if *this.polling {
+                    metrics.panicked_count.fetch_add(1, SeqCst);
+                } else if spawned {
+                    // spawned tasks are only dropped early by the runtime; i.e., if aborted, or on
+                    // shutdown
+                    metrics.dropped_before_completion_count.fetch_add(1, SeqCst);
+                } else {
+                    metrics.cancelled_count.fetch_add(1, SeqCst);
+                }
+                if spawned {
+                    metrics.join_error_count.fetch_add(1, SeqCst);
+                }
+            }
+
+            this.state.metrics.flush_local_batch();
+
+            // the cached waker shares the state, which is only released if unshared
+            *this.waker = None;
+            // SAFETY: `state` is never accessed again after being taken here.
+            State::release(unsafe { ManuallyDrop::take(this.state) });
         }
     }
 }
 
+pin_project! {
+    /// An async task that has been instrumented with [`TaskMonitor::instrument_with_result`].
+    ///
+    /// Resolves to the output of the task, paired with its [`TaskSummary`].
+    pub struct InstrumentedWithSummary<T> {
+        #[pin]
+        task: Instrumented<T>,
+    }
+}
+
+pin_project! {
+    /// An async task resolving to a [`Result`] that has been instrumented with
+    /// [`TaskMonitor::instrument_result`] or [`TaskMonitor::instrument_result_with`].
+    pub struct InstrumentedResult<T, C> {
+        #[pin]
+        task: Instrumented<T>,
+        classify: C,
+    }
+}
+
+pin_project! {
+    /// A branch of a task that has been instrumented with [`TaskMonitor::instrument_branch`].
+    pub struct InstrumentedBranch<T> {
+        #[pin]
+        branch: T,
+        metrics: Arc<RawMetrics>,
+        counters: Arc<WorkerCounters>,
+    }
+}
+
+#[cfg(feature = "rt")]
+pin_project! {
+    /// An async task that has been instrumented with [`TaskMonitor::instrument_with_timeout`].
+    ///
+    /// Resolves to the output of the task, or to an error if the timeout elapsed first.
+    #[cfg_attr(docsrs, doc(cfg(feature = "rt")))]
+    pub struct InstrumentedWithTimeout<T> {
+        #[pin]
+        task: Instrumented<tokio::time::Timeout<T>>,
+    }
+}
+
 /// Key metrics of [instrumented][`TaskMonitor::instrument`] tasks.
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, Default)]
@@ -562,6 +776,56 @@ pub struct TaskMetrics {
     /// ```
     pub instrumented_count: u64,
 
+    /// The total duration elapsed between the instrumentation of each task, and the
+    /// instrumentation of the task before it (or, for the first task, the construction of the
+    /// monitor).
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_instrumentation_gap`][TaskMetrics::mean_instrumentation_gap]**   
+    ///   The mean duration elapsed between the instrumentation of consecutive tasks; i.e., the
+    ///   inverse of the rate at which tasks are instrumented.
+    pub total_instrumentation_gap: Duration,
+
+    /// The greatest duration elapsed between the instrumentation of consecutive tasks.
+    ///
+    /// A gap that has yet to end, because no task has been instrumented since, is included as it
+    /// grows; a producer of tasks that stopped producing is thus reflected by this metric before
+    /// it resumes, even though nothing failed.
+    ///
+    /// The metrics produced by [`TaskMonitor::intervals`] and [`TaskMonitor::sampled_intervals`]
+    /// carry the maximum of each interval; other metrics (e.g., those produced by
+    /// [`TaskMonitor::cumulative`]) carry the maximum since the monitor was constructed. The
+    /// difference of two [`TaskMetrics`] carries the maximum of the minuend.
+    ///
+    /// This metric is always zero unless watermarks are
+    /// [enabled][TaskMonitorBuilder::with_watermarks].
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_watermarks(true)
+    ///         .build();
+    ///     let mut intervals = monitor.intervals();
+    ///     let mut next_interval = || intervals.next().unwrap();
+    ///
+    ///     monitor.instrument(async {}).await;
+    ///     tokio::time::sleep(Duration::from_millis(50)).await;
+    ///     monitor.instrument(async {}).await;
+    ///     assert!(next_interval().max_instrumentation_gap >= Duration::from_millis(50));
+    ///
+    ///     // the producer stops producing
+    ///     tokio::time::sleep(Duration::from_millis(100)).await;
+    ///     let interval = next_interval();
+    ///     assert_eq!(interval.instrumented_count, 0);
+    ///     assert!(interval.max_instrumentation_gap >= Duration::from_millis(100));
+    /// }
+    /// ```
+    pub max_instrumentation_gap: Duration,
+
     /// The number of tasks dropped.
     ///
     /// ##### Examples
@@ -592,6 +856,55 @@ pub struct TaskMetrics {
     /// ```
     pub dropped_count: u64,
 
+    /// The total duration spent dropping tasks.
+    ///
+    /// This is the time spent in the destructor of instrumented tasks (i.e., dropping the state
+    /// held by the future). Futures that hold large amounts of state, or that perform blocking
+    /// cleanup in their [`Drop`] implementations, stall the worker thread that drops them.
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_drop_duration`][TaskMetrics::mean_drop_duration]**   
+    ///   The mean duration spent dropping tasks.
+    ///
+    /// ##### Examples
+    /// In the below example, a task holding a value whose destructor blocks for 10ms is dropped;
+    /// dropping the task takes at least 10ms:
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut interval = monitor.intervals();
+    ///     let mut next_interval = || interval.next().unwrap();
+    ///
+    ///     // no tasks have been dropped
+    ///     assert_eq!(next_interval().total_drop_duration, Duration::ZERO);
+    ///
+    ///     /// A value whose destructor blocks the current thread.
+    ///     struct SlowDrop(Duration);
+    ///
+    ///     impl Drop for SlowDrop {
+    ///         fn drop(&mut self) {
+    ///             std::thread::sleep(self.0);
+    ///         }
+    ///     }
+    ///
+    ///     let delay = Duration::from_millis(10);
+    ///     let slow_drop = SlowDrop(delay);
+    ///
+    ///     // the instrumented task owns `slow_drop`; since it is never polled, `slow_drop` is
+    ///     // dropped when the task is dropped
+    ///     let task = monitor.instrument(async move {
+    ///         let _slow_drop = slow_drop;
+    ///     });
+    ///     drop(task);
+    ///
+    ///     assert!(next_interval().total_drop_duration >= delay);
+    /// }
+    /// ```
+    pub total_drop_duration: Duration,
+
     /// The number of tasks polled for the first time.
     ///
     /// ##### Derived metrics
@@ -757,6 +1070,144 @@ pub struct TaskMetrics {
     /// ```
     pub total_first_poll_delay: Duration,
 
+    /// The greatest duration elapsed between the instant a task was instrumented, and the instant
+    /// it was first polled.
+    ///
+    /// Unlike [`total_first_poll_delay`][TaskMetrics::total_first_poll_delay], this is not diluted
+    /// by the tasks that were polled promptly; a single task that waited seconds for its first
+    /// poll is reflected in full.
+    ///
+    /// The metrics produced by [`TaskMonitor::intervals`] and [`TaskMonitor::sampled_intervals`]
+    /// carry the maximum of each interval; other metrics (e.g., those produced by
+    /// [`TaskMonitor::cumulative`]) carry the maximum since the monitor was constructed. The
+    /// difference of two [`TaskMetrics`] carries the maximum of the minuend, as the maximum of the
+    /// events between them cannot be recovered.
+    ///
+    /// This metric is always zero unless watermarks are
+    /// [enabled][TaskMonitorBuilder::with_watermarks].
+    ///
+    /// Like [`total_first_poll_delay`][TaskMetrics::total_first_poll_delay], this is not tracked
+    /// if first-poll tracking is [disabled][TaskMonitorBuilder::with_first_poll_tracking].
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_watermarks(true)
+    ///         .build();
+    ///     let mut intervals = monitor.intervals();
+    ///     let mut next_interval = || intervals.next().unwrap();
+    ///
+    ///     // this task is first polled promptly...
+    ///     monitor.instrument(async {}).await;
+    ///
+    ///     // ...and this one only after 50ms
+    ///     let task = monitor.instrument(async {});
+    ///     tokio::time::sleep(Duration::from_millis(50)).await;
+    ///     task.await;
+    ///
+    ///     assert!(next_interval().max_first_poll_delay >= Duration::from_millis(50));
+    ///
+    ///     // no task was first polled in the next interval
+    ///     assert_eq!(next_interval().max_first_poll_delay, Duration::ZERO);
+    ///     assert!(monitor.cumulative().max_first_poll_delay >= Duration::from_millis(50));
+    /// }
+    /// ```
+    pub max_first_poll_delay: Duration,
+
+    /// The number of tasks spawned with [`TaskMonitor::spawn`] or [`Instrumented::spawn`].
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut interval = monitor.intervals();
+    ///     let mut next_interval = || interval.next().unwrap();
+    ///
+    ///     // awaiting an instrumented task does not spawn it
+    ///     monitor.instrument(async {}).await;
+    ///     assert_eq!(next_interval().spawned_count, 0);
+    ///
+    ///     monitor.spawn(async {}).await.unwrap();
+    ///     monitor.instrument(async {}).spawn().await.unwrap();
+    ///     assert_eq!(next_interval().spawned_count, 2);
+    /// }
+    /// ```
+    pub spawned_count: u64,
+
+    /// The number of tasks spawned with [`TaskMonitor::spawn`] or [`Instrumented::spawn`] that were
+    /// polled for the first time.
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_spawn_delay`][TaskMetrics::mean_spawn_delay]**   
+    ///   The mean duration elapsed between the instant spawned tasks are instrumented, and the
+    ///   instant they are spawned.
+    /// - **[`mean_spawn_queue_delay`][TaskMetrics::mean_spawn_queue_delay]**   
+    ///   The mean duration elapsed between the instant spawned tasks are spawned, and the instant
+    ///   they are first polled.
+    pub spawned_first_poll_count: u64,
+
+    /// The total duration elapsed between the instant spawned tasks are instrumented, and the
+    /// instant they are spawned.
+    ///
+    /// Together with [`total_spawn_queue_delay`][TaskMetrics::total_spawn_queue_delay], this
+    /// splits the [first-poll delay][TaskMetrics::total_first_poll_delay] of spawned tasks into
+    /// time spent by the application before spawning the task, and time spent by the task in the
+    /// runtime's queues. Like the first-poll delay, it is recorded when the task is first polled.
+    ///
+    /// ##### Examples
+    /// In the below example, a task is instrumented 500ms before it is spawned:
+    /// ```
+    /// use tokio::time::Duration;
+    ///
+    /// #[tokio::main(flavor = "current_thread", start_paused = true)]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     let task = monitor.instrument(async {});
+    ///     tokio::time::sleep(Duration::from_millis(500)).await;
+    ///     task.spawn().await.unwrap();
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.spawned_first_poll_count, 1);
+    ///     assert_eq!(metrics.total_spawn_delay, Duration::from_millis(500));
+    ///     assert_eq!(metrics.total_spawn_queue_delay, Duration::ZERO);
+    ///     assert_eq!(metrics.total_first_poll_delay, Duration::from_millis(500));
+    /// }
+    /// ```
+    pub total_spawn_delay: Duration,
+
+    /// The total duration elapsed between the instant spawned tasks are spawned, and the instant
+    /// they are first polled.
+    ///
+    /// This is the time spawned tasks spent waiting in the runtime's queues before their first
+    /// poll. See [`total_spawn_delay`][TaskMetrics::total_spawn_delay].
+    ///
+    /// ##### Examples
+    /// In the below example, a task is spawned onto a runtime that is blocked for 100ms:
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     let handle = monitor.spawn(async {});
+    ///     // block the only worker thread
+    ///     std::thread::sleep(Duration::from_millis(100));
+    ///     handle.await.unwrap();
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert!(metrics.total_spawn_queue_delay >= Duration::from_millis(100));
+    ///     assert!(metrics.total_spawn_delay < Duration::from_millis(100));
+    /// }
+    /// ```
+    pub total_spawn_queue_delay: Duration,
+
     /// The total number of times that tasks idled, waiting to be awoken.
     ///
     /// An idle is recorded as occurring if a if a non-zero duration elapses between the instant a
@@ -973,6 +1424,55 @@ pub struct TaskMetrics {
     /// ```
     pub total_scheduled_duration: Duration,
 
+    /// The greatest duration that a task spent waiting to be polled after awakening.
+    ///
+    /// Unlike [`mean_scheduled_duration`][TaskMetrics::mean_scheduled_duration], this is not
+    /// diluted by the many wakes that were polled promptly; a single task that waited seconds to
+    /// be scheduled is reflected in full.
+    ///
+    /// The metrics produced by [`TaskMonitor::intervals`] and [`TaskMonitor::sampled_intervals`]
+    /// carry the maximum of each interval; other metrics (e.g., those produced by
+    /// [`TaskMonitor::cumulative`]) carry the maximum since the monitor was constructed. The
+    /// difference of two [`TaskMetrics`] carries the maximum of the minuend, as the maximum of the
+    /// events between them cannot be recovered.
+    ///
+    /// This metric is always zero unless watermarks are
+    /// [enabled][TaskMonitorBuilder::with_watermarks].
+    ///
+    /// ##### Examples
+    /// In the below example, a task is awoken, but the executor is blocked for 50ms before it is
+    /// polled:
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_watermarks(true)
+    ///         .build();
+    ///     let mut intervals = monitor.intervals();
+    ///     let mut next_interval = || intervals.next().unwrap();
+    ///
+    ///     let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+    ///     let task = tokio::spawn(monitor.instrument(rx));
+    ///
+    ///     // let `task` idle, awaiting `rx`
+    ///     tokio::time::sleep(Duration::from_millis(1)).await;
+    ///
+    ///     // awaken `task`, then block the executor before it can be polled
+    ///     tx.send(()).unwrap();
+    ///     std::thread::sleep(Duration::from_millis(50));
+    ///     task.await.unwrap().unwrap();
+    ///
+    ///     assert!(next_interval().max_scheduled_duration >= Duration::from_millis(50));
+    ///
+    ///     // no task was awoken in the next interval
+    ///     assert_eq!(next_interval().max_scheduled_duration, Duration::ZERO);
+    ///     assert!(monitor.cumulative().max_scheduled_duration >= Duration::from_millis(50));
+    /// }
+    /// ```
+    pub max_scheduled_duration: Duration,
+
     /// The total number of times that tasks were polled.
     ///
     /// ##### Definition
@@ -1073,76 +1573,280 @@ pub struct TaskMetrics {
     /// ```
     pub total_poll_duration: Duration,
 
-    /// The total number of times that polling tasks completed swiftly.
+    /// The total number of times that polling tasks returned [`Poll::Ready`].
     ///
-    /// Here, 'swiftly' is defined as completing in strictly less time than
-    /// [`slow_poll_threshold`][TaskMonitor::slow_poll_threshold].
-    ///
-    /// ##### Derived metrics
-    /// - **[`mean_fast_poll_duration`][TaskMetrics::mean_fast_poll_duration]**   
-    ///   The mean duration of fast polls.
+    /// ##### Definition
+    /// A task is polled to completion at most once, so this metric is equal to
+    /// [`completed_count`][TaskMetrics::completed_count]. Together with
+    /// [`total_pending_poll_count`][TaskMetrics::total_pending_poll_count], it accounts for
+    /// every poll counted in [`total_poll_count`][TaskMetrics::total_poll_count].
     ///
     /// ##### Examples
-    /// In the below example, 0 polls occur within the first sampling interval, 3 fast polls occur
-    /// within the second sampling interval, and 2 fast polls occur within the third sampling
-    /// interval:
     /// ```
-    /// use std::future::Future;
-    /// use std::time::Duration;
-    ///
     /// #[tokio::main]
     /// async fn main() {
-    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
-    ///     let mut interval = metrics_monitor.intervals();
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut interval = monitor.intervals();
     ///     let mut next_interval = || interval.next().unwrap();
     ///
-    ///     // no tasks have been constructed, instrumented, or polled
-    ///     assert_eq!(next_interval().total_fast_poll_count, 0);
+    ///     monitor.instrument(async {
+    ///         tokio::task::yield_now().await; // poll 1 (pending)
+    ///         tokio::task::yield_now().await; // poll 2 (pending)
+    ///     }).await;                           // poll 3 (ready)
     ///
-    ///     let fast = Duration::ZERO;
+    ///     let interval = next_interval();
+    ///     assert_eq!(interval.total_ready_poll_count, 1);
+    ///     assert_eq!(interval.total_pending_poll_count, 2);
+    ///     assert_eq!(interval.total_poll_count, 3);
+    /// }
+    /// ```
+    pub total_ready_poll_count: u64,
+
+    /// The total number of times that polling tasks returned [`Poll::Pending`].
     ///
-    ///     // this task completes in three fast polls
-    ///     let _ = metrics_monitor.instrument(async {
-    ///         spin_for(fast).await; // fast poll 1
-    ///         spin_for(fast).await; // fast poll 2
-    ///         spin_for(fast)        // fast poll 3
-    ///     }).await;
+    /// A task that is polled many times without completing (e.g., one that is awoken spuriously,
+    /// or that makes no progress on each poll) inflates this metric relative to
+    /// [`total_ready_poll_count`][TaskMetrics::total_ready_poll_count].
     ///
-    ///     assert_eq!(next_interval().total_fast_poll_count, 3);
+    /// ##### Definition
+    /// This metric is equal to [`total_poll_count`][TaskMetrics::total_poll_count] -
+    /// [`total_ready_poll_count`][TaskMetrics::total_ready_poll_count].
     ///
-    ///     // this task completes in two fast polls
-    ///     let _ = metrics_monitor.instrument(async {
-    ///         spin_for(fast).await; // fast poll 1
-    ///         spin_for(fast)        // fast poll 2
-    ///     }).await;
+    /// ##### Derived metrics
+    /// - **[`pending_poll_ratio`][TaskMetrics::pending_poll_ratio]**   
+    ///   The ratio between the number of polls that returned [`Poll::Pending`] and the number of
+    ///   polls.
     ///
-    ///     assert_eq!(next_interval().total_fast_poll_count, 2);
-    /// }
+    /// ##### Examples
+    /// See [`total_ready_poll_count`][TaskMetrics::total_ready_poll_count].
+    pub total_pending_poll_count: u64,
+
+    /// The number of tasks that were being polled at the instant the metrics were produced.
     ///
-    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
-    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
-    ///     let start = tokio::time::Instant::now();
-    ///     while start.elapsed() <= duration {}
-    ///     tokio::task::yield_now()
-    /// }
-    /// ```
-    pub total_fast_poll_count: u64,
+    /// Unlike most metrics, this is a gauge: it is not accumulated across an interval, and the
+    /// difference of two [`TaskMetrics`] carries the value of the minuend.
+    ///
+    /// This metric is always zero unless watermarks are
+    /// [enabled][TaskMonitorBuilder::with_watermarks].
+    ///
+    /// ##### See also
+    /// - **[`max_concurrent_poll_count`][TaskMetrics::max_concurrent_poll_count]**   
+    ///   The greatest number of tasks that were being polled at once.
+    pub concurrent_poll_count: u64,
 
-    /// The total duration of fast polls.
+    /// The greatest number of tasks that were being polled at once; i.e., the parallelism that
+    /// the tasks actually achieved.
     ///
-    /// Here, 'fast' is defined as completing in strictly less time than
-    /// [`slow_poll_threshold`][TaskMonitor::slow_poll_threshold].
+    /// The metrics produced by [`TaskMonitor::intervals`] and [`TaskMonitor::sampled_intervals`]
+    /// carry the maximum of each interval, which includes the tasks still being polled as the
+    /// interval began; other metrics (e.g., those produced by [`TaskMonitor::cumulative`]) carry
+    /// the maximum since the monitor was constructed. The difference of two [`TaskMetrics`]
+    /// carries the maximum of the minuend.
     ///
-    /// ##### Derived metrics
-    /// - **[`mean_fast_poll_duration`][TaskMetrics::mean_fast_poll_duration]**   
-    ///   The mean duration of fast polls.
+    /// This metric is always zero unless watermarks are
+    /// [enabled][TaskMonitorBuilder::with_watermarks].
     ///
     /// ##### Examples
-    /// In the below example, no tasks are polled in the first sampling interval; three fast polls
-    /// consume a total of 3μs time in the second sampling interval; and two fast polls consume a
-    /// total of 2μs time in the third sampling interval:
+    /// In the below example, two tasks block until both are being polled, on two workers:
     /// ```
-    /// use std::future::Future;
+    /// use std::sync::{Arc, Barrier};
+    ///
+    /// #[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_watermarks(true)
+    ///         .build();
+    ///     let mut intervals = monitor.intervals();
+    ///     let mut next_interval = || intervals.next().unwrap();
+    ///
+    ///     let barrier = Arc::new(Barrier::new(2));
+    ///     let tasks: Vec<_> = (0..2)
+    ///         .map(|_| {
+    ///             let barrier = barrier.clone();
+    ///             monitor.spawn(async move {
+    ///                 barrier.wait();
+    ///             })
+    ///         })
+    ///         .collect();
+    ///     for task in tasks {
+    ///         task.await.unwrap();
+    ///     }
+    ///
+    ///     let interval = next_interval();
+    ///     assert_eq!(interval.max_concurrent_poll_count, 2);
+    ///     assert_eq!(interval.concurrent_poll_count, 0);
+    ///
+    ///     // no task was polled in the next interval
+    ///     assert_eq!(next_interval().max_concurrent_poll_count, 0);
+    ///     assert_eq!(monitor.cumulative().max_concurrent_poll_count, 2);
+    /// }
+    /// ```
+    pub max_concurrent_poll_count: u64,
+
+    /// The number of distinct threads that polled tasks.
+    ///
+    /// This is only counted if polls are attributed to threads, with
+    /// [`TaskMonitorBuilder::with_worker_attribution`]; otherwise, it is zero. It reveals, e.g.,
+    /// tasks that were polled by a runtime other than the one they were meant for, or on more
+    /// threads than a runtime was expected to provide.
+    ///
+    /// The metrics produced by [`TaskMonitor::intervals`] and [`TaskMonitor::sampled_intervals`]
+    /// count the threads that polled tasks within each interval; other metrics (e.g., those
+    /// produced by [`TaskMonitor::cumulative`]) count the threads that have polled tasks since
+    /// the monitor was constructed. The difference of two [`TaskMetrics`] carries the count of
+    /// the minuend.
+    ///
+    /// ##### Examples
+    /// In the below example, a task is polled by a thread other than the runtime's:
+    /// ```
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_worker_attribution(true)
+    ///         .build();
+    ///     let mut intervals = monitor.intervals();
+    ///     let mut next_interval = || intervals.next().unwrap();
+    ///
+    ///     monitor.instrument(async {}).await;
+    ///
+    ///     // a task is polled on a runtime of its own
+    ///     let task = monitor.instrument(async {});
+    ///     std::thread::spawn(move || {
+    ///         let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    ///         runtime.block_on(task)
+    ///     })
+    ///     .join()
+    ///     .unwrap();
+    ///
+    ///     assert_eq!(next_interval().polling_thread_count, 2);
+    ///
+    ///     monitor.instrument(async {}).await;
+    ///     assert_eq!(next_interval().polling_thread_count, 1);
+    ///     assert_eq!(monitor.cumulative().polling_thread_count, 2);
+    /// }
+    /// ```
+    pub polling_thread_count: u64,
+
+    /// The total number of polls that were likely ended by tokio's cooperative scheduling budget.
+    ///
+    /// Tokio's resources (e.g., channels, sockets and timers) share a per-task budget of
+    /// operations. Once that budget is exhausted, resources that would otherwise be ready instead
+    /// wake the task immediately and return [`Poll::Pending`], forcing it to yield to the
+    /// scheduler. Such polls are counted in [`total_poll_count`][TaskMetrics::total_poll_count],
+    /// just like polls that end because the task is genuinely waiting on an external event.
+    ///
+    /// ##### Definition
+    /// This metric is a heuristic: it counts polls that returned [`Poll::Pending`] after the task
+    /// was woken *during* that poll. Futures that wake themselves before yielding for other
+    /// reasons, and wakeups that happen to race with a poll, are counted as well.
+    ///
+    /// ##### Examples
+    /// In the below example, a task receives many messages that are already buffered in a
+    /// channel; since every message is immediately available, the task only yields when its
+    /// cooperative budget is exhausted:
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut interval = metrics_monitor.intervals();
+    ///     let mut next_interval = || interval.next().unwrap();
+    ///
+    ///     assert_eq!(next_interval().total_budget_yield_count, 0);
+    ///
+    ///     let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    ///     for i in 0..1000 {
+    ///         tx.send(i).unwrap();
+    ///     }
+    ///     drop(tx);
+    ///
+    ///     // receive every buffered message
+    ///     metrics_monitor.instrument(async move {
+    ///         while let Some(_) = rx.recv().await {}
+    ///     }).await;
+    ///
+    ///     // the task was forced to yield at least once
+    ///     assert!(next_interval().total_budget_yield_count >= 1);
+    ///
+    ///     // a task that sleeps is genuinely waiting, and is not forced to yield
+    ///     metrics_monitor.instrument(async {
+    ///         tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    ///     }).await;
+    ///
+    ///     assert_eq!(next_interval().total_budget_yield_count, 0);
+    /// }
+    /// ```
+    pub total_budget_yield_count: u64,
+
+    /// The total number of times that polling tasks completed swiftly.
+    ///
+    /// Here, 'swiftly' is defined as completing in strictly less time than
+    /// [`slow_poll_threshold`][TaskMonitor::slow_poll_threshold].
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_fast_poll_duration`][TaskMetrics::mean_fast_poll_duration]**   
+    ///   The mean duration of fast polls.
+    ///
+    /// ##### Examples
+    /// In the below example, 0 polls occur within the first sampling interval, 3 fast polls occur
+    /// within the second sampling interval, and 2 fast polls occur within the third sampling
+    /// interval:
+    /// ```
+    /// use std::future::Future;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut interval = metrics_monitor.intervals();
+    ///     let mut next_interval = || interval.next().unwrap();
+    ///
+    ///     // no tasks have been constructed, instrumented, or polled
+    ///     assert_eq!(next_interval().total_fast_poll_count, 0);
+    ///
+    ///     let fast = Duration::ZERO;
+    ///
+    ///     // this task completes in three fast polls
+    ///     let _ = metrics_monitor.instrument(async {
+    ///         spin_for(fast).await; // fast poll 1
+    ///         spin_for(fast).await; // fast poll 2
+    ///         spin_for(fast)        // fast poll 3
+    ///     }).await;
+    ///
+    ///     assert_eq!(next_interval().total_fast_poll_count, 3);
+    ///
+    ///     // this task completes in two fast polls
+    ///     let _ = metrics_monitor.instrument(async {
+    ///         spin_for(fast).await; // fast poll 1
+    ///         spin_for(fast)        // fast poll 2
+    ///     }).await;
+    ///
+    ///     assert_eq!(next_interval().total_fast_poll_count, 2);
+    /// }
+    ///
+    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
+    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
+    ///     let start = tokio::time::Instant::now();
+    ///     while start.elapsed() <= duration {}
+    ///     tokio::task::yield_now()
+    /// }
+    /// ```
+    pub total_fast_poll_count: u64,
+
+    /// The total duration of fast polls.
+    ///
+    /// Here, 'fast' is defined as completing in strictly less time than
+    /// [`slow_poll_threshold`][TaskMonitor::slow_poll_threshold].
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_fast_poll_duration`][TaskMetrics::mean_fast_poll_duration]**   
+    ///   The mean duration of fast polls.
+    ///
+    /// ##### Examples
+    /// In the below example, no tasks are polled in the first sampling interval; three fast polls
+    /// consume a total of 3μs time in the second sampling interval; and two fast polls consume a
+    /// total of 2μs time in the third sampling interval:
+    /// ```
+    /// use std::future::Future;
     /// use std::time::Duration;
     ///
     /// #[tokio::main]
@@ -1319,496 +2023,5093 @@ pub struct TaskMetrics {
     /// }
     /// ```
     pub total_slow_poll_duration: Duration,
-}
-
-/// Tracks the metrics, shared across the various types.
-struct RawMetrics {
-    /// A task poll takes longer than this, it is considered a slow poll.
-    slow_poll_threshold: Duration,
-
-    /// Total number of instrumented tasks.
-    instrumented_count: AtomicU64,
-
-    /// Total number of instrumented tasks polled at least once.
-    first_poll_count: AtomicU64,
-
-    /// Total number of times tasks entered the `idle` state.
-    total_idled_count: AtomicU64,
-
-    /// Total number of times tasks were scheduled.
-    total_scheduled_count: AtomicU64,
-
-    /// Total number of times tasks were polled fast
-    total_fast_poll_count: AtomicU64,
 
-    /// Total number of times tasks were polled slow
-    total_slow_poll_count: AtomicU64,
-
-    /// Total number of times tasks were dropped
-    dropped_count: AtomicU64,
-
-    /// Total amount of time until the first poll
-    total_first_poll_delay_ns: AtomicU64,
-
-    /// Total amount of time tasks spent in the `idle` state.
-    total_idle_duration_ns: AtomicU64,
-
-    /// Total amount of time tasks spent in the waking state.
-    total_scheduled_duration_ns: AtomicU64,
-
-    /// Total amount of time tasks spent being polled below the slow cut off.
-    total_fast_poll_duration_ns: AtomicU64,
+    /// The total number of times that polling tasks completed so slowly that they were
+    /// 'probably blocking'.
+    ///
+    /// Here, 'probably blocking' is defined as completing in at least as much time as
+    /// [`blocking_poll_threshold`][TaskMonitor::blocking_poll_threshold]. Blocking polls are also
+    /// counted as slow polls. This metric is always zero unless blocking-poll detection is
+    /// [enabled][TaskMonitorBuilder::with_blocking_poll_threshold].
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let metrics_monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_blocking_poll_threshold(Duration::from_millis(100))
+    ///         .build();
+    ///     let mut interval = metrics_monitor.intervals();
+    ///     let mut next_interval = || interval.next().unwrap();
+    ///
+    ///     // no tasks have been constructed, instrumented, or polled
+    ///     assert_eq!(next_interval().total_blocking_poll_count, 0);
+    ///
+    ///     // this task blocks the executor in its second poll
+    ///     metrics_monitor.instrument(async {
+    ///         tokio::task::yield_now().await;
+    ///         std::thread::sleep(Duration::from_millis(150));
+    ///     }).await;
+    ///
+    ///     let interval = next_interval();
+    ///     assert_eq!(interval.total_poll_count, 2);
+    ///     assert_eq!(interval.total_blocking_poll_count, 1);
+    /// }
+    /// ```
+    pub total_blocking_poll_count: u64,
 
-    /// Total amount of time tasks spent being polled above the slow cut off.
-    total_slow_poll_duration: AtomicU64,
-}
+    /// The total CPU time consumed by the polling thread while polling tasks.
+    ///
+    /// Whereas [`total_poll_duration`][TaskMetrics::total_poll_duration] measures the wall time
+    /// elapsed during polls, this metric measures the CPU time the polling thread actually
+    /// consumed, as reported by `clock_gettime(CLOCK_THREAD_CPUTIME_ID)`. A poll that took much
+    /// longer in wall time than in CPU time was likely preempted, or blocked in a system call,
+    /// rather than burning CPU.
+    ///
+    /// This metric is always zero unless CPU time measurement is
+    /// [enabled][TaskMonitorBuilder::with_cpu_time], which requires the crate feature `cpu-time`
+    /// and a Unix platform.
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_poll_cpu_duration`][TaskMetrics::mean_poll_cpu_duration]**   
+    ///   The mean CPU time consumed per poll.
+    pub total_poll_cpu_duration: Duration,
 
-struct State {
-    /// Where metrics should be recorded
-    metrics: Arc<RawMetrics>,
+    /// The total number of allocations (including reallocations) performed while polling tasks.
+    ///
+    /// Allocation churn inside polls is a common hidden cost: it consumes CPU time in the
+    /// allocator, and contends on its locks.
+    ///
+    /// This metric is always zero unless allocation tracking is
+    /// [enabled][TaskMonitorBuilder::with_allocation_tracking], and
+    /// [`TrackingAllocator`][crate::TrackingAllocator] is installed as the global allocator.
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_poll_allocation_count`][TaskMetrics::mean_poll_allocation_count]**   
+    ///   The mean number of allocations performed per poll.
+    pub total_allocation_count: u64,
 
-    /// Instant at which the task was instrumented. This is used to track the time to first poll.
-    instrumented_at: Instant,
+    /// The total number of bytes allocated (including by reallocations) while polling tasks.
+    ///
+    /// This metric is always zero unless allocation tracking is
+    /// [enabled][TaskMonitorBuilder::with_allocation_tracking], and
+    /// [`TrackingAllocator`][crate::TrackingAllocator] is installed as the global allocator.
+    pub total_allocated_bytes: u64,
 
-    /// The instant, tracked as nanoseconds since `instrumented_at`, at which the future
-    /// was last woken.
-    woke_at: AtomicU64,
+    /// The total number of times that tasks were scheduled by a wake originating on the same
+    /// thread that last polled them.
+    ///
+    /// Each time a task is [scheduled][TaskMetrics::total_scheduled_count] after having been
+    /// polled at least once, the wake that scheduled it is classified as either local (counted
+    /// by this metric) or [remote][TaskMetrics::total_remote_wake_count]. Wakes are not
+    /// classified if tasks are polled with their original waker; i.e., if both
+    /// [scheduled][TaskMonitorBuilder::with_scheduled_tracking] and
+    /// [idle][TaskMonitorBuilder::with_idle_tracking] tracking are disabled.
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     // `yield_now` wakes the task from within its own poll
+    ///     monitor.instrument(tokio::task::yield_now()).await;
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.total_local_wake_count, 1);
+    ///     assert_eq!(metrics.total_remote_wake_count, 0);
+    /// }
+    /// ```
+    pub total_local_wake_count: u64,
 
-    /// Waker to forward notifications to.
-    waker: AtomicWaker,
-}
+    /// The total number of times that tasks were scheduled by a wake originating on a thread
+    /// other than the one that last polled them.
+    ///
+    /// Remote wakes correlate strongly with scheduling latency and cache misses: the woken task
+    /// may have to be handed to another worker, and its state is unlikely to be in the cache of
+    /// the waking thread. A high proportion of remote wakes, relative to
+    /// [local wakes][TaskMetrics::total_local_wake_count], suggests that tasks communicate
+    /// heavily across worker threads.
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let (tx, rx) = tokio::sync::oneshot::channel();
+    ///
+    ///     let task = monitor.instrument(rx);
+    ///     let sender = std::thread::spawn(move || {
+    ///         std::thread::sleep(std::time::Duration::from_millis(10));
+    ///         tx.send(()).unwrap();
+    ///     });
+    ///
+    ///     task.await.unwrap();
+    ///     sender.join().unwrap();
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.total_local_wake_count, 0);
+    ///     assert_eq!(metrics.total_remote_wake_count, 1);
+    /// }
+    /// ```
+    pub total_remote_wake_count: u64,
 
-impl TaskMonitor {
-    /// The default duration at which polls cross the threshold into being categorized as 'slow' is
-    /// 50μs.
-    #[cfg(not(test))]
-    pub const DEFAULT_SLOW_POLL_THRESHOLD: Duration = Duration::from_micros(50);
-    #[cfg(test)]
-    pub const DEFAULT_SLOW_POLL_THRESHOLD: Duration = Duration::from_millis(500);
+    /// The total number of synchronous regions measured by [scopes][TaskMonitor::scope].
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_inline_duration`][TaskMetrics::mean_inline_duration]**   
+    ///   The mean duration of measured regions.
+    pub total_inline_count: u64,
 
-    /// Constructs a new task monitor.
+    /// The total duration of synchronous regions measured by [scopes][TaskMonitor::scope].
     ///
-    /// Uses [`Self::DEFAULT_SLOW_POLL_THRESHOLD`] as the threshold at which polls will be
-    /// considered 'slow'.
-    pub fn new() -> TaskMonitor {
-        TaskMonitor::with_slow_poll_threshold(Self::DEFAULT_SLOW_POLL_THRESHOLD)
-    }
+    /// Regions measured within polls of instrumented tasks are also included in
+    /// [`total_poll_duration`][TaskMetrics::total_poll_duration]; the remainder of the poll
+    /// duration is spent outside of explicitly-measured regions.
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_inline_duration`][TaskMetrics::mean_inline_duration]**   
+    ///   The mean duration of measured regions.
+    pub total_inline_duration: Duration,
 
-    /// Constructs a new task monitor with a given threshold at which polls are considered 'slow'.
+    /// The number of tasks [instrumented with a timeout][TaskMonitor::instrument_with_timeout]
+    /// that timed out.
     ///
-    /// ##### Selecting an appropriate threshold
-    /// TODO. What advice can we give here?
+    /// Timed-out tasks are also counted by [`dropped_count`][TaskMetrics::dropped_count], since
+    /// they are dropped upon timing out.
+    ///
+    /// ##### Derived metrics
+    /// - **[`mean_timed_out_duration`][TaskMetrics::mean_timed_out_duration]**   
+    ///   The mean time elapsed before tasks timed out.
     ///
     /// ##### Examples
-    /// In the below example, low-threshold and high-threshold monitors are constructed and
-    /// instrument identical tasks; the low-threshold monitor reports4 slow polls, and the
-    /// high-threshold monitor reports only 2 slow polls:
     /// ```
-    /// use std::future::Future;
     /// use std::time::Duration;
-    /// use tokio_metrics::TaskMonitor;
     ///
     /// #[tokio::main]
     /// async fn main() {
-    ///     let lo_threshold = Duration::from_micros(10);
-    ///     let hi_threshold = Duration::from_millis(10);
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let timeout = Duration::from_millis(10);
     ///
-    ///     let lo_monitor = TaskMonitor::with_slow_poll_threshold(lo_threshold);
-    ///     let hi_monitor = TaskMonitor::with_slow_poll_threshold(hi_threshold);
+    ///     let fast = monitor.instrument_with_timeout(async { 42 }, timeout);
+    ///     assert_eq!(fast.await.unwrap(), 42);
     ///
-    ///     let make_task = || async {
-    ///         spin_for(lo_threshold).await; // faster poll 1
-    ///         spin_for(lo_threshold).await; // faster poll 2
-    ///         spin_for(hi_threshold).await; // slower poll 3
-    ///         spin_for(hi_threshold).await  // slower poll 4
-    ///     };
-    ///
-    ///     lo_monitor.instrument(make_task()).await;
-    ///     hi_monitor.instrument(make_task()).await;
-    ///
-    ///     // the low-threshold monitor reported 4 slow polls:
-    ///     assert_eq!(lo_monitor.cumulative().total_slow_poll_count, 4);
-    ///     // the high-threshold monitor reported only 2 slow polls:
-    ///     assert_eq!(hi_monitor.cumulative().total_slow_poll_count, 2);
-    /// }
+    ///     let slow = monitor.instrument_with_timeout(std::future::pending::<()>(), timeout);
+    ///     assert!(slow.await.is_err());
     ///
-    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
-    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
-    ///     let start = tokio::time::Instant::now();
-    ///     while start.elapsed() <= duration {}
-    ///     tokio::task::yield_now()
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.instrumented_count, 2);
+    ///     assert_eq!(metrics.timed_out_count, 1);
+    ///     assert!(metrics.total_timed_out_duration >= timeout);
     /// }
     /// ```
-    pub fn with_slow_poll_threshold(slow_poll_cut_off: Duration) -> TaskMonitor {
-        TaskMonitor {
-            metrics: Arc::new(RawMetrics {
-                slow_poll_threshold: slow_poll_cut_off,
-                first_poll_count: AtomicU64::new(0),
-                total_idled_count: AtomicU64::new(0),
-                total_scheduled_count: AtomicU64::new(0),
-                total_fast_poll_count: AtomicU64::new(0),
-                total_slow_poll_count: AtomicU64::new(0),
-                instrumented_count: AtomicU64::new(0),
-                dropped_count: AtomicU64::new(0),
-                total_first_poll_delay_ns: AtomicU64::new(0),
-                total_scheduled_duration_ns: AtomicU64::new(0),
-                total_idle_duration_ns: AtomicU64::new(0),
-                total_fast_poll_duration_ns: AtomicU64::new(0),
-                total_slow_poll_duration: AtomicU64::new(0),
-            }),
-        }
-    }
+    pub timed_out_count: u64,
 
-    /// Produces the duration greater-than-or-equal-to at which polls are categorized as slow.
-    ///
-    /// ##### Examples
-    /// In the below example, [`TaskMonitor`] is initialized with [`TaskMonitor::new`];
-    /// consequently, its slow-poll threshold equals [`TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD`]:
-    /// ```
-    /// use tokio_metrics::TaskMonitor;
-    ///
-    /// #[tokio::main]
-    /// async fn main() {
-    ///     let metrics_monitor = TaskMonitor::new();
+    /// The total time elapsed between the instrumentation of tasks and their timing out.
     ///
-    ///     assert_eq!(
-    ///         metrics_monitor.slow_poll_threshold(),
-    ///         TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD
-    ///     );
-    /// }
-    /// ```
-    pub fn slow_poll_threshold(&self) -> Duration {
-        self.metrics.slow_poll_threshold
-    }
+    /// ##### Derived metrics
+    /// - **[`mean_timed_out_duration`][TaskMetrics::mean_timed_out_duration]**   
+    ///   The mean time elapsed before tasks timed out.
+    pub total_timed_out_duration: Duration,
 
-    /// Produces an instrumented façade around a given async task.
-    ///
-    /// ##### Examples
-    /// Instrument an async task by passing it to [`TaskMonitor::instrument`]:
-    /// ```
-    /// #[tokio::main]
-    /// async fn main() {
-    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
-    ///
-    ///     // 0 tasks have been instrumented, much less polled
-    ///     assert_eq!(metrics_monitor.cumulative().first_poll_count, 0);
-    ///
-    ///     // instrument a task and poll it to completion
-    ///     metrics_monitor.instrument(async {}).await;
+    /// The number of tasks that completed; i.e., that were polled to completion. Tasks that
+    /// [timed out][TaskMetrics::timed_out_count] are counted as completed.
     ///
-    ///     // 1 task has been instrumented and polled
-    ///     assert_eq!(metrics_monitor.cumulative().first_poll_count, 1);
+    /// Each instrumented task that is [dropped][TaskMetrics::dropped_count] is counted by
+    /// exactly one of this metric,
+    /// [`dropped_before_completion_count`][TaskMetrics::dropped_before_completion_count],
+    /// [`cancelled_count`][TaskMetrics::cancelled_count], and
+    /// [`panicked_count`][TaskMetrics::panicked_count], unless it is
+    /// [uninstrumented][Instrumented::into_inner] first.
     ///
-    ///     // instrument a task and poll it to completion
-    ///     metrics_monitor.instrument(async {}).await;
+    /// ##### Derived metrics
+    /// - **[`mean_polls_to_complete`][TaskMetrics::mean_polls_to_complete]**   
+    ///   The mean number of times that tasks were polled before completing.
+    pub completed_count: u64,
+
+    /// The total number of times that the tasks counted by
+    /// [`completed_count`][TaskMetrics::completed_count] were polled, over their lifetimes.
     ///
-    ///     // 2 tasks have been instrumented and polled
-    ///     assert_eq!(metrics_monitor.cumulative().first_poll_count, 2);
-    /// }
-    /// ```
-    /// An aync task may be tracked by multiple [`TaskMonitor`]s; e.g.:
-    /// ```
-    /// #[tokio::main]
-    /// async fn main() {
-    ///     let monitor_a = tokio_metrics::TaskMonitor::new();
-    ///     let monitor_b = tokio_metrics::TaskMonitor::new();
+    /// A task's polls are counted here only once it completes; polls of tasks that have yet to
+    /// complete, or that never do, are counted only by
+    /// [`total_poll_count`][TaskMetrics::total_poll_count].
     ///
-    ///     // 0 tasks have been instrumented, much less polled
-    ///     assert_eq!(monitor_a.cumulative().first_poll_count, 0);
-    ///     assert_eq!(monitor_b.cumulative().first_poll_count, 0);
+    /// ##### Derived metrics
+    /// - **[`mean_polls_to_complete`][TaskMetrics::mean_polls_to_complete]**   
+    ///   The mean number of times that tasks were polled before completing.
+    pub total_completed_poll_count: u64,
+
+    /// The number of tasks [spawned by the monitor][TaskMonitor::spawn] that were dropped before
+    /// completing, without panicking.
     ///
-    ///     // instrument a task and poll it to completion
-    ///     monitor_a.instrument(monitor_b.instrument(async {})).await;
+    /// Spawned tasks are only dropped early by the runtime: either because they were aborted
+    /// (e.g., with [`JoinHandle::abort`][tokio::task::JoinHandle::abort]), or because the
+    /// runtime shut down while they were still pending. Both are counted here, and are not
+    /// distinguished; a surge in this metric while the runtime is otherwise healthy is
+    /// characteristic of an abort storm.
     ///
-    ///     // 1 task has been instrumented and polled
-    ///     assert_eq!(monitor_a.cumulative().first_poll_count, 1);
-    ///     assert_eq!(monitor_b.cumulative().first_poll_count, 1);
-    /// }
-    /// ```
-    /// It is also possible (but probably undesirable) to instrument an async task multiple times
-    /// with the same [`TaskMonitor`]; e.g.:
+    /// ##### Examples
     /// ```
     /// #[tokio::main]
     /// async fn main() {
     ///     let monitor = tokio_metrics::TaskMonitor::new();
     ///
-    ///     // 0 tasks have been instrumented, much less polled
-    ///     assert_eq!(monitor.cumulative().first_poll_count, 0);
+    ///     let handle = monitor.spawn(std::future::pending::<()>());
+    ///     handle.abort();
+    ///     assert!(handle.await.unwrap_err().is_cancelled());
     ///
-    ///     // instrument a task and poll it to completion
-    ///     monitor.instrument(monitor.instrument(async {})).await;
+    ///     monitor.spawn(async {}).await.unwrap();
     ///
-    ///     // 2 tasks have been instrumented and polled, supposedly
-    ///     assert_eq!(monitor.cumulative().first_poll_count, 2);
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.dropped_before_completion_count, 1);
+    ///     assert_eq!(metrics.completed_count, 1);
+    ///     assert_eq!(metrics.cancelled_count, 0);
     /// }
     /// ```
-    pub fn instrument<F: Future>(&self, task: F) -> Instrumented<F> {
-        self.metrics.instrumented_count.fetch_add(1, SeqCst);
-        Instrumented {
-            task,
-            did_poll_once: false,
-            idled_at: 0,
-            state: Arc::new(State {
-                metrics: self.metrics.clone(),
-                instrumented_at: Instant::now(),
-                woke_at: AtomicU64::new(0),
-                waker: AtomicWaker::new(),
-            }),
-        }
-    }
+    pub dropped_before_completion_count: u64,
 
-    /// Produces [`TaskMetrics`] for the tasks instrumented by this [`TaskMonitor`], collected since
-    /// the construction of [`TaskMonitor`].
+    /// The number of tasks, not spawned by the monitor, that were dropped before completing.
     ///
-    /// ##### See also
-    /// - [`TaskMonitor::intervals`]:
-    ///     produces [`TaskMetrics`] for user-defined sampling intervals, instead of cumulatively
+    /// Futures are cancelled by being dropped; e.g., by losing a `select!`, by timing out, or by
+    /// the cancellation of the task that awaits them.
     ///
     /// ##### Examples
-    /// In the below example, 0 polls occur within the first sampling interval, 3 slow polls occur
-    /// within the second sampling interval, and 2 slow polls occur within the third sampling
-    /// interval; five slow polls occur across all sampling intervals:
     /// ```
-    /// use std::future::Future;
     /// use std::time::Duration;
     ///
     /// #[tokio::main]
     /// async fn main() {
-    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
-    ///
-    ///     // initialize a stream of sampling intervals
-    ///     let mut intervals = metrics_monitor.intervals();
-    ///     // each call of `next_interval` will produce metrics for the last sampling interval
-    ///     let mut next_interval = || intervals.next().unwrap();
-    ///
-    ///     let slow = 10 * metrics_monitor.slow_poll_threshold();
-    ///
-    ///     // this task completes in three slow polls
-    ///     let _ = metrics_monitor.instrument(async {
-    ///         spin_for(slow).await; // slow poll 1
-    ///         spin_for(slow).await; // slow poll 2
-    ///         spin_for(slow)        // slow poll 3
-    ///     }).await;
-    ///
-    ///     // in the previous sampling interval, there were 3 slow polls
-    ///     assert_eq!(next_interval().total_slow_poll_count, 3);
-    ///     assert_eq!(metrics_monitor.cumulative().total_slow_poll_count, 3);
-    ///
-    ///     // this task completes in two slow polls
-    ///     let _ = metrics_monitor.instrument(async {
-    ///         spin_for(slow).await; // slow poll 1
-    ///         spin_for(slow)        // slow poll 2
-    ///     }).await;
-    ///
-    ///     // in the previous sampling interval, there were 3 slow polls
-    ///     assert_eq!(next_interval().total_slow_poll_count, 2);
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
     ///
-    ///     // across all sampling interval, there were a total of 5 slow polls
-    ///     assert_eq!(metrics_monitor.cumulative().total_slow_poll_count, 5);
-    /// }
+    ///     tokio::select! {
+    ///         _ = monitor.instrument(std::future::pending::<()>()) => unreachable!(),
+    ///         _ = tokio::time::sleep(Duration::from_millis(10)) => {}
+    ///     }
     ///
-    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
-    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
-    ///     let start = tokio::time::Instant::now();
-    ///     while start.elapsed() <= duration {}
-    ///     tokio::task::yield_now()
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.cancelled_count, 1);
+    ///     assert_eq!(metrics.completed_count, 0);
     /// }
     /// ```
-    pub fn cumulative(&self) -> TaskMetrics {
-        self.metrics.metrics()
-    }
+    pub cancelled_count: u64,
 
-    /// Produces an unending iterator of metric sampling intervals.
+    /// The number of tasks that panicked while being polled.
     ///
-    /// Each sampling interval is defined by the time elapsed between advancements of the iterator
-    /// produced by [`TaskMonitor::intervals`]. The item type of this iterator is [`TaskMetrics`],
-    /// which is a bundle of task metrics that describe *only* events occurring within that sampling
-    /// interval.
+    /// Panics in spawned tasks are caught by the runtime, and otherwise only surface when their
+    /// [`JoinHandle`][tokio::task::JoinHandle] is awaited, or in logs; a steadily rising count of
+    /// panics reveals background tasks caught in a crash-loop.
     ///
     /// ##### Examples
-    /// In the below example, 0 polls occur within the first sampling interval, 3 slow polls occur
-    /// within the second sampling interval, and 2 slow polls occur within the third sampling
-    /// interval; five slow polls occur across all sampling intervals:
     /// ```
-    /// use std::future::Future;
-    /// use std::time::Duration;
-    ///
     /// #[tokio::main]
     /// async fn main() {
-    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
-    ///
-    ///     // initialize a stream of sampling intervals
-    ///     let mut intervals = metrics_monitor.intervals();
-    ///     // each call of `next_interval` will produce metrics for the last sampling interval
-    ///     let mut next_interval = || intervals.next().unwrap();
-    ///
-    ///     let slow = 10 * metrics_monitor.slow_poll_threshold();
-    ///
-    ///     // this task completes in three slow polls
-    ///     let _ = metrics_monitor.instrument(async {
-    ///         spin_for(slow).await; // slow poll 1
-    ///         spin_for(slow).await; // slow poll 2
-    ///         spin_for(slow)        // slow poll 3
-    ///     }).await;
-    ///
-    ///     // in the previous sampling interval, there were 3 slow polls
-    ///     assert_eq!(next_interval().total_slow_poll_count, 3);
-    ///
-    ///     // this task completes in two slow polls
-    ///     let _ = metrics_monitor.instrument(async {
-    ///         spin_for(slow).await; // slow poll 1
-    ///         spin_for(slow)        // slow poll 2
-    ///     }).await;
-    ///
-    ///     // in the previous sampling interval, there were 3 slow polls
-    ///     assert_eq!(next_interval().total_slow_poll_count, 2);
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
     ///
-    ///     // across all sampling intervals, there were a total of 5 slow polls
-    ///     assert_eq!(metrics_monitor.cumulative().total_slow_poll_count, 5);
-    /// }
+    ///     let handle = monitor.spawn(async { panic!("oh no") });
+    ///     assert!(handle.await.unwrap_err().is_panic());
     ///
-    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
-    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
-    ///     let start = tokio::time::Instant::now();
-    ///     while start.elapsed() <= duration {}
-    ///     tokio::task::yield_now()
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.panicked_count, 1);
+    ///     assert_eq!(metrics.join_error_count, 1);
     /// }
     /// ```
-    pub fn intervals(&self) -> impl Iterator<Item = TaskMetrics> {
-        let latest = self.metrics.clone();
-        let mut previous: Option<TaskMetrics> = None;
+    pub panicked_count: u64,
 
-        std::iter::from_fn(move || {
-            let latest: TaskMetrics = latest.metrics();
-            let next = if let Some(previous) = previous {
-                TaskMetrics {
-                    instrumented_count: latest
-                        .instrumented_count
-                        .wrapping_sub(previous.instrumented_count),
-                    dropped_count: latest.dropped_count.wrapping_sub(previous.dropped_count),
-                    total_poll_count: latest
-                        .total_poll_count
-                        .wrapping_sub(previous.total_poll_count),
-                    total_poll_duration: sub(
-                        latest.total_poll_duration,
-                        previous.total_poll_duration,
-                    ),
-                    first_poll_count: latest
-                        .first_poll_count
-                        .wrapping_sub(previous.first_poll_count),
-                    total_idled_count: latest
-                        .total_idled_count
-                        .wrapping_sub(previous.total_idled_count),
-                    total_scheduled_count: latest
-                        .total_scheduled_count
-                        .wrapping_sub(previous.total_scheduled_count),
-                    total_fast_poll_count: latest
-                        .total_fast_poll_count
-                        .wrapping_sub(previous.total_fast_poll_count),
-                    total_slow_poll_count: latest
-                        .total_slow_poll_count
-                        .wrapping_sub(previous.total_slow_poll_count),
-                    total_first_poll_delay: sub(
-                        latest.total_first_poll_delay,
-                        previous.total_first_poll_delay,
-                    ),
-                    total_idle_duration: sub(
-                        latest.total_idle_duration,
-                        previous.total_idle_duration,
-                    ),
-                    total_scheduled_duration: sub(
-                        latest.total_scheduled_duration,
-                        previous.total_scheduled_duration,
-                    ),
-                    total_fast_poll_duration: sub(
-                        latest.total_fast_poll_duration,
-                        previous.total_fast_poll_duration,
-                    ),
-                    total_slow_poll_duration: sub(
-                        latest.total_slow_poll_duration,
-                        previous.total_slow_poll_duration,
-                    ),
-                }
-            } else {
-                latest
-            };
+    /// The number of tasks [spawned by the monitor][TaskMonitor::spawn] that were dropped before
+    /// completing, for any reason.
+    ///
+    /// This counts every spawned task whose [`JoinHandle`][tokio::task::JoinHandle], if awaited,
+    /// would resolve to an error: the spawned tasks that
+    /// [panicked while being polled][TaskMetrics::panicked_count], and those counted by
+    /// [`dropped_before_completion_count`][TaskMetrics::dropped_before_completion_count]; i.e.,
+    /// that were aborted, or that were still pending when the runtime shut down. A panic raised
+    /// while the task is not being polled (e.g., by the task's destructor) is not counted as a
+    /// panic, but the early drop that follows it is counted here all the same.
+    pub join_error_count: u64,
 
-            previous = Some(latest);
+    /// The number of tasks [instrumented as results][TaskMonitor::instrument_result] that
+    /// succeeded.
+    ///
+    /// ##### Derived metrics
+    /// - **[`failed_ratio`][TaskMetrics::failed_ratio]**   
+    ///   The ratio of classified tasks that failed.
+    pub succeeded_count: u64,
 
-            Some(next)
-        })
+    /// The number of tasks [instrumented as results][TaskMonitor::instrument_result] that
+    /// failed.
+    ///
+    /// ##### Derived metrics
+    /// - **[`failed_ratio`][TaskMetrics::failed_ratio]**   
+    ///   The ratio of classified tasks that failed.
+    pub failed_count: u64,
+}
+
+/// Identifies an instrumented task, uniquely among the tasks instrumented by the same
+/// [`TaskMonitor`].
+///
+/// Produced by [`Instrumented::id`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskId(u64);
+
+impl TaskId {
+    /// Produces the integer representation of this id.
+    pub fn as_u64(&self) -> u64 {
+        self.0
     }
 }
 
-impl RawMetrics {
-    fn metrics(&self) -> TaskMetrics {
-        let total_fast_poll_count = self.total_fast_poll_count.load(SeqCst);
-        let total_slow_poll_count = self.total_slow_poll_count.load(SeqCst);
+/// Key metrics of a single [instrumented][`TaskMonitor::instrument`] task.
+///
+/// Produced, upon the completion of a task, for the callback set with
+/// [`TaskMonitorBuilder::with_completion_callback`].
+///
+/// ##### Examples
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let summaries = Arc::new(Mutex::new(Vec::new()));
+///     let monitor = {
+///         let summaries = summaries.clone();
+///         tokio_metrics::TaskMonitor::builder()
+///             .with_completion_callback(move |summary| summaries.lock().unwrap().push(*summary))
+///             .build()
+///     };
+///
+///     monitor.instrument(async {
+///         tokio::time::sleep(Duration::from_millis(10)).await; // poll 1
+///         tokio::task::yield_now().await;                      // poll 2
+///     }).await;                                                // poll 3
+///
+///     let summaries = summaries.lock().unwrap();
+///     assert_eq!(summaries.len(), 1);
+///     assert_eq!(summaries[0].poll_count, 3);
+///     assert!(summaries[0].total_idle_duration >= Duration::from_millis(10));
+///     assert!(summaries[0].lifetime >= Duration::from_millis(10));
+/// }
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TaskSummary {
+    /// The id of the task.
+    pub id: TaskId,
 
-        let total_fast_poll_duration =
-            Duration::from_nanos(self.total_fast_poll_duration_ns.load(SeqCst));
-        let total_slow_poll_duration =
-            Duration::from_nanos(self.total_slow_poll_duration.load(SeqCst));
+    /// The number of times the task was polled.
+    pub poll_count: u64,
 
-        let total_poll_count = total_fast_poll_count + total_slow_poll_count;
-        let total_poll_duration = total_fast_poll_duration + total_slow_poll_duration;
+    /// The total duration elapsed during polls of the task.
+    pub total_poll_duration: Duration,
 
-        TaskMetrics {
-            instrumented_count: self.instrumented_count.load(SeqCst),
-            dropped_count: self.dropped_count.load(SeqCst),
+    /// The total duration the task spent waiting to be polled after awakening.
+    pub total_scheduled_duration: Duration,
 
-            total_poll_count,
-            total_poll_duration,
-            first_poll_count: self.first_poll_count.load(SeqCst),
-            total_idled_count: self.total_idled_count.load(SeqCst),
-            total_scheduled_count: self.total_scheduled_count.load(SeqCst),
-            total_fast_poll_count: self.total_fast_poll_count.load(SeqCst),
-            total_slow_poll_count: self.total_slow_poll_count.load(SeqCst),
-            total_first_poll_delay: Duration::from_nanos(
-                self.total_first_poll_delay_ns.load(SeqCst),
-            ),
-            total_idle_duration: Duration::from_nanos(self.total_idle_duration_ns.load(SeqCst)),
+    /// The total duration the task spent waiting to be awoken.
+    pub total_idle_duration: Duration,
+
+    /// The duration elapsed between the instant the task was instrumented, and the instant it was
+    /// first polled.
+    pub first_poll_delay: Duration,
+
+    /// The duration elapsed between the instant the task was instrumented, and the instant it
+    /// completed.
+    pub lifetime: Duration,
+
+    /// The name of the task, if it was [named][TaskMonitor::instrument_named].
+    pub name: Option<&'static str>,
+
+    /// The source location at which the task was instrumented, if
+    /// [captured][TaskMonitorBuilder::with_location_capture].
+    pub location: Option<&'static Location<'static>>,
+}
+
+/// A live task that appears to be stalled, as reported by [`TaskMonitor::stalled_tasks`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy)]
+pub struct StalledTask {
+    /// The reason this task is considered stalled.
+    pub kind: StallKind,
+
+    /// The duration for which this task has been stalled; e.g., for
+    /// [`StallKind::Scheduled`], the duration elapsed since it was awoken, and for
+    /// [`StallKind::Incomplete`], the duration elapsed since it was instrumented.
+    pub stalled_for: Duration,
+
+    /// The duration elapsed since this task was instrumented.
+    pub age: Duration,
+
+    /// The name of this task, if it was [named][TaskMonitor::instrument_named].
+    pub name: Option<&'static str>,
+
+    /// The source location at which this task was instrumented, if
+    /// [captured][TaskMonitorBuilder::with_location_capture].
+    pub location: Option<&'static Location<'static>>,
+}
+
+/// The live tasks of a monitor that appeared stalled, and a dump of the runtime's tasks captured
+/// upon detecting them, as produced by [`TaskMonitor::dump_stalled_tasks`] and passed to the
+/// [stall-dump callback][TaskMonitorBuilder::with_stall_dump_callback].
+///
+/// The dump is of the whole runtime, not of each stalled task: its traces are not associated
+/// with the entries of `stalled`.
+///
+/// **This functionality requires `--cfg tokio_unstable --cfg tokio_taskdump`.**
+#[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+#[cfg_attr(docsrs, doc(cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))))]
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct StallReport {
+    /// The tasks that appeared stalled, as reported by [`TaskMonitor::stalled_tasks`].
+    pub stalled: Vec<StalledTask>,
+
+    /// A dump of every task of the current runtime, including the stalled tasks, with a trace of
+    /// where each is awaiting, in no particular relation to the order of `stalled`.
+    pub dump: tokio::runtime::Dump,
+}
+
+/// A poll recorded in the leaderboard of slowest polls, as reported by
+/// [`TaskMonitor::take_slowest_polls`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy)]
+pub struct SlowPoll {
+    /// The duration of the poll.
+    pub duration: Duration,
+
+    /// The wall-clock time at which the poll completed.
+    pub completed_at: SystemTime,
+
+    /// The name of the polled task, if it was [named][TaskMonitor::instrument_named].
+    pub name: Option<&'static str>,
+
+    /// The source location at which the polled task was instrumented, if
+    /// [captured][TaskMonitorBuilder::with_location_capture].
+    pub location: Option<&'static Location<'static>>,
+
+    /// The hardware performance counters sampled around the poll, if
+    /// [enabled][TaskMonitorBuilder::with_perf_counters] and available.
+    pub perf: Option<PerfCounters>,
+}
+
+/// The sketches of poll durations and schedule delays of a monitor.
+struct LatencySketches {
+    polls: Mutex<LatencySketch>,
+    scheduled: Mutex<LatencySketch>,
+}
+
+/// The polls that fell into a latency bucket, as reported by [`TaskMonitor::poll_buckets`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PollBucket {
+    /// The inclusive lower bound of the durations of the polls in this bucket.
+    pub lower_bound: Duration,
+
+    /// The exclusive upper bound of the durations of the polls in this bucket, or `None` if this
+    /// is the last bucket.
+    pub upper_bound: Option<Duration>,
+
+    /// The number of polls in this bucket.
+    pub count: u64,
+
+    /// The total duration of the polls in this bucket.
+    pub total_duration: Duration,
+
+    /// The most recent [slow][TaskMonitor::slow_poll_threshold] poll in this bucket, if any; e.g.,
+    /// to link an outlying bucket to a trace.
+    pub exemplar: Option<PollExemplar>,
+}
+
+/// The polls that reached a slow-poll severity tier, as reported by
+/// [`TaskMonitor::slow_poll_tiers`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlowPollTier {
+    /// The name of the tier; e.g., `critical`.
+    pub name: &'static str,
+
+    /// The duration at or beyond which polls reach this tier.
+    pub threshold: Duration,
+
+    /// The number of polls that reached this tier.
+    pub poll_count: u64,
+
+    /// The total duration of the polls that reached this tier.
+    pub total_poll_duration: Duration,
+}
+
+/// A poll exemplifying a [`PollBucket`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollExemplar {
+    /// The duration of the poll.
+    pub duration: Duration,
+
+    /// The wall-clock time at which the poll completed.
+    pub completed_at: SystemTime,
+
+    /// The id of the [`tracing`] span that was current during the poll, if any.
+    ///
+    /// **This is always `None` unless the crate feature `tracing` is enabled.**
+    pub span_id: Option<u64>,
+}
+
+/// The polls performed by a worker thread, as reported by [`TaskMonitor::worker_polls`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkerPolls {
+    /// The thread that performed the polls.
+    pub thread: ThreadId,
+
+    /// The number of polls performed by the thread.
+    pub poll_count: u64,
+
+    /// The total duration of the polls performed by the thread.
+    pub total_poll_duration: Duration,
+
+    /// The number of [slow][TaskMonitor::slow_poll_threshold] polls performed by the thread.
+    pub slow_poll_count: u64,
+
+    /// The total duration of the slow polls performed by the thread.
+    pub total_slow_poll_duration: Duration,
+}
+
+/// The polls performed within a branch of a task, as reported by [`TaskMonitor::branch_polls`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BranchPolls {
+    /// The name of the branch.
+    pub name: &'static str,
+
+    /// The number of polls of the branch.
+    pub poll_count: u64,
+
+    /// The total duration of the polls of the branch.
+    pub total_poll_duration: Duration,
+
+    /// The number of [slow][TaskMonitor::slow_poll_threshold] polls of the branch.
+    pub slow_poll_count: u64,
+
+    /// The total duration of the slow polls of the branch.
+    pub total_slow_poll_duration: Duration,
+}
+
+/// The poll counters of a worker thread, or of a branch; aligned to a cache line, since those of
+/// different threads are updated concurrently.
+#[derive(Debug, Default)]
+#[repr(align(128))]
+struct WorkerCounters {
+    poll_count: AtomicU64,
+    poll_duration_ns: AtomicU64,
+    slow_poll_count: AtomicU64,
+    slow_poll_duration_ns: AtomicU64,
+}
+
+/// The greatest delays observed, in nanoseconds; aligned to a cache line, since they are raised
+/// by polls.
+#[derive(Debug, Default)]
+#[repr(align(128))]
+struct Watermarks {
+    first_poll_delay_ns: AtomicU64,
+    scheduled_ns: AtomicU64,
+    concurrent_polls: AtomicU64,
+    instrumentation_gap_ns: AtomicU64,
+}
+
+/// The maxima and polling threads of the current interval of an interval iterator.
+struct IntervalTracker {
+    watermarks: Arc<Watermarks>,
+    threads: HashMap<ThreadId, u64>,
+}
+
+/// The counters updated by polls and wakes, grouped on cache lines of their own.
+///
+/// Since every poll updates some of these counters, from whichever worker thread it runs on, they
+/// are kept apart from the configuration of the monitor, which every poll reads; the counters
+/// most often updated together come first, so that a poll mostly touches the first line. The
+/// alignment is 128 bytes, since x86-64 and aarch64 prefetch cache lines in adjacent pairs.
+#[derive(Debug, Default)]
+#[repr(C, align(128))]
+struct PollCounters {
+    /// Total number of times tasks were scheduled.
+    total_scheduled_count: AtomicU64,
+
+    /// Total amount of time tasks spent in the waking state.
+    total_scheduled_duration_ns: AtomicU64,
+
+    /// Total number of times tasks were polled fast
+    total_fast_poll_count: AtomicU64,
+
+    /// Total amount of time tasks spent being polled below the slow cut off.
+    total_fast_poll_duration_ns: AtomicU64,
+
+    /// Total number of times polling tasks returned `Pending`
+    total_pending_poll_count: AtomicU64,
+
+    /// Total number of times tasks were woken during a poll that returned `Pending`
+    total_budget_yield_count: AtomicU64,
+
+    /// Total number of times tasks were woken from the thread that last polled them.
+    total_local_wake_count: AtomicU64,
+
+    /// Total number of times tasks were woken from another thread than the one that last polled
+    /// them.
+    total_remote_wake_count: AtomicU64,
+
+    /// Total number of times tasks entered the `idle` state.
+    total_idled_count: AtomicU64,
+
+    /// Total amount of time tasks spent in the `idle` state.
+    total_idle_duration_ns: AtomicU64,
+
+    /// Total number of times tasks were polled slow
+    total_slow_poll_count: AtomicU64,
+
+    /// Total amount of time tasks spent being polled above the slow cut off.
+    total_slow_poll_duration: AtomicU64,
+
+    /// Total number of times tasks were polled without being timed
+    total_untimed_poll_count: AtomicU64,
+
+    /// Number of tasks being polled
+    concurrent_poll_count: AtomicU64,
+
+    /// Total number of tasks that completed.
+    completed_count: AtomicU64,
+
+    /// Total number of times tasks that completed were polled.
+    total_completed_poll_count: AtomicU64,
+
+    /// Total amount of CPU time consumed by threads polling tasks.
+    total_poll_cpu_duration_ns: AtomicU64,
+
+    /// Total number of allocations performed by threads polling tasks.
+    total_allocation_count: AtomicU64,
+
+    /// Total number of bytes allocated by threads polling tasks.
+    total_allocated_bytes: AtomicU64,
+}
+
+/// Counts a poll as in progress until dropped; i.e., until the poll returns or unwinds.
+struct ConcurrentPoll<'a>(&'a RawMetrics);
+
+/// A slow-poll severity tier, and its counters; aligned to a cache line, since adjacent tiers are
+/// updated concurrently.
+#[repr(align(128))]
+struct SlowPollTierCounters {
+    name: &'static str,
+    threshold: Duration,
+    callback: Option<SlowPollCallback>,
+    count: AtomicU64,
+    duration_ns: AtomicU64,
+}
+
+/// The counters of a poll latency bucket; aligned to a cache line, since adjacent buckets are
+/// updated concurrently.
+#[derive(Debug, Default)]
+#[repr(align(128))]
+struct PollBucketCounters {
+    count: AtomicU64,
+    duration_ns: AtomicU64,
+    exemplar: Mutex<Option<PollExemplar>>,
+}
+
+/// The [cumulative][TaskMonitor::cumulative] metrics of a monitor, as of the instant they were
+/// taken by [`TaskMonitor::snapshot`].
+///
+/// Unlike the iterator produced by [`TaskMonitor::intervals`], snapshots may be retained and
+/// compared freely; e.g., by an external poller with its own scheduling.
+///
+/// ##### Examples
+/// ```
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let monitor = tokio_metrics::TaskMonitor::new();
+///     let earlier = monitor.snapshot();
+///
+///     monitor.instrument(async {}).await;
+///     tokio::time::sleep(Duration::from_millis(10)).await;
+///
+///     let later = monitor.snapshot();
+///     assert_eq!(later.delta_since(&earlier).instrumented_count, 1);
+///     assert!(later.elapsed_since(&earlier) >= Duration::from_millis(10));
+/// }
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy)]
+pub struct Snapshot {
+    /// The cumulative metrics of the monitor.
+    pub metrics: TaskMetrics,
+
+    /// The instant at which the metrics were taken.
+    pub taken_at: Instant,
+}
+
+/// The [`TaskMetrics`] of a sampling interval, together with the bounds of the interval, as
+/// produced by [`TaskMonitor::sampled_intervals`].
+///
+/// Rates (e.g., polls per second) are only meaningful with respect to the length of the interval
+/// from which they are derived; exporters that attach explicit timestamps to samples need its
+/// bounds. The bounds are taken on the wall clock, whereas the length of the interval is measured
+/// on the monotonic clock.
+///
+/// ##### Examples
+/// ```
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let monitor = tokio_metrics::TaskMonitor::new();
+///     let mut intervals = monitor.sampled_intervals();
+///
+///     monitor.instrument(async {}).await;
+///     tokio::time::sleep(Duration::from_millis(10)).await;
+///
+///     let sample = intervals.next().unwrap();
+///     assert_eq!(sample.metrics.instrumented_count, 1);
+///     assert!(sample.elapsed >= Duration::from_millis(10));
+///     assert!(sample.ended_at >= sample.started_at);
+///
+///     let next = intervals.next().unwrap();
+///     assert_eq!(next.started_at, sample.ended_at);
+/// }
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy)]
+pub struct IntervalSample {
+    /// The metrics of the events that occurred within the interval.
+    pub metrics: TaskMetrics,
+
+    /// The wall-clock time at which the interval started.
+    pub started_at: SystemTime,
+
+    /// The wall-clock time at which the interval ended.
+    pub ended_at: SystemTime,
+
+    /// The length of the interval.
+    pub elapsed: Duration,
+}
+
+impl IntervalSample {
+    /// Normalizes the metrics of this sample by the length of its interval.
+    ///
+    /// See [`TaskMetrics::rates`].
+    pub fn rates(&self) -> TaskMetricsRates {
+        self.metrics.rates(self.elapsed)
+    }
+}
+
+/// The [`TaskMetrics`] of a sampling interval, normalized by the interval's duration, as
+/// produced by [`TaskMetrics::rates`].
+///
+/// Every rate is zero for an interval of zero duration. Since tasks are polled, scheduled and
+/// idle concurrently, the fractions of time each state accounts for are summed across tasks, and
+/// may exceed `1.0`; e.g., a polling fraction of `2.0` means the tasks kept two worker threads
+/// busy.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TaskMetricsRates {
+    /// The number of tasks [instrumented][TaskMetrics::instrumented_count] per second.
+    pub instrumented_per_second: f64,
+
+    /// The number of tasks [dropped][TaskMetrics::dropped_count] per second.
+    pub dropped_per_second: f64,
+
+    /// The number of [polls][TaskMetrics::total_poll_count] per second.
+    pub polls_per_second: f64,
+
+    /// The number of [slow polls][TaskMetrics::total_slow_poll_count] per second.
+    pub slow_polls_per_second: f64,
+
+    /// The number of times tasks were [scheduled][TaskMetrics::total_scheduled_count] per second.
+    pub scheduled_per_second: f64,
+
+    /// The fraction of the interval that tasks spent
+    /// [being polled][TaskMetrics::total_poll_duration]; i.e., the
+    /// [duty cycle][TaskMetrics::duty_cycle].
+    pub polling_fraction: f64,
+
+    /// The fraction of the interval that tasks spent
+    /// [awaiting a poll after being awoken][TaskMetrics::total_scheduled_duration].
+    pub scheduled_fraction: f64,
+
+    /// The fraction of the interval that tasks spent [idle][TaskMetrics::total_idle_duration].
+    pub idle_fraction: f64,
+}
+
+/// A live task, as reported by [`TaskMonitor::live_tasks`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy)]
+pub struct LiveTask {
+    /// The id of this task.
+    pub id: TaskId,
+
+    /// The state of this task.
+    pub state: LiveTaskState,
+
+    /// The duration elapsed since this task was instrumented.
+    pub age: Duration,
+
+    /// The number of times this task has been polled.
+    pub poll_count: u64,
+
+    /// The total duration of the polls of this task. Polls are not timed if
+    /// [timing is disabled][TaskMonitorBuilder::with_timing].
+    pub total_poll_duration: Duration,
+
+    /// The name of this task, if it was [named][TaskMonitor::instrument_named].
+    pub name: Option<&'static str>,
+
+    /// The source location at which this task was instrumented, if
+    /// [captured][TaskMonitorBuilder::with_location_capture].
+    pub location: Option<&'static Location<'static>>,
+}
+
+/// The state of a [`LiveTask`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveTaskState {
+    /// The task has been instrumented, but not yet polled.
+    FirstPoll,
+
+    /// The task has been polled, and is awaiting an event; or, it is being polled.
+    Idle,
+
+    /// The task has been awoken, and is awaiting a poll.
+    Scheduled,
+}
+
+/// A report of the live tasks of a monitor, as produced by [`TaskMonitor::dump`].
+///
+/// Formats as a line per task, with its id, name, state, age, polls and location.
+#[derive(Debug, Clone)]
+pub struct TaskDump {
+    tasks: Vec<LiveTask>,
+}
+
+/// The order of the tasks of a [`TaskDump`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpOrder {
+    /// Oldest first.
+    Age,
+
+    /// Greatest [total poll duration][LiveTask::total_poll_duration] first.
+    PollDuration,
+}
+
+/// The reason a [`StalledTask`] is considered stalled.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StallKind {
+    /// The task was instrumented, but has not been polled for at least the
+    /// [stalled-task threshold][TaskMonitorBuilder::with_stalled_task_threshold].
+    FirstPoll,
+
+    /// The task was awoken, but has not been polled since for at least the
+    /// [stalled-task threshold][TaskMonitorBuilder::with_stalled_task_threshold]. The task is
+    /// likely starved by other tasks that do not yield to the scheduler.
+    Scheduled,
+
+    /// The task has not completed within the
+    /// [incomplete-task threshold][TaskMonitorBuilder::with_incomplete_task_threshold]. The task
+    /// may be deadlocked, or waiting on an event that will never occur.
+    Incomplete,
+}
+
+/// Tracks the metrics, shared across the various types.
+struct RawMetrics {
+    /// A task poll takes longer than this, it is considered a slow poll. Changes at most once,
+    /// when calibrated.
+    slow_poll_threshold_ns: AtomicU64,
+
+    /// The calibration of the slow-poll threshold, if enabled.
+    calibration: Option<Calibration>,
+
+    /// A task poll takes longer than this, it is considered a blocking poll.
+    blocking_poll_threshold: Option<Duration>,
+
+    /// Invoked with the duration of each blocking poll.
+    on_blocking_poll: Option<BlockingPollCallback>,
+
+    /// A task awoken, but not polled, for longer than this, is considered stalled.
+    stalled_task_threshold: Option<Duration>,
+
+    /// A task incomplete for longer than this, is considered stalled.
+    incomplete_task_threshold: Option<Duration>,
+
+    /// Invoked with a dump of the runtime when tasks are found stalled, if present.
+    #[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+    on_stall_dump: Option<StallDumpCallback>,
+
+    /// Whether a dump for `on_stall_dump` is in progress.
+    #[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+    dumping: AtomicBool,
+
+    /// The allocations of the states of completed tasks, for reuse; if pooling is enabled.
+    state_pool: Option<StatePool>,
+
+    /// The live instrumented tasks, keyed by task id; tracked only if live-task tracking or
+    /// stalled-task detection is enabled.
+    live_tasks: Option<Mutex<HashMap<u64, Weak<State>>>>,
+
+    /// The id of the next instrumented task.
+    next_task_id: AtomicU64,
+
+    /// The maximum number of polls retained by `slowest_polls`.
+    slowest_polls_capacity: usize,
+
+    /// The slowest polls since the leaderboard was last taken.
+    slowest_polls: Mutex<Vec<SlowPoll>>,
+
+    /// The duration, in nanoseconds, a poll must reach to enter `slowest_polls`: one more than
+    /// the duration of its fastest poll while it is full, and zero otherwise. It is only written
+    /// while `slowest_polls` is locked, and only rises until the leaderboard is taken; polls that
+    /// pass this fast path are checked again under the lock, so a stale floor is harmless.
+    slowest_polls_floor_ns: AtomicU64,
+
+    /// Where poll and schedule events should be recorded, if anywhere.
+    trace_recorder: Option<TraceRecorder>,
+
+    /// Notified of each raw event, if present.
+    observer: Option<Arc<dyn TaskObserver>>,
+
+    /// Whether polls are timed. If not, the monitor records only counts.
+    timing: bool,
+
+    /// Whether time-to-first-poll is tracked.
+    track_first_poll: bool,
+
+    /// Whether time-scheduled is tracked.
+    track_scheduled: bool,
+
+    /// Whether time-idled is tracked.
+    track_idle: bool,
+
+    /// Whether tasks are polled with an instrumented waker, which notes when they are awoken.
+    track_wakes: bool,
+
+    /// Whether the source locations at which tasks are instrumented are captured.
+    capture_locations: bool,
+
+    /// Periodic checkpoints of the cumulative metrics, if enabled.
+    checkpoints: Option<Checkpoints>,
+
+    /// Whether maxima (e.g., of scheduling delays) and the number of tasks being polled are
+    /// tracked.
+    track_watermarks: bool,
+
+    /// When the counters updated by each poll are flushed from thread-local batches, if
+    /// batching is enabled.
+    batching: Option<Batching>,
+
+    /// The ascending thresholds that divide polls into latency buckets.
+    poll_bucket_thresholds: Vec<Duration>,
+
+    /// The counters of each latency bucket; one more than the thresholds, or none if no
+    /// thresholds are configured.
+    poll_buckets: Vec<PollBucketCounters>,
+
+    /// The slow-poll severity tiers, in ascending order of their thresholds.
+    slow_poll_tiers: Vec<SlowPollTierCounters>,
+
+    /// The sketches of poll durations and schedule delays, if enabled, since they were last
+    /// taken.
+    latency_sketches: Option<LatencySketches>,
+
+    /// Logs outlying polls and schedule delays, if enabled.
+    #[cfg(feature = "tracing")]
+    outlier_logger: Option<OutlierLogger>,
+
+    /// Whether the CPU time consumed by each poll is measured.
+    measure_cpu_time: bool,
+
+    /// Whether the allocations performed by each poll are counted.
+    track_allocations: bool,
+
+    /// Whether hardware performance counters are sampled around each poll.
+    sample_perf: bool,
+
+    /// The schedule delay at which the `long_schedule` probe fires, if probes are enabled.
+    #[cfg(feature = "usdt")]
+    usdt_long_schedule_threshold: Option<Duration>,
+
+    /// The poll counters of each thread that has polled tasks, if worker attribution is enabled.
+    workers: Option<RwLock<HashMap<ThreadId, Arc<WorkerCounters>>>>,
+
+    /// The poll counters of each [instrumented branch][TaskMonitor::instrument_branch], by name.
+    branches: RwLock<HashMap<&'static str, Arc<WorkerCounters>>>,
+
+    /// Invoked with the summary of each task that completes.
+    on_completion: Option<CompletionCallback>,
+
+    /// False while the monitor is disabled, in which case polls are not measured.
+    enabled: AtomicBool,
+
+    /// Total number of instrumented tasks.
+    instrumented_count: AtomicU64,
+
+    /// The instant the monitor was constructed.
+    created_at: Instant,
+
+    /// The instant the latest task was instrumented, as nanoseconds since `created_at`.
+    last_instrumented_ns: AtomicU64,
+
+    /// Total amount of time between the instrumentation of consecutive tasks.
+    total_instrumentation_gap_ns: AtomicU64,
+
+    /// Total number of instrumented tasks polled at least once.
+    first_poll_count: AtomicU64,
+
+    /// The counters updated by polls and wakes.
+    polls: PollCounters,
+
+    /// Total number of times tasks were polled above the blocking cut off
+    total_blocking_poll_count: AtomicU64,
+
+    /// Total number of times tasks were dropped
+    dropped_count: AtomicU64,
+
+    /// Total amount of time spent dropping tasks.
+    total_drop_duration_ns: AtomicU64,
+
+    /// Total amount of time until the first poll
+    total_first_poll_delay_ns: AtomicU64,
+
+    /// The maxima of delays since the monitor was constructed.
+    watermarks: Watermarks,
+
+    /// The maxima of delays within the current interval of each live interval iterator.
+    interval_watermarks: RwLock<Vec<Weak<Watermarks>>>,
+
+    /// The cumulative metrics of a previous run of the monitor, if restored.
+    restored: RwLock<Option<TaskMetrics>>,
+
+    /// Total number of tasks spawned via the spawn wrappers
+    spawned_count: AtomicU64,
+
+    /// Total number of spawned tasks polled at least once
+    spawned_first_poll_count: AtomicU64,
+
+    /// Total amount of time between the instrumentation and spawning of spawned tasks
+    total_spawn_delay_ns: AtomicU64,
+
+    /// Total amount of time between the spawning and first poll of spawned tasks
+    total_spawn_queue_delay_ns: AtomicU64,
+
+    /// Total number of regions measured by scopes.
+    total_inline_count: AtomicU64,
+
+    /// Total amount of time spent in regions measured by scopes.
+    total_inline_duration_ns: AtomicU64,
+
+    /// Total number of tasks that timed out.
+    timed_out_count: AtomicU64,
+
+    /// Total amount of time elapsed before tasks timed out.
+    total_timed_out_duration_ns: AtomicU64,
+
+    /// Total number of spawned tasks dropped before completing, without panicking.
+    dropped_before_completion_count: AtomicU64,
+
+    /// Total number of tasks, not spawned, dropped before completing.
+    cancelled_count: AtomicU64,
+
+    /// Total number of tasks that panicked.
+    panicked_count: AtomicU64,
+
+    /// Total number of spawned tasks dropped before completing, for any reason.
+    join_error_count: AtomicU64,
+
+    /// Total number of tasks classified as successful.
+    succeeded_count: AtomicU64,
+
+    /// Total number of tasks classified as failed.
+    failed_count: AtomicU64,
+}
+
+/// Periodic checkpoints of the cumulative metrics of a monitor, which back
+/// [`TaskMonitor::cumulative_since`].
+struct Checkpoints {
+    /// The minimum duration between checkpoints.
+    interval: Duration,
+
+    /// The maximum number of retained checkpoints.
+    capacity: usize,
+
+    /// The instant from which `next_ns` is measured.
+    created_at: Instant,
+
+    /// The instant, tracked as nanoseconds since `created_at`, at or after which the next
+    /// checkpoint is due.
+    next_ns: AtomicU64,
+
+    /// The most recent checkpoints, oldest first.
+    retained: Mutex<VecDeque<Snapshot>>,
+}
+
+/// The calibration of the slow-poll threshold from the durations of the first polls.
+struct Calibration {
+    /// The number of polls to calibrate from.
+    warm_up_polls: usize,
+
+    /// The quantile of the durations of the warm-up polls at which to set the threshold.
+    quantile: f64,
+
+    /// The durations of the warm-up polls so far, in nanoseconds.
+    samples: Mutex<Vec<u64>>,
+
+    /// True once the threshold has been calibrated.
+    done: AtomicBool,
+}
+
+/// When the thread-local batches of a monitor's poll counters are flushed.
+#[derive(Debug, Clone, Copy)]
+struct Batching {
+    /// The maximum number of polls batched before a flush.
+    max_polls: u64,
+
+    /// The maximum duration a batch is held before a flush.
+    max_delay: Duration,
+}
+
+/// Increments of the counters updated by each poll.
+#[derive(Debug, Default)]
+struct PollDeltas {
+    idled_count: u64,
+    idle_ns: u64,
+    scheduled_count: u64,
+    scheduled_ns: u64,
+    max_scheduled_ns: u64,
+    fast_poll_count: u64,
+    fast_poll_ns: u64,
+    slow_poll_count: u64,
+    slow_poll_ns: u64,
+    pending_poll_count: u64,
+}
+
+/// The poll counters of a monitor accumulated by the current thread, but not yet flushed to the
+/// monitor. The batch is flushed when dropped; e.g., when the thread exits. It does not keep the
+/// monitor's metrics alive.
+struct LocalBatch {
+    metrics: Weak<RawMetrics>,
+    deltas: PollDeltas,
+    polls: u64,
+    started_at: Instant,
+}
+
+thread_local! {
+    /// The unflushed batches of the current thread, one per monitor.
+    static LOCAL_BATCHES: RefCell<Vec<LocalBatch>> = RefCell::new(Vec::new());
+}
+
+/// Produces the id of the current [`tracing`] span, if any.
+fn current_span_id() -> Option<u64> {
+    #[cfg(feature = "tracing")]
+    return tracing::Span::current().id().map(|id| id.into_u64());
+    #[cfg(not(feature = "tracing"))]
+    return None;
+}
+
+/// The configuration of the pools of the allocations of the [`State`]s of completed tasks, which
+/// are kept per thread, in [`LOCAL_STATE_POOLS`].
+struct StatePool {
+    /// The maximum number of states pooled by each thread.
+    capacity: usize,
+
+    /// The metrics with which pooled states are associated, in lieu of those of their monitor,
+    /// which they would otherwise keep alive.
+    placeholder: Arc<RawMetrics>,
+}
+
+/// The allocations of the [`State`]s of a monitor's completed tasks pooled by the current thread.
+/// It does not keep the monitor's metrics alive.
+struct LocalStatePool {
+    metrics: Weak<RawMetrics>,
+    states: Vec<Arc<State>>,
+}
+
+thread_local! {
+    /// The state pools of the current thread, one per monitor.
+    static LOCAL_STATE_POOLS: RefCell<Vec<LocalStatePool>> = RefCell::new(Vec::new());
+}
+
+struct State {
+    /// Where metrics should be recorded
+    metrics: Arc<RawMetrics>,
+
+    /// Uniquely identifies the task among those instrumented by the same monitor.
+    id: u64,
+
+    /// True once the task has been polled for the first time.
+    polled: AtomicBool,
+
+    /// Instant at which the task was instrumented. This is used to track the time to first poll.
+    instrumented_at: Instant,
+
+    /// The name of the task, if named.
+    name: Option<&'static str>,
+
+    /// The source location at which the task was instrumented, if captured.
+    location: Option<&'static Location<'static>>,
+
+    /// The instant, tracked as nanoseconds since `instrumented_at`, at which the future
+    /// was last woken.
+    woke_at: AtomicU64,
+
+    /// The [id][current_thread_id] of the thread that last polled the task, or zero if it has
+    /// not been polled.
+    polled_on: AtomicU64,
+
+    /// The number of times the task has been polled; counted only if live tasks are tracked.
+    poll_count: AtomicU64,
+
+    /// The total duration of the polls of the task; timed only if live tasks are tracked.
+    poll_duration_ns: AtomicU64,
+}
+
+impl TaskMonitor {
+    /// The default duration at which polls cross the threshold into being categorized as 'slow' is
+    /// 50μs.
+    #[cfg(not(test))]
+    pub const DEFAULT_SLOW_POLL_THRESHOLD: Duration = Duration::from_micros(50);
+    #[cfg(test)]
+    pub const DEFAULT_SLOW_POLL_THRESHOLD: Duration = Duration::from_millis(500);
+
+    /// Constructs a new task monitor.
+    ///
+    /// Uses [`Self::DEFAULT_SLOW_POLL_THRESHOLD`] as the threshold at which polls will be
+    /// considered 'slow'.
+    pub fn new() -> TaskMonitor {
+        TaskMonitor::with_slow_poll_threshold(Self::DEFAULT_SLOW_POLL_THRESHOLD)
+    }
+
+    /// Constructs a new task monitor with a given threshold at which polls are considered 'slow'.
+    ///
+    /// ##### Selecting an appropriate threshold
+    /// A poll is slow if it holds up its worker thread long enough to delay the other tasks
+    /// scheduled on it; how long that is depends on the application. To start, a threshold can be
+    /// derived from the polls of the application itself: a
+    /// [calibrated][TaskMonitorBuilder::with_slow_poll_calibration] threshold is set to a quantile
+    /// (e.g., the 95th percentile) of the durations of the first polls of the monitor, so that
+    /// slow polls are those unusually slow for the tasks it instruments.
+    ///
+    /// ##### Examples
+    /// In the below example, low-threshold and high-threshold monitors are constructed and
+    /// instrument identical tasks; the low-threshold monitor reports4 slow polls, and the
+    /// high-threshold monitor reports only 2 slow polls:
+    /// ```
+    /// use std::future::Future;
+    /// use std::time::Duration;
+    /// use tokio_metrics::TaskMonitor;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let lo_threshold = Duration::from_micros(10);
+    ///     let hi_threshold = Duration::from_millis(10);
+    ///
+    ///     let lo_monitor = TaskMonitor::with_slow_poll_threshold(lo_threshold);
+    ///     let hi_monitor = TaskMonitor::with_slow_poll_threshold(hi_threshold);
+    ///
+    ///     let make_task = || async {
+    ///         spin_for(lo_threshold).await; // faster poll 1
+    ///         spin_for(lo_threshold).await; // faster poll 2
+    ///         spin_for(hi_threshold).await; // slower poll 3
+    ///         spin_for(hi_threshold).await  // slower poll 4
+    ///     };
+    ///
+    ///     lo_monitor.instrument(make_task()).await;
+    ///     hi_monitor.instrument(make_task()).await;
+    ///
+    ///     // the low-threshold monitor reported 4 slow polls:
+    ///     assert_eq!(lo_monitor.cumulative().total_slow_poll_count, 4);
+    ///     // the high-threshold monitor reported only 2 slow polls:
+    ///     assert_eq!(hi_monitor.cumulative().total_slow_poll_count, 2);
+    /// }
+    ///
+    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
+    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
+    ///     let start = tokio::time::Instant::now();
+    ///     while start.elapsed() <= duration {}
+    ///     tokio::task::yield_now()
+    /// }
+    /// ```
+    pub fn with_slow_poll_threshold(slow_poll_cut_off: Duration) -> TaskMonitor {
+        TaskMonitor::builder()
+            .with_slow_poll_threshold(slow_poll_cut_off)
+            .build()
+    }
+
+    /// Produces a [`TaskMonitorBuilder`], for configuring a new task monitor.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::TaskMonitor;
+    ///
+    /// let monitor = TaskMonitor::builder()
+    ///     .with_slow_poll_threshold(Duration::from_micros(100))
+    ///     .with_blocking_poll_threshold(Duration::from_millis(100))
+    ///     .build();
+    ///
+    /// assert_eq!(monitor.slow_poll_threshold(), Duration::from_micros(100));
+    /// assert_eq!(monitor.blocking_poll_threshold(), Some(Duration::from_millis(100)));
+    /// ```
+    pub fn builder() -> TaskMonitorBuilder {
+        TaskMonitorBuilder::new()
+    }
+
+    /// Produces the duration greater-than-or-equal-to at which polls are categorized as slow.
+    ///
+    /// ##### Examples
+    /// In the below example, [`TaskMonitor`] is initialized with [`TaskMonitor::new`];
+    /// consequently, its slow-poll threshold equals [`TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD`]:
+    /// ```
+    /// use tokio_metrics::TaskMonitor;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let metrics_monitor = TaskMonitor::new();
+    ///
+    ///     assert_eq!(
+    ///         metrics_monitor.slow_poll_threshold(),
+    ///         TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD
+    ///     );
+    /// }
+    /// ```
+    pub fn slow_poll_threshold(&self) -> Duration {
+        self.metrics.slow_poll_threshold()
+    }
+
+    /// Produces whether the slow-poll threshold has been
+    /// [calibrated][TaskMonitorBuilder::with_slow_poll_calibration]; or, if calibration is not
+    /// enabled, `true`.
+    pub fn is_slow_poll_threshold_calibrated(&self) -> bool {
+        match &self.metrics.calibration {
+            Some(calibration) => calibration.done.load(SeqCst),
+            None => true,
+        }
+    }
+
+    /// Produces the duration greater-than-or-equal-to at which polls are categorized as
+    /// 'probably blocking', if blocking-poll detection is enabled.
+    ///
+    /// Blocking-poll detection is disabled by default; enable it with
+    /// [`TaskMonitorBuilder::with_blocking_poll_threshold`].
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::TaskMonitor;
+    ///
+    /// assert_eq!(TaskMonitor::new().blocking_poll_threshold(), None);
+    ///
+    /// let monitor = TaskMonitor::builder()
+    ///     .with_blocking_poll_threshold(Duration::from_millis(100))
+    ///     .build();
+    ///
+    /// assert_eq!(monitor.blocking_poll_threshold(), Some(Duration::from_millis(100)));
+    /// ```
+    pub fn blocking_poll_threshold(&self) -> Option<Duration> {
+        self.metrics.blocking_poll_threshold
+    }
+
+    /// Enables or disables the measurement of the tasks instrumented by this monitor (and its
+    /// clones); e.g., so that operators can switch instrumentation on only while investigating an
+    /// incident. Monitors are enabled by default.
+    ///
+    /// While a monitor is disabled, its tasks are polled as if they were not instrumented, but for
+    /// a single relaxed load of this switch: polls, schedules and idles are not measured, and
+    /// [observers][TaskMonitorBuilder::with_observer] are not notified of them. Tasks are still
+    /// counted as they are [instrumented][TaskMetrics::instrumented_count] and
+    /// [dropped][TaskMetrics::dropped_count]. A task first polled while its monitor is disabled
+    /// is not counted as [polled][TaskMetrics::first_poll_count].
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     monitor.set_enabled(false);
+    ///     assert!(!monitor.is_enabled());
+    ///     monitor.instrument(async {}).await;
+    ///     assert_eq!(monitor.cumulative().total_poll_count, 0);
+    ///
+    ///     monitor.set_enabled(true);
+    ///     monitor.instrument(async {}).await;
+    ///     assert_eq!(monitor.cumulative().total_poll_count, 1);
+    /// }
+    /// ```
+    pub fn set_enabled(&self, enabled: bool) {
+        self.metrics.enabled.store(enabled, Relaxed);
+    }
+
+    /// Produces whether the tasks instrumented by this monitor are
+    /// [measured][TaskMonitor::set_enabled].
+    pub fn is_enabled(&self) -> bool {
+        self.metrics.enabled.load(Relaxed)
+    }
+
+    /// Produces an instrumented façade around a given async task.
+    ///
+    /// ##### Examples
+    /// Instrument an async task by passing it to [`TaskMonitor::instrument`]:
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     // 0 tasks have been instrumented, much less polled
+    ///     assert_eq!(metrics_monitor.cumulative().first_poll_count, 0);
+    ///
+    ///     // instrument a task and poll it to completion
+    ///     metrics_monitor.instrument(async {}).await;
+    ///
+    ///     // 1 task has been instrumented and polled
+    ///     assert_eq!(metrics_monitor.cumulative().first_poll_count, 1);
+    ///
+    ///     // instrument a task and poll it to completion
+    ///     metrics_monitor.instrument(async {}).await;
+    ///
+    ///     // 2 tasks have been instrumented and polled
+    ///     assert_eq!(metrics_monitor.cumulative().first_poll_count, 2);
+    /// }
+    /// ```
+    /// An aync task may be tracked by multiple [`TaskMonitor`]s; e.g.:
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor_a = tokio_metrics::TaskMonitor::new();
+    ///     let monitor_b = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     // 0 tasks have been instrumented, much less polled
+    ///     assert_eq!(monitor_a.cumulative().first_poll_count, 0);
+    ///     assert_eq!(monitor_b.cumulative().first_poll_count, 0);
+    ///
+    ///     // instrument a task and poll it to completion
+    ///     monitor_a.instrument(monitor_b.instrument(async {})).await;
+    ///
+    ///     // 1 task has been instrumented and polled
+    ///     assert_eq!(monitor_a.cumulative().first_poll_count, 1);
+    ///     assert_eq!(monitor_b.cumulative().first_poll_count, 1);
+    /// }
+    /// ```
+    /// It is also possible (but probably undesirable) to instrument an async task multiple times
+    /// with the same [`TaskMonitor`]; e.g.:
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     // 0 tasks have been instrumented, much less polled
+    ///     assert_eq!(monitor.cumulative().first_poll_count, 0);
+    ///
+    ///     // instrument a task and poll it to completion
+    ///     monitor.instrument(monitor.instrument(async {})).await;
+    ///
+    ///     // 2 tasks have been instrumented and polled, supposedly
+    ///     assert_eq!(monitor.cumulative().first_poll_count, 2);
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn instrument<F: Future>(&self, task: F) -> Instrumented<F> {
+        self.instrument_with_name(task, None)
+    }
+
+    /// Produces an instrumented façade around a given async task, like
+    /// [`TaskMonitor::instrument`], that is identified by `name` in diagnostics.
+    ///
+    /// The name is reported by the [`TaskSummary`], [`StalledTask`] and [`SlowPoll`] of the task,
+    /// so that, e.g., a slow poll can be attributed to `"connection_reader"`, rather than an
+    /// anonymous future.
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_incomplete_task_threshold(std::time::Duration::ZERO)
+    ///         .build();
+    ///
+    ///     let task = monitor.instrument_named("connection_reader", async {});
+    ///     assert_eq!(task.name(), Some("connection_reader"));
+    ///
+    ///     let stalled_tasks = monitor.stalled_tasks();
+    ///     assert_eq!(stalled_tasks[0].name, Some("connection_reader"));
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn instrument_named<F: Future>(&self, name: &'static str, task: F) -> Instrumented<F> {
+        self.instrument_with_name(task, Some(name))
+    }
+
+    /// Produces an instrumented façade around a given boxed async task, like
+    /// [`TaskMonitor::instrument`], which is itself boxed.
+    ///
+    /// Tasks are thereby instrumented through a single, object-safe signature, rather than one
+    /// instantiation of [`Instrumented`] per type of task; e.g., by plugin systems that deal
+    /// only in boxed futures, or by code that avoids monomorphization.
+    ///
+    /// The methods of traits declared with [`async-trait`](https://docs.rs/async-trait) return
+    /// boxed futures; a service layer that invokes such a trait may instrument each of its methods
+    /// at the call site, once for every implementation of the trait, with a monitor per method.
+    /// Tasks that are not [`Send`] may be instrumented with
+    /// [`TaskMonitor::instrument_boxed_local`].
+    ///
+    /// ##### Examples
+    /// ```
+    /// use futures::future::BoxFuture;
+    ///
+    /// /// A plugin, invoked through a trait object.
+    /// trait Handler {
+    ///     fn handle(&self) -> BoxFuture<'static, u32>;
+    /// }
+    ///
+    /// struct Answer;
+    ///
+    /// impl Handler for Answer {
+    ///     fn handle(&self) -> BoxFuture<'static, u32> {
+    ///         Box::pin(async { 42 })
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let handlers: Vec<Box<dyn Handler>> = vec![Box::new(Answer)];
+    ///
+    ///     for handler in &handlers {
+    ///         let task: BoxFuture<'static, u32> = monitor.instrument_boxed(handler.handle());
+    ///         assert_eq!(task.await, 42);
+    ///     }
+    ///
+    ///     assert_eq!(monitor.cumulative().first_poll_count, 1);
+    /// }
+    /// ```
+    /// Instrument each method of an `async-trait` trait, whatever its implementation:
+    /// ```
+    /// use async_trait::async_trait;
+    /// use tokio_metrics::TaskMonitor;
+    ///
+    /// #[async_trait]
+    /// trait Store {
+    ///     async fn get(&self, key: &str) -> Option<String>;
+    ///     async fn put(&self, key: &str, value: String);
+    /// }
+    ///
+    /// struct Null;
+    ///
+    /// #[async_trait]
+    /// impl Store for Null {
+    ///     async fn get(&self, _key: &str) -> Option<String> {
+    ///         None
+    ///     }
+    ///
+    ///     async fn put(&self, _key: &str, _value: String) {}
+    /// }
+    ///
+    /// /// Instruments the methods of any [`Store`].
+    /// struct InstrumentedStore<S> {
+    ///     inner: S,
+    ///     get: TaskMonitor,
+    ///     put: TaskMonitor,
+    /// }
+    ///
+    /// #[async_trait]
+    /// impl<S: Store + Send + Sync> Store for InstrumentedStore<S> {
+    ///     async fn get(&self, key: &str) -> Option<String> {
+    ///         self.get.instrument_boxed(self.inner.get(key)).await
+    ///     }
+    ///
+    ///     async fn put(&self, key: &str, value: String) {
+    ///         self.put.instrument_boxed(self.inner.put(key, value)).await
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let store = InstrumentedStore {
+    ///         inner: Null,
+    ///         get: TaskMonitor::new(),
+    ///         put: TaskMonitor::new(),
+    ///     };
+    ///
+    ///     store.put("greeting", "hello".to_owned()).await;
+    ///     assert_eq!(store.get("greeting").await, None);
+    ///
+    ///     assert_eq!(store.get.cumulative().first_poll_count, 1);
+    ///     assert_eq!(store.put.cumulative().first_poll_count, 1);
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn instrument_boxed<'a, T: 'a>(
+        &self,
+        task: Pin<Box<dyn Future<Output = T> + Send + 'a>>,
+    ) -> BoxFuture<'a, T> {
+        Box::pin(self.instrument(task))
+    }
+
+    /// Produces an instrumented façade around a given boxed async task that need not be
+    /// [`Send`], like [`TaskMonitor::instrument_boxed`].
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::rc::Rc;
+    ///
+    /// #[async_trait::async_trait(?Send)]
+    /// trait Handler {
+    ///     async fn handle(&self, request: Rc<str>) -> usize;
+    /// }
+    ///
+    /// struct Len;
+    ///
+    /// #[async_trait::async_trait(?Send)]
+    /// impl Handler for Len {
+    ///     async fn handle(&self, request: Rc<str>) -> usize {
+    ///         request.len()
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let handler: Box<dyn Handler> = Box::new(Len);
+    ///
+    ///     let len = monitor.instrument_boxed_local(handler.handle(Rc::from("hello"))).await;
+    ///     assert_eq!(len, 5);
+    ///     assert_eq!(monitor.cumulative().first_poll_count, 1);
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn instrument_boxed_local<'a, T: 'a>(
+        &self,
+        task: Pin<Box<dyn Future<Output = T> + 'a>>,
+    ) -> LocalBoxFuture<'a, T> {
+        Box::pin(self.instrument(task))
+    }
+
+    /// Wraps a factory of async tasks, such that every task it produces is
+    /// [instrumented][TaskMonitor::instrument] with this monitor; e.g., a handler function passed
+    /// to a framework that invokes it once per request.
+    ///
+    /// The factory takes a single argument; factories of several arguments may take a tuple. The
+    /// tasks it produces are attributed to the location at which it was wrapped, if
+    /// [locations are captured][TaskMonitorBuilder::with_location_capture].
+    ///
+    /// ##### Examples
+    /// ```
+    /// async fn handle(request: u32) -> u32 {
+    ///     request * 2
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut handle = monitor.instrument_fn(handle);
+    ///
+    ///     // e.g., a server invoking the handler once per request
+    ///     for request in 1..=3 {
+    ///         assert_eq!(handle(request).await, request * 2);
+    ///     }
+    ///
+    ///     assert_eq!(monitor.cumulative().instrumented_count, 3);
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn instrument_fn<F, A, Fut>(&self, mut f: F) -> impl FnMut(A) -> Instrumented<Fut>
+    where
+        F: FnMut(A) -> Fut,
+        Fut: Future,
+    {
+        let monitor = self.clone();
+        let location = if self.metrics.capture_locations {
+            Some(Location::caller())
+        } else {
+            None
+        };
+        move |args| monitor.instrument_at(f(args), None, location)
+    }
+
+    /// Produces an instrumented façade around a branch of a task, whose polls are counted and
+    /// timed under `name`; e.g., a branch of a `tokio::select!` loop, so that the poll time of the
+    /// loop can be attributed to the branch that consumes it, rather than the loop as a whole.
+    ///
+    /// A branch is not a task: it is polled within the polls of its task, which is measured as it
+    /// otherwise would be, and it is not counted in the [`TaskMetrics`] of this monitor. The
+    /// polls of branches are instead reported, by name, by [`TaskMonitor::branch_polls`].
+    /// Branches need not be within tasks instrumented by this monitor.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio::sync::mpsc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let (commands, mut inbox) = mpsc::unbounded_channel();
+    ///     let mut ticks = tokio::time::interval(Duration::from_millis(1));
+    ///
+    ///     commands.send("refresh").unwrap();
+    ///     drop(commands);
+    ///
+    ///     let event_loop = monitor.instrument({
+    ///         let monitor = monitor.clone();
+    ///         async move {
+    ///             loop {
+    ///                 tokio::select! {
+    ///                     command = monitor.instrument_branch("inbox", inbox.recv()) => {
+    ///                         if command.is_none() {
+    ///                             break;
+    ///                         }
+    ///                     }
+    ///                     _ = monitor.instrument_branch("tick", ticks.tick()) => {}
+    ///                 }
+    ///             }
+    ///         }
+    ///     });
+    ///     event_loop.await;
+    ///
+    ///     let branches = monitor.branch_polls();
+    ///     assert_eq!(branches[0].name, "inbox");
+    ///     assert!(branches[0].poll_count >= 2);
+    ///     assert!(monitor.cumulative().total_poll_duration >= branches[0].total_poll_duration);
+    /// }
+    /// ```
+    pub fn instrument_branch<F: Future>(
+        &self,
+        name: &'static str,
+        branch: F,
+    ) -> InstrumentedBranch<F> {
+        InstrumentedBranch {
+            branch,
+            metrics: self.metrics.clone(),
+            counters: self.metrics.branch_counters(name),
+        }
+    }
+
+    #[track_caller]
+    fn instrument_with_name<F: Future>(
+        &self,
+        task: F,
+        name: Option<&'static str>,
+    ) -> Instrumented<F> {
+        let location = if self.metrics.capture_locations {
+            Some(Location::caller())
+        } else {
+            None
+        };
+        self.instrument_at(task, name, location)
+    }
+
+    /// Instruments `task`, which is identified by `name` and was instrumented at `location` in
+    /// diagnostics.
+    fn instrument_at<F>(
+        &self,
+        task: F,
+        name: Option<&'static str>,
+        location: Option<&'static Location<'static>>,
+    ) -> Instrumented<F> {
+        let instrumented_at = Instant::now();
+        self.metrics.checkpoint(instrumented_at);
+        self.metrics.instrumented_count.fetch_add(1, SeqCst);
+        self.metrics.record_instrumentation(instrumented_at);
+        let state = State::acquire(State {
+            metrics: self.metrics.clone(),
+            id: self.metrics.next_task_id.fetch_add(1, SeqCst),
+            polled: AtomicBool::new(false),
+            instrumented_at,
+            name,
+            location,
+            woke_at: AtomicU64::new(0),
+            polled_on: AtomicU64::new(0),
+            poll_count: AtomicU64::new(0),
+            poll_duration_ns: AtomicU64::new(0),
+        });
+        if let Some(live_tasks) = &self.metrics.live_tasks {
+            live_tasks
+                .lock()
+                .unwrap()
+                .insert(state.id, Arc::downgrade(&state));
+        }
+        if let Some(observer) = &self.metrics.observer {
+            observer.on_instrument(TaskId(state.id));
+        }
+        Instrumented {
+            task: ManuallyDrop::new(task),
+            did_poll_once: false,
+            idled_at: 0,
+            poll_count: 0,
+            summary: self
+                .metrics
+                .on_completion
+                .is_some()
+                .then(|| state.summary()),
+            spawned_at: None,
+            completed: false,
+            polling: false,
+            state: ManuallyDrop::new(state),
+            waker: None,
+        }
+    }
+
+    /// Produces an instrumented façade around a given async task, which resolves to the output of
+    /// the task paired with the [`TaskSummary`] of that task alone.
+    ///
+    /// The task is otherwise instrumented exactly as it would be by [`TaskMonitor::instrument`];
+    /// its metrics are also recorded by this monitor. This allows, e.g., request handlers to
+    /// attach the scheduling and poll metrics of each request to their response logs.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     let (response, summary) = monitor.instrument_with_result(async {
+    ///         tokio::time::sleep(Duration::from_millis(10)).await;
+    ///         "Hello, World!"
+    ///     }).await;
+    ///
+    ///     assert_eq!(response, "Hello, World!");
+    ///     assert_eq!(summary.poll_count, 2);
+    ///     assert!(summary.lifetime >= Duration::from_millis(10));
+    ///
+    ///     // the task was also recorded by the monitor
+    ///     assert_eq!(monitor.cumulative().total_poll_count, 2);
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn instrument_with_result<F: Future>(&self, task: F) -> InstrumentedWithSummary<F> {
+        let mut task = self.instrument(task);
+        if task.summary.is_none() {
+            task.summary = Some(task.state.summary());
+        }
+        InstrumentedWithSummary { task }
+    }
+
+    /// Produces an instrumented façade around a given async task resolving to a [`Result`],
+    /// which counts whether the task [succeeded][TaskMetrics::succeeded_count] or
+    /// [failed][TaskMetrics::failed_count].
+    ///
+    /// The task is otherwise instrumented exactly as it would be by [`TaskMonitor::instrument`].
+    /// Error rates thereby live beside latencies in the same metrics. Tasks that resolve to `Ok`
+    /// are counted as successes; see [`TaskMonitor::instrument_result_with`] to classify
+    /// outcomes otherwise.
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     let _ = monitor.instrument_result(async { Ok::<_, ()>(1) }).await;
+    ///     let _ = monitor.instrument_result(async { Err::<(), _>("oh no") }).await;
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.succeeded_count, 1);
+    ///     assert_eq!(metrics.failed_count, 1);
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn instrument_result<F, T, E>(&self, task: F) -> InstrumentedResult<F, IsOk<T, E>>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        self.instrument_result_with(task, Result::is_ok)
+    }
+
+    /// Produces an instrumented façade around a given async task, which counts whether the task
+    /// [succeeded][TaskMetrics::succeeded_count] or [failed][TaskMetrics::failed_count], as
+    /// determined by `classify`; `classify` receives the output of the task, and produces `true`
+    /// if the task succeeded.
+    ///
+    /// The task is otherwise instrumented exactly as it would be by [`TaskMonitor::instrument`].
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::io::{Error, ErrorKind};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     // a missing file is an expected outcome, not a failure
+    ///     let lookup = async { Err::<(), _>(Error::from(ErrorKind::NotFound)) };
+    ///     let result = monitor
+    ///         .instrument_result_with(lookup, |result| match result {
+    ///             Ok(_) => true,
+    ///             Err(error) => error.kind() == ErrorKind::NotFound,
+    ///         })
+    ///         .await;
+    ///     assert!(result.is_err());
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.succeeded_count, 1);
+    ///     assert_eq!(metrics.failed_count, 0);
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn instrument_result_with<F, C>(&self, task: F, classify: C) -> InstrumentedResult<F, C>
+    where
+        F: Future,
+        C: FnMut(&F::Output) -> bool,
+    {
+        InstrumentedResult {
+            task: self.instrument(task),
+            classify,
+        }
+    }
+
+    /// Produces an instrumented façade around a given async task, which resolves to an error if
+    /// the task does not complete within `timeout`.
+    ///
+    /// The task is otherwise instrumented as it would be by [`TaskMonitor::instrument`], with
+    /// the timer included; tasks that time out are counted by
+    /// [`timed_out_count`][TaskMetrics::timed_out_count]. Timeout hit-rates thereby appear next
+    /// to the rest of the metrics of the task, rather than in ad-hoc application counters.
+    ///
+    /// The timeout begins when this method is called, like [`tokio::time::timeout`].
+    ///
+    /// ##### Panics
+    /// This method panics if called outside of a tokio runtime with the timer enabled.
+    #[cfg(feature = "rt")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rt")))]
+    #[track_caller]
+    pub fn instrument_with_timeout<F: Future>(
+        &self,
+        task: F,
+        timeout: Duration,
+    ) -> InstrumentedWithTimeout<F> {
+        InstrumentedWithTimeout {
+            task: self.instrument(tokio::time::timeout(timeout, task)),
+        }
+    }
+
+    /// Instruments a given async task, and spawns it onto the current tokio runtime.
+    ///
+    /// Equivalent to `monitor.instrument(task).spawn()`; see [`Instrumented::spawn`].
+    ///
+    /// ##### Panics
+    /// Panics if called outside of a tokio runtime, like [`tokio::spawn`].
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     let handle = monitor.spawn(async { 42 });
+    ///     assert_eq!(handle.await.unwrap(), 42);
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.spawned_count, 1);
+    ///     assert_eq!(metrics.spawned_first_poll_count, 1);
+    /// }
+    /// ```
+    #[cfg(feature = "rt")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rt")))]
+    #[track_caller]
+    pub fn spawn<F>(&self, task: F) -> tokio::task::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.instrument(task).spawn()
+    }
+
+    /// Instruments a given async task under `name`, and spawns it onto the current tokio runtime.
+    ///
+    /// Equivalent to `monitor.instrument_named(name, task).spawn()`; see
+    /// [`TaskMonitor::instrument_named`] and [`Instrumented::spawn`].
+    ///
+    /// ##### Panics
+    /// Panics if called outside of a tokio runtime, like [`tokio::spawn`].
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let names = Arc::new(Mutex::new(Vec::new()));
+    ///     let monitor = {
+    ///         let names = names.clone();
+    ///         tokio_metrics::TaskMonitor::builder()
+    ///             .with_completion_callback(move |summary| {
+    ///                 names.lock().unwrap().push(summary.name);
+    ///             })
+    ///             .build()
+    ///     };
+    ///
+    ///     monitor.spawn_named("connection_reader", async {}).await.unwrap();
+    ///
+    ///     assert_eq!(*names.lock().unwrap(), [Some("connection_reader")]);
+    /// }
+    /// ```
+    #[cfg(feature = "rt")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rt")))]
+    #[track_caller]
+    pub fn spawn_named<F>(&self, name: &'static str, task: F) -> tokio::task::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.instrument_named(name, task).spawn()
+    }
+
+    /// Produces [`TaskMetrics`] for the tasks instrumented by this [`TaskMonitor`], collected since
+    /// the construction of [`TaskMonitor`].
+    ///
+    /// ##### See also
+    /// - [`TaskMonitor::intervals`]:
+    ///   produces [`TaskMetrics`] for user-defined sampling intervals, instead of cumulatively
+    ///
+    /// ##### Examples
+    /// In the below example, 0 polls occur within the first sampling interval, 3 slow polls occur
+    /// within the second sampling interval, and 2 slow polls occur within the third sampling
+    /// interval; five slow polls occur across all sampling intervals:
+    /// ```
+    /// use std::future::Future;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     // initialize a stream of sampling intervals
+    ///     let mut intervals = metrics_monitor.intervals();
+    ///     // each call of `next_interval` will produce metrics for the last sampling interval
+    ///     let mut next_interval = || intervals.next().unwrap();
+    ///
+    ///     let slow = 10 * metrics_monitor.slow_poll_threshold();
+    ///
+    ///     // this task completes in three slow polls
+    ///     let _ = metrics_monitor.instrument(async {
+    ///         spin_for(slow).await; // slow poll 1
+    ///         spin_for(slow).await; // slow poll 2
+    ///         spin_for(slow)        // slow poll 3
+    ///     }).await;
+    ///
+    ///     // in the previous sampling interval, there were 3 slow polls
+    ///     assert_eq!(next_interval().total_slow_poll_count, 3);
+    ///     assert_eq!(metrics_monitor.cumulative().total_slow_poll_count, 3);
+    ///
+    ///     // this task completes in two slow polls
+    ///     let _ = metrics_monitor.instrument(async {
+    ///         spin_for(slow).await; // slow poll 1
+    ///         spin_for(slow)        // slow poll 2
+    ///     }).await;
+    ///
+    ///     // in the previous sampling interval, there were 3 slow polls
+    ///     assert_eq!(next_interval().total_slow_poll_count, 2);
+    ///
+    ///     // across all sampling interval, there were a total of 5 slow polls
+    ///     assert_eq!(metrics_monitor.cumulative().total_slow_poll_count, 5);
+    /// }
+    ///
+    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
+    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
+    ///     let start = tokio::time::Instant::now();
+    ///     while start.elapsed() <= duration {}
+    ///     tokio::task::yield_now()
+    /// }
+    /// ```
+    pub fn cumulative(&self) -> TaskMetrics {
+        self.metrics.metrics()
+    }
+
+    /// Restores the cumulative metrics of a previous run of this monitor (e.g., read from disk
+    /// with [`TaskMetrics::read_from`]), so that counters survive restarts for consumers that rely
+    /// on their cumulative semantics; e.g., those that compute rates from monotonic counters.
+    ///
+    /// The [cumulative][TaskMonitor::cumulative] metrics of this monitor thereafter include
+    /// those of `snapshot`: its counts and durations are added to those of this monitor, and
+    /// its maxima are combined with those of this monitor. Gauges (e.g.,
+    /// [`concurrent_poll_count`][TaskMetrics::concurrent_poll_count]) describe the current run
+    /// alone. Restoring replaces any previously restored snapshot.
+    ///
+    /// The snapshot is only included in the cumulative metrics of this monitor (e.g., those
+    /// served by a reporter's metrics server, or pushed to a Prometheus Pushgateway). The metrics
+    /// of [sampling
+    /// intervals][TaskMonitor::intervals], and those of [recent
+    /// windows][TaskMonitor::cumulative_since], describe the current run alone, and so are
+    /// unaffected by restoring.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use tokio_metrics::{TaskMetrics, TaskMonitor};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let path = std::env::temp_dir().join(format!("tokio-metrics-{}", std::process::id()));
+    ///
+    ///     // the previous run persists its metrics on shutdown
+    ///     let previous = TaskMonitor::new();
+    ///     previous.instrument(async {}).await;
+    ///     previous.cumulative().write_to(std::fs::File::create(&path)?)?;
+    ///
+    ///     // the next run restores them on startup
+    ///     let monitor = TaskMonitor::new();
+    ///     let mut intervals = monitor.intervals();
+    ///     let snapshot = TaskMetrics::read_from(std::fs::File::open(&path)?)?;
+    ///     monitor.restore(snapshot);
+    ///
+    ///     monitor.instrument(async {}).await;
+    ///     assert_eq!(monitor.cumulative().instrumented_count, 2);
+    ///     assert_eq!(monitor.cumulative().total_poll_count, 2);
+    ///
+    ///     // sampling intervals describe the current run alone
+    ///     assert_eq!(intervals.next().unwrap().instrumented_count, 1);
+    ///
+    ///     std::fs::remove_file(&path)
+    /// }
+    /// ```
+    pub fn restore(&self, snapshot: TaskMetrics) {
+        *self.metrics.restored.write().unwrap() = Some(snapshot);
+    }
+
+    /// Produces [`TaskMetrics`] for the tasks instrumented by this [`TaskMonitor`], collected
+    /// since `since`; e.g., "what happened in the last five minutes".
+    ///
+    /// This is backed by the periodic checkpoints configured with
+    /// [`TaskMonitorBuilder::with_checkpoints`], and so is only as precise as their interval: the
+    /// metrics are collected since the earliest checkpoint taken at or after `since`. Because
+    /// checkpoints are taken lazily, this omits, at most, the events of one interval. If `since`
+    /// precedes the oldest retained checkpoint, the metrics are collected since that checkpoint
+    /// instead. If checkpoints are not enabled, this is equivalent to
+    /// [`TaskMonitor::cumulative`].
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio::time::{sleep, Instant};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_checkpoints(Duration::from_millis(10), 100)
+    ///         .build();
+    ///
+    ///     monitor.instrument(async {}).await;
+    ///     sleep(Duration::from_millis(100)).await;
+    ///     monitor.instrument(async {}).await;
+    ///     sleep(Duration::from_millis(100)).await;
+    ///
+    ///     // only the second task was instrumented within the last 150ms
+    ///     let since = Instant::now() - Duration::from_millis(150);
+    ///     assert_eq!(monitor.cumulative_since(since).instrumented_count, 1);
+    ///     assert_eq!(monitor.cumulative().instrumented_count, 2);
+    /// }
+    /// ```
+    pub fn cumulative_since(&self, since: Instant) -> TaskMetrics {
+        let checkpoints = match &self.metrics.checkpoints {
+            Some(checkpoints) => checkpoints,
+            None => return self.metrics.metrics(),
+        };
+        self.metrics.checkpoint(Instant::now());
+
+        let earlier = {
+            let retained = checkpoints.retained.lock().unwrap();
+            retained
+                .iter()
+                .find(|checkpoint| checkpoint.taken_at >= since)
+                .or_else(|| retained.back())
+                .map(|checkpoint| checkpoint.metrics)
+                .unwrap_or_default()
+        };
+        self.metrics.live_metrics().delta_since(&earlier)
+    }
+
+    /// Produces a [`Snapshot`] of the [cumulative][TaskMonitor::cumulative] metrics of this
+    /// [`TaskMonitor`], noting the instant at which it was taken.
+    ///
+    /// See [`Snapshot`] for an example.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            taken_at: Instant::now(),
+            metrics: self.metrics.metrics(),
+        }
+    }
+
+    /// Produces a [`ScopeGuard`] that measures the synchronous region of code in which it is
+    /// alive, recording it as inline work; see
+    /// [`total_inline_duration`][TaskMetrics::total_inline_duration].
+    ///
+    /// CPU-heavy sections within a poll (e.g., parsing, compression, or serialization) can thereby
+    /// be attributed explicitly, rather than inferred from the totals of slow polls. Scopes need
+    /// not be within tasks instrumented by this monitor, or within tasks at all.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     monitor.instrument({
+    ///         let monitor = monitor.clone();
+    ///         async move {
+    ///             let _scope = monitor.scope();
+    ///             // stand-in for CPU-heavy work
+    ///             std::thread::sleep(Duration::from_millis(10));
+    ///         }
+    ///     }).await;
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.total_inline_count, 1);
+    ///     assert!(metrics.total_inline_duration >= Duration::from_millis(10));
+    ///     assert!(metrics.total_poll_duration >= metrics.total_inline_duration);
+    /// }
+    /// ```
+    pub fn scope(&self) -> ScopeGuard<'_> {
+        ScopeGuard {
+            monitor: self,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Produces a [`WeakTaskMonitor`] handle to this monitor, which does not keep its metrics
+    /// alive.
+    pub fn downgrade(&self) -> WeakTaskMonitor {
+        WeakTaskMonitor {
+            metrics: Arc::downgrade(&self.metrics),
+        }
+    }
+
+    /// Produces an unending iterator of metric sampling intervals.
+    ///
+    /// Each sampling interval is defined by the time elapsed between advancements of the iterator
+    /// produced by [`TaskMonitor::intervals`]. The item type of this iterator is [`TaskMetrics`],
+    /// which is a bundle of task metrics that describe *only* events occurring within that sampling
+    /// interval.
+    ///
+    /// ##### Examples
+    /// In the below example, 0 polls occur within the first sampling interval, 3 slow polls occur
+    /// within the second sampling interval, and 2 slow polls occur within the third sampling
+    /// interval; five slow polls occur across all sampling intervals:
+    /// ```
+    /// use std::future::Future;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     // initialize a stream of sampling intervals
+    ///     let mut intervals = metrics_monitor.intervals();
+    ///     // each call of `next_interval` will produce metrics for the last sampling interval
+    ///     let mut next_interval = || intervals.next().unwrap();
+    ///
+    ///     let slow = 10 * metrics_monitor.slow_poll_threshold();
+    ///
+    ///     // this task completes in three slow polls
+    ///     let _ = metrics_monitor.instrument(async {
+    ///         spin_for(slow).await; // slow poll 1
+    ///         spin_for(slow).await; // slow poll 2
+    ///         spin_for(slow)        // slow poll 3
+    ///     }).await;
+    ///
+    ///     // in the previous sampling interval, there were 3 slow polls
+    ///     assert_eq!(next_interval().total_slow_poll_count, 3);
+    ///
+    ///     // this task completes in two slow polls
+    ///     let _ = metrics_monitor.instrument(async {
+    ///         spin_for(slow).await; // slow poll 1
+    ///         spin_for(slow)        // slow poll 2
+    ///     }).await;
+    ///
+    ///     // in the previous sampling interval, there were 3 slow polls
+    ///     assert_eq!(next_interval().total_slow_poll_count, 2);
+    ///
+    ///     // across all sampling intervals, there were a total of 5 slow polls
+    ///     assert_eq!(metrics_monitor.cumulative().total_slow_poll_count, 5);
+    /// }
+    ///
+    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
+    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
+    ///     let start = tokio::time::Instant::now();
+    ///     while start.elapsed() <= duration {}
+    ///     tokio::task::yield_now()
+    /// }
+    /// ```
+    pub fn intervals(&self) -> impl Iterator<Item = TaskMetrics> {
+        let latest = self.metrics.clone();
+        let mut tracker = IntervalTracker::new(&latest);
+        let mut previous: Option<TaskMetrics> = None;
+
+        std::iter::from_fn(move || {
+            let raw = &latest;
+            let latest: TaskMetrics = raw.live_metrics();
+            let next = match previous {
+                Some(previous) => tracker.finish(raw, latest - previous),
+                // the first interval began when the monitor was constructed, and so its maxima
+                // are those since
+                None => {
+                    tracker.finish(raw, latest);
+                    latest
+                }
+            };
+
+            previous = Some(latest);
+
+            Some(next)
+        })
+    }
+
+    /// Produces an unending iterator of [`IntervalSample`]s: the metrics of sampling intervals,
+    /// like [`TaskMonitor::intervals`], together with the bounds of each interval.
+    ///
+    /// Unlike [`TaskMonitor::intervals`], the first sampling interval begins when this method
+    /// is called, rather than when the monitor was constructed; its bounds are thus known.
+    ///
+    /// See [`IntervalSample`] for an example.
+    pub fn sampled_intervals(&self) -> impl Iterator<Item = IntervalSample> {
+        let latest = self.metrics.clone();
+        let mut tracker = IntervalTracker::new(&latest);
+        let mut previous = (latest.live_metrics(), SystemTime::now(), Instant::now());
+
+        std::iter::from_fn(move || {
+            let (previous_metrics, started_at, started) = previous;
+            let metrics = latest.live_metrics();
+            let (ended_at, ended) = (SystemTime::now(), Instant::now());
+            previous = (metrics, ended_at, ended);
+
+            Some(IntervalSample {
+                metrics: tracker.finish(&latest, metrics - previous_metrics),
+                started_at,
+                ended_at,
+                elapsed: ended.saturating_duration_since(started),
+            })
+        })
+    }
+
+    /// Produces the live tasks instrumented by this [`TaskMonitor`] that appear to be stalled.
+    ///
+    /// A task is considered stalled if it has gone unpolled, since being instrumented or awoken,
+    /// for at least the [stalled-task threshold][TaskMonitorBuilder::with_stalled_task_threshold],
+    /// or if it has not completed within the
+    /// [incomplete-task threshold][TaskMonitorBuilder::with_incomplete_task_threshold]. Call this
+    /// method periodically (e.g., from a watchdog task) to catch starved or deadlocked tasks.
+    /// If a stall-dump callback is configured (with `TaskMonitorBuilder::with_stall_dump_callback`),
+    /// finding stalled tasks also dumps the runtime in the background.
+    ///
+    /// This method always produces an empty list unless at least one of these thresholds has been
+    /// configured.
+    ///
+    /// ##### Examples
+    /// In the below example, a task is instrumented but not polled for 100ms:
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::{StallKind, TaskMonitor};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = TaskMonitor::builder()
+    ///         .with_stalled_task_threshold(Duration::from_millis(50))
+    ///         .build();
+    ///
+    ///     let task = monitor.instrument(async {});
+    ///
+    ///     // `task` has not been stalled for long enough to be reported
+    ///     assert!(monitor.stalled_tasks().is_empty());
+    ///
+    ///     tokio::time::sleep(Duration::from_millis(100)).await;
+    ///
+    ///     // `task` has been waiting for its first poll for at least 100ms
+    ///     let stalled = monitor.stalled_tasks();
+    ///     assert_eq!(stalled.len(), 1);
+    ///     assert_eq!(stalled[0].kind, StallKind::FirstPoll);
+    ///     assert!(stalled[0].stalled_for >= Duration::from_millis(100));
+    ///
+    ///     task.await;
+    ///
+    ///     // completed tasks are never reported
+    ///     assert!(monitor.stalled_tasks().is_empty());
+    /// }
+    /// ```
+    pub fn stalled_tasks(&self) -> Vec<StalledTask> {
+        let stalled = self.metrics.stalled_tasks();
+        #[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+        if !stalled.is_empty() {
+            self.metrics
+                .dump_stalled_tasks_in_background(stalled.clone());
+        }
+        stalled
+    }
+
+    /// Produces the [stalled tasks][TaskMonitor::stalled_tasks] of this [`TaskMonitor`], along
+    /// with a [`Dump`][tokio::runtime::Dump] of the current runtime's tasks, in which the traces
+    /// of the stalled tasks show where they are awaiting. Produces `None`, without dumping the
+    /// runtime, if no tasks appear stalled.
+    ///
+    /// Dumps are expensive: the runtime pauses its workers to re-poll every task in a tracing
+    /// mode. The dump may never complete if a worker is blocked; await this method with a
+    /// [timeout][tokio::time::timeout]. See [`Handle::dump`][tokio::runtime::Handle::dump] for
+    /// the runtime's requirements.
+    ///
+    /// The traces of the dump are not associated with the stalled tasks: tokio identifies the
+    /// tasks of a dump by no means that this crate can match to its own tasks. Find the traces of
+    /// the stalled tasks by the futures they are awaiting, or by their
+    /// [locations][StalledTask::location].
+    ///
+    /// **This functionality requires `--cfg tokio_unstable --cfg tokio_taskdump`.**
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::TaskMonitor;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let monitor = TaskMonitor::builder()
+    ///         .with_incomplete_task_threshold(Duration::from_millis(50))
+    ///         .build();
+    ///
+    ///     // nothing is stalled, so the runtime is not dumped
+    ///     assert!(monitor.dump_stalled_tasks().await.is_none());
+    ///
+    ///     let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+    ///     let task = tokio::spawn(monitor.instrument(async move {
+    ///         let _ = rx.await;
+    ///     }));
+    ///     tokio::time::sleep(Duration::from_millis(100)).await;
+    ///
+    ///     let timeout = Duration::from_secs(5);
+    ///     let report = tokio::time::timeout(timeout, monitor.dump_stalled_tasks())
+    ///         .await
+    ///         .expect("the runtime should be dumped")
+    ///         .expect("the task should appear stalled");
+    ///     assert_eq!(report.stalled.len(), 1);
+    ///     for task in report.dump.tasks().iter() {
+    ///         println!("{}", task.trace());
+    ///     }
+    ///
+    ///     drop(tx);
+    ///     task.await.unwrap();
+    /// }
+    /// ```
+    #[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+    #[cfg_attr(docsrs, doc(cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))))]
+    pub async fn dump_stalled_tasks(&self) -> Option<StallReport> {
+        let stalled = self.metrics.stalled_tasks();
+        if stalled.is_empty() {
+            return None;
+        }
+        let dump = tokio::runtime::Handle::current().dump().await;
+        Some(StallReport { stalled, dump })
+    }
+
+    /// Produces the live tasks instrumented by this [`TaskMonitor`], in the order in which they were
+    /// instrumented; e.g., to answer "what is this monitor running right now?".
+    ///
+    /// This method always produces an empty list unless live tasks are tracked; see
+    /// [`TaskMonitorBuilder::with_live_task_tracking`].
+    ///
+    /// ##### Examples
+    /// ```
+    /// use tokio_metrics::{LiveTaskState, TaskMonitor};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = TaskMonitor::builder()
+    ///         .with_live_task_tracking(true)
+    ///         .build();
+    ///
+    ///     let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+    ///     let waiting = tokio::spawn(monitor.instrument_named("waiting", rx));
+    ///     let pending = monitor.instrument_named("pending", async {});
+    ///     tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    ///
+    ///     let live_tasks = monitor.live_tasks();
+    ///     assert_eq!(live_tasks.len(), 2);
+    ///     assert_eq!(live_tasks[0].name, Some("waiting"));
+    ///     assert_eq!(live_tasks[0].state, LiveTaskState::Idle);
+    ///     assert_eq!(live_tasks[0].poll_count, 1);
+    ///     assert_eq!(live_tasks[1].name, Some("pending"));
+    ///     assert_eq!(live_tasks[1].state, LiveTaskState::FirstPoll);
+    ///
+    ///     tx.send(()).unwrap();
+    ///     waiting.await.unwrap().unwrap();
+    ///     pending.await;
+    ///
+    ///     // completed tasks are never reported
+    ///     assert!(monitor.live_tasks().is_empty());
+    /// }
+    /// ```
+    pub fn live_tasks(&self) -> Vec<LiveTask> {
+        let live_tasks = match &self.metrics.live_tasks {
+            Some(live_tasks) => live_tasks,
+            None => return Vec::new(),
+        };
+
+        let now = Instant::now();
+        let mut live_tasks: Vec<LiveTask> = live_tasks
+            .lock()
+            .unwrap()
+            .values()
+            .filter_map(Weak::upgrade)
+            .map(|state| state.live(now))
+            .collect();
+        live_tasks.sort_unstable_by_key(|task| task.id.0);
+        live_tasks
+    }
+
+    /// Produces a [`TaskDump`] of the [live tasks][TaskMonitor::live_tasks] instrumented by this
+    /// [`TaskMonitor`], in the given `order`; e.g., to find what a stuck service is waiting on.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::{DumpOrder, TaskMonitor};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = TaskMonitor::builder()
+    ///         .with_live_task_tracking(true)
+    ///         .with_location_capture(true)
+    ///         .build();
+    ///
+    ///     let (_tx, rx) = tokio::sync::oneshot::channel::<()>();
+    ///     let stuck = tokio::spawn(monitor.instrument_named("stuck", rx));
+    ///     tokio::time::sleep(Duration::from_millis(10)).await;
+    ///     let fresh = monitor.instrument_named("fresh", async {});
+    ///
+    ///     let dump = monitor.dump(DumpOrder::Age);
+    ///     assert_eq!(dump.tasks()[0].name, Some("stuck"));
+    ///     assert_eq!(dump.tasks()[1].name, Some("fresh"));
+    ///     println!("{}", dump);
+    ///
+    ///     stuck.abort();
+    ///     drop(fresh);
+    /// }
+    /// ```
+    pub fn dump(&self, order: DumpOrder) -> TaskDump {
+        let mut tasks = self.live_tasks();
+        match order {
+            DumpOrder::Age => tasks.sort_by_key(|task| Reverse(task.age)),
+            DumpOrder::PollDuration => tasks.sort_by_key(|task| Reverse(task.total_poll_duration)),
+        }
+        TaskDump { tasks }
+    }
+
+    /// Produces the slowest polls of tasks instrumented by this [`TaskMonitor`] since the last
+    /// call to this method, slowest first, and resets the leaderboard.
+    ///
+    /// Call this method each time an [interval][TaskMonitor::intervals] is produced, to retrieve
+    /// the worst offenders of that interval. This method always produces an empty list unless the
+    /// leaderboard has been [enabled][TaskMonitorBuilder::with_slowest_polls_capacity].
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_slowest_polls_capacity(10)
+    ///         .build();
+    ///     let mut intervals = monitor.intervals();
+    ///
+    ///     monitor.instrument(async {
+    ///         std::thread::sleep(Duration::from_millis(10));
+    ///     }).await;
+    ///
+    ///     let interval = intervals.next().unwrap();
+    ///     let slowest_polls = monitor.take_slowest_polls();
+    ///     assert_eq!(interval.total_poll_count, 1);
+    ///     assert_eq!(slowest_polls.len(), 1);
+    ///     assert!(slowest_polls[0].duration >= Duration::from_millis(10));
+    ///
+    ///     // the leaderboard is empty until more polls occur
+    ///     let interval = intervals.next().unwrap();
+    ///     assert_eq!(interval.total_poll_count, 0);
+    ///     assert!(monitor.take_slowest_polls().is_empty());
+    /// }
+    /// ```
+    pub fn take_slowest_polls(&self) -> Vec<SlowPoll> {
+        let mut slowest_polls = {
+            let mut leaderboard = self.metrics.slowest_polls.lock().unwrap();
+            self.metrics.slowest_polls_floor_ns.store(0, SeqCst);
+            std::mem::take(&mut *leaderboard)
+        };
+        slowest_polls.sort_by_key(|slow_poll| Reverse(slow_poll.duration));
+        slowest_polls
+    }
+
+    /// Produces the [`PollBucket`]s of the polls of tasks instrumented by this [`TaskMonitor`],
+    /// collected since its construction.
+    ///
+    /// The buckets are delimited by the thresholds configured with
+    /// [`TaskMonitorBuilder::with_poll_buckets`], in ascending order. If no thresholds are
+    /// configured, no buckets are produced.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_poll_buckets([Duration::from_millis(1), Duration::from_millis(10)])
+    ///         .build();
+    ///
+    ///     monitor.instrument(async {
+    ///         std::thread::sleep(Duration::from_millis(5)); // poll 1 (~5ms)
+    ///         tokio::task::yield_now().await;
+    ///         std::thread::sleep(Duration::from_millis(20)); // poll 2 (~20ms)
+    ///     }).await;
+    ///
+    ///     let buckets = monitor.poll_buckets();
+    ///     assert_eq!(buckets.len(), 3);
+    ///
+    ///     // [0, 1ms)
+    ///     assert_eq!(buckets[0].upper_bound, Some(Duration::from_millis(1)));
+    ///     assert_eq!(buckets[0].count, 0);
+    ///
+    ///     // [1ms, 10ms)
+    ///     assert_eq!(buckets[1].lower_bound, Duration::from_millis(1));
+    ///     assert_eq!(buckets[1].count, 1);
+    ///
+    ///     // [10ms, ∞)
+    ///     assert_eq!(buckets[2].upper_bound, None);
+    ///     assert_eq!(buckets[2].count, 1);
+    ///     assert!(buckets[2].total_duration >= Duration::from_millis(20));
+    ///
+    ///     // the slow poll exemplifies its bucket
+    ///     let exemplar = buckets[2].exemplar.unwrap();
+    ///     assert!(exemplar.duration >= Duration::from_millis(20));
+    /// }
+    /// ```
+    pub fn poll_buckets(&self) -> Vec<PollBucket> {
+        self.metrics.poll_buckets()
+    }
+
+    /// Takes the sketches of the durations of polls, and of the delays between tasks being
+    /// awoken and polled, recorded since they were last taken, and resets them; or `None`, if
+    /// sketches are not [enabled][TaskMonitorBuilder::with_latency_sketch].
+    pub fn take_latency_sketches(&self) -> Option<(LatencySketch, LatencySketch)> {
+        let sketches = self.metrics.latency_sketches.as_ref()?;
+        let take = |sketch: &Mutex<LatencySketch>| {
+            let mut sketch = sketch.lock().unwrap();
+            let empty = sketch.empty();
+            std::mem::replace(&mut *sketch, empty)
+        };
+        Some((take(&sketches.polls), take(&sketches.scheduled)))
+    }
+
+    /// Produces an unending iterator of the [`PollBucket`]s of sampling intervals, like
+    /// [`TaskMonitor::intervals`].
+    pub fn poll_bucket_intervals(&self) -> impl Iterator<Item = Vec<PollBucket>> {
+        let latest = self.metrics.clone();
+        let mut previous: Option<Vec<PollBucket>> = None;
+
+        std::iter::from_fn(move || {
+            let latest = latest.poll_buckets();
+            let next = match &previous {
+                Some(previous) => latest
+                    .iter()
+                    .zip(previous)
+                    .map(|(latest, previous)| PollBucket {
+                        count: latest.count.wrapping_sub(previous.count),
+                        total_duration: sub(latest.total_duration, previous.total_duration),
+                        // the exemplar is only new if it changed within the interval
+                        exemplar: latest.exemplar.filter(|&e| Some(e) != previous.exemplar),
+                        ..*latest
+                    })
+                    .collect(),
+                None => latest.clone(),
+            };
+            previous = Some(latest);
+            Some(next)
+        })
+    }
+
+    /// Produces the [`SlowPollTier`]s of the polls of tasks instrumented by this [`TaskMonitor`],
+    /// collected since its construction.
+    ///
+    /// The tiers are those added with [`TaskMonitorBuilder::with_slow_poll_tier`], in ascending
+    /// order of their thresholds. If no tiers are added, none are produced.
+    ///
+    /// See [`TaskMonitorBuilder::with_slow_poll_tier`] for an example.
+    pub fn slow_poll_tiers(&self) -> Vec<SlowPollTier> {
+        self.metrics.slow_poll_tiers()
+    }
+
+    /// Produces an unending iterator of the [`SlowPollTier`]s of sampling intervals, like
+    /// [`TaskMonitor::intervals`].
+    pub fn slow_poll_tier_intervals(&self) -> impl Iterator<Item = Vec<SlowPollTier>> {
+        let latest = self.metrics.clone();
+        let mut previous: Option<Vec<SlowPollTier>> = None;
+
+        std::iter::from_fn(move || {
+            let latest = latest.slow_poll_tiers();
+            let next = match &previous {
+                Some(previous) => latest
+                    .iter()
+                    .zip(previous)
+                    .map(|(latest, previous)| SlowPollTier {
+                        poll_count: latest.poll_count.wrapping_sub(previous.poll_count),
+                        total_poll_duration: sub(
+                            latest.total_poll_duration,
+                            previous.total_poll_duration,
+                        ),
+                        ..*latest
+                    })
+                    .collect(),
+                None => latest.clone(),
+            };
+            previous = Some(latest);
+            Some(next)
+        })
+    }
+
+    /// Produces the [`WorkerPolls`] of each thread that has polled tasks instrumented by this
+    /// [`TaskMonitor`], collected since its construction.
+    ///
+    /// This reveals, e.g., whether one worker of a runtime is absorbing all the slow polls. Polls
+    /// are only attributed to threads if enabled with
+    /// [`TaskMonitorBuilder::with_worker_attribution`]; otherwise, nothing is produced.
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_worker_attribution(true)
+    ///         .build();
+    ///
+    ///     for _ in 0..10 {
+    ///         monitor.spawn(async {}).await.unwrap();
+    ///     }
+    ///
+    ///     let worker_polls = monitor.worker_polls();
+    ///     assert!(!worker_polls.is_empty());
+    ///     let poll_count: u64 = worker_polls.iter().map(|worker| worker.poll_count).sum();
+    ///     assert_eq!(poll_count, 10);
+    /// }
+    /// ```
+    pub fn worker_polls(&self) -> Vec<WorkerPolls> {
+        self.metrics.worker_polls()
+    }
+
+    /// Produces the [`BranchPolls`] of each [instrumented branch][TaskMonitor::instrument_branch]
+    /// of the tasks of this [`TaskMonitor`], collected since its construction, ordered by name.
+    ///
+    /// See [`TaskMonitor::instrument_branch`] for an example.
+    pub fn branch_polls(&self) -> Vec<BranchPolls> {
+        self.metrics.branch_polls()
+    }
+
+    /// Produces an unending iterator of the [`WorkerPolls`] of sampling intervals, like
+    /// [`TaskMonitor::intervals`].
+    ///
+    /// Each interval reports the polls performed by each thread within it; threads that performed
+    /// no polls within an interval are omitted.
+    pub fn worker_poll_intervals(&self) -> impl Iterator<Item = Vec<WorkerPolls>> {
+        let latest = self.metrics.clone();
+        let mut previous: HashMap<ThreadId, WorkerPolls> = HashMap::new();
+
+        std::iter::from_fn(move || {
+            let latest = latest.worker_polls();
+            let next = latest
+                .iter()
+                .map(|latest| match previous.get(&latest.thread) {
+                    Some(previous) => WorkerPolls {
+                        poll_count: latest.poll_count.wrapping_sub(previous.poll_count),
+                        total_poll_duration: sub(
+                            latest.total_poll_duration,
+                            previous.total_poll_duration,
+                        ),
+                        slow_poll_count: latest
+                            .slow_poll_count
+                            .wrapping_sub(previous.slow_poll_count),
+                        total_slow_poll_duration: sub(
+                            latest.total_slow_poll_duration,
+                            previous.total_slow_poll_duration,
+                        ),
+                        ..*latest
+                    },
+                    None => *latest,
+                })
+                .filter(|worker| worker.poll_count > 0)
+                .collect();
+            previous = latest
+                .into_iter()
+                .map(|worker| (worker.thread, worker))
+                .collect();
+            Some(next)
+        })
+    }
+}
+
+impl IntervalTracker {
+    fn new(metrics: &RawMetrics) -> IntervalTracker {
+        let mut tracker = IntervalTracker {
+            watermarks: metrics.interval_watermarks(),
+            threads: HashMap::new(),
+        };
+        metrics.polling_threads_since(&mut tracker.threads);
+        tracker
+    }
+
+    /// Completes `interval`, the differences of the metrics of the interval that ends now, with
+    /// the maxima and threads of that interval, and begins the next.
+    fn finish(&mut self, metrics: &RawMetrics, interval: TaskMetrics) -> TaskMetrics {
+        let watermarks = &self.watermarks;
+        // the tasks being polled as this interval ends are being polled as the next begins
+        let polling = interval.concurrent_poll_count;
+        // a gap that has yet to end is reflected as it grows, so that a producer that stopped
+        // producing is noticed before it resumes
+        let instrumentation_gap_ns = watermarks
+            .instrumentation_gap_ns
+            .swap(0, SeqCst)
+            .max(metrics.ongoing_instrumentation_gap_ns());
+        TaskMetrics {
+            max_first_poll_delay: Duration::from_nanos(
+                watermarks.first_poll_delay_ns.swap(0, SeqCst),
+            ),
+            max_scheduled_duration: Duration::from_nanos(watermarks.scheduled_ns.swap(0, SeqCst)),
+            max_concurrent_poll_count: watermarks
+                .concurrent_polls
+                .swap(polling, SeqCst)
+                .max(polling),
+            max_instrumentation_gap: Duration::from_nanos(instrumentation_gap_ns),
+            polling_thread_count: metrics.polling_threads_since(&mut self.threads),
+            ..interval
+        }
+    }
+}
+
+impl Drop for ConcurrentPoll<'_> {
+    fn drop(&mut self) {
+        self.0.polls.concurrent_poll_count.fetch_sub(1, SeqCst);
+    }
+}
+
+impl PollDeltas {
+    fn accumulate(&mut self, other: &PollDeltas) {
+        self.idled_count += other.idled_count;
+        self.idle_ns += other.idle_ns;
+        self.scheduled_count += other.scheduled_count;
+        self.scheduled_ns += other.scheduled_ns;
+        self.max_scheduled_ns = self.max_scheduled_ns.max(other.max_scheduled_ns);
+        self.fast_poll_count += other.fast_poll_count;
+        self.fast_poll_ns += other.fast_poll_ns;
+        self.slow_poll_count += other.slow_poll_count;
+        self.slow_poll_ns += other.slow_poll_ns;
+        self.pending_poll_count += other.pending_poll_count;
+    }
+}
+
+impl Drop for LocalBatch {
+    fn drop(&mut self) {
+        if let Some(metrics) = self.metrics.upgrade() {
+            metrics.apply_poll_deltas(&self.deltas);
+        }
+    }
+}
+
+impl Checkpoints {
+    fn new(interval: Duration, capacity: usize) -> Checkpoints {
+        let created_at = Instant::now();
+        let mut retained = VecDeque::with_capacity(capacity);
+        // the monitor's metrics are zero as of its construction
+        retained.push_back(Snapshot {
+            metrics: TaskMetrics::default(),
+            taken_at: created_at,
+        });
+        Checkpoints {
+            interval,
+            capacity,
+            created_at,
+            next_ns: AtomicU64::new(to_nanos(interval)),
+            retained: Mutex::new(retained),
+        }
+    }
+}
+
+impl RawMetrics {
+    /// Produces the CPU time consumed by the current thread, if CPU time measurement is enabled.
+    #[inline(always)]
+    fn thread_cpu_time(&self) -> Option<Duration> {
+        if !self.measure_cpu_time {
+            return None;
+        }
+        #[cfg(all(feature = "cpu-time", unix))]
+        return crate::cpu::thread_cpu_time();
+        #[cfg(not(all(feature = "cpu-time", unix)))]
+        return None;
+    }
+
+    /// Produces the number of allocations, and of bytes allocated, by the current thread, if
+    /// allocation tracking is enabled.
+    #[inline(always)]
+    fn thread_allocations(&self) -> Option<(u64, u64)> {
+        self.track_allocations
+            .then(crate::allocation::thread_allocations)
+    }
+
+    /// Samples the hardware performance counters of the current thread, if enabled.
+    #[inline(always)]
+    fn perf_sample(&self) -> Option<PerfCounters> {
+        if !self.sample_perf {
+            return None;
+        }
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        return crate::perf::sample();
+        #[cfg(not(all(feature = "perf", target_os = "linux")))]
+        return None;
+    }
+
+    /// Attributes a poll to the current thread, if worker attribution is enabled.
+    fn record_worker_poll(&self, duration_ns: u64, slow: bool) {
+        let workers = match &self.workers {
+            Some(workers) => workers,
+            None => return,
+        };
+
+        let thread = std::thread::current().id();
+        let known = workers.read().unwrap().get(&thread).cloned();
+        let counters = match known {
+            Some(counters) => counters,
+            None => workers.write().unwrap().entry(thread).or_default().clone(),
+        };
+
+        counters.poll_count.fetch_add(1, SeqCst);
+        counters.poll_duration_ns.fetch_add(duration_ns, SeqCst);
+        if slow {
+            counters.slow_poll_count.fetch_add(1, SeqCst);
+            counters
+                .slow_poll_duration_ns
+                .fetch_add(duration_ns, SeqCst);
+        }
+    }
+
+    /// Produces the poll counters of the branch `name`.
+    fn branch_counters(&self, name: &'static str) -> Arc<WorkerCounters> {
+        let known = self.branches.read().unwrap().get(name).cloned();
+        match known {
+            Some(counters) => counters,
+            None => self
+                .branches
+                .write()
+                .unwrap()
+                .entry(name)
+                .or_default()
+                .clone(),
+        }
+    }
+
+    fn branch_polls(&self) -> Vec<BranchPolls> {
+        let mut branches: Vec<BranchPolls> = self
+            .branches
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, counters)| BranchPolls {
+                name,
+                poll_count: counters.poll_count.load(SeqCst),
+                total_poll_duration: Duration::from_nanos(counters.poll_duration_ns.load(SeqCst)),
+                slow_poll_count: counters.slow_poll_count.load(SeqCst),
+                total_slow_poll_duration: Duration::from_nanos(
+                    counters.slow_poll_duration_ns.load(SeqCst),
+                ),
+            })
+            .collect();
+        branches.sort_unstable_by_key(|branch| branch.name);
+        branches
+    }
+
+    /// Counts the threads that have polled tasks since their poll counts were recorded in
+    /// `previous`, and records their latest poll counts.
+    fn polling_threads_since(&self, previous: &mut HashMap<ThreadId, u64>) -> u64 {
+        let workers = match &self.workers {
+            Some(workers) => workers.read().unwrap(),
+            None => return 0,
+        };
+        let mut count = 0;
+        for (thread, counters) in workers.iter() {
+            let poll_count = counters.poll_count.load(SeqCst);
+            if previous.insert(*thread, poll_count) != Some(poll_count) {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    fn worker_polls(&self) -> Vec<WorkerPolls> {
+        let workers = match &self.workers {
+            Some(workers) => workers.read().unwrap(),
+            None => return Vec::new(),
+        };
+        workers
+            .iter()
+            .map(|(thread, counters)| WorkerPolls {
+                thread: *thread,
+                poll_count: counters.poll_count.load(SeqCst),
+                total_poll_duration: Duration::from_nanos(counters.poll_duration_ns.load(SeqCst)),
+                slow_poll_count: counters.slow_poll_count.load(SeqCst),
+                total_slow_poll_duration: Duration::from_nanos(
+                    counters.slow_poll_duration_ns.load(SeqCst),
+                ),
+            })
+            .collect()
+    }
+
+    /// Records a poll in its latency bucket, if buckets are configured.
+    fn record_poll_bucket(&self, duration: Duration, duration_ns: u64) {
+        if self.poll_buckets.is_empty() {
+            return;
+        }
+        let i = self
+            .poll_bucket_thresholds
+            .partition_point(|&threshold| threshold <= duration);
+        let bucket = &self.poll_buckets[i];
+        bucket.count.fetch_add(1, SeqCst);
+        bucket.duration_ns.fetch_add(duration_ns, SeqCst);
+        if duration >= self.slow_poll_threshold() {
+            *bucket.exemplar.lock().unwrap() = Some(PollExemplar {
+                duration,
+                completed_at: SystemTime::now(),
+                span_id: current_span_id(),
+            });
+        }
+    }
+
+    /// Records a poll in each slow-poll tier it reached, and invokes the callback of the most
+    /// severe.
+    fn record_slow_poll_tiers(&self, duration: Duration, duration_ns: u64) {
+        let reached = self
+            .slow_poll_tiers
+            .partition_point(|tier| tier.threshold <= duration);
+        let tiers = &self.slow_poll_tiers[..reached];
+        for tier in tiers {
+            tier.count.fetch_add(1, SeqCst);
+            tier.duration_ns.fetch_add(duration_ns, SeqCst);
+        }
+        if let Some(callback) = tiers.last().and_then(|tier| tier.callback.as_ref()) {
+            callback(duration);
+        }
+    }
+
+    /// The tasks found stalled now, without invoking the stall-dump callback.
+    fn stalled_tasks(&self) -> Vec<StalledTask> {
+        let live_tasks = match &self.live_tasks {
+            Some(live_tasks) => live_tasks,
+            None => return Vec::new(),
+        };
+
+        let now = Instant::now();
+        let live_tasks = live_tasks.lock().unwrap();
+        live_tasks
+            .values()
+            .filter_map(Weak::upgrade)
+            .filter_map(|state| state.stall(now))
+            .collect()
+    }
+
+    /// Dumps the current runtime on one of its tasks, and invokes the stall-dump callback with
+    /// the dump and `stalled`, unless no callback is configured, no runtime is current, or a dump
+    /// is already in progress.
+    #[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+    fn dump_stalled_tasks_in_background(self: &Arc<Self>, stalled: Vec<StalledTask>) {
+        let callback = match &self.on_stall_dump {
+            Some(callback) => callback.clone(),
+            None => return,
+        };
+        let handle = match tokio::runtime::Handle::try_current() {
+            Ok(handle) => handle,
+            Err(_) => return,
+        };
+        if self.dumping.swap(true, SeqCst) {
+            return;
+        }
+
+        let metrics = self.clone();
+        handle.clone().spawn(async move {
+            let dump = handle.dump().await;
+            metrics.dumping.store(false, SeqCst);
+            callback(StallReport { stalled, dump });
+        });
+    }
+
+    fn slow_poll_tiers(&self) -> Vec<SlowPollTier> {
+        self.slow_poll_tiers
+            .iter()
+            .map(|tier| SlowPollTier {
+                name: tier.name,
+                threshold: tier.threshold,
+                poll_count: tier.count.load(SeqCst),
+                total_poll_duration: Duration::from_nanos(tier.duration_ns.load(SeqCst)),
+            })
+            .collect()
+    }
+
+    fn poll_buckets(&self) -> Vec<PollBucket> {
+        let thresholds = &self.poll_bucket_thresholds;
+        self.poll_buckets
+            .iter()
+            .enumerate()
+            .map(|(i, bucket)| PollBucket {
+                lower_bound: i.checked_sub(1).map_or(Duration::ZERO, |i| thresholds[i]),
+                upper_bound: thresholds.get(i).copied(),
+                count: bucket.count.load(SeqCst),
+                total_duration: Duration::from_nanos(bucket.duration_ns.load(SeqCst)),
+                exemplar: *bucket.exemplar.lock().unwrap(),
+            })
+            .collect()
+    }
+
+    /// Records the counters updated by a poll that completed at `now`, either directly, or into
+    /// the current thread's batch.
+    fn record_poll_deltas(self: &Arc<Self>, deltas: PollDeltas, now: Instant) {
+        let batching = match self.batching {
+            Some(batching) => batching,
+            None => return self.apply_poll_deltas(&deltas),
+        };
+
+        let batched = LOCAL_BATCHES.try_with(|batches| {
+            let mut batches = batches.borrow_mut();
+            let i = match batches
+                .iter()
+                .position(|batch| ptr::eq(batch.metrics.as_ptr(), &**self))
+            {
+                Some(i) => i,
+                None => {
+                    // discard the batches of monitors whose metrics have since been freed
+                    batches.retain(|batch| batch.metrics.strong_count() > 0);
+                    batches.push(LocalBatch {
+                        metrics: Arc::downgrade(self),
+                        deltas: PollDeltas::default(),
+                        polls: 0,
+                        started_at: now,
+                    });
+                    batches.len() - 1
+                }
+            };
+
+            let batch = &mut batches[i];
+            batch.deltas.accumulate(&deltas);
+            batch.polls += 1;
+            if batch.polls >= batching.max_polls
+                || now.saturating_duration_since(batch.started_at) >= batching.max_delay
+            {
+                // dropping the batch flushes it
+                batches.swap_remove(i);
+            }
+        });
+
+        // the thread-local batches are unavailable while the thread exits
+        if batched.is_err() {
+            self.apply_poll_deltas(&deltas);
+        }
+    }
+
+    /// Flushes the current thread's batch of this monitor's poll counters, if any.
+    fn flush_local_batch(&self) {
+        if self.batching.is_none() {
+            return;
+        }
+        let _ = LOCAL_BATCHES.try_with(|batches| {
+            let batch = {
+                let mut batches = batches.borrow_mut();
+                batches
+                    .iter()
+                    .position(|batch| ptr::eq(batch.metrics.as_ptr(), self))
+                    .map(|i| batches.swap_remove(i))
+            };
+            // dropping the batch flushes it
+            drop(batch);
+        });
+    }
+
+    /// Adds the counters updated by one or more polls to the shared counters.
+    fn apply_poll_deltas(&self, deltas: &PollDeltas) {
+        if deltas.idled_count > 0 {
+            self.polls
+                .total_idled_count
+                .fetch_add(deltas.idled_count, SeqCst);
+            self.polls
+                .total_idle_duration_ns
+                .fetch_add(deltas.idle_ns, SeqCst);
+        }
+        if deltas.scheduled_count > 0 {
+            self.polls
+                .total_scheduled_count
+                .fetch_add(deltas.scheduled_count, SeqCst);
+            self.polls
+                .total_scheduled_duration_ns
+                .fetch_add(deltas.scheduled_ns, SeqCst);
+            self.raise_watermark(|w| &w.scheduled_ns, deltas.max_scheduled_ns);
+        }
+        if deltas.fast_poll_count > 0 {
+            self.polls
+                .total_fast_poll_count
+                .fetch_add(deltas.fast_poll_count, SeqCst);
+            self.polls
+                .total_fast_poll_duration_ns
+                .fetch_add(deltas.fast_poll_ns, SeqCst);
+        }
+        if deltas.slow_poll_count > 0 {
+            self.polls
+                .total_slow_poll_count
+                .fetch_add(deltas.slow_poll_count, SeqCst);
+            self.polls
+                .total_slow_poll_duration
+                .fetch_add(deltas.slow_poll_ns, SeqCst);
+        }
+        if deltas.pending_poll_count > 0 {
+            self.polls
+                .total_pending_poll_count
+                .fetch_add(deltas.pending_poll_count, SeqCst);
+        }
+    }
+
+    /// Takes a checkpoint of the cumulative metrics, if one is due as of `now`.
+    ///
+    /// This is invoked *before* each event is recorded; since the metrics do not change between
+    /// events, a checkpoint taken lazily in this way is exact as of `now`.
+    fn checkpoint(&self, now: Instant) {
+        let checkpoints = match &self.checkpoints {
+            Some(checkpoints) => checkpoints,
+            None => return,
+        };
+
+        let elapsed = to_nanos(now.saturating_duration_since(checkpoints.created_at));
+        let next = checkpoints.next_ns.load(SeqCst);
+        if elapsed < next {
+            return;
+        }
+        // only one of the threads that observe a due checkpoint takes it
+        let following = elapsed.saturating_add(to_nanos(checkpoints.interval));
+        if checkpoints
+            .next_ns
+            .compare_exchange(next, following, SeqCst, SeqCst)
+            .is_err()
+        {
+            return;
+        }
+
+        let snapshot = Snapshot {
+            metrics: self.live_metrics(),
+            taken_at: now,
+        };
+        let mut retained = checkpoints.retained.lock().unwrap();
+        if retained.len() == checkpoints.capacity {
+            retained.pop_front();
+        }
+        retained.push_back(snapshot);
+    }
+
+    /// Records a poll in the leaderboard of slowest polls, if it is slow enough to enter it.
+    fn record_slow_poll(&self, duration: Duration, state: &State, perf: Option<PerfCounters>) {
+        let mut leaderboard = self.slowest_polls.lock().unwrap();
+        let slow_poll = SlowPoll {
+            duration,
+            completed_at: SystemTime::now(),
+            name: state.name,
+            location: state.location,
+            perf,
+        };
+
+        if leaderboard.len() < self.slowest_polls_capacity {
+            leaderboard.push(slow_poll);
+        } else {
+            // replace the fastest poll on the leaderboard, if this poll was slower
+            let fastest = leaderboard
+                .iter_mut()
+                .min_by_key(|slow_poll| slow_poll.duration)
+                .expect("the leaderboard has a non-zero capacity");
+            if fastest.duration >= duration {
+                return;
+            }
+            *fastest = slow_poll;
+        }
+
+        // a poll must now be slower than the fastest poll on a full leaderboard to enter it
+        if leaderboard.len() == self.slowest_polls_capacity {
+            let floor = leaderboard
+                .iter()
+                .map(|slow_poll| slow_poll.duration)
+                .min()
+                .unwrap_or_default();
+            self.slowest_polls_floor_ns
+                .store(to_nanos(floor).saturating_add(1), SeqCst);
+        }
+    }
+
+    /// Raises the watermark selected by `watermark` to `ns`, since the monitor was constructed
+    /// and within the current interval of each live interval iterator, if watermarks are tracked.
+    fn raise_watermark(&self, watermark: fn(&Watermarks) -> &AtomicU64, ns: u64) {
+        if !self.track_watermarks {
+            return;
+        }
+        // watermarks are rarely raised; check before writing, to spare the cache line
+        let raise = |watermark: &AtomicU64| {
+            if watermark.load(Relaxed) < ns {
+                watermark.fetch_max(ns, SeqCst);
+            }
+        };
+        raise(watermark(&self.watermarks));
+        for interval in self.interval_watermarks.read().unwrap().iter() {
+            if let Some(interval) = interval.upgrade() {
+                raise(watermark(&interval));
+            }
+        }
+    }
+
+    /// Produces the duration greater-than-or-equal-to at which polls are categorized as slow.
+    fn slow_poll_threshold(&self) -> Duration {
+        Duration::from_nanos(self.slow_poll_threshold_ns.load(Relaxed))
+    }
+
+    /// Samples the duration of a poll for the calibration of the slow-poll threshold, if it is
+    /// enabled and incomplete; and, once enough polls are sampled, calibrates the threshold.
+    fn calibrate_slow_poll_threshold(&self, duration_ns: u64) {
+        let calibration = match &self.calibration {
+            Some(calibration) if !calibration.done.load(Relaxed) => calibration,
+            _ => return,
+        };
+
+        let mut samples = calibration.samples.lock().unwrap();
+        // another poll may have completed the calibration while this one waited for the lock
+        if calibration.done.load(SeqCst) {
+            return;
+        }
+        samples.push(duration_ns);
+        if samples.len() < calibration.warm_up_polls {
+            return;
+        }
+
+        samples.sort_unstable();
+        let rank = (calibration.quantile * (samples.len() - 1) as f64).round() as usize;
+        self.slow_poll_threshold_ns.store(samples[rank], SeqCst);
+        calibration.done.store(true, SeqCst);
+        *samples = Vec::new();
+    }
+
+    /// Counts a poll as in progress, until the produced guard is dropped, if watermarks are
+    /// tracked.
+    fn begin_poll(&self) -> Option<ConcurrentPoll<'_>> {
+        if !self.track_watermarks {
+            return None;
+        }
+        let polls = self.polls.concurrent_poll_count.fetch_add(1, SeqCst) + 1;
+        self.raise_watermark(|w| &w.concurrent_polls, polls);
+        Some(ConcurrentPoll(self))
+    }
+
+    /// Records the gap between the instrumentation of a task at `now`, and of the task before it.
+    fn record_instrumentation(&self, now: Instant) {
+        let now_ns = now
+            .saturating_duration_since(self.created_at)
+            .as_nanos()
+            .try_into()
+            .unwrap_or(u64::MAX);
+        let last_ns = self.last_instrumented_ns.fetch_max(now_ns, SeqCst);
+        let gap_ns = now_ns.saturating_sub(last_ns);
+        self.total_instrumentation_gap_ns.fetch_add(gap_ns, SeqCst);
+        self.raise_watermark(|w| &w.instrumentation_gap_ns, gap_ns);
+    }
+
+    /// Produces the time elapsed since the latest task was instrumented (or, if none has been,
+    /// since the monitor was constructed), in nanoseconds; or zero, if watermarks are not tracked.
+    fn ongoing_instrumentation_gap_ns(&self) -> u64 {
+        if !self.track_watermarks {
+            return 0;
+        }
+        let now_ns = self
+            .created_at
+            .elapsed()
+            .as_nanos()
+            .try_into()
+            .unwrap_or(u64::MAX);
+        now_ns.saturating_sub(self.last_instrumented_ns.load(SeqCst))
+    }
+
+    /// Registers the watermarks of a new interval iterator.
+    fn interval_watermarks(&self) -> Arc<Watermarks> {
+        let watermarks = Arc::new(Watermarks::default());
+        let mut intervals = self.interval_watermarks.write().unwrap();
+        intervals.retain(|interval| interval.strong_count() > 0);
+        intervals.push(Arc::downgrade(&watermarks));
+        watermarks
+    }
+
+    fn metrics(&self) -> TaskMetrics {
+        let metrics = self.live_metrics();
+        match &*self.restored.read().unwrap() {
+            Some(restored) => metrics.with_restored(restored),
+            None => metrics,
+        }
+    }
+
+    /// Produces the metrics collected since the construction of the monitor, excluding those
+    /// restored from a previous run.
+    fn live_metrics(&self) -> TaskMetrics {
+        self.flush_local_batch();
+        let total_fast_poll_count = self.polls.total_fast_poll_count.load(SeqCst);
+        let total_slow_poll_count = self.polls.total_slow_poll_count.load(SeqCst);
+
+        let total_fast_poll_duration =
+            Duration::from_nanos(self.polls.total_fast_poll_duration_ns.load(SeqCst));
+        let total_slow_poll_duration =
+            Duration::from_nanos(self.polls.total_slow_poll_duration.load(SeqCst));
+
+        let total_poll_count = total_fast_poll_count
+            + total_slow_poll_count
+            + self.polls.total_untimed_poll_count.load(SeqCst);
+        let total_poll_duration = total_fast_poll_duration + total_slow_poll_duration;
+
+        TaskMetrics {
+            instrumented_count: self.instrumented_count.load(SeqCst),
+            total_instrumentation_gap: Duration::from_nanos(
+                self.total_instrumentation_gap_ns.load(SeqCst),
+            ),
+            max_instrumentation_gap: Duration::from_nanos(
+                self.watermarks
+                    .instrumentation_gap_ns
+                    .load(SeqCst)
+                    .max(self.ongoing_instrumentation_gap_ns()),
+            ),
+            dropped_count: self.dropped_count.load(SeqCst),
+            total_drop_duration: Duration::from_nanos(self.total_drop_duration_ns.load(SeqCst)),
+
+            total_poll_count,
+            total_poll_duration,
+            total_ready_poll_count: self.polls.completed_count.load(SeqCst),
+            total_pending_poll_count: self.polls.total_pending_poll_count.load(SeqCst),
+            concurrent_poll_count: self.polls.concurrent_poll_count.load(SeqCst),
+            max_concurrent_poll_count: self.watermarks.concurrent_polls.load(SeqCst),
+            polling_thread_count: self
+                .workers
+                .as_ref()
+                .map_or(0, |workers| workers.read().unwrap().len() as u64),
+            first_poll_count: self.first_poll_count.load(SeqCst),
+            total_idled_count: self.polls.total_idled_count.load(SeqCst),
+            total_scheduled_count: self.polls.total_scheduled_count.load(SeqCst),
+            total_fast_poll_count: self.polls.total_fast_poll_count.load(SeqCst),
+            total_slow_poll_count: self.polls.total_slow_poll_count.load(SeqCst),
+            total_budget_yield_count: self.polls.total_budget_yield_count.load(SeqCst),
+            total_blocking_poll_count: self.total_blocking_poll_count.load(SeqCst),
+            total_first_poll_delay: Duration::from_nanos(
+                self.total_first_poll_delay_ns.load(SeqCst),
+            ),
+            max_first_poll_delay: Duration::from_nanos(
+                self.watermarks.first_poll_delay_ns.load(SeqCst),
+            ),
+            max_scheduled_duration: Duration::from_nanos(self.watermarks.scheduled_ns.load(SeqCst)),
+            spawned_count: self.spawned_count.load(SeqCst),
+            spawned_first_poll_count: self.spawned_first_poll_count.load(SeqCst),
+            total_spawn_delay: Duration::from_nanos(self.total_spawn_delay_ns.load(SeqCst)),
+            total_spawn_queue_delay: Duration::from_nanos(
+                self.total_spawn_queue_delay_ns.load(SeqCst),
+            ),
+            total_idle_duration: Duration::from_nanos(
+                self.polls.total_idle_duration_ns.load(SeqCst),
+            ),
             total_scheduled_duration: Duration::from_nanos(
-                self.total_scheduled_duration_ns.load(SeqCst),
+                self.polls.total_scheduled_duration_ns.load(SeqCst),
             ),
             total_fast_poll_duration: Duration::from_nanos(
-                self.total_fast_poll_duration_ns.load(SeqCst),
+                self.polls.total_fast_poll_duration_ns.load(SeqCst),
             ),
             total_slow_poll_duration: Duration::from_nanos(
-                self.total_slow_poll_duration.load(SeqCst),
+                self.polls.total_slow_poll_duration.load(SeqCst),
+            ),
+            total_poll_cpu_duration: Duration::from_nanos(
+                self.polls.total_poll_cpu_duration_ns.load(SeqCst),
             ),
+            total_allocation_count: self.polls.total_allocation_count.load(SeqCst),
+            total_allocated_bytes: self.polls.total_allocated_bytes.load(SeqCst),
+            total_local_wake_count: self.polls.total_local_wake_count.load(SeqCst),
+            total_remote_wake_count: self.polls.total_remote_wake_count.load(SeqCst),
+            total_inline_count: self.total_inline_count.load(SeqCst),
+            total_inline_duration: Duration::from_nanos(self.total_inline_duration_ns.load(SeqCst)),
+            timed_out_count: self.timed_out_count.load(SeqCst),
+            total_timed_out_duration: Duration::from_nanos(
+                self.total_timed_out_duration_ns.load(SeqCst),
+            ),
+            completed_count: self.polls.completed_count.load(SeqCst),
+            total_completed_poll_count: self.polls.total_completed_poll_count.load(SeqCst),
+            dropped_before_completion_count: self.dropped_before_completion_count.load(SeqCst),
+            cancelled_count: self.cancelled_count.load(SeqCst),
+            panicked_count: self.panicked_count.load(SeqCst),
+            join_error_count: self.join_error_count.load(SeqCst),
+            succeeded_count: self.succeeded_count.load(SeqCst),
+            failed_count: self.failed_count.load(SeqCst),
+        }
+    }
+}
+
+impl Default for TaskMonitor {
+    fn default() -> TaskMonitor {
+        TaskMonitor::new()
+    }
+}
+
+impl Drop for TaskMonitor {
+    fn drop(&mut self) {
+        self.metrics.flush_local_batch();
+    }
+}
+
+impl WeakTaskMonitor {
+    /// Produces a [`TaskMonitor`] handle to the monitor, or `None` if its metrics have been
+    /// freed.
+    pub fn upgrade(&self) -> Option<TaskMonitor> {
+        self.metrics
+            .upgrade()
+            .map(|metrics| TaskMonitor { metrics })
+    }
+}
+
+impl Drop for ScopeGuard<'_> {
+    fn drop(&mut self) {
+        let metrics = &self.monitor.metrics;
+        let ended_at = Instant::now();
+        metrics.checkpoint(ended_at);
+        metrics.total_inline_count.fetch_add(1, SeqCst);
+        metrics
+            .total_inline_duration_ns
+            .fetch_add(to_nanos(ended_at - self.started_at), SeqCst);
+    }
+}
+
+impl TaskMonitorBuilder {
+    /// Constructs a new builder, initialized with the default configuration of [`TaskMonitor`].
+    pub fn new() -> TaskMonitorBuilder {
+        TaskMonitorBuilder {
+            slow_poll_threshold: TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD,
+            calibration: None,
+            blocking_poll_threshold: None,
+            on_blocking_poll: None,
+            stalled_task_threshold: None,
+            incomplete_task_threshold: None,
+            #[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+            on_stall_dump: None,
+            slowest_polls_capacity: 0,
+            trace_recorder: None,
+            observer: None,
+            on_completion: None,
+            timing: true,
+            track_first_poll: true,
+            track_scheduled: true,
+            track_idle: true,
+            capture_locations: false,
+            checkpoints: None,
+            track_watermarks: false,
+            batching: None,
+            poll_bucket_thresholds: Vec::new(),
+            slow_poll_tiers: Vec::new(),
+            latency_sketch: None,
+            #[cfg(feature = "tracing")]
+            outlier_logging: None,
+            attribute_workers: false,
+            track_live_tasks: false,
+            state_pool_capacity: 0,
+            measure_cpu_time: false,
+            track_allocations: false,
+            sample_perf: false,
+            #[cfg(feature = "usdt")]
+            usdt_long_schedule_threshold: None,
+        }
+    }
+
+    /// Sets the threshold at which polls are considered 'slow'.
+    ///
+    /// Defaults to [`TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD`]. See
+    /// [`TaskMonitor::with_slow_poll_threshold`].
+    pub fn with_slow_poll_threshold(mut self, threshold: Duration) -> TaskMonitorBuilder {
+        self.slow_poll_threshold = threshold;
+        self
+    }
+
+    /// Calibrates the slow-poll threshold from the durations of the monitor's first
+    /// `warm_up_polls` polls: once they are observed, the threshold is set to the `quantile` of
+    /// their durations (e.g., `0.95` for their 95th percentile), so that about `1 - quantile` of
+    /// polls like them are categorized as slow.
+    ///
+    /// Until then, polls are categorized by the threshold configured by
+    /// [`with_slow_poll_threshold`][TaskMonitorBuilder::with_slow_poll_threshold] (or its
+    /// default); their categorization is not revised after calibration. The threshold is calibrated
+    /// once, and does not track subsequent changes in the durations of polls; check
+    /// [`TaskMonitor::is_slow_poll_threshold_calibrated`] before relying on it.
+    ///
+    /// Calibration requires [timing][TaskMonitorBuilder::with_timing]. If `warm_up_polls` is zero,
+    /// calibration is disabled.
+    ///
+    /// ##### Panics
+    /// This method panics if `quantile` is not between 0 and 1, inclusive.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_slow_poll_calibration(20, 0.95)
+    ///         .build();
+    ///     assert!(!monitor.is_slow_poll_threshold_calibrated());
+    ///
+    ///     // 19 polls spin for 100µs, and 1 for 10ms
+    ///     for i in 0..20 {
+    ///         let spin = if i == 10 { 10_000 } else { 100 };
+    ///         monitor.instrument(async move {
+    ///             let start = std::time::Instant::now();
+    ///             while start.elapsed() < Duration::from_micros(spin) {}
+    ///         }).await;
+    ///     }
+    ///
+    ///     // the threshold excludes all but the outlier
+    ///     assert!(monitor.is_slow_poll_threshold_calibrated());
+    ///     let threshold = monitor.slow_poll_threshold();
+    ///     assert!(threshold >= Duration::from_micros(100));
+    ///     assert!(threshold < Duration::from_millis(10));
+    /// }
+    /// ```
+    pub fn with_slow_poll_calibration(
+        mut self,
+        warm_up_polls: usize,
+        quantile: f64,
+    ) -> TaskMonitorBuilder {
+        assert!(
+            (0.0..=1.0).contains(&quantile),
+            "the quantile must be between 0 and 1"
+        );
+        self.calibration = Some((warm_up_polls, quantile)).filter(|&(polls, _)| polls > 0);
+        self
+    }
+
+    /// Enables blocking-poll detection, with a given threshold at which polls are considered
+    /// 'probably blocking'.
+    ///
+    /// A poll that takes a *very* long time (e.g., 100ms) is almost certainly blocking the worker
+    /// thread; e.g., by performing synchronous I/O, or by waiting on a synchronous lock. Such
+    /// polls are counted in [`total_blocking_poll_count`][TaskMetrics::total_blocking_poll_count],
+    /// in addition to being counted as slow polls.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_blocking_poll_threshold(Duration::from_millis(100))
+    ///         .build();
+    ///
+    ///     // this task accidentally blocks the executor
+    ///     monitor.instrument(async {
+    ///         std::thread::sleep(Duration::from_millis(150));
+    ///     }).await;
+    ///
+    ///     assert_eq!(monitor.cumulative().total_blocking_poll_count, 1);
+    ///     assert_eq!(monitor.cumulative().total_slow_poll_count, 1);
+    /// }
+    /// ```
+    pub fn with_blocking_poll_threshold(mut self, threshold: Duration) -> TaskMonitorBuilder {
+        self.blocking_poll_threshold = Some(threshold);
+        self
+    }
+
+    /// Sets a callback to invoke with the duration of each poll that exceeds the
+    /// [blocking-poll threshold][TaskMonitorBuilder::with_blocking_poll_threshold].
+    ///
+    /// The callback is invoked on the thread that polled the task, immediately after the poll
+    /// completes; it should return promptly. It is never invoked unless blocking-poll detection
+    /// is enabled.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_blocking_poll_threshold(Duration::from_millis(100))
+    ///         .with_blocking_poll_callback(|elapsed| {
+    ///             eprintln!("a task blocked its worker thread for {:?}", elapsed);
+    ///         })
+    ///         .build();
+    ///
+    ///     monitor.instrument(async {
+    ///         std::thread::sleep(Duration::from_millis(150));
+    ///     }).await;
+    /// }
+    /// ```
+    pub fn with_blocking_poll_callback<F>(mut self, callback: F) -> TaskMonitorBuilder
+    where
+        F: Fn(Duration) + Send + Sync + 'static,
+    {
+        self.on_blocking_poll = Some(Arc::new(callback));
+        self
+    }
+
+    /// Adds a slow-poll severity tier named `name`, which polls reach if they take at least
+    /// `threshold`; e.g., tiers of `slow` at 50µs, `very_slow` at 5ms and `critical` at 100ms.
+    ///
+    /// The tiers are nested: a poll is counted by every tier it reaches, so that, e.g., a
+    /// `critical` poll is also counted as `very_slow` and `slow`. The count and total duration of
+    /// the polls that reached each tier are reported by [`TaskMonitor::slow_poll_tiers`].
+    /// Adding a tier of the same name as an existing tier replaces its threshold.
+    ///
+    /// Tiers are independent of the [slow-poll threshold][TaskMonitorBuilder::with_slow_poll_threshold].
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_slow_poll_tier("slow", Duration::from_micros(50))
+    ///         .with_slow_poll_tier("very_slow", Duration::from_millis(5))
+    ///         .with_slow_poll_tier("critical", Duration::from_millis(100))
+    ///         .build();
+    ///
+    ///     monitor.instrument(async {
+    ///         std::thread::sleep(Duration::from_millis(10)); // very slow
+    ///     }).await;
+    ///
+    ///     let tiers = monitor.slow_poll_tiers();
+    ///     let counts: Vec<_> = tiers.iter().map(|tier| (tier.name, tier.poll_count)).collect();
+    ///     assert_eq!(counts, [("slow", 1), ("very_slow", 1), ("critical", 0)]);
+    /// }
+    /// ```
+    pub fn with_slow_poll_tier(
+        mut self,
+        name: &'static str,
+        threshold: Duration,
+    ) -> TaskMonitorBuilder {
+        match self.slow_poll_tiers.iter_mut().find(|tier| tier.0 == name) {
+            Some(tier) => tier.1 = threshold,
+            None => self.slow_poll_tiers.push((name, threshold, None)),
+        }
+        self
+    }
+
+    /// Sets a callback to invoke with the duration of each poll whose most severe tier is the
+    /// [slow-poll tier][TaskMonitorBuilder::with_slow_poll_tier] named `name`.
+    ///
+    /// Unlike the counts of tiers, callbacks are not nested: only the callback of the most severe
+    /// tier that a poll reached is invoked, if that tier has one. The callback is invoked on the
+    /// thread that polled the task, immediately after the poll completes; it should return
+    /// promptly.
+    ///
+    /// ##### Panics
+    /// This method panics if no tier named `name` has been added.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_slow_poll_tier("slow", Duration::from_micros(50))
+    ///         .with_slow_poll_tier("critical", Duration::from_millis(100))
+    ///         .with_slow_poll_tier_callback("critical", |elapsed| {
+    ///             eprintln!("a task stalled its worker thread for {:?}", elapsed);
+    ///         })
+    ///         .build();
+    ///
+    ///     monitor.instrument(async {
+    ///         std::thread::sleep(Duration::from_millis(150));
+    ///     }).await;
+    /// }
+    /// ```
+    pub fn with_slow_poll_tier_callback<F>(
+        mut self,
+        name: &'static str,
+        callback: F,
+    ) -> TaskMonitorBuilder
+    where
+        F: Fn(Duration) + Send + Sync + 'static,
+    {
+        let tier = self
+            .slow_poll_tiers
+            .iter_mut()
+            .find(|tier| tier.0 == name)
+            .unwrap_or_else(|| panic!("no slow-poll tier named `{}`", name));
+        tier.2 = Some(Arc::new(callback));
+        self
+    }
+
+    /// Enables stalled-task detection, with a given threshold beyond which tasks awaiting a poll
+    /// are considered stalled.
+    ///
+    /// Tasks that have been instrumented or awoken, but not polled since for at least this
+    /// duration, are reported by [`TaskMonitor::stalled_tasks`]. Enabling stalled-task detection
+    /// requires tracking every live task instrumented by the monitor, which incurs a small cost
+    /// each time a task is instrumented or dropped.
+    ///
+    /// ##### Examples
+    /// In the below example, a task is awoken, but is then starved by a task that blocks the
+    /// executor:
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::{StallKind, TaskMonitor};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let monitor = TaskMonitor::builder()
+    ///         .with_stalled_task_threshold(Duration::from_millis(50))
+    ///         .build();
+    ///
+    ///     let starved_task = tokio::spawn(monitor.instrument(async {
+    ///         tokio::task::yield_now().await;
+    ///     }));
+    ///     // allow `starved_task` to be polled once, and then awoken
+    ///     tokio::task::yield_now().await;
+    ///
+    ///     // the current task blocks the executor, starving `starved_task`
+    ///     std::thread::sleep(Duration::from_millis(100));
+    ///
+    ///     let stalled = monitor.stalled_tasks();
+    ///     assert_eq!(stalled.len(), 1);
+    ///     assert_eq!(stalled[0].kind, StallKind::Scheduled);
+    ///
+    ///     starved_task.await.unwrap();
+    /// }
+    /// ```
+    pub fn with_stalled_task_threshold(mut self, threshold: Duration) -> TaskMonitorBuilder {
+        self.stalled_task_threshold = Some(threshold);
+        self
+    }
+
+    /// Enables stalled-task detection, with a given threshold beyond which incomplete tasks are
+    /// considered stalled.
+    ///
+    /// Tasks that have not completed within this duration of being instrumented are reported by
+    /// [`TaskMonitor::stalled_tasks`]. As with
+    /// [`with_stalled_task_threshold`][TaskMonitorBuilder::with_stalled_task_threshold], this
+    /// requires tracking every live task instrumented by the monitor.
+    ///
+    /// ##### Examples
+    /// In the below example, a task waits for an event that never occurs:
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::{StallKind, TaskMonitor};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = TaskMonitor::builder()
+    ///         .with_incomplete_task_threshold(Duration::from_millis(50))
+    ///         .build();
+    ///
+    ///     let (_tx, rx) = tokio::sync::oneshot::channel::<()>();
+    ///     let deadlocked_task = tokio::spawn(monitor.instrument(rx));
+    ///
+    ///     tokio::time::sleep(Duration::from_millis(100)).await;
+    ///
+    ///     let stalled = monitor.stalled_tasks();
+    ///     assert_eq!(stalled.len(), 1);
+    ///     assert_eq!(stalled[0].kind, StallKind::Incomplete);
+    ///     assert!(stalled[0].age >= Duration::from_millis(100));
+    ///
+    ///     deadlocked_task.abort();
+    /// }
+    /// ```
+    pub fn with_incomplete_task_threshold(mut self, threshold: Duration) -> TaskMonitorBuilder {
+        self.incomplete_task_threshold = Some(threshold);
+        self
+    }
+
+    /// Sets a callback to invoke with a [`StallReport`] whenever tasks of the monitor are found
+    /// stalled: a [`Dump`][tokio::runtime::Dump] of the current runtime, captured in the
+    /// background, as by [`TaskMonitor::dump_stalled_tasks`].
+    ///
+    /// Stalled tasks are sought after each slow poll of the monitor's tasks, which likely starved
+    /// other tasks, and whenever [`TaskMonitor::stalled_tasks`] is called (e.g., by a watchdog);
+    /// tasks are only found stalled if the
+    /// [stalled-task][TaskMonitorBuilder::with_stalled_task_threshold] or
+    /// [incomplete-task][TaskMonitorBuilder::with_incomplete_task_threshold] threshold is
+    /// configured. At most one dump is in progress at a time, and none is captured outside of a
+    /// tokio runtime. The callback is invoked on a task of the runtime.
+    ///
+    /// The traces of a dump cover every task of the runtime, and are not associated with the
+    /// stalled tasks; see [`TaskMonitor::dump_stalled_tasks`].
+    ///
+    /// **This functionality requires `--cfg tokio_unstable --cfg tokio_taskdump`.**
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::TaskMonitor;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let (reports, mut reported) = tokio::sync::mpsc::unbounded_channel();
+    ///     let monitor = TaskMonitor::builder()
+    ///         .with_stalled_task_threshold(Duration::from_millis(50))
+    ///         .with_stall_dump_callback(move |report| {
+    ///             let _ = reports.send(report);
+    ///         })
+    ///         .build();
+    ///
+    ///     let starved_task = tokio::spawn(monitor.instrument(async {
+    ///         tokio::task::yield_now().await;
+    ///     }));
+    ///     // allow `starved_task` to be polled once, and then awoken
+    ///     tokio::task::yield_now().await;
+    ///
+    ///     // a slow poll starves `starved_task`, which is then found stalled
+    ///     monitor.instrument(async {
+    ///         std::thread::sleep(Duration::from_millis(100));
+    ///     }).await;
+    ///
+    ///     let report = reported.recv().await.unwrap();
+    ///     assert_eq!(report.stalled.len(), 1);
+    ///
+    ///     starved_task.await.unwrap();
+    /// }
+    /// ```
+    #[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+    #[cfg_attr(docsrs, doc(cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))))]
+    pub fn with_stall_dump_callback<F>(mut self, callback: F) -> TaskMonitorBuilder
+    where
+        F: Fn(StallReport) + Send + Sync + 'static,
+    {
+        self.on_stall_dump = Some(Arc::new(callback));
+        self
+    }
+
+    /// Enables the leaderboard of slowest polls, retaining up to `capacity` polls.
+    ///
+    /// The leaderboard records the `capacity` slowest polls of tasks instrumented by the monitor,
+    /// since it was last [taken][TaskMonitor::take_slowest_polls]. Aggregate metrics, like
+    /// [`mean_slow_poll_duration`][TaskMetrics::mean_slow_poll_duration], do not reveal when the
+    /// worst offenders occurred; the leaderboard does. Maintaining the leaderboard requires
+    /// briefly acquiring a lock after each poll that is slow enough to enter it.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_slowest_polls_capacity(2)
+    ///         .build();
+    ///
+    ///     monitor.instrument(async {
+    ///         std::thread::sleep(Duration::from_millis(10)); // poll 1 (~10ms)
+    ///         tokio::task::yield_now().await;
+    ///         std::thread::sleep(Duration::from_millis(30)); // poll 2 (~30ms)
+    ///         tokio::task::yield_now().await;
+    ///         std::thread::sleep(Duration::from_millis(20)); // poll 3 (~20ms)
+    ///     }).await;
+    ///
+    ///     // the two slowest polls are retained, slowest first
+    ///     let slowest_polls = monitor.take_slowest_polls();
+    ///     assert_eq!(slowest_polls.len(), 2);
+    ///     assert!(slowest_polls[0].duration >= Duration::from_millis(30));
+    ///     assert!(slowest_polls[1].duration >= Duration::from_millis(20));
+    ///     assert!(slowest_polls[1].duration < slowest_polls[0].duration);
+    /// }
+    /// ```
+    pub fn with_slowest_polls_capacity(mut self, capacity: usize) -> TaskMonitorBuilder {
+        self.slowest_polls_capacity = capacity;
+        self
+    }
+
+    /// Sets whether the monitor captures the source location at which each task is instrumented.
+    ///
+    /// Disabled by default. If enabled, the [location][Location] of each call to
+    /// [`TaskMonitor::instrument`] (or [`TaskMonitor::instrument_with_result`], or
+    /// [`TaskMonitor::spawn`]) is reported by the [`TaskSummary`], [`StalledTask`] and
+    /// [`SlowPoll`] of the instrumented task, so that slow or stalled tasks can be traced back to
+    /// the code that created them.
+    ///
+    /// Locations propagate through functions annotated with `#[track_caller]`.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_location_capture(true)
+    ///         .build();
+    ///
+    ///     let (_, summary) = monitor.instrument_with_result(async {}).await;
+    ///
+    ///     let location = summary.location.unwrap();
+    ///     assert_eq!(location.file(), file!());
+    ///     assert_eq!(location.line(), line!() - 4);
+    /// }
+    /// ```
+    pub fn with_location_capture(mut self, enabled: bool) -> TaskMonitorBuilder {
+        self.capture_locations = enabled;
+        self
+    }
+
+    /// Records the polls and schedules of tasks instrumented by the monitor into a given
+    /// [`TraceRecorder`].
+    ///
+    /// The same recorder may be attached to multiple monitors, whose events it distinguishes.
+    /// See [`TraceRecorder`] for an example.
+    pub fn with_trace_recorder(mut self, recorder: TraceRecorder) -> TaskMonitorBuilder {
+        self.trace_recorder = Some(recorder);
+        self
+    }
+
+    /// Notifies a given [`TaskObserver`] of each raw event in the lifecycle of tasks instrumented
+    /// by the monitor.
+    ///
+    /// See [`TaskObserver`] for an example.
+    pub fn with_observer<O>(mut self, observer: O) -> TaskMonitorBuilder
+    where
+        O: TaskObserver + 'static,
+    {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Sets a callback to invoke with the [`TaskSummary`] of each task that completes.
+    ///
+    /// The callback is invoked on the thread that polled the task to completion, immediately after
+    /// that final poll; it should return promptly. Tasks that are dropped before completing are
+    /// not summarized.
+    ///
+    /// ##### Examples
+    /// In the below example, a postmortem is logged for each task that takes longer than 10ms
+    /// to complete:
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_completion_callback(|summary| {
+    ///             if summary.lifetime >= Duration::from_millis(10) {
+    ///                 eprintln!("slow task: {:?}", summary);
+    ///             }
+    ///         })
+    ///         .build();
+    ///
+    ///     monitor.instrument(async {
+    ///         tokio::time::sleep(Duration::from_millis(20)).await;
+    ///     }).await;
+    /// }
+    /// ```
+    pub fn with_completion_callback<F>(mut self, callback: F) -> TaskMonitorBuilder
+    where
+        F: Fn(&TaskSummary) + Send + Sync + 'static,
+    {
+        self.on_completion = Some(Arc::new(callback));
+        self
+    }
+
+    /// Sets whether the monitor times the polls of tasks.
+    ///
+    /// Enabled by default. If disabled, the monitor operates in a count-only mode: it never reads
+    /// the clock while polling tasks, and records only counts, such as
+    /// [`total_poll_count`][TaskMetrics::total_poll_count],
+    /// [`first_poll_count`][TaskMetrics::first_poll_count],
+    /// [`total_idled_count`][TaskMetrics::total_idled_count] and
+    /// [`total_scheduled_count`][TaskMetrics::total_scheduled_count]. This is the cheapest
+    /// instrumentation this crate offers, suitable for leaving on at all times; timing can be
+    /// enabled when debugging.
+    ///
+    /// In count-only mode, all durations remain zero, polls are counted as neither fast nor slow,
+    /// and the features that depend on poll timing — e.g., the
+    /// [leaderboard of slowest polls][TaskMonitorBuilder::with_slowest_polls_capacity],
+    /// [blocking-poll detection][TaskMonitorBuilder::with_blocking_poll_threshold],
+    /// [trace recording][TaskMonitorBuilder::with_trace_recorder],
+    /// [checkpoints][TaskMonitorBuilder::with_checkpoints] and
+    /// [batched updates][TaskMonitorBuilder::with_batched_updates] — record nothing on the poll
+    /// path.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_timing(false)
+    ///         .build();
+    ///
+    ///     monitor.instrument(async {
+    ///         tokio::task::yield_now().await;
+    ///     }).await;
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.total_poll_count, 2);
+    ///     assert_eq!(metrics.total_scheduled_count, 1);
+    ///     assert_eq!(metrics.total_poll_duration, Duration::ZERO);
+    ///     assert_eq!(metrics.total_fast_poll_count + metrics.total_slow_poll_count, 0);
+    /// }
+    /// ```
+    pub fn with_timing(mut self, enabled: bool) -> TaskMonitorBuilder {
+        self.timing = enabled;
+        self
+    }
+
+    /// Sets whether the monitor tracks the delay between the instrumentation and first poll of
+    /// tasks.
+    ///
+    /// Enabled by default. If disabled,
+    /// [`total_first_poll_delay`][TaskMetrics::total_first_poll_delay],
+    /// [`total_spawn_delay`][TaskMetrics::total_spawn_delay] and
+    /// [`total_spawn_queue_delay`][TaskMetrics::total_spawn_queue_delay] are not recorded, and
+    /// remain zero.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_first_poll_tracking(false)
+    ///         .build();
+    ///
+    ///     let task = monitor.instrument(async {});
+    ///     tokio::time::sleep(Duration::from_millis(10)).await;
+    ///     task.await;
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.first_poll_count, 1);
+    ///     assert_eq!(metrics.total_first_poll_delay, Duration::ZERO);
+    /// }
+    /// ```
+    pub fn with_first_poll_tracking(mut self, enabled: bool) -> TaskMonitorBuilder {
+        self.track_first_poll = enabled;
+        self
+    }
+
+    /// Sets whether the monitor tracks the time tasks spend waiting to be polled after
+    /// awakening.
+    ///
+    /// Enabled by default. If disabled,
+    /// [`total_scheduled_count`][TaskMetrics::total_scheduled_count] and
+    /// [`total_scheduled_duration`][TaskMetrics::total_scheduled_duration] are not recorded, and
+    /// remain zero.
+    ///
+    /// If both scheduled-time and [idle-time][TaskMonitorBuilder::with_idle_tracking] tracking are
+    /// disabled, and neither [stalled-task detection][TaskMonitorBuilder::with_stalled_task_threshold]
+    /// nor an [observer][TaskMonitorBuilder::with_observer] is configured, the monitor no longer
+    /// needs to know when tasks are awoken. Tasks are then polled with their original wakers,
+    /// which avoids the cost of instrumenting them, and
+    /// [`total_budget_yield_count`][TaskMetrics::total_budget_yield_count] is not recorded.
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_scheduled_tracking(false)
+    ///         .with_idle_tracking(false)
+    ///         .build();
+    ///
+    ///     monitor.instrument(async {
+    ///         tokio::task::yield_now().await;
+    ///     }).await;
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert_eq!(metrics.total_poll_count, 2);
+    ///     assert_eq!(metrics.total_scheduled_count, 0);
+    ///     assert_eq!(metrics.total_idled_count, 0);
+    /// }
+    /// ```
+    pub fn with_scheduled_tracking(mut self, enabled: bool) -> TaskMonitorBuilder {
+        self.track_scheduled = enabled;
+        self
+    }
+
+    /// Sets whether the monitor tracks the time tasks spend idling, waiting to be awoken.
+    ///
+    /// Enabled by default. If disabled, [`total_idled_count`][TaskMetrics::total_idled_count] and
+    /// [`total_idle_duration`][TaskMetrics::total_idle_duration] are not recorded, and remain
+    /// zero. See also [`with_scheduled_tracking`][TaskMonitorBuilder::with_scheduled_tracking].
+    pub fn with_idle_tracking(mut self, enabled: bool) -> TaskMonitorBuilder {
+        self.track_idle = enabled;
+        self
+    }
+
+    /// Enables periodic checkpoints of the cumulative metrics of the monitor, which back
+    /// [`TaskMonitor::cumulative_since`].
+    ///
+    /// A checkpoint is taken at most once per `interval`, and only the `capacity` most recent
+    /// checkpoints are retained; e.g., an interval of ten seconds and a capacity of 360 supports
+    /// queries over the last hour. Checkpoints are taken lazily, by the first event (e.g., a poll)
+    /// to occur after one is due, so they require no background task.
+    ///
+    /// Disabled by default. See [`TaskMonitor::cumulative_since`] for an example.
+    pub fn with_checkpoints(mut self, interval: Duration, capacity: usize) -> TaskMonitorBuilder {
+        self.checkpoints = Some((interval, capacity));
+        self
+    }
+
+    /// Sets whether the monitor tracks watermarks: the maxima of
+    /// [first-poll delays][TaskMetrics::max_first_poll_delay],
+    /// [scheduling delays][TaskMetrics::max_scheduled_duration] and
+    /// [instrumentation gaps][TaskMetrics::max_instrumentation_gap], and the
+    /// [number of tasks being polled][TaskMetrics::concurrent_poll_count] and
+    /// [its maximum][TaskMetrics::max_concurrent_poll_count].
+    ///
+    /// Disabled by default, in which case these metrics are always zero. Tracking them costs each
+    /// poll a few more atomic operations on state shared by all the tasks of the monitor, and a
+    /// read of the registry of the monitor's live interval iterators.
+    ///
+    /// ##### Examples
+    /// See [`TaskMetrics::max_concurrent_poll_count`].
+    pub fn with_watermarks(mut self, enabled: bool) -> TaskMonitorBuilder {
+        self.track_watermarks = enabled;
+        self
+    }
+
+    /// Batches the updates of the counters incremented by each poll (e.g., poll counts and
+    /// durations, and idle and scheduled counts and durations) in thread-local storage, flushing
+    /// them to the monitor after `max_polls` polls or `max_delay`, whichever comes first.
+    ///
+    /// By default, each poll updates the counters of the monitor directly. If the monitor is shared
+    /// by many tasks polled concurrently on many worker threads, these updates contend for the
+    /// same cache lines; batching them cuts this cross-core traffic. In exchange, metrics observed
+    /// via the monitor (e.g., [`TaskMonitor::cumulative`]) may lag the polls of other threads by
+    /// up to `max_polls` polls, or until the thread next polls a task of the monitor after
+    /// `max_delay` has elapsed.
+    ///
+    /// A thread's batch is also flushed when a task of the monitor, or a handle to the monitor,
+    /// is dropped on it, when the monitor's metrics are read on it, and when it exits. Batches do
+    /// not keep the monitor's metrics alive.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use futures::FutureExt;
+    /// use std::sync::mpsc;
+    /// use std::time::Duration;
+    /// use tokio_metrics::TaskMonitor;
+    ///
+    /// let monitor = TaskMonitor::builder()
+    ///     .with_batched_updates(4, Duration::from_secs(1))
+    ///     .build();
+    ///
+    /// let (polled, wait) = mpsc::channel();
+    /// let (exit, exited) = mpsc::channel::<()>();
+    /// let worker = {
+    ///     let monitor = monitor.clone();
+    ///     std::thread::spawn(move || {
+    ///         let mut task = monitor.instrument(std::future::pending::<()>()).boxed();
+    ///         assert!((&mut task).now_or_never().is_none());
+    ///         polled.send(()).unwrap();
+    ///         let _ = exited.recv();
+    ///     })
+    /// };
+    ///
+    /// // the poll is batched by the worker thread
+    /// wait.recv().unwrap();
+    /// assert_eq!(monitor.cumulative().total_poll_count, 0);
+    ///
+    /// // dropping the task on the worker thread flushes its batch
+    /// drop(exit);
+    /// worker.join().unwrap();
+    /// assert_eq!(monitor.cumulative().total_poll_count, 1);
+    /// ```
+    pub fn with_batched_updates(
+        mut self,
+        max_polls: u64,
+        max_delay: Duration,
+    ) -> TaskMonitorBuilder {
+        self.batching = Some(Batching {
+            max_polls,
+            max_delay,
+        });
+        self
+    }
+
+    /// Divides polls into latency buckets delimited by the given `thresholds`, in addition to
+    /// dividing them into fast and slow polls.
+    ///
+    /// `n` thresholds delimit `n + 1` buckets; e.g., thresholds of 10µs, 100µs and 1ms produce
+    /// buckets of polls taking less than 10µs, less than 100µs, less than 1ms, and at least 1ms.
+    /// The count and total duration of the polls in each bucket are reported by
+    /// [`TaskMonitor::poll_buckets`] — a middle ground between two buckets and a full histogram.
+    /// The thresholds need not be given in order.
+    pub fn with_poll_buckets<I>(mut self, thresholds: I) -> TaskMonitorBuilder
+    where
+        I: IntoIterator<Item = Duration>,
+    {
+        let mut thresholds: Vec<Duration> = thresholds.into_iter().collect();
+        thresholds.sort();
+        thresholds.dedup();
+        self.poll_bucket_thresholds = thresholds;
+        self
+    }
+
+    /// Summarizes the durations of polls, and the delays between tasks being awoken and polled,
+    /// in quantile sketches of the kind and parameters of the given empty `sketch`; e.g., a
+    /// [`DDSketch`][crate::DDSketch] of 1% relative accuracy, a [`TDigest`][crate::TDigest] for
+    /// pipelines that standardize on t-digests, an
+    /// [`ExponentialHistogram`][crate::ExponentialHistogram] for OpenTelemetry, or, with the crate
+    /// feature `hdrhistogram`, an `HdrHistogram` for accurate high-dynamic-range quantiles.
+    ///
+    /// Unlike [poll buckets][TaskMonitorBuilder::with_poll_buckets], sketches estimate arbitrary
+    /// quantiles, and merge across monitors. They are [taken][TaskMonitor::take_latency_sketches]
+    /// once per sampling interval, to be shipped to a central aggregator, or merged locally.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use tokio_metrics::DDSketch;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_latency_sketch(DDSketch::new(0.01))
+    ///         .build();
+    ///
+    ///     monitor.instrument(async {
+    ///         std::thread::sleep(Duration::from_millis(10));
+    ///     }).await;
+    ///
+    ///     let (polls, scheduled) = monitor.take_latency_sketches().unwrap();
+    ///     assert_eq!(polls.count(), 1);
+    ///     assert!(polls.quantile(0.99).unwrap() >= Duration::from_millis(9));
+    ///     assert_eq!(scheduled.count(), 0);
+    ///
+    ///     // taking the sketches resets them
+    ///     let (polls, _) = monitor.take_latency_sketches().unwrap();
+    ///     assert_eq!(polls.count(), 0);
+    /// }
+    /// ```
+    pub fn with_latency_sketch(mut self, sketch: impl Into<LatencySketch>) -> TaskMonitorBuilder {
+        self.latency_sketch = Some(sketch.into());
+        self
+    }
+
+    /// Logs polls and schedule delays that take at least `high_watermark`, at most
+    /// `max_per_minute` times per minute.
+    ///
+    /// Outliers are logged as `tracing` events at the `WARN` level, with the target
+    /// `tokio_metrics::outliers`; e.g., "task poll took 800ms". The id and, if
+    /// [named][TaskMonitor::instrument_named], the name of the task are recorded as the fields
+    /// `task_id` and `task_name`. To emit these events as `log` records, enable the `log` feature
+    /// of `tracing`.
+    ///
+    /// Outliers beyond the first `max_per_minute` of each minute are not logged.
+    ///
+    /// **This functionality requires the crate feature `tracing`.**
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let monitor = tokio_metrics::TaskMonitor::builder()
+    ///     .with_outlier_logging(Duration::from_millis(100), 10)
+    ///     .build();
+    /// ```
+    #[cfg(feature = "tracing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+    pub fn with_outlier_logging(
+        mut self,
+        high_watermark: Duration,
+        max_per_minute: u32,
+    ) -> TaskMonitorBuilder {
+        self.outlier_logging = Some((high_watermark, max_per_minute));
+        self
+    }
+
+    /// Sets whether the monitor attributes each poll to the thread that performed it.
+    ///
+    /// Disabled by default. If enabled, the polls performed by each thread are reported by
+    /// [`TaskMonitor::worker_polls`] and [`TaskMonitor::worker_poll_intervals`], and the threads
+    /// are counted by [`TaskMetrics::polling_thread_count`]. Attribution costs a lookup of the
+    /// current thread in a shared table per poll.
+    pub fn with_worker_attribution(mut self, enabled: bool) -> TaskMonitorBuilder {
+        self.attribute_workers = enabled;
+        self
+    }
+
+    /// Sets whether the monitor tracks its live tasks, so that they may be enumerated by
+    /// [`TaskMonitor::live_tasks`].
+    ///
+    /// Disabled by default, unless a [stalled-task][TaskMonitorBuilder::with_stalled_task_threshold]
+    /// or [incomplete-task][TaskMonitorBuilder::with_incomplete_task_threshold] threshold is
+    /// configured. Tracking costs an insertion into and a removal from a shared table per task,
+    /// and the observation of every wake.
+    pub fn with_live_task_tracking(mut self, enabled: bool) -> TaskMonitorBuilder {
+        self.track_live_tasks = enabled;
+        self
+    }
+
+    /// Sets the number of the allocations of the instrumentation state of completed tasks that
+    /// the monitor retains, to recycle for the tasks it instruments next.
+    ///
+    /// Disabled (i.e., zero) by default. Monitors that instrument millions of short-lived tasks
+    /// per second may pool their allocations to relieve the allocator, at the cost of the memory
+    /// of the retained allocations. The allocations are pooled by each thread, without locking: a
+    /// task's allocation is retained by the thread that drops it, which retains at most `capacity`
+    /// allocations of the monitor, and recycles them for the tasks instrumented on it. The state
+    /// of a task is not recycled while a waker of the task outlives it.
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_state_pool(1024)
+    ///         .build();
+    ///
+    ///     // after the first, each task reuses the allocation of its predecessor
+    ///     for _ in 0..1000 {
+    ///         monitor.instrument(async {}).await;
+    ///     }
+    ///     assert_eq!(monitor.cumulative().dropped_count, 1000);
+    ///
+    ///     // pooled allocations do not keep the metrics of their monitor alive
+    ///     let weak_monitor = monitor.downgrade();
+    ///     drop(monitor);
+    ///     assert!(weak_monitor.upgrade().is_none());
+    /// }
+    /// ```
+    pub fn with_state_pool(mut self, capacity: usize) -> TaskMonitorBuilder {
+        self.state_pool_capacity = capacity;
+        self
+    }
+
+    /// Sets whether the monitor measures the CPU time, in addition to the wall time, consumed by
+    /// each poll; see [`total_poll_cpu_duration`][TaskMetrics::total_poll_cpu_duration].
+    ///
+    /// Disabled by default. Measuring CPU time costs two calls of `clock_gettime` per poll.
+    ///
+    /// **This functionality requires the crate feature `cpu-time` and a Unix platform.**
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_cpu_time(true)
+    ///         .build();
+    ///
+    ///     monitor.instrument(async {
+    ///         // sleeping consumes wall time, but little CPU time
+    ///         std::thread::sleep(Duration::from_millis(50));
+    ///     }).await;
+    ///
+    ///     let metrics = monitor.cumulative();
+    ///     assert!(metrics.total_poll_duration >= Duration::from_millis(50));
+    ///     assert!(metrics.total_poll_cpu_duration < Duration::from_millis(25));
+    /// }
+    /// ```
+    #[cfg(all(feature = "cpu-time", unix))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "cpu-time", unix))))]
+    pub fn with_cpu_time(mut self, enabled: bool) -> TaskMonitorBuilder {
+        self.measure_cpu_time = enabled;
+        self
+    }
+
+    /// Sets whether the monitor counts the allocations performed by each poll; see
+    /// [`total_allocation_count`][TaskMetrics::total_allocation_count] and
+    /// [`total_allocated_bytes`][TaskMetrics::total_allocated_bytes].
+    ///
+    /// Disabled by default. Allocations are only counted if
+    /// [`TrackingAllocator`][crate::TrackingAllocator] is installed as the global allocator;
+    /// see its documentation for an example. Allocations performed by other threads on behalf
+    /// of a task (e.g., by `spawn_blocking`) are not attributed to it, and allocations performed
+    /// within nested instrumented tasks are attributed to each of their monitors.
+    pub fn with_allocation_tracking(mut self, enabled: bool) -> TaskMonitorBuilder {
+        self.track_allocations = enabled;
+        self
+    }
+
+    /// Sets whether the monitor samples hardware performance counters (CPU cycles, instructions
+    /// retired, and cache misses) around each poll, and attaches them to the polls recorded in
+    /// the [leaderboard of slowest polls][TaskMonitorBuilder::with_slowest_polls_capacity].
+    ///
+    /// Disabled by default. Counters are opened per thread via `perf_event_open`, and only count
+    /// events in user space. Sampling them costs two system calls per poll, so this is intended
+    /// for performance debugging, not for always-on use. If perf events are unavailable (e.g.,
+    /// forbidden by `kernel.perf_event_paranoid`, or within a container), polls are recorded
+    /// without counters. Has no effect unless the leaderboard is enabled.
+    ///
+    /// **This functionality requires the crate feature `perf`, and Linux.**
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::builder()
+    ///         .with_slowest_polls_capacity(10)
+    ///         .with_perf_counters(true)
+    ///         .build();
+    ///
+    ///     monitor.instrument(async {
+    ///         let mut sum = 0u64;
+    ///         for i in 0..1_000_000 {
+    ///             sum = sum.wrapping_add(std::hint::black_box(i));
+    ///         }
+    ///         sum
+    ///     }).await;
+    ///
+    ///     let slowest_polls = monitor.take_slowest_polls();
+    ///     if let Some(perf) = slowest_polls[0].perf {
+    ///         assert!(perf.instructions > 1_000_000);
+    ///     }
+    /// }
+    /// ```
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "perf", target_os = "linux"))))]
+    pub fn with_perf_counters(mut self, enabled: bool) -> TaskMonitorBuilder {
+        self.sample_perf = enabled;
+        self
+    }
+
+    /// Fires USDT probes at delays between tasks being awoken and polled of at least
+    /// `long_schedule_threshold`, in addition to slow polls, so that kernel-side tooling can
+    /// correlate runtime stalls with task-level events.
+    ///
+    /// The `slow_poll` probe fires at the slow polls of every monitor, regardless of this setting;
+    /// like any probe, it costs only a `nop` unless a tracer enables it. The probes belong to the provider `tokio_metrics`, and carry two arguments: the
+    /// [id][TaskId] of the task, and the duration of the poll or delay, in nanoseconds:
+    ///
+    /// - **`slow_poll`**: a poll took at least the
+    ///   [slow-poll threshold][TaskMonitorBuilder::with_slow_poll_threshold].
+    /// - **`long_schedule`**: a task waited at least `long_schedule_threshold` to be polled.
+    ///
+    /// Probes are emitted on Linux, on x86-64 and AArch64; e.g., with `bpftrace`:
+    ///
+    /// ```text
+    /// bpftrace -e 'usdt:./server:tokio_metrics:slow_poll { @[ustack] = hist(arg1); }'
+    /// ```
+    ///
+    /// **This functionality requires the crate feature `usdt`, and Rust 1.59.**
+    #[cfg(feature = "usdt")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "usdt")))]
+    pub fn with_usdt_probes(mut self, long_schedule_threshold: Duration) -> TaskMonitorBuilder {
+        self.usdt_long_schedule_threshold = Some(long_schedule_threshold);
+        self
+    }
+
+    /// Consumes the builder, producing a [`TaskMonitor`].
+    pub fn build(self) -> TaskMonitor {
+        let track_live_tasks = self.track_live_tasks
+            || self.stalled_task_threshold.is_some()
+            || self.incomplete_task_threshold.is_some();
+        let track_wakes =
+            self.track_scheduled || self.track_idle || track_live_tasks || self.observer.is_some();
+
+        TaskMonitor {
+            metrics: Arc::new(RawMetrics {
+                slow_poll_threshold_ns: AtomicU64::new(to_nanos(self.slow_poll_threshold)),
+                calibration: self
+                    .calibration
+                    .map(|(warm_up_polls, quantile)| Calibration {
+                        warm_up_polls,
+                        quantile,
+                        samples: Mutex::new(Vec::with_capacity(warm_up_polls)),
+                        done: AtomicBool::new(false),
+                    }),
+                blocking_poll_threshold: self.blocking_poll_threshold,
+                on_blocking_poll: self.on_blocking_poll,
+                stalled_task_threshold: self.stalled_task_threshold,
+                incomplete_task_threshold: self.incomplete_task_threshold,
+                #[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+                on_stall_dump: self.on_stall_dump,
+                #[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+                dumping: AtomicBool::new(false),
+                state_pool: (self.state_pool_capacity > 0).then(|| StatePool {
+                    capacity: self.state_pool_capacity,
+                    placeholder: TaskMonitor::new().metrics.clone(),
+                }),
+                live_tasks: track_live_tasks.then(|| Mutex::new(HashMap::new())),
+                next_task_id: AtomicU64::new(0),
+                slowest_polls_capacity: self.slowest_polls_capacity,
+                slowest_polls: Mutex::new(Vec::with_capacity(self.slowest_polls_capacity)),
+                slowest_polls_floor_ns: AtomicU64::new(0),
+                trace_recorder: self.trace_recorder.map(TraceRecorder::attach),
+                observer: self.observer,
+                on_completion: self.on_completion,
+                timing: self.timing,
+                track_first_poll: self.track_first_poll,
+                track_scheduled: self.track_scheduled,
+                track_idle: self.track_idle,
+                track_wakes,
+                capture_locations: self.capture_locations,
+                checkpoints: self
+                    .checkpoints
+                    .filter(|&(_, capacity)| capacity > 0)
+                    .map(|(interval, capacity)| Checkpoints::new(interval, capacity)),
+                track_watermarks: self.track_watermarks,
+                batching: self.batching,
+                poll_buckets: if self.poll_bucket_thresholds.is_empty() {
+                    Vec::new()
+                } else {
+                    (0..=self.poll_bucket_thresholds.len())
+                        .map(|_| PollBucketCounters::default())
+                        .collect()
+                },
+                poll_bucket_thresholds: self.poll_bucket_thresholds,
+                slow_poll_tiers: {
+                    let mut tiers: Vec<_> = self
+                        .slow_poll_tiers
+                        .into_iter()
+                        .map(|(name, threshold, callback)| SlowPollTierCounters {
+                            name,
+                            threshold,
+                            callback,
+                            count: AtomicU64::new(0),
+                            duration_ns: AtomicU64::new(0),
+                        })
+                        .collect();
+                    tiers.sort_by_key(|tier| tier.threshold);
+                    tiers
+                },
+                latency_sketches: self.latency_sketch.map(|sketch| LatencySketches {
+                    polls: Mutex::new(sketch.empty()),
+                    scheduled: Mutex::new(sketch.empty()),
+                }),
+                #[cfg(feature = "tracing")]
+                outlier_logger: self
+                    .outlier_logging
+                    .map(|(high_watermark, max_per_minute)| {
+                        OutlierLogger::new(high_watermark, max_per_minute)
+                    }),
+                measure_cpu_time: self.measure_cpu_time,
+                track_allocations: self.track_allocations,
+                sample_perf: self.sample_perf && self.slowest_polls_capacity > 0,
+                #[cfg(feature = "usdt")]
+                usdt_long_schedule_threshold: self.usdt_long_schedule_threshold,
+                workers: self.attribute_workers.then(|| RwLock::new(HashMap::new())),
+                branches: RwLock::new(HashMap::new()),
+                first_poll_count: AtomicU64::new(0),
+                polls: PollCounters::default(),
+                total_blocking_poll_count: AtomicU64::new(0),
+                enabled: AtomicBool::new(true),
+                instrumented_count: AtomicU64::new(0),
+                created_at: Instant::now(),
+                last_instrumented_ns: AtomicU64::new(0),
+                total_instrumentation_gap_ns: AtomicU64::new(0),
+                dropped_count: AtomicU64::new(0),
+                total_drop_duration_ns: AtomicU64::new(0),
+                total_first_poll_delay_ns: AtomicU64::new(0),
+                watermarks: Watermarks::default(),
+                interval_watermarks: RwLock::new(Vec::new()),
+                restored: RwLock::new(None),
+                spawned_count: AtomicU64::new(0),
+                spawned_first_poll_count: AtomicU64::new(0),
+                total_spawn_delay_ns: AtomicU64::new(0),
+                total_spawn_queue_delay_ns: AtomicU64::new(0),
+                total_inline_count: AtomicU64::new(0),
+                total_inline_duration_ns: AtomicU64::new(0),
+                timed_out_count: AtomicU64::new(0),
+                total_timed_out_duration_ns: AtomicU64::new(0),
+                dropped_before_completion_count: AtomicU64::new(0),
+                cancelled_count: AtomicU64::new(0),
+                panicked_count: AtomicU64::new(0),
+                join_error_count: AtomicU64::new(0),
+                succeeded_count: AtomicU64::new(0),
+                failed_count: AtomicU64::new(0),
+            }),
         }
     }
-}
+}
+
+impl Default for TaskMonitorBuilder {
+    fn default() -> TaskMonitorBuilder {
+        TaskMonitorBuilder::new()
+    }
+}
+
+impl TaskMetrics {
+    /// The mean duration elapsed between the instant tasks are instrumented, and the instant they
+    /// are first polled.
+    ///
+    /// ##### Definition
+    /// This metric is derived from [`total_first_poll_delay`][TaskMetrics::total_first_poll_delay]
+    /// ÷ [`first_poll_count`][TaskMetrics::first_poll_count].
+    ///
+    /// ##### Interpretation
+    /// If this metric increases, it means that, on average, tasks spent longer waiting to be
+    /// initially polled.
+    ///
+    /// ##### See also
+    /// - **[`mean_scheduled_duration`][TaskMetrics::mean_scheduled_duration]**   
+    ///   The mean duration that tasks spent waiting to be executed after awakening.
+    ///
+    /// ##### Examples
+    /// In the below example, no tasks are instrumented or polled within the first sampling
+    /// interval; in the second sampling interval, 500ms elapse between the instrumentation of a
+    /// task and its first poll; in the third sampling interval, a mean of 750ms elapse between the
+    /// instrumentation and first poll of two tasks:
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut interval = metrics_monitor.intervals();
+    ///     let mut next_interval = || interval.next().unwrap();
+    ///
+    ///     // no tasks have yet been created, instrumented, or polled
+    ///     assert_eq!(next_interval().mean_first_poll_delay(), Duration::ZERO);
+    ///
+    ///     // constructs and instruments a task, pauses for `pause_time`, awaits the task, then
+    ///     // produces the total time it took to do all of the aforementioned
+    ///     async fn instrument_pause_await(
+    ///         metrics_monitor: &tokio_metrics::TaskMonitor,
+    ///         pause_time: Duration
+    ///     ) -> Duration
+    ///     {
+    ///         let before_instrumentation = tokio::time::Instant::now();
+    ///         let task = metrics_monitor.instrument(async move {});
+    ///         tokio::time::sleep(pause_time).await;
+    ///         task.await;
+    ///         before_instrumentation.elapsed()
+    ///     }
+    ///
+    ///     // construct and await a task that pauses for 500ms between instrumentation and first poll
+    ///     let task_a_pause_time = Duration::from_millis(500);
+    ///     let task_a_total_time = instrument_pause_await(&metrics_monitor, task_a_pause_time).await;
+    ///
+    ///     // the `mean_first_poll_delay` will be some duration greater-than-or-equal-to the
+    ///     // pause time of 500ms, and less-than-or-equal-to the total runtime of `task_a`
+    ///     let mean_first_poll_delay = next_interval().mean_first_poll_delay();
+    ///     assert!(mean_first_poll_delay >= task_a_pause_time);
+    ///     assert!(mean_first_poll_delay <= task_a_total_time);
+    ///
+    ///     // construct and await a task that pauses for 500ms between instrumentation and first poll
+    ///     let task_b_pause_time = Duration::from_millis(500);
+    ///     let task_b_total_time = instrument_pause_await(&metrics_monitor, task_b_pause_time).await;
+    ///
+    ///     // construct and await a task that pauses for 1000ms between instrumentation and first poll
+    ///     let task_c_pause_time = Duration::from_millis(1000);
+    ///     let task_c_total_time = instrument_pause_await(&metrics_monitor, task_c_pause_time).await;
+    ///
+    ///     // the `mean_first_poll_delay` will be some duration greater-than-or-equal-to the
+    ///     // average pause time of 500ms, and less-than-or-equal-to the combined total runtime of
+    ///     // `task_b` and `task_c`
+    ///     let mean_first_poll_delay = next_interval().mean_first_poll_delay();
+    ///     assert!(mean_first_poll_delay >= (task_b_pause_time + task_c_pause_time) / 2);
+    ///     assert!(mean_first_poll_delay <= (task_b_total_time + task_c_total_time) / 2);
+    /// }
+    /// ```
+    pub fn mean_first_poll_delay(&self) -> Duration {
+        mean(self.total_first_poll_delay, self.first_poll_count)
+    }
+
+    /// The mean duration elapsed between the instant spawned tasks are instrumented, and the
+    /// instant they are spawned.
+    ///
+    /// ##### Definition
+    /// This metric is derived from [`total_spawn_delay`][TaskMetrics::total_spawn_delay] ÷
+    /// [`spawned_first_poll_count`][TaskMetrics::spawned_first_poll_count].
+    ///
+    /// ##### Interpretation
+    /// If this metric increases, it means that, on average, your application held on to tasks for
+    /// longer between instrumenting and spawning them; e.g., because it batches tasks before
+    /// spawning them. Unlike [`mean_spawn_queue_delay`][TaskMetrics::mean_spawn_queue_delay], this
+    /// delay is not attributable to the runtime.
+    ///
+    /// ##### See also
+    /// - **[`mean_first_poll_delay`][TaskMetrics::mean_first_poll_delay]**   
+    ///   The mean duration elapsed between the instant tasks are instrumented, and the instant they
+    ///   are first polled.
+    pub fn mean_spawn_delay(&self) -> Duration {
+        mean(self.total_spawn_delay, self.spawned_first_poll_count)
+    }
+
+    /// The mean duration elapsed between the instant spawned tasks are spawned, and the instant
+    /// they are first polled.
+    ///
+    /// ##### Definition
+    /// This metric is derived from
+    /// [`total_spawn_queue_delay`][TaskMetrics::total_spawn_queue_delay] ÷
+    /// [`spawned_first_poll_count`][TaskMetrics::spawned_first_poll_count].
+    ///
+    /// ##### Interpretation
+    /// If this metric increases, it means that, on average, newly-spawned tasks spent longer in the
+    /// runtime's queues before being polled; i.e., the runtime is backlogged.
+    ///
+    /// ##### See also
+    /// - **[`mean_spawn_delay`][TaskMetrics::mean_spawn_delay]**   
+    ///   The mean duration elapsed between the instant spawned tasks are instrumented, and the
+    ///   instant they are spawned.
+    /// - **[`mean_scheduled_duration`][TaskMetrics::mean_scheduled_duration]**   
+    ///   The mean duration that tasks spent waiting to be executed after awakening.
+    pub fn mean_spawn_queue_delay(&self) -> Duration {
+        mean(self.total_spawn_queue_delay, self.spawned_first_poll_count)
+    }
+
+    /// The mean duration of idles.
+    ///
+    /// ##### Definition
+    /// This metric is derived from [`total_idle_duration`][TaskMetrics::total_idle_duration] ÷
+    /// [`total_idled_count`][TaskMetrics::total_idled_count].
+    ///
+    /// ##### Interpretation
+    /// The idle state is the duration spanning the instant a task completes a poll, and the instant
+    /// that it is next awoken. Tasks inhabit this state when they are waiting for task-external
+    /// events to complete (e.g., an asynchronous sleep, a network request, file I/O, etc.). If this
+    /// metric increases, it means that tasks, in aggregate, spent more time waiting for
+    /// task-external events to complete.
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let one_sec = std::time::Duration::from_secs(1);
+    ///
+    ///     monitor.instrument(async move {
+    ///         tokio::time::sleep(one_sec).await;
+    ///     }).await;
+    ///
+    ///     assert!(monitor.cumulative().mean_idle_duration() >= one_sec);
+    /// }
+    /// ```
+    pub fn mean_idle_duration(&self) -> Duration {
+        mean(self.total_idle_duration, self.total_idled_count)
+    }
+
+    /// The mean duration that tasks spent waiting to be executed after awakening.
+    ///
+    /// ##### Definition
+    /// This metric is derived from
+    /// [`total_scheduled_duration`][TaskMetrics::total_scheduled_duration] ÷
+    /// [`total_scheduled_count`][`TaskMetrics::total_scheduled_count`].
+    ///
+    /// ##### Interpretation
+    /// If this metric increases, it means that, on average, tasks spent longer in the runtime's
+    /// queues before being polled.
+    ///
+    /// ##### See also
+    /// - **[`mean_first_poll_delay`][TaskMetrics::mean_first_poll_delay]**   
+    ///   The mean duration elapsed between the instant tasks are instrumented, and the instant they
+    ///   are first polled.
+    ///
+    /// ##### Examples
+    /// In the below example, a task that yields endlessly is raced against a task that blocks the
+    /// executor for 1 second; the yielding task spends approximately 1 second waiting to
+    /// be scheduled. In the next sampling interval, a task that yields endlessly is raced against a
+    /// task that blocks the executor for half a second; the yielding task spends approximately half
+    /// a second waiting to be scheduled.
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut interval = metrics_monitor.intervals();
+    ///     let mut next_interval = || interval.next().unwrap();
+    ///
+    ///     // construct and instrument a task that yields endlessly
+    ///     let endless_task = metrics_monitor.instrument(async {
+    ///         loop { tokio::task::yield_now().await }
+    ///     });
+    ///
+    ///     // construct and spawn a task that blocks the executor for 1 second
+    ///     let one_sec_task = tokio::spawn(async {
+    ///         std::thread::sleep(Duration::from_secs(1))
+    ///     });
+    ///
+    ///     // ensure that at least 2s elapse between the instrumentation of
+    ///     // `endless_task` and its first poll
+    ///     std::thread::sleep(Duration::from_secs(2));
+    ///
+    ///     // race `endless_task` against `one_sec_task`
+    ///     tokio::select! {
+    ///         biased;
+    ///         _ = endless_task => { unreachable!() }
+    ///         _ = one_sec_task => {}
+    ///     }
+    ///
+    ///     // `endless_task` will have spent approximately one second waiting:
+    ///     let interval = next_interval();
+    ///     assert!(interval.mean_scheduled_duration() >= Duration::from_secs(1));
+    ///     // ...but *not* 2s waiting:
+    ///     assert!(interval.mean_scheduled_duration() <= Duration::from_secs(2));
+    ///     // i.e., time_to_first_poll is not factored into total_scheduled_duration:
+    ///     assert!(interval.mean_first_poll_delay() >= Duration::from_secs(2));
+    ///
+    ///     // construct and instrument and spawn a task that yields endlessly
+    ///     let endless_task = metrics_monitor.instrument(async {
+    ///         loop { tokio::task::yield_now().await }
+    ///     });
+    ///
+    ///     // construct (but do not spawn) and a task that blocks the executor for 1 second
+    ///     let one_sec_task = async {
+    ///         std::thread::sleep(Duration::from_secs(1))
+    ///     };
+    ///
+    ///     // race `endless_task` against `one_sec_task`
+    ///     tokio::select! {
+    ///         biased;
+    ///         _ = endless_task => { unreachable!() }
+    ///         _ = one_sec_task => {}
+    ///     }
+    ///
+    ///     // `endless_task` will NOT have spent 1 second waiting to be scheduled
+    ///     assert!(next_interval().mean_scheduled_duration() < Duration::from_secs(1));
+    /// }
+    /// ```
+    pub fn mean_scheduled_duration(&self) -> Duration {
+        mean(self.total_scheduled_duration, self.total_scheduled_count)
+    }
+
+    /// The mean duration that each completed task spent, in total, waiting to be executed after
+    /// awakening.
+    ///
+    /// ##### Definition
+    /// This metric is derived from
+    /// [`total_scheduled_duration`][TaskMetrics::total_scheduled_duration] ÷
+    /// [`completed_count`][`TaskMetrics::completed_count`].
+    ///
+    /// ##### Interpretation
+    /// Unlike [`mean_scheduled_duration`][TaskMetrics::mean_scheduled_duration], which averages
+    /// over individual schedules, this metric averages over tasks. A task that is rescheduled
+    /// hundreds of times may spend only a little time in the runtime's queues per schedule, yet a
+    /// great deal of time in total. If this metric increases while `mean_scheduled_duration`
+    /// does not, tasks are being scheduled more often, rather than waiting longer per schedule.
+    ///
+    /// Since the scheduled time of tasks that have yet to complete, or that never do (e.g., that
+    /// are cancelled), is included in the numerator, this metric is most meaningful for
+    /// short-lived tasks that run to completion.
+    ///
+    /// ##### See also
+    /// - **[`mean_scheduled_duration`][TaskMetrics::mean_scheduled_duration]**   
+    ///   The mean duration that tasks spent waiting to be executed after awakening.
+    ///
+    /// ##### Examples
+    /// In the below example, a task that yields ten times is raced against a task that blocks the
+    /// executor for 10ms between each of its own yields; the yielding task spends approximately
+    /// 10ms waiting to be polled after each of its ten wakeups:
+    /// ```
+    /// use std::task::Poll;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut interval = metrics_monitor.intervals();
+    ///     let mut next_interval = || interval.next().unwrap();
+    ///
+    ///     // no tasks have completed
+    ///     assert_eq!(next_interval().mean_scheduled_duration_per_task(), Duration::ZERO);
+    ///
+    ///     let delay = Duration::from_millis(10);
+    ///
+    ///     // construct and instrument a task that yields ten times
+    ///     let yielding_task = metrics_monitor.instrument(async {
+    ///         for _ in 0..10 { yield_now().await }
+    ///     });
+    ///
+    ///     // construct a task that blocks the executor for 10ms between yields
+    ///     let blocking_task = async move {
+    ///         for _ in 0..10 {
+    ///             std::thread::sleep(delay);
+    ///             yield_now().await
+    ///         }
+    ///     };
+    ///
+    ///     tokio::join!(yielding_task, blocking_task);
+    ///
+    ///     let interval = next_interval();
+    ///     assert_eq!(interval.completed_count, 1);
+    ///     // each schedule took about 10ms...
+    ///     assert!(interval.mean_scheduled_duration() >= delay);
+    ///     // ...but the task spent about 100ms scheduled in total
+    ///     assert!(interval.mean_scheduled_duration_per_task() >= 10 * delay);
+    /// }
+    ///
+    /// /// Wake the current task, then yield to the scheduler.
+    /// async fn yield_now() {
+    ///     let mut yielded = false;
+    ///     futures::future::poll_fn(|cx| {
+    ///         if yielded {
+    ///             return Poll::Ready(());
+    ///         }
+    ///         yielded = true;
+    ///         cx.waker().wake_by_ref();
+    ///         Poll::Pending
+    ///     }).await
+    /// }
+    /// ```
+    pub fn mean_scheduled_duration_per_task(&self) -> Duration {
+        mean(self.total_scheduled_duration, self.completed_count)
+    }
 
-impl Default for TaskMonitor {
-    fn default() -> TaskMonitor {
-        TaskMonitor::new()
+    /// The mean duration of polls.
+    ///
+    /// ##### Definition
+    /// This metric is derived from [`total_poll_duration`][TaskMetrics::total_poll_duration] ÷
+    /// [`total_poll_count`][TaskMetrics::total_poll_count].
+    ///
+    /// ##### Interpretation
+    /// If this metric increases, it means that, on average, individual polls are tending to take
+    /// longer. However, this does not necessarily imply increased task latency: An increase in poll
+    /// durations could be offset by fewer polls.
+    ///
+    /// ##### See also
+    /// - **[`slow_poll_ratio`][TaskMetrics::slow_poll_ratio]**   
+    ///   The ratio between the number polls categorized as slow and fast.
+    /// - **[`mean_slow_poll_duration`][TaskMetrics::mean_slow_poll_duration]**   
+    ///   The mean duration of slow polls.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main(flavor = "current_thread", start_paused = true)]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut interval = monitor.intervals();
+    ///     let mut next_interval = move || interval.next().unwrap();
+    ///  
+    ///     assert_eq!(next_interval().mean_poll_duration(), Duration::ZERO);
+    ///  
+    ///     monitor.instrument(async {
+    ///         tokio::time::advance(Duration::from_secs(1)).await; // poll 1 (1s)
+    ///         tokio::time::advance(Duration::from_secs(1)).await; // poll 2 (1s)
+    ///         ()                                                  // poll 3 (0s)
+    ///     }).await;
+    ///  
+    ///     assert_eq!(next_interval().mean_poll_duration(), Duration::from_secs(2) / 3);
+    /// }
+    /// ```
+    pub fn mean_poll_duration(&self) -> Duration {
+        mean(self.total_poll_duration, self.total_poll_count)
     }
-}
 
-impl TaskMetrics {
-    /// The mean duration elapsed between the instant tasks are instrumented, and the instant they
-    /// are first polled.
+    /// The ratio between the number polls categorized as slow and fast.
     ///
     /// ##### Definition
-    /// This metric is derived from [`total_first_poll_delay`][TaskMetrics::total_first_poll_delay]
-    /// ÷ [`first_poll_count`][TaskMetrics::first_poll_count].
+    /// This metric is derived from [`total_slow_poll_count`][TaskMetrics::total_slow_poll_count] ÷
+    /// [`total_poll_count`][TaskMetrics::total_poll_count].
     ///
     /// ##### Interpretation
-    /// If this metric increases, it means that, on average, tasks spent longer waiting to be
-    /// initially polled.
+    /// If this metric increases, it means that a greater proportion of polls took excessively long
+    /// before yielding to the scheduler. This does not necessarily imply increased task latency:
+    /// An increase in the proportion of slow polls could be offset by fewer or faster polls.
+    /// However, as a rule, *should* yield to the scheduler frequently.
     ///
     /// ##### See also
-    /// - **[`mean_scheduled_duration`][TaskMetrics::mean_scheduled_duration]**   
-    ///   The mean duration that tasks spent waiting to be executed after awakening.
+    /// - **[`mean_poll_duration`][TaskMetrics::mean_poll_duration]**   
+    ///   The mean duration of polls.
+    /// - **[`mean_slow_poll_duration`][TaskMetrics::mean_slow_poll_duration]**   
+    ///   The mean duration of slow polls.
     ///
     /// ##### Examples
-    /// In the below example, no tasks are instrumented or polled within the first sampling
-    /// interval; in the second sampling interval, 500ms elapse between the instrumentation of a
-    /// task and its first poll; in the third sampling interval, a mean of 750ms elapse between the
-    /// instrumentation and first poll of two tasks:
+    /// Changes in this metric may be observed by varying the ratio of slow and slow fast within
+    /// sampling intervals; for instance:
     /// ```
+    /// use std::future::Future;
     /// use std::time::Duration;
     ///
     /// #[tokio::main]
@@ -1817,466 +7118,1337 @@ impl TaskMetrics {
     ///     let mut interval = metrics_monitor.intervals();
     ///     let mut next_interval = || interval.next().unwrap();
     ///
-    ///     // no tasks have yet been created, instrumented, or polled
-    ///     assert_eq!(next_interval().mean_first_poll_delay(), Duration::ZERO);
+    ///     // no tasks have been constructed, instrumented, or polled
+    ///     let interval = next_interval();
+    ///     assert_eq!(interval.total_fast_poll_count, 0);
+    ///     assert_eq!(interval.total_slow_poll_count, 0);
+    ///     assert!(interval.slow_poll_ratio().is_nan());
     ///
-    ///     // constructs and instruments a task, pauses for `pause_time`, awaits the task, then
-    ///     // produces the total time it took to do all of the aforementioned
-    ///     async fn instrument_pause_await(
-    ///         metrics_monitor: &tokio_metrics::TaskMonitor,
-    ///         pause_time: Duration
-    ///     ) -> Duration
-    ///     {
-    ///         let before_instrumentation = tokio::time::Instant::now();
-    ///         let task = metrics_monitor.instrument(async move {});
-    ///         tokio::time::sleep(pause_time).await;
-    ///         task.await;
-    ///         before_instrumentation.elapsed()
-    ///     }
+    ///     let fast = Duration::ZERO;
+    ///     let slow = 10 * metrics_monitor.slow_poll_threshold();
     ///
-    ///     // construct and await a task that pauses for 500ms between instrumentation and first poll
-    ///     let task_a_pause_time = Duration::from_millis(500);
-    ///     let task_a_total_time = instrument_pause_await(&metrics_monitor, task_a_pause_time).await;
+    ///     // this task completes in three fast polls
+    ///     metrics_monitor.instrument(async {
+    ///         spin_for(fast).await;   // fast poll 1
+    ///         spin_for(fast).await;   // fast poll 2
+    ///         spin_for(fast);         // fast poll 3
+    ///     }).await;
     ///
-    ///     // the `mean_first_poll_delay` will be some duration greater-than-or-equal-to the
-    ///     // pause time of 500ms, and less-than-or-equal-to the total runtime of `task_a`
-    ///     let mean_first_poll_delay = next_interval().mean_first_poll_delay();
-    ///     assert!(mean_first_poll_delay >= task_a_pause_time);
-    ///     assert!(mean_first_poll_delay <= task_a_total_time);
+    ///     // this task completes in two slow polls
+    ///     metrics_monitor.instrument(async {
+    ///         spin_for(slow).await;   // slow poll 1
+    ///         spin_for(slow);         // slow poll 2
+    ///     }).await;
     ///
-    ///     // construct and await a task that pauses for 500ms between instrumentation and first poll
-    ///     let task_b_pause_time = Duration::from_millis(500);
-    ///     let task_b_total_time = instrument_pause_await(&metrics_monitor, task_b_pause_time).await;
+    ///     let interval = next_interval();
+    ///     assert_eq!(interval.total_fast_poll_count, 3);
+    ///     assert_eq!(interval.total_slow_poll_count, 2);
+    ///     assert_eq!(interval.slow_poll_ratio(), ratio(2., 3.));
     ///
-    ///     // construct and await a task that pauses for 1000ms between instrumentation and first poll
-    ///     let task_c_pause_time = Duration::from_millis(1000);
-    ///     let task_c_total_time = instrument_pause_await(&metrics_monitor, task_c_pause_time).await;
+    ///     // this task completes in three slow polls
+    ///     metrics_monitor.instrument(async {
+    ///         spin_for(slow).await;   // slow poll 1
+    ///         spin_for(slow).await;   // slow poll 2
+    ///         spin_for(slow);         // slow poll 3
+    ///     }).await;
     ///
-    ///     // the `mean_first_poll_delay` will be some duration greater-than-or-equal-to the
-    ///     // average pause time of 500ms, and less-than-or-equal-to the combined total runtime of
-    ///     // `task_b` and `task_c`
-    ///     let mean_first_poll_delay = next_interval().mean_first_poll_delay();
-    ///     assert!(mean_first_poll_delay >= (task_b_pause_time + task_c_pause_time) / 2);
-    ///     assert!(mean_first_poll_delay <= (task_b_total_time + task_c_total_time) / 2);
+    ///     // this task completes in two fast polls
+    ///     metrics_monitor.instrument(async {
+    ///         spin_for(fast).await; // fast poll 1
+    ///         spin_for(fast);       // fast poll 2
+    ///     }).await;
+    ///
+    ///     let interval = next_interval();
+    ///     assert_eq!(interval.total_fast_poll_count, 2);
+    ///     assert_eq!(interval.total_slow_poll_count, 3);
+    ///     assert_eq!(interval.slow_poll_ratio(), ratio(3., 2.));
+    /// }
+    ///
+    /// fn ratio(a: f64, b: f64) -> f64 {
+    ///     a / (a + b)
+    /// }
+    ///
+    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
+    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
+    ///     let start = tokio::time::Instant::now();
+    ///     while start.elapsed() <= duration {}
+    ///     tokio::task::yield_now()
     /// }
     /// ```
-    pub fn mean_first_poll_delay(&self) -> Duration {
-        mean(self.total_first_poll_delay, self.first_poll_count)
+    pub fn slow_poll_ratio(&self) -> f64 {
+        self.total_slow_poll_count as f64 / self.total_poll_count as f64
     }
 
-    /// The mean duration of idles.
+    /// The ratio between the number of polls that returned [`Poll::Pending`] and the number of
+    /// polls.
     ///
     /// ##### Definition
-    /// This metric is derived from [`total_idle_duration`][TaskMetrics::total_idle_duration] ÷
-    /// [`total_idled_count`][TaskMetrics::total_idled_count].
+    /// This metric is derived from
+    /// [`total_pending_poll_count`][TaskMetrics::total_pending_poll_count] ÷
+    /// [`total_poll_count`][TaskMetrics::total_poll_count].
     ///
     /// ##### Interpretation
-    /// The idle state is the duration spanning the instant a task completes a poll, and the instant
-    /// that it is next awoken. Tasks inhabit this state when they are waiting for task-external
-    /// events to complete (e.g., an asynchronous sleep, a network request, file I/O, etc.). If this
-    /// metric increases, it means that tasks, in aggregate, spent more time waiting for
-    /// task-external events to complete.
+    /// One minus this metric is the rate at which polls complete tasks. If this metric approaches
+    /// one, tasks are polled many times for each that completes; e.g., because they are awoken
+    /// spuriously, or because they make little progress on each poll.
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     monitor.instrument(async {
+    ///         tokio::task::yield_now().await; // poll 1 (pending)
+    ///     }).await;                           // poll 2 (ready)
+    ///
+    ///     assert_eq!(monitor.cumulative().pending_poll_ratio(), 0.5);
+    /// }
+    /// ```
+    pub fn pending_poll_ratio(&self) -> f64 {
+        self.total_pending_poll_count as f64 / self.total_poll_count as f64
+    }
+
+    /// The mean number of times that tasks were polled before completing.
+    ///
+    /// ##### Definition
+    /// This metric is derived from
+    /// [`total_completed_poll_count`][TaskMetrics::total_completed_poll_count] ÷
+    /// [`completed_count`][TaskMetrics::completed_count].
+    ///
+    /// ##### Interpretation
+    /// This metric quantifies how often tasks return to the scheduler before finishing their
+    /// work. If it decreases after, e.g., batching the work of each poll or tuning where tasks
+    /// yield, tasks are completing with fewer round-trips through the scheduler.
+    ///
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///
+    ///     // this task completes in three polls
+    ///     monitor.instrument(async {
+    ///         tokio::task::yield_now().await;
+    ///         tokio::task::yield_now().await;
+    ///     }).await;
+    ///
+    ///     // this task completes in one poll
+    ///     monitor.instrument(async {}).await;
+    ///
+    ///     assert_eq!(monitor.cumulative().mean_polls_to_complete(), 2.0);
+    /// }
+    /// ```
+    pub fn mean_polls_to_complete(&self) -> f64 {
+        if self.completed_count == 0 {
+            0.0
+        } else {
+            self.total_completed_poll_count as f64 / self.completed_count as f64
+        }
+    }
+
+    /// The mean duration of fast polls.
+    ///
+    /// ##### Definition
+    /// This metric is derived from
+    /// [`total_fast_poll_duration`][TaskMetrics::total_fast_poll_duration] ÷
+    /// [`total_fast_poll_count`][TaskMetrics::total_fast_poll_count].
+    ///
+    /// ##### Examples
+    /// In the below example, no tasks are polled in the first sampling interval; three fast polls
+    /// consume a mean of
+    /// ⅜ × [`DEFAULT_SLOW_POLL_THRESHOLD`][TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD] time in the
+    /// second sampling interval; and two fast polls consume a total of
+    /// ½ × [`DEFAULT_SLOW_POLL_THRESHOLD`][TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD] time in the
+    /// third sampling interval:
+    /// ```
+    /// use std::future::Future;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut interval = metrics_monitor.intervals();
+    ///     let mut next_interval = || interval.next().unwrap();
+    ///
+    ///     // no tasks have been constructed, instrumented, or polled
+    ///     assert_eq!(next_interval().mean_fast_poll_duration(), Duration::ZERO);
+    ///
+    ///     let threshold = metrics_monitor.slow_poll_threshold();
+    ///     let fast_1 = 1 * Duration::from_micros(1);
+    ///     let fast_2 = 2 * Duration::from_micros(1);
+    ///     let fast_3 = 3 * Duration::from_micros(1);
+    ///
+    ///     // this task completes in two fast polls
+    ///     let total_time = time(metrics_monitor.instrument(async {
+    ///         spin_for(fast_1).await; // fast poll 1
+    ///         spin_for(fast_2)        // fast poll 2
+    ///     })).await;
+    ///
+    ///     // `mean_fast_poll_duration` ≈ the mean of `fast_1` and `fast_2`
+    ///     let mean_fast_poll_duration = next_interval().mean_fast_poll_duration();
+    ///     assert!(mean_fast_poll_duration >= (fast_1 + fast_2) / 2);
+    ///     assert!(mean_fast_poll_duration <= total_time / 2);
+    ///
+    ///     // this task completes in three fast polls
+    ///     let total_time = time(metrics_monitor.instrument(async {
+    ///         spin_for(fast_1).await; // fast poll 1
+    ///         spin_for(fast_2).await; // fast poll 2
+    ///         spin_for(fast_3)        // fast poll 3
+    ///     })).await;
     ///
-    /// ##### Examples
-    /// ```
-    /// #[tokio::main]
-    /// async fn main() {
-    ///     let monitor = tokio_metrics::TaskMonitor::new();
-    ///     let one_sec = std::time::Duration::from_secs(1);
+    ///     // `mean_fast_poll_duration` ≈ the mean of `fast_1`, `fast_2`, `fast_3`
+    ///     let mean_fast_poll_duration = next_interval().mean_fast_poll_duration();
+    ///     assert!(mean_fast_poll_duration >= (fast_1 + fast_2 + fast_3) / 3);
+    ///     assert!(mean_fast_poll_duration <= total_time / 3);
+    /// }
     ///
-    ///     monitor.instrument(async move {
-    ///         tokio::time::sleep(one_sec).await;
-    ///     }).await;
+    /// /// Produces the amount of time it took to await a given task.
+    /// async fn time(task: impl Future) -> Duration {
+    ///     let start = tokio::time::Instant::now();
+    ///     task.await;
+    ///     start.elapsed()
+    /// }
     ///
-    ///     assert!(monitor.cumulative().mean_idle_duration() >= one_sec);
+    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
+    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
+    ///     let start = tokio::time::Instant::now();
+    ///     while start.elapsed() <= duration {}
+    ///     tokio::task::yield_now()
     /// }
     /// ```
-    pub fn mean_idle_duration(&self) -> Duration {
-        mean(self.total_idle_duration, self.total_idled_count)
+    pub fn mean_fast_poll_duration(&self) -> Duration {
+        mean(self.total_fast_poll_duration, self.total_fast_poll_count)
     }
 
-    /// The mean duration that tasks spent waiting to be executed after awakening.
+    /// The mean duration of slow polls.
     ///
     /// ##### Definition
     /// This metric is derived from
-    /// [`total_scheduled_duration`][TaskMetrics::total_scheduled_duration] ÷
-    /// [`total_scheduled_count`][`TaskMetrics::total_scheduled_count`].
+    /// [`total_slow_poll_duration`][TaskMetrics::total_slow_poll_duration] ÷
+    /// [`total_slow_poll_count`][TaskMetrics::total_slow_poll_count].
     ///
     /// ##### Interpretation
-    /// If this metric increases, it means that, on average, tasks spent longer in the runtime's
-    /// queues before being polled.
+    /// If this metric increases, it means that a greater proportion of polls took excessively long
+    /// before yielding to the scheduler. This does not necessarily imply increased task latency:
+    /// An increase in the proportion of slow polls could be offset by fewer or faster polls.
     ///
     /// ##### See also
-    /// - **[`mean_first_poll_delay`][TaskMetrics::mean_first_poll_delay]**   
-    ///   The mean duration elapsed between the instant tasks are instrumented, and the instant they
-    ///   are first polled.
+    /// - **[`mean_poll_duration`][TaskMetrics::mean_poll_duration]**   
+    ///   The mean duration of polls.
+    /// - **[`slow_poll_ratio`][TaskMetrics::slow_poll_ratio]**   
+    ///   The ratio between the number polls categorized as slow and fast.
+    ///
+    /// ##### Interpretation
+    /// If this metric increases, it means that, on average, slow polls got even slower. This does
+    /// necessarily imply increased task latency: An increase in average slow poll duration could be
+    /// offset by fewer or faster polls. However, as a rule, *should* yield to the scheduler
+    /// frequently.
     ///
     /// ##### Examples
-    /// In the below example, a task that yields endlessly is raced against a task that blocks the
-    /// executor for 1 second; the yielding task spends approximately 1 second waiting to
-    /// be scheduled. In the next sampling interval, a task that yields endlessly is raced against a
-    /// task that blocks the executor for half a second; the yielding task spends approximately half
-    /// a second waiting to be scheduled.
+    /// In the below example, no tasks are polled in the first sampling interval; three slow polls
+    /// consume a mean of
+    /// 1.5 × [`DEFAULT_SLOW_POLL_THRESHOLD`][TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD] time in the
+    /// second sampling interval; and two slow polls consume a total of
+    /// 2 × [`DEFAULT_SLOW_POLL_THRESHOLD`][TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD] time in the
+    /// third sampling interval:
     /// ```
+    /// use std::future::Future;
     /// use std::time::Duration;
     ///
-    /// #[tokio::main(flavor = "current_thread")]
+    /// #[tokio::main]
     /// async fn main() {
     ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
     ///     let mut interval = metrics_monitor.intervals();
     ///     let mut next_interval = || interval.next().unwrap();
     ///
-    ///     // construct and instrument a task that yields endlessly
-    ///     let endless_task = metrics_monitor.instrument(async {
-    ///         loop { tokio::task::yield_now().await }
-    ///     });
-    ///
-    ///     // construct and spawn a task that blocks the executor for 1 second
-    ///     let one_sec_task = tokio::spawn(async {
-    ///         std::thread::sleep(Duration::from_secs(1))
-    ///     });
+    ///     // no tasks have been constructed, instrumented, or polled
+    ///     assert_eq!(next_interval().mean_slow_poll_duration(), Duration::ZERO);
     ///
-    ///     // ensure that at least 2s elapse between the instrumentation of
-    ///     // `endless_task` and its first poll
-    ///     std::thread::sleep(Duration::from_secs(2));
+    ///     let threshold = metrics_monitor.slow_poll_threshold();
+    ///     let slow_1 = 1 * threshold;
+    ///     let slow_2 = 2 * threshold;
+    ///     let slow_3 = 3 * threshold;
     ///
-    ///     // race `endless_task` against `one_sec_task`
-    ///     tokio::select! {
-    ///         biased;
-    ///         _ = endless_task => { unreachable!() }
-    ///         _ = one_sec_task => {}
-    ///     }
+    ///     // this task completes in two slow polls
+    ///     let total_time = time(metrics_monitor.instrument(async {
+    ///         spin_for(slow_1).await; // slow poll 1
+    ///         spin_for(slow_2)        // slow poll 2
+    ///     })).await;
     ///
-    ///     // `endless_task` will have spent approximately one second waiting:
-    ///     let interval = next_interval();
-    ///     assert!(interval.mean_scheduled_duration() >= Duration::from_secs(1));
-    ///     // ...but *not* 2s waiting:
-    ///     assert!(interval.mean_scheduled_duration() <= Duration::from_secs(2));
-    ///     // i.e., time_to_first_poll is not factored into total_scheduled_duration:
-    ///     assert!(interval.mean_first_poll_delay() >= Duration::from_secs(2));
+    ///     // `mean_slow_poll_duration` ≈ the mean of `slow_1` and `slow_2`
+    ///     let mean_slow_poll_duration = next_interval().mean_slow_poll_duration();
+    ///     assert!(mean_slow_poll_duration >= (slow_1 + slow_2) / 2);
+    ///     assert!(mean_slow_poll_duration <= total_time / 2);
     ///
-    ///     // construct and instrument and spawn a task that yields endlessly
-    ///     let endless_task = metrics_monitor.instrument(async {
-    ///         loop { tokio::task::yield_now().await }
-    ///     });
+    ///     // this task completes in three slow polls
+    ///     let total_time = time(metrics_monitor.instrument(async {
+    ///         spin_for(slow_1).await; // slow poll 1
+    ///         spin_for(slow_2).await; // slow poll 2
+    ///         spin_for(slow_3)        // slow poll 3
+    ///     })).await;
     ///
-    ///     // construct (but do not spawn) and a task that blocks the executor for 1 second
-    ///     let one_sec_task = async {
-    ///         std::thread::sleep(Duration::from_secs(1))
-    ///     };
+    ///     // `mean_slow_poll_duration` ≈ the mean of `slow_1`, `slow_2`, `slow_3`
+    ///     let mean_slow_poll_duration = next_interval().mean_slow_poll_duration();
+    ///     assert!(mean_slow_poll_duration >= (slow_1 + slow_2 + slow_3) / 3);
+    ///     assert!(mean_slow_poll_duration <= total_time / 3);
+    /// }
     ///
-    ///     // race `endless_task` against `one_sec_task`
-    ///     tokio::select! {
-    ///         biased;
-    ///         _ = endless_task => { unreachable!() }
-    ///         _ = one_sec_task => {}
-    ///     }
+    /// /// Produces the amount of time it took to await a given task.
+    /// async fn time(task: impl Future) -> Duration {
+    ///     let start = tokio::time::Instant::now();
+    ///     task.await;
+    ///     start.elapsed()
+    /// }
     ///
-    ///     // `endless_task` will NOT have spent 1 second waiting to be scheduled
-    ///     assert!(next_interval().mean_scheduled_duration() < Duration::from_secs(1));
+    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
+    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
+    ///     let start = tokio::time::Instant::now();
+    ///     while start.elapsed() <= duration {}
+    ///     tokio::task::yield_now()
     /// }
     /// ```
-    pub fn mean_scheduled_duration(&self) -> Duration {
-        mean(self.total_scheduled_duration, self.total_scheduled_count)
+    pub fn mean_slow_poll_duration(&self) -> Duration {
+        mean(self.total_slow_poll_duration, self.total_slow_poll_count)
     }
 
-    /// The mean duration of polls.
+    /// The mean CPU time consumed per poll.
     ///
     /// ##### Definition
-    /// This metric is derived from [`total_poll_duration`][TaskMetrics::total_poll_duration] ÷
+    /// This metric is derived from
+    /// [`total_poll_cpu_duration`][TaskMetrics::total_poll_cpu_duration] ÷
     /// [`total_poll_count`][TaskMetrics::total_poll_count].
     ///
     /// ##### Interpretation
-    /// If this metric increases, it means that, on average, individual polls are tending to take
-    /// longer. However, this does not necessarily imply increased task latency: An increase in poll
-    /// durations could be offset by fewer polls.
+    /// If [`mean_poll_duration`][TaskMetrics::mean_poll_duration] increases while this metric does
+    /// not, polls are taking longer in wall time without consuming more CPU; e.g., because worker
+    /// threads are preempted, or block in system calls.
     ///
     /// ##### See also
-    /// - **[`slow_poll_ratio`][TaskMetrics::slow_poll_ratio]**   
-    ///   The ratio between the number polls categorized as slow and fast.
-    /// - **[`mean_slow_poll_duration`][TaskMetrics::mean_slow_poll_duration]**   
-    ///   The mean duration of slow polls.
+    /// - **[`mean_poll_duration`][TaskMetrics::mean_poll_duration]**   
+    ///   The mean duration of polls, in wall time.
+    pub fn mean_poll_cpu_duration(&self) -> Duration {
+        mean(self.total_poll_cpu_duration, self.total_poll_count)
+    }
+
+    /// The ratio of tasks [instrumented as results][TaskMonitor::instrument_result] that failed.
     ///
-    /// ##### Examples
-    /// ```
-    /// use std::time::Duration;
+    /// ##### Definition
+    /// This metric is derived from [`failed_count`][TaskMetrics::failed_count] ÷
+    /// ([`succeeded_count`][TaskMetrics::succeeded_count] +
+    /// [`failed_count`][TaskMetrics::failed_count]).
+    pub fn failed_ratio(&self) -> f64 {
+        self.failed_count as f64 / (self.succeeded_count + self.failed_count) as f64
+    }
+
+    /// The mean time elapsed between the instrumentation of tasks and their timing out.
     ///
-    /// #[tokio::main(flavor = "current_thread", start_paused = true)]
-    /// async fn main() {
-    ///     let monitor = tokio_metrics::TaskMonitor::new();
-    ///     let mut interval = monitor.intervals();
-    ///     let mut next_interval = move || interval.next().unwrap();
-    ///  
-    ///     assert_eq!(next_interval().mean_poll_duration(), Duration::ZERO);
-    ///  
-    ///     monitor.instrument(async {
-    ///         tokio::time::advance(Duration::from_secs(1)).await; // poll 1 (1s)
-    ///         tokio::time::advance(Duration::from_secs(1)).await; // poll 2 (1s)
-    ///         ()                                                  // poll 3 (0s)
-    ///     }).await;
-    ///  
-    ///     assert_eq!(next_interval().mean_poll_duration(), Duration::from_secs(2) / 3);
-    /// }
-    /// ```
-    pub fn mean_poll_duration(&self) -> Duration {
-        mean(self.total_poll_duration, self.total_poll_count)
+    /// ##### Definition
+    /// This metric is derived from
+    /// [`total_timed_out_duration`][TaskMetrics::total_timed_out_duration] ÷
+    /// [`timed_out_count`][TaskMetrics::timed_out_count].
+    ///
+    /// ##### Interpretation
+    /// This metric is at least the shortest timeout with which tasks were instrumented. If it
+    /// greatly exceeds the timeouts, tasks were not polled promptly upon timing out; e.g.,
+    /// because the runtime was overloaded.
+    pub fn mean_timed_out_duration(&self) -> Duration {
+        mean(self.total_timed_out_duration, self.timed_out_count)
     }
 
-    /// The ratio between the number polls categorized as slow and fast.
+    /// The mean duration of synchronous regions measured by [scopes][TaskMonitor::scope].
     ///
     /// ##### Definition
-    /// This metric is derived from [`total_slow_poll_count`][TaskMetrics::total_slow_poll_count] ÷
+    /// This metric is derived from
+    /// [`total_inline_duration`][TaskMetrics::total_inline_duration] ÷
+    /// [`total_inline_count`][TaskMetrics::total_inline_count].
+    ///
+    /// ##### See also
+    /// - **[`mean_poll_duration`][TaskMetrics::mean_poll_duration]**   
+    ///   The mean duration of polls, which include the regions measured within them.
+    pub fn mean_inline_duration(&self) -> Duration {
+        mean(self.total_inline_duration, self.total_inline_count)
+    }
+
+    /// The mean number of allocations performed per poll.
+    ///
+    /// ##### Definition
+    /// This metric is derived from
+    /// [`total_allocation_count`][TaskMetrics::total_allocation_count] ÷
     /// [`total_poll_count`][TaskMetrics::total_poll_count].
     ///
     /// ##### Interpretation
-    /// If this metric increases, it means that a greater proportion of polls took excessively long
-    /// before yielding to the scheduler. This does not necessarily imply increased task latency:
-    /// An increase in the proportion of slow polls could be offset by fewer or faster polls.
-    /// However, as a rule, *should* yield to the scheduler frequently.
+    /// If this metric increases, polls are allocating more; if
+    /// [`mean_poll_duration`][TaskMetrics::mean_poll_duration] increases with it, allocation
+    /// churn is a likely contributor to the slowdown.
     ///
     /// ##### See also
-    /// - **[`mean_poll_duration`][TaskMetrics::mean_poll_duration]**   
-    ///   The mean duration of polls.
-    /// - **[`mean_slow_poll_duration`][TaskMetrics::mean_slow_poll_duration]**   
-    ///   The mean duration of slow polls.
+    /// - **[`total_allocated_bytes`][TaskMetrics::total_allocated_bytes]**   
+    ///   The total number of bytes allocated while polling tasks.
+    pub fn mean_poll_allocation_count(&self) -> f64 {
+        if self.total_poll_count == 0 {
+            0.0
+        } else {
+            self.total_allocation_count as f64 / self.total_poll_count as f64
+        }
+    }
+
+    /// The mean duration elapsed between the instrumentation of consecutive tasks; i.e., the
+    /// inverse of the rate at which tasks are instrumented.
+    ///
+    /// ##### Definition
+    /// This metric is derived from
+    /// [`total_instrumentation_gap`][TaskMetrics::total_instrumentation_gap] ÷
+    /// [`instrumented_count`][TaskMetrics::instrumented_count].
+    ///
+    /// ##### Interpretation
+    /// If this metric increases, tasks are being instrumented (e.g., spawned) less often. Unlike
+    /// [`max_instrumentation_gap`][TaskMetrics::max_instrumentation_gap], this metric does not
+    /// reflect a gap until it ends.
     ///
     /// ##### Examples
-    /// Changes in this metric may be observed by varying the ratio of slow and slow fast within
-    /// sampling intervals; for instance:
     /// ```
-    /// use std::future::Future;
     /// use std::time::Duration;
     ///
     /// #[tokio::main]
     /// async fn main() {
-    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
-    ///     let mut interval = metrics_monitor.intervals();
-    ///     let mut next_interval = || interval.next().unwrap();
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut intervals = monitor.intervals();
+    ///     let mut next_interval = || intervals.next().unwrap();
+    ///     next_interval();
+    ///
+    ///     for _ in 0..3 {
+    ///         tokio::time::sleep(Duration::from_millis(20)).await;
+    ///         monitor.instrument(async {}).await;
+    ///     }
     ///
-    ///     // no tasks have been constructed, instrumented, or polled
     ///     let interval = next_interval();
-    ///     assert_eq!(interval.total_fast_poll_count, 0);
-    ///     assert_eq!(interval.total_slow_poll_count, 0);
-    ///     assert!(interval.slow_poll_ratio().is_nan());
+    ///     assert_eq!(interval.instrumented_count, 3);
+    ///     assert!(interval.mean_instrumentation_gap() >= Duration::from_millis(20));
+    /// }
+    /// ```
+    pub fn mean_instrumentation_gap(&self) -> Duration {
+        mean(self.total_instrumentation_gap, self.instrumented_count)
+    }
+
+    /// The mean duration spent dropping tasks.
     ///
-    ///     let fast = Duration::ZERO;
-    ///     let slow = 10 * metrics_monitor.slow_poll_threshold();
+    /// ##### Definition
+    /// This metric is derived from [`total_drop_duration`][TaskMetrics::total_drop_duration] ÷
+    /// [`dropped_count`][TaskMetrics::dropped_count].
     ///
-    ///     // this task completes in three fast polls
-    ///     metrics_monitor.instrument(async {
-    ///         spin_for(fast).await;   // fast poll 1
-    ///         spin_for(fast).await;   // fast poll 2
-    ///         spin_for(fast);         // fast poll 3
-    ///     }).await;
+    /// ##### Interpretation
+    /// If this metric increases, it means that, on average, tasks took longer to drop; i.e., they
+    /// held more state, or performed more expensive cleanup in their destructors. Time spent
+    /// dropping a task is time its worker thread cannot spend polling other tasks.
     ///
-    ///     // this task completes in two slow polls
-    ///     metrics_monitor.instrument(async {
-    ///         spin_for(slow).await;   // slow poll 1
-    ///         spin_for(slow);         // slow poll 2
-    ///     }).await;
+    /// ##### Examples
+    /// ```
+    /// use std::time::Duration;
     ///
-    ///     let interval = next_interval();
-    ///     assert_eq!(interval.total_fast_poll_count, 3);
-    ///     assert_eq!(interval.total_slow_poll_count, 2);
-    ///     assert_eq!(interval.slow_poll_ratio(), ratio(2., 3.));
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut interval = monitor.intervals();
+    ///     let mut next_interval = || interval.next().unwrap();
     ///
-    ///     // this task completes in three slow polls
-    ///     metrics_monitor.instrument(async {
-    ///         spin_for(slow).await;   // slow poll 1
-    ///         spin_for(slow).await;   // slow poll 2
-    ///         spin_for(slow);         // slow poll 3
-    ///     }).await;
+    ///     assert_eq!(next_interval().mean_drop_duration(), Duration::ZERO);
     ///
-    ///     // this task completes in two fast polls
-    ///     metrics_monitor.instrument(async {
-    ///         spin_for(fast).await; // fast poll 1
-    ///         spin_for(fast);       // fast poll 2
-    ///     }).await;
+    ///     // this task is never polled; dropping it deallocates a large buffer
+    ///     let buffer = vec![0u8; 1 << 20];
+    ///     drop(monitor.instrument(async move { drop(buffer) }));
+    ///     // dropping this task does nothing
+    ///     monitor.instrument(async {}).await;
     ///
     ///     let interval = next_interval();
-    ///     assert_eq!(interval.total_fast_poll_count, 2);
-    ///     assert_eq!(interval.total_slow_poll_count, 3);
-    ///     assert_eq!(interval.slow_poll_ratio(), ratio(3., 2.));
+    ///     assert_eq!(interval.dropped_count, 2);
+    ///     assert_eq!(interval.mean_drop_duration(), interval.total_drop_duration / 2);
     /// }
+    /// ```
+    pub fn mean_drop_duration(&self) -> Duration {
+        mean(self.total_drop_duration, self.dropped_count)
+    }
+
+    /// The fraction of a given span of wall-clock time that tasks spent being polled.
     ///
-    /// fn ratio(a: f64, b: f64) -> f64 {
-    ///     a / (a + b)
-    /// }
+    /// `elapsed` should be the wall-clock duration of the sampling interval these metrics describe;
+    /// e.g., the time elapsed between two advancements of the iterator produced by
+    /// [`TaskMonitor::intervals`].
     ///
-    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
-    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
-    ///     let start = tokio::time::Instant::now();
-    ///     while start.elapsed() <= duration {}
-    ///     tokio::task::yield_now()
+    /// ##### Definition
+    /// This metric is derived from [`total_poll_duration`][TaskMetrics::total_poll_duration] ÷
+    /// `elapsed`. It is zero if `elapsed` is zero.
+    ///
+    /// ##### Interpretation
+    /// This metric captures, in a single number, how CPU-hungry a group of tasks is. A duty cycle
+    /// of `0.5` means that the tasks kept half of a worker thread busy. Since tasks may be polled
+    /// concurrently on multiple worker threads, this metric may exceed `1.0`; it cannot exceed
+    /// the number of worker threads.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use tokio::time::{Duration, Instant};
+    ///
+    /// #[tokio::main(flavor = "current_thread", start_paused = true)]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut interval = monitor.intervals();
+    ///     let interval_start = Instant::now();
+    ///
+    ///     monitor.instrument(async {
+    ///         tokio::time::advance(Duration::from_millis(250)).await; // poll 1 (250ms)
+    ///         tokio::time::sleep(Duration::from_millis(750)).await;   // poll 2 (0ms)
+    ///     }).await;                                                   // poll 3 (0ms)
+    ///
+    ///     let elapsed = interval_start.elapsed();
+    ///     assert_eq!(elapsed, Duration::from_secs(1));
+    ///     assert_eq!(interval.next().unwrap().duty_cycle(elapsed), 0.25);
     /// }
     /// ```
-    pub fn slow_poll_ratio(&self) -> f64 {
-        self.total_slow_poll_count as f64 / self.total_poll_count as f64
+    pub fn duty_cycle(&self, elapsed: Duration) -> f64 {
+        if elapsed.is_zero() {
+            0.0
+        } else {
+            self.total_poll_duration.as_secs_f64() / elapsed.as_secs_f64()
+        }
     }
 
-    /// The mean duration of fast polls.
+    /// Normalizes these metrics by `elapsed`, the wall-clock duration of the sampling interval
+    /// they describe, into [`TaskMetricsRates`].
     ///
-    /// ##### Definition
-    /// This metric is derived from
-    /// [`total_fast_poll_duration`][TaskMetrics::total_fast_poll_duration] ÷
-    /// [`total_fast_poll_count`][TaskMetrics::total_fast_poll_count].
+    /// See also [`IntervalSample::rates`], which knows the duration of its interval.
     ///
     /// ##### Examples
-    /// In the below example, no tasks are polled in the first sampling interval; three fast polls
-    /// consume a mean of
-    /// ⅜ × [`DEFAULT_SLOW_POLL_THRESHOLD`][TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD] time in the
-    /// second sampling interval; and two fast polls consume a total of
-    /// ½ × [`DEFAULT_SLOW_POLL_THRESHOLD`][TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD] time in the
-    /// third sampling interval:
     /// ```
-    /// use std::future::Future;
     /// use std::time::Duration;
+    /// use tokio_metrics::TaskMetrics;
+    ///
+    /// let mut interval = TaskMetrics::default();
+    /// interval.instrumented_count = 50;
+    /// interval.total_poll_count = 200;
+    /// interval.total_poll_duration = Duration::from_millis(500);
+    ///
+    /// let rates = interval.rates(Duration::from_secs(2));
+    /// assert_eq!(rates.instrumented_per_second, 25.0);
+    /// assert_eq!(rates.polls_per_second, 100.0);
+    /// assert_eq!(rates.polling_fraction, 0.25);
+    /// ```
+    pub fn rates(&self, elapsed: Duration) -> TaskMetricsRates {
+        if elapsed.is_zero() {
+            return TaskMetricsRates::default();
+        }
+        let seconds = elapsed.as_secs_f64();
+        let per_second = |count: u64| count as f64 / seconds;
+        let fraction = |duration: Duration| duration.as_secs_f64() / seconds;
+        TaskMetricsRates {
+            instrumented_per_second: per_second(self.instrumented_count),
+            dropped_per_second: per_second(self.dropped_count),
+            polls_per_second: per_second(self.total_poll_count),
+            slow_polls_per_second: per_second(self.total_slow_poll_count),
+            scheduled_per_second: per_second(self.total_scheduled_count),
+            polling_fraction: fraction(self.total_poll_duration),
+            scheduled_fraction: fraction(self.total_scheduled_duration),
+            idle_fraction: fraction(self.total_idle_duration),
+        }
+    }
+
+    /// Records each of these metrics into a given [`MetricsSink`], qualified by `labels`.
+    ///
+    /// Each metric is recorded under the name of its field; e.g., `total_poll_count`. Derived
+    /// metrics are not recorded.
+    ///
+    /// ##### Examples
+    /// ```
+    /// use tokio_metrics::{MetricValue, TaskMonitor};
     ///
     /// #[tokio::main]
     /// async fn main() {
-    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
-    ///     let mut interval = metrics_monitor.intervals();
-    ///     let mut next_interval = || interval.next().unwrap();
+    ///     let monitor = TaskMonitor::new();
+    ///     monitor.instrument(async {}).await;
     ///
-    ///     // no tasks have been constructed, instrumented, or polled
-    ///     assert_eq!(next_interval().mean_fast_poll_duration(), Duration::ZERO);
+    ///     let sink = |name: &'static str, value: MetricValue, labels: &[(&str, &str)]| {
+    ///         if name == "total_poll_count" {
+    ///             assert_eq!(value, MetricValue::Count(1));
+    ///             assert_eq!(labels, &[("service", "api")]);
+    ///         }
+    ///     };
     ///
-    ///     let threshold = metrics_monitor.slow_poll_threshold();
-    ///     let fast_1 = 1 * Duration::from_micros(1);
-    ///     let fast_2 = 2 * Duration::from_micros(1);
-    ///     let fast_3 = 3 * Duration::from_micros(1);
+    ///     monitor.cumulative().record_to(&sink, &[("service", "api")]);
+    /// }
+    /// ```
+    pub fn record_to(&self, sink: &dyn MetricsSink, labels: &[(&str, &str)]) {
+        for (name, value) in self.fields() {
+            sink.record(name, value, labels);
+        }
+    }
+
+    /// Produces the name and value of each of these metrics, followed by those of the derived
+    /// metrics that can be computed from them alone.
     ///
-    ///     // this task completes in two fast polls
-    ///     let total_time = time(metrics_monitor.instrument(async {
-    ///         spin_for(fast_1).await; // fast poll 1
-    ///         spin_for(fast_2)        // fast poll 2
-    ///     })).await;
+    /// The fields are produced in the order of [`TaskMetrics::descriptors`], and under the same
+    /// names. Each derived metric is produced under the name of its method; e.g.,
+    /// `mean_poll_duration`. [`duty_cycle`][TaskMetrics::duty_cycle], which depends on the length
+    /// of the sampling interval, is not produced.
     ///
-    ///     // `mean_fast_poll_duration` ≈ the mean of `fast_1` and `fast_2`
-    ///     let mean_fast_poll_duration = next_interval().mean_fast_poll_duration();
-    ///     assert!(mean_fast_poll_duration >= (fast_1 + fast_2) / 2);
-    ///     assert!(mean_fast_poll_duration <= total_time / 2);
+    /// ##### Examples
+    /// ```
+    /// use tokio_metrics::{MetricValue, TaskMonitor};
     ///
-    ///     // this task completes in three fast polls
-    ///     let total_time = time(metrics_monitor.instrument(async {
-    ///         spin_for(fast_1).await; // fast poll 1
-    ///         spin_for(fast_2).await; // fast poll 2
-    ///         spin_for(fast_3)        // fast poll 3
-    ///     })).await;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = TaskMonitor::new();
+    ///     monitor.instrument(async {}).await;
     ///
-    ///     // `mean_fast_poll_duration` ≈ the mean of `fast_1`, `fast_2`, `fast_3`
-    ///     let mean_fast_poll_duration = next_interval().mean_fast_poll_duration();
-    ///     assert!(mean_fast_poll_duration >= (fast_1 + fast_2 + fast_3) / 3);
-    ///     assert!(mean_fast_poll_duration <= total_time / 3);
-    /// }
+    ///     for (name, value) in monitor.cumulative().iter() {
+    ///         match value {
+    ///             MetricValue::Count(count) => println!("{}: {}", name, count),
+    ///             MetricValue::Duration(duration) => println!("{}: {:?}", name, duration),
+    ///             MetricValue::Ratio(ratio) => println!("{}: {:.3}", name, ratio),
+    ///             _ => {}
+    ///         }
+    ///     }
     ///
-    /// /// Produces the amount of time it took to await a given task.
-    /// async fn time(task: impl Future) -> Duration {
-    ///     let start = tokio::time::Instant::now();
-    ///     task.await;
-    ///     start.elapsed()
+    ///     let metrics: Vec<_> = monitor.cumulative().iter().collect();
+    ///     assert!(metrics.contains(&("total_poll_count", MetricValue::Count(1))));
+    ///     assert!(metrics.iter().any(|(name, _)| *name == "slow_poll_ratio"));
     /// }
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, MetricValue)> {
+        let derived = [
+            (
+                "mean_instrumentation_gap",
+                MetricValue::Duration(self.mean_instrumentation_gap()),
+            ),
+            (
+                "mean_drop_duration",
+                MetricValue::Duration(self.mean_drop_duration()),
+            ),
+            (
+                "mean_first_poll_delay",
+                MetricValue::Duration(self.mean_first_poll_delay()),
+            ),
+            (
+                "mean_spawn_delay",
+                MetricValue::Duration(self.mean_spawn_delay()),
+            ),
+            (
+                "mean_spawn_queue_delay",
+                MetricValue::Duration(self.mean_spawn_queue_delay()),
+            ),
+            (
+                "mean_idle_duration",
+                MetricValue::Duration(self.mean_idle_duration()),
+            ),
+            (
+                "mean_scheduled_duration",
+                MetricValue::Duration(self.mean_scheduled_duration()),
+            ),
+            (
+                "mean_scheduled_duration_per_task",
+                MetricValue::Duration(self.mean_scheduled_duration_per_task()),
+            ),
+            (
+                "mean_poll_duration",
+                MetricValue::Duration(self.mean_poll_duration()),
+            ),
+            (
+                "slow_poll_ratio",
+                MetricValue::Ratio(self.slow_poll_ratio()),
+            ),
+            (
+                "pending_poll_ratio",
+                MetricValue::Ratio(self.pending_poll_ratio()),
+            ),
+            (
+                "mean_polls_to_complete",
+                MetricValue::Ratio(self.mean_polls_to_complete()),
+            ),
+            (
+                "mean_fast_poll_duration",
+                MetricValue::Duration(self.mean_fast_poll_duration()),
+            ),
+            (
+                "mean_slow_poll_duration",
+                MetricValue::Duration(self.mean_slow_poll_duration()),
+            ),
+            (
+                "mean_poll_cpu_duration",
+                MetricValue::Duration(self.mean_poll_cpu_duration()),
+            ),
+            (
+                "mean_poll_allocation_count",
+                MetricValue::Ratio(self.mean_poll_allocation_count()),
+            ),
+            (
+                "mean_inline_duration",
+                MetricValue::Duration(self.mean_inline_duration()),
+            ),
+            (
+                "mean_timed_out_duration",
+                MetricValue::Duration(self.mean_timed_out_duration()),
+            ),
+            ("failed_ratio", MetricValue::Ratio(self.failed_ratio())),
+        ];
+        self.fields().into_iter().chain(derived)
+    }
+
+    /// Produces a [`MetricDescriptor`] for each metric recorded by [`TaskMetrics::record_to`],
+    /// in the order in which they are recorded.
     ///
-    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
-    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
-    ///     let start = tokio::time::Instant::now();
-    ///     while start.elapsed() <= duration {}
-    ///     tokio::task::yield_now()
-    /// }
+    /// Generic exporters can enumerate the descriptors to register every metric up front (e.g.,
+    /// to emit `HELP` and `TYPE` lines), rather than hard-coding a list of fields that falls out
+    /// of date as fields are added.
+    ///
+    /// ##### Examples
     /// ```
-    pub fn mean_fast_poll_duration(&self) -> Duration {
-        mean(self.total_fast_poll_duration, self.total_fast_poll_count)
+    /// use tokio_metrics::{MetricKind, TaskMetrics};
+    ///
+    /// let descriptors = TaskMetrics::descriptors();
+    /// let poll_count = descriptors
+    ///     .iter()
+    ///     .find(|descriptor| descriptor.name == "total_poll_count")
+    ///     .unwrap();
+    /// assert_eq!(poll_count.kind, MetricKind::Count);
+    /// assert_eq!(poll_count.help, "The total number of times that tasks were polled.");
+    /// ```
+    pub fn descriptors() -> &'static [MetricDescriptor] {
+        DESCRIPTORS
     }
 
-    /// The mean duration of slow polls.
+    /// Produces the metrics of the events that occurred between `earlier` and `self`, where both
+    /// were produced by the same monitor; e.g., by [`TaskMonitor::cumulative`].
     ///
-    /// ##### Definition
-    /// This metric is derived from
-    /// [`total_slow_poll_duration`][TaskMetrics::total_slow_poll_duration] ÷
-    /// [`total_slow_poll_count`][TaskMetrics::total_slow_poll_count].
+    /// This is equivalent to `self - earlier`, and is how [`TaskMonitor::intervals`] produces
+    /// each sampling interval. Pollers with their own scheduling can use it to compute intervals
+    /// without holding an iterator; see also [`Snapshot`].
     ///
-    /// ##### Interpretation
-    /// If this metric increases, it means that a greater proportion of polls took excessively long
-    /// before yielding to the scheduler. This does not necessarily imply increased task latency:
-    /// An increase in the proportion of slow polls could be offset by fewer or faster polls.
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
     ///
-    /// ##### See also
-    /// - **[`mean_poll_duration`][TaskMetrics::mean_poll_duration]**   
-    ///   The mean duration of polls.
-    /// - **[`slow_poll_ratio`][TaskMetrics::slow_poll_ratio]**   
-    ///   The ratio between the number polls categorized as slow and fast.
+    ///     monitor.instrument(async {}).await;
+    ///     let earlier = monitor.cumulative();
     ///
-    /// ##### Interpretation
-    /// If this metric increases, it means that, on average, slow polls got even slower. This does
-    /// necessarily imply increased task latency: An increase in average slow poll duration could be
-    /// offset by fewer or faster polls. However, as a rule, *should* yield to the scheduler
-    /// frequently.
+    ///     monitor.instrument(async {}).await;
+    ///     monitor.instrument(async {}).await;
+    ///     let later = monitor.cumulative();
+    ///
+    ///     let delta = later.delta_since(&earlier);
+    ///     assert_eq!(delta.instrumented_count, 2);
+    ///     assert_eq!(delta.total_poll_count, 2);
+    /// }
+    /// ```
+    pub fn delta_since(&self, earlier: &TaskMetrics) -> TaskMetrics {
+        *self - *earlier
+    }
+
+    /// Writes these metrics to `writer`, so that they may be [read][TaskMetrics::read_from] back;
+    /// e.g., to persist the [cumulative][TaskMonitor::cumulative] metrics of a monitor across
+    /// restarts, with [`TaskMonitor::restore`].
+    ///
+    /// The metrics are written as lines of text, each holding the name of a field and its value,
+    /// separated by a space; durations are written in nanoseconds.
+    pub fn write_to<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        for (name, value) in self.fields() {
+            let value = match value {
+                MetricValue::Count(count) | MetricValue::Gauge(count) => count,
+                MetricValue::Duration(duration) | MetricValue::MaxDuration(duration) => {
+                    duration.as_nanos().try_into().unwrap_or(u64::MAX)
+                }
+                // ratios are derived, and so are never fields
+                MetricValue::Ratio(_) => continue,
+            };
+            writeln!(writer, "{} {}", name, value)?;
+        }
+        writer.flush()
+    }
+
+    /// Reads metrics written by [`TaskMetrics::write_to`] from `reader`.
+    ///
+    /// Fields that are not written (e.g., those added in a later version of this crate) are
+    /// zero, and fields that are not known (e.g., those removed in a later version) are ignored.
+    /// Produces an error of the kind [`InvalidData`][std::io::ErrorKind::InvalidData] if a line
+    /// is malformed.
+    pub fn read_from<R: std::io::Read>(reader: R) -> std::io::Result<TaskMetrics> {
+        use std::io::BufRead;
+
+        let mut metrics = TaskMetrics::default();
+        for line in std::io::BufReader::new(reader).lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let value = line
+                .split_once(' ')
+                .and_then(|(name, value)| Some((name, value.trim().parse().ok()?)));
+            match value {
+                Some((name, value)) => metrics.set_field(name, value),
+                None => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("malformed metric `{}`", line),
+                    ))
+                }
+            }
+        }
+        Ok(metrics)
+    }
+}
+
+/// Produces the value of a field of the given [`MetricKind`] from its value as written by
+/// [`TaskMetrics::write_to`].
+macro_rules! read_field {
+    (Count, $value:expr) => {
+        $value
+    };
+    (Gauge, $value:expr) => {
+        $value
+    };
+    (Duration, $value:expr) => {
+        Duration::from_nanos($value)
+    };
+    (MaxDuration, $value:expr) => {
+        Duration::from_nanos($value)
+    };
+}
+
+/// Combines the `live` value of a field of the given [`MetricKind`] with its `restored` value.
+macro_rules! restore_field {
+    (Count, $live:expr, $restored:expr) => {
+        $live.wrapping_add($restored)
+    };
+    (Gauge, $live:expr, $restored:expr) => {
+        $live
+    };
+    (Duration, $live:expr, $restored:expr) => {
+        $live.saturating_add($restored)
+    };
+    (MaxDuration, $live:expr, $restored:expr) => {
+        $live.max($restored)
+    };
+}
+
+/// Defines the metrics recorded by [`TaskMetrics::record_to`], in order: the name of each field,
+/// the [`MetricKind`] with which it is recorded, and its help string.
+macro_rules! task_metrics_schema {
+    ($($name:ident: $kind:ident, $help:literal;)*) => {
+        /// The descriptors produced by [`TaskMetrics::descriptors`].
+        const DESCRIPTORS: &[MetricDescriptor] = &[$(
+            MetricDescriptor {
+                name: stringify!($name),
+                kind: MetricKind::$kind,
+                help: $help,
+            },
+        )*];
+
+        impl TaskMetrics {
+            /// Produces the name and value of each field, in the order of [`DESCRIPTORS`].
+            fn fields(&self) -> [(&'static str, MetricValue); DESCRIPTORS.len()] {
+                [$((stringify!($name), MetricValue::$kind(self.$name)),)*]
+            }
+
+            /// Sets the field named `name` from its value as written by
+            /// [`TaskMetrics::write_to`], if there is such a field.
+            fn set_field(&mut self, name: &str, value: u64) {
+                match name {
+                    $(stringify!($name) => self.$name = read_field!($kind, value),)*
+                    _ => {}
+                }
+            }
+
+            /// Combines these metrics of the current run of a monitor with those `restored`
+            /// from a previous run; see [`TaskMonitor::restore`].
+            fn with_restored(self, restored: &TaskMetrics) -> TaskMetrics {
+                TaskMetrics {
+                    $($name: restore_field!($kind, self.$name, restored.$name),)*
+                }
+            }
+        }
+    };
+}
+
+task_metrics_schema! {
+    instrumented_count: Count, "The number of tasks instrumented.";
+    total_instrumentation_gap: Duration,
+        "The total duration elapsed between the instrumentation of consecutive tasks.";
+    max_instrumentation_gap: MaxDuration,
+        "The greatest duration elapsed between the instrumentation of consecutive tasks.";
+    dropped_count: Count, "The number of tasks dropped.";
+    total_drop_duration: Duration, "The total duration spent dropping tasks.";
+    first_poll_count: Count, "The number of tasks polled for the first time.";
+    total_first_poll_delay: Duration,
+        "The total duration elapsed between the instant tasks are instrumented, and the instant \
+        they are first polled.";
+    max_first_poll_delay: MaxDuration,
+        "The greatest duration elapsed between the instant a task was instrumented, and the \
+        instant it was first polled.";
+    spawned_count: Count,
+        "The number of tasks spawned with TaskMonitor::spawn or Instrumented::spawn.";
+    spawned_first_poll_count: Count,
+        "The number of tasks spawned with TaskMonitor::spawn or Instrumented::spawn that were \
+        polled for the first time.";
+    total_spawn_delay: Duration,
+        "The total duration elapsed between the instant spawned tasks are instrumented, and the \
+        instant they are spawned.";
+    total_spawn_queue_delay: Duration,
+        "The total duration elapsed between the instant spawned tasks are spawned, and the \
+        instant they are first polled.";
+    total_idled_count: Count, "The total number of times that tasks idled, waiting to be awoken.";
+    total_idle_duration: Duration, "The total duration that tasks idled.";
+    total_scheduled_count: Count,
+        "The total number of times that tasks were awoken (and then, presumably, scheduled for \
+        execution).";
+    total_scheduled_duration: Duration,
+        "The total duration that tasks spent waiting to be polled after awakening.";
+    max_scheduled_duration: MaxDuration,
+        "The greatest duration that a task spent waiting to be polled after awakening.";
+    total_poll_count: Count, "The total number of times that tasks were polled.";
+    total_poll_duration: Duration, "The total duration elapsed during polls.";
+    total_ready_poll_count: Count, "The total number of times that polling tasks returned Ready.";
+    total_pending_poll_count: Count,
+        "The total number of times that polling tasks returned Pending.";
+    concurrent_poll_count: Gauge, "The number of tasks being polled.";
+    max_concurrent_poll_count: Gauge,
+        "The greatest number of tasks that were being polled at once.";
+    polling_thread_count: Gauge, "The number of distinct threads that polled tasks.";
+    total_budget_yield_count: Count,
+        "The total number of polls that were likely ended by tokio's cooperative scheduling \
+        budget.";
+    total_fast_poll_count: Count, "The total number of times that polling tasks completed swiftly.";
+    total_fast_poll_duration: Duration, "The total duration of fast polls.";
+    total_slow_poll_count: Count, "The total number of times that polling tasks completed slowly.";
+    total_slow_poll_duration: Duration, "The total duration of slow polls.";
+    total_blocking_poll_count: Count,
+        "The total number of times that polling tasks completed so slowly that they were \
+        'probably blocking'.";
+    total_poll_cpu_duration: Duration,
+        "The total CPU time consumed by the polling thread while polling tasks.";
+    total_allocation_count: Count,
+        "The total number of allocations (including reallocations) performed while polling \
+        tasks.";
+    total_allocated_bytes: Count,
+        "The total number of bytes allocated (including by reallocations) while polling tasks.";
+    total_local_wake_count: Count,
+        "The total number of times that tasks were scheduled by a wake originating on the same \
+        thread that last polled them.";
+    total_remote_wake_count: Count,
+        "The total number of times that tasks were scheduled by a wake originating on a thread \
+        other than the one that last polled them.";
+    total_inline_count: Count, "The total number of synchronous regions measured by scopes.";
+    total_inline_duration: Duration,
+        "The total duration of synchronous regions measured by scopes.";
+    timed_out_count: Count, "The number of tasks instrumented with a timeout that timed out.";
+    total_timed_out_duration: Duration,
+        "The total time elapsed between the instrumentation of tasks and their timing out.";
+    completed_count: Count,
+        "The number of tasks that completed; i.e., that were polled to completion.";
+    total_completed_poll_count: Count,
+        "The total number of times that tasks that completed were polled, over their lifetimes.";
+    dropped_before_completion_count: Count,
+        "The number of tasks spawned by the monitor that were dropped before completing, without \
+        panicking; i.e., that were aborted, or that were pending when the runtime shut down.";
+    cancelled_count: Count,
+        "The number of tasks, not spawned by the monitor, that were dropped before completing.";
+    panicked_count: Count, "The number of tasks that panicked while being polled.";
+    join_error_count: Count,
+        "The number of tasks spawned by the monitor that were dropped before completing, for any \
+        reason; i.e., that panicked, were aborted, or were pending when the runtime shut down.";
+    succeeded_count: Count, "The number of tasks instrumented as results that succeeded.";
+    failed_count: Count, "The number of tasks instrumented as results that failed.";
+}
+
+impl std::ops::Sub for TaskMetrics {
+    type Output = TaskMetrics;
+
+    fn sub(self, rhs: TaskMetrics) -> TaskMetrics {
+        TaskMetrics {
+            instrumented_count: self.instrumented_count.wrapping_sub(rhs.instrumented_count),
+            total_instrumentation_gap: sub(
+                self.total_instrumentation_gap,
+                rhs.total_instrumentation_gap,
+            ),
+            max_instrumentation_gap: self.max_instrumentation_gap,
+            dropped_count: self.dropped_count.wrapping_sub(rhs.dropped_count),
+            total_drop_duration: sub(self.total_drop_duration, rhs.total_drop_duration),
+            total_poll_count: self.total_poll_count.wrapping_sub(rhs.total_poll_count),
+            total_poll_duration: sub(self.total_poll_duration, rhs.total_poll_duration),
+            total_ready_poll_count: self
+                .total_ready_poll_count
+                .wrapping_sub(rhs.total_ready_poll_count),
+            total_pending_poll_count: self
+                .total_pending_poll_count
+                .wrapping_sub(rhs.total_pending_poll_count),
+            concurrent_poll_count: self.concurrent_poll_count,
+            max_concurrent_poll_count: self.max_concurrent_poll_count,
+            polling_thread_count: self.polling_thread_count,
+            first_poll_count: self.first_poll_count.wrapping_sub(rhs.first_poll_count),
+            total_idled_count: self.total_idled_count.wrapping_sub(rhs.total_idled_count),
+            total_scheduled_count: self
+                .total_scheduled_count
+                .wrapping_sub(rhs.total_scheduled_count),
+            total_fast_poll_count: self
+                .total_fast_poll_count
+                .wrapping_sub(rhs.total_fast_poll_count),
+            total_slow_poll_count: self
+                .total_slow_poll_count
+                .wrapping_sub(rhs.total_slow_poll_count),
+            total_budget_yield_count: self
+                .total_budget_yield_count
+                .wrapping_sub(rhs.total_budget_yield_count),
+            total_blocking_poll_count: self
+                .total_blocking_poll_count
+                .wrapping_sub(rhs.total_blocking_poll_count),
+            total_first_poll_delay: sub(self.total_first_poll_delay, rhs.total_first_poll_delay),
+            max_first_poll_delay: self.max_first_poll_delay,
+            spawned_count: self.spawned_count.wrapping_sub(rhs.spawned_count),
+            spawned_first_poll_count: self
+                .spawned_first_poll_count
+                .wrapping_sub(rhs.spawned_first_poll_count),
+            total_spawn_delay: sub(self.total_spawn_delay, rhs.total_spawn_delay),
+            total_spawn_queue_delay: sub(self.total_spawn_queue_delay, rhs.total_spawn_queue_delay),
+            total_idle_duration: sub(self.total_idle_duration, rhs.total_idle_duration),
+            total_scheduled_duration: sub(
+                self.total_scheduled_duration,
+                rhs.total_scheduled_duration,
+            ),
+            max_scheduled_duration: self.max_scheduled_duration,
+            total_fast_poll_duration: sub(
+                self.total_fast_poll_duration,
+                rhs.total_fast_poll_duration,
+            ),
+            total_slow_poll_duration: sub(
+                self.total_slow_poll_duration,
+                rhs.total_slow_poll_duration,
+            ),
+            total_poll_cpu_duration: sub(self.total_poll_cpu_duration, rhs.total_poll_cpu_duration),
+            total_allocation_count: self
+                .total_allocation_count
+                .wrapping_sub(rhs.total_allocation_count),
+            total_allocated_bytes: self
+                .total_allocated_bytes
+                .wrapping_sub(rhs.total_allocated_bytes),
+            total_local_wake_count: self
+                .total_local_wake_count
+                .wrapping_sub(rhs.total_local_wake_count),
+            total_remote_wake_count: self
+                .total_remote_wake_count
+                .wrapping_sub(rhs.total_remote_wake_count),
+            total_inline_count: self.total_inline_count.wrapping_sub(rhs.total_inline_count),
+            total_inline_duration: sub(self.total_inline_duration, rhs.total_inline_duration),
+            timed_out_count: self.timed_out_count.wrapping_sub(rhs.timed_out_count),
+            total_timed_out_duration: sub(
+                self.total_timed_out_duration,
+                rhs.total_timed_out_duration,
+            ),
+            completed_count: self.completed_count.wrapping_sub(rhs.completed_count),
+            total_completed_poll_count: self
+                .total_completed_poll_count
+                .wrapping_sub(rhs.total_completed_poll_count),
+            dropped_before_completion_count: self
+                .dropped_before_completion_count
+                .wrapping_sub(rhs.dropped_before_completion_count),
+            cancelled_count: self.cancelled_count.wrapping_sub(rhs.cancelled_count),
+            panicked_count: self.panicked_count.wrapping_sub(rhs.panicked_count),
+            join_error_count: self.join_error_count.wrapping_sub(rhs.join_error_count),
+            succeeded_count: self.succeeded_count.wrapping_sub(rhs.succeeded_count),
+            failed_count: self.failed_count.wrapping_sub(rhs.failed_count),
+        }
+    }
+}
+
+impl Snapshot {
+    /// Produces the metrics of the events that occurred between the `earlier` snapshot and this
+    /// one. See [`TaskMetrics::delta_since`].
+    pub fn delta_since(&self, earlier: &Snapshot) -> TaskMetrics {
+        self.metrics.delta_since(&earlier.metrics)
+    }
+
+    /// Produces the duration elapsed between the `earlier` snapshot and this one; i.e., the
+    /// duration of the interval described by [`Snapshot::delta_since`].
+    pub fn elapsed_since(&self, earlier: &Snapshot) -> Duration {
+        self.taken_at.saturating_duration_since(earlier.taken_at)
+    }
+}
+
+/// Clones the underlying task, and instruments the clone as a distinct task of the same monitor;
+/// e.g., so that the attempts of a retrying or hedging layer, which clones request futures, are
+/// each instrumented.
+///
+/// The clone has its own [`TaskId`] and instrumentation state: its time-to-first-poll is measured
+/// from the instant it was cloned, and it is woken independently of the original. It bears the
+/// [name][Instrumented::name] and location of the original, but not the instant at which the
+/// original was [spawned][Instrumented::spawn].
+///
+/// ##### Examples
+/// ```
+/// #[tokio::main]
+/// async fn main() {
+///     let monitor = tokio_metrics::TaskMonitor::new();
+///     let request = monitor.instrument_named("request", std::future::ready(42));
+///
+///     // e.g., a hedged request
+///     let hedge = request.clone();
+///     assert_ne!(hedge.id(), request.id());
+///     assert_eq!(hedge.name(), Some("request"));
+///
+///     assert_eq!(request.await, 42);
+///     assert_eq!(hedge.await, 42);
+///     assert_eq!(monitor.cumulative().instrumented_count, 2);
+///     assert_eq!(monitor.cumulative().first_poll_count, 2);
+/// }
+/// ```
+impl TaskDump {
+    /// Produces the tasks of this dump, in order.
+    pub fn tasks(&self) -> &[LiveTask] {
+        &self.tasks
+    }
+}
+
+impl fmt::Display for TaskDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} live tasks", self.tasks.len())?;
+        for task in &self.tasks {
+            write!(f, "#{}", task.id.as_u64())?;
+            if let Some(name) = task.name {
+                write!(f, " {:?}", name)?;
+            }
+            let state = match task.state {
+                LiveTaskState::FirstPoll => "awaiting first poll",
+                LiveTaskState::Idle => "idle",
+                LiveTaskState::Scheduled => "scheduled",
+            };
+            write!(
+                f,
+                ": {}, age {:?}, {} polls ({:?})",
+                state, task.age, task.poll_count, task.total_poll_duration
+            )?;
+            if let Some(location) = task.location {
+                write!(f, ", at {}", location)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Clone> Clone for Instrumented<T> {
+    fn clone(&self) -> Self {
+        let monitor = TaskMonitor {
+            metrics: self.state.metrics.clone(),
+        };
+        monitor.instrument_at((*self.task).clone(), self.state.name, self.state.location)
+    }
+}
+
+impl<T> Instrumented<T> {
+    /// Produces the [`TaskId`] of this task.
+    ///
+    /// ##### Examples
+    /// ```
+    /// let monitor = tokio_metrics::TaskMonitor::new();
+    /// let task_a = monitor.instrument(async {});
+    /// let task_b = monitor.instrument(async {});
+    /// assert_ne!(task_a.id(), task_b.id());
+    /// ```
+    pub fn id(&self) -> TaskId {
+        TaskId(self.state.id)
+    }
+
+    /// Produces the name of this task, if it was [named][TaskMonitor::instrument_named].
+    pub fn name(&self) -> Option<&'static str> {
+        self.state.name
+    }
+
+    /// Gets a reference to the underlying task.
+    pub fn get_ref(&self) -> &T {
+        &self.task
+    }
+
+    /// Gets a mutable reference to the underlying task.
     ///
     /// ##### Examples
-    /// In the below example, no tasks are polled in the first sampling interval; three slow polls
-    /// consume a mean of
-    /// 1.5 × [`DEFAULT_SLOW_POLL_THRESHOLD`][TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD] time in the
-    /// second sampling interval; and two slow polls consume a total of
-    /// 2 × [`DEFAULT_SLOW_POLL_THRESHOLD`][TaskMonitor::DEFAULT_SLOW_POLL_THRESHOLD] time in the
-    /// third sampling interval:
     /// ```
-    /// use std::future::Future;
-    /// use std::time::Duration;
+    /// use futures::stream::{self, StreamExt};
     ///
     /// #[tokio::main]
     /// async fn main() {
-    ///     let metrics_monitor = tokio_metrics::TaskMonitor::new();
-    ///     let mut interval = metrics_monitor.intervals();
-    ///     let mut next_interval = || interval.next().unwrap();
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
+    ///     let mut task = monitor.instrument(stream::iter(1..=3).collect::<Vec<u32>>());
     ///
-    ///     // no tasks have been constructed, instrumented, or polled
-    ///     assert_eq!(next_interval().mean_slow_poll_duration(), Duration::ZERO);
+    ///     // the wrapped value remains accessible after instrumentation
+    ///     let _: &mut stream::Collect<_, _> = task.get_mut();
     ///
-    ///     let threshold = metrics_monitor.slow_poll_threshold();
-    ///     let slow_1 = 1 * threshold;
-    ///     let slow_2 = 2 * threshold;
-    ///     let slow_3 = 3 * threshold;
+    ///     assert_eq!(task.await, [1, 2, 3]);
+    /// }
+    /// ```
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.task
+    }
+
+    /// Gets a pinned mutable reference to the underlying task.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        // SAFETY: `ManuallyDrop` is a transparent wrapper, so the pinning guarantees of the
+        // wrapper carry over to the task.
+        unsafe { self.project().task.map_unchecked_mut(|task| &mut **task) }
+    }
+
+    /// Consumes this wrapper, returning the underlying task.
     ///
-    ///     // this task completes in two slow polls
-    ///     let total_time = time(metrics_monitor.instrument(async {
-    ///         spin_for(slow_1).await; // slow poll 1
-    ///         spin_for(slow_2)        // slow poll 2
-    ///     })).await;
+    /// The task is no longer instrumented; the monitor counts it as
+    /// [dropped][TaskMetrics::dropped_count], but does not time its destructor.
     ///
-    ///     // `mean_slow_poll_duration` ≈ the mean of `slow_1` and `slow_2`
-    ///     let mean_slow_poll_duration = next_interval().mean_slow_poll_duration();
-    ///     assert!(mean_slow_poll_duration >= (slow_1 + slow_2) / 2);
-    ///     assert!(mean_slow_poll_duration <= total_time / 2);
+    /// ##### Examples
+    /// ```
+    /// let monitor = tokio_metrics::TaskMonitor::new();
+    /// let task = monitor.instrument(std::future::ready(42));
     ///
-    ///     // this task completes in three slow polls
-    ///     let total_time = time(metrics_monitor.instrument(async {
-    ///         spin_for(slow_1).await; // slow poll 1
-    ///         spin_for(slow_2).await; // slow poll 2
-    ///         spin_for(slow_3)        // slow poll 3
-    ///     })).await;
+    /// let _task: std::future::Ready<u32> = task.into_inner();
+    /// assert_eq!(monitor.cumulative().dropped_count, 1);
+    /// ```
+    pub fn into_inner(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+        this.state.untrack();
+        this.state.record_drop(0);
+        // SAFETY: `this` is never dropped or accessed again, so `task` and `state` are each moved
+        // out of it exactly once.
+        unsafe {
+            State::release(ManuallyDrop::take(&mut this.state));
+            ManuallyDrop::take(&mut this.task)
+        }
+    }
+}
+
+#[cfg(feature = "rt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rt")))]
+impl<T> Instrumented<T>
+where
+    T: Future + Send + 'static,
+    T::Output: Send + 'static,
+{
+    /// Spawns this task onto the current tokio runtime, noting the instant at which it was
+    /// spawned.
+    ///
+    /// Spawning an instrumented task with this method, rather than with [`tokio::spawn`], allows
+    /// its [first-poll delay][TaskMetrics::total_first_poll_delay] to be split into
+    /// [time spent before spawning][TaskMetrics::total_spawn_delay], and
+    /// [time spent in the runtime's queues][TaskMetrics::total_spawn_queue_delay].
+    ///
+    /// ##### Panics
+    /// Panics if called outside of a tokio runtime, like [`tokio::spawn`].
     ///
-    ///     // `mean_slow_poll_duration` ≈ the mean of `slow_1`, `slow_2`, `slow_3`
-    ///     let mean_slow_poll_duration = next_interval().mean_slow_poll_duration();
-    ///     assert!(mean_slow_poll_duration >= (slow_1 + slow_2 + slow_3) / 3);
-    ///     assert!(mean_slow_poll_duration <= total_time / 3);
-    /// }
+    /// ##### Examples
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let monitor = tokio_metrics::TaskMonitor::new();
     ///
-    /// /// Produces the amount of time it took to await a given task.
-    /// async fn time(task: impl Future) -> Duration {
-    ///     let start = tokio::time::Instant::now();
-    ///     task.await;
-    ///     start.elapsed()
-    /// }
+    ///     let task = monitor.instrument(async { 42 });
+    ///     assert_eq!(task.spawn().await.unwrap(), 42);
     ///
-    /// /// Block the current thread for a given `duration`, then (optionally) yield to the scheduler.
-    /// fn spin_for(duration: Duration) -> impl Future<Output=()> {
-    ///     let start = tokio::time::Instant::now();
-    ///     while start.elapsed() <= duration {}
-    ///     tokio::task::yield_now()
+    ///     assert_eq!(monitor.cumulative().spawned_count, 1);
     /// }
     /// ```
-    pub fn mean_slow_poll_duration(&self) -> Duration {
-        mean(self.total_slow_poll_duration, self.total_slow_poll_count)
+    pub fn spawn(mut self) -> tokio::task::JoinHandle<T::Output> {
+        self.state.metrics.spawned_count.fetch_add(1, SeqCst);
+        self.spawned_at = Some(Instant::now());
+        tokio::spawn(self)
+    }
+}
+
+impl<T: Future> Instrumented<T> {
+    /// Polls the task without measuring it, while its monitor is disabled.
+    fn poll_disabled(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T::Output> {
+        let this = self.project();
+        // a wake noted before the monitor was disabled would inflate the next measured schedule
+        this.state.woke_at.store(0, Relaxed);
+        *this.did_poll_once = true;
+
+        // SAFETY: `ManuallyDrop` is a transparent wrapper, so the pinning guarantees of the
+        // wrapper carry over to the task.
+        let task = unsafe { this.task.map_unchecked_mut(|task| &mut **task) };
+        let ret = Future::poll(task, cx);
+        // the task is not counted as cancelled when it is dropped
+        *this.completed = ret.is_ready();
+        ret
+    }
+
+    /// Polls the task without reading the clock, recording only counts.
+    fn poll_untimed(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T::Output> {
+        let this = self.project();
+        let state = this.state;
+        let metrics = &state.metrics;
+
+        if !*this.did_poll_once {
+            *this.did_poll_once = true;
+            metrics.first_poll_count.fetch_add(1, SeqCst);
+            state.polled.store(true, SeqCst);
+            if this.spawned_at.is_some() {
+                metrics.spawned_first_poll_count.fetch_add(1, SeqCst);
+            }
+            if let Some(observer) = &metrics.observer {
+                observer.on_first_poll(TaskId(state.id), Duration::ZERO);
+            }
+        }
+
+        // a task that was awoken since its last poll has both idled and been scheduled
+        if state.woke_at.swap(0, SeqCst) > 0 {
+            if metrics.track_idle {
+                metrics.polls.total_idled_count.fetch_add(1, SeqCst);
+            }
+            if metrics.track_scheduled {
+                metrics.polls.total_scheduled_count.fetch_add(1, SeqCst);
+            }
+        }
+
+        // SAFETY: `ManuallyDrop` is a transparent wrapper, so the pinning guarantees of the
+        // wrapper carry over to the task.
+        let task = unsafe { this.task.map_unchecked_mut(|task| &mut **task) };
+        *this.polling = true;
+        let concurrent_poll = metrics.begin_poll();
+        let ret = if metrics.track_wakes {
+            state.polled_on.store(current_thread_id(), SeqCst);
+            let waker = InstrumentedWaker::get(this.waker, state, cx.waker()).as_waker();
+            Future::poll(task, &mut Context::from_waker(&waker))
+        } else {
+            Future::poll(task, cx)
+        };
+        drop(concurrent_poll);
+        *this.polling = false;
+        state.count_poll(0);
+
+        metrics.polls.total_untimed_poll_count.fetch_add(1, SeqCst);
+        if ret.is_pending() {
+            metrics.polls.total_pending_poll_count.fetch_add(1, SeqCst);
+        }
+
+        if let Some(observer) = &metrics.observer {
+            observer.on_poll(TaskId(state.id), Duration::ZERO, ret.is_ready());
+        }
+
+        *this.poll_count += 1;
+        if ret.is_ready() {
+            *this.completed = true;
+            metrics.polls.completed_count.fetch_add(1, SeqCst);
+            metrics
+                .polls
+                .total_completed_poll_count
+                .fetch_add(*this.poll_count, SeqCst);
+            if let Some(summary) = this.summary.as_deref_mut() {
+                summary.poll_count = *this.poll_count;
+                if let Some(on_completion) = &metrics.on_completion {
+                    on_completion(summary);
+                }
+            }
+        }
+
+        if ret.is_pending() && state.woke_at.load(SeqCst) != 0 {
+            metrics.polls.total_budget_yield_count.fetch_add(1, SeqCst);
+        }
+
+        ret
     }
 }
 
@@ -2284,12 +8456,21 @@ impl<T: Future> Future for Instrumented<T> {
     type Output = T::Output;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if !self.state.metrics.enabled.load(Relaxed) {
+            return self.poll_disabled(cx);
+        }
+        if !self.state.metrics.timing {
+            return self.poll_untimed(cx);
+        }
+
         let poll_start = Instant::now();
         let this = self.project();
         let idled_at = this.idled_at;
         let state = this.state;
         let instrumented_at = state.instrumented_at;
         let metrics = &state.metrics;
+        metrics.checkpoint(poll_start);
+        let mut deltas = PollDeltas::default();
 
         /* accounting for time-to-first-poll and tasks-count */
         // is this the first time this task has been polled?
@@ -2306,10 +8487,33 @@ impl<T: Future> Future for Instrumented<T> {
                 .try_into()
                 .unwrap_or(u64::MAX);
             // add this duration to `time_to_first_poll_ns_total`
-            metrics.total_first_poll_delay_ns.fetch_add(elapsed, SeqCst);
+            if metrics.track_first_poll {
+                metrics.total_first_poll_delay_ns.fetch_add(elapsed, SeqCst);
+                metrics.raise_watermark(|w| &w.first_poll_delay_ns, elapsed);
+            }
 
             /* 3. increment the count of tasks that have been polled at least once */
             state.metrics.first_poll_count.fetch_add(1, SeqCst);
+            state.polled.store(true, SeqCst);
+
+            if let Some(observer) = &metrics.observer {
+                observer.on_first_poll(TaskId(state.id), Duration::from_nanos(elapsed));
+            }
+
+            if let Some(summary) = this.summary.as_deref_mut() {
+                summary.first_poll_delay = Duration::from_nanos(elapsed);
+            }
+
+            /* 4. split the time-to-first-poll of spawned tasks at the instant of spawning */
+            if let (true, Some(spawned_at)) = (metrics.track_first_poll, *this.spawned_at) {
+                let spawn_delay = to_nanos(spawned_at.saturating_duration_since(instrumented_at));
+                let queue_delay = to_nanos(poll_start.saturating_duration_since(spawned_at));
+                metrics.total_spawn_delay_ns.fetch_add(spawn_delay, SeqCst);
+                metrics
+                    .total_spawn_queue_delay_ns
+                    .fetch_add(queue_delay, SeqCst);
+                metrics.spawned_first_poll_count.fetch_add(1, SeqCst);
+            }
         }
 
         /* accounting for time-idled and time-scheduled */
@@ -2318,22 +8522,25 @@ impl<T: Future> Future for Instrumented<T> {
 
         // The state of a future is *idling* in the interim between the instant
         // it completes a `poll`, and the instant it is next awoken.
-        if *idled_at < woke_at {
+        if metrics.track_idle && *idled_at < woke_at {
             // increment the counter of how many idles occured
-            metrics.total_idled_count.fetch_add(1, SeqCst);
+            deltas.idled_count += 1;
 
             // compute the duration of the idle
             let idle_ns = woke_at - *idled_at;
 
             // adjust the total elasped time monitored tasks spent idling
-            metrics.total_idle_duration_ns.fetch_add(idle_ns, SeqCst);
+            deltas.idle_ns += idle_ns;
+            if let Some(summary) = this.summary.as_deref_mut() {
+                summary.total_idle_duration += Duration::from_nanos(idle_ns);
+            }
         }
 
         // if this task spent any time in the scheduled state after instrumentation,
         // and after first poll, `woke_at` will be greater than 0.
-        if woke_at > 0 {
+        if metrics.track_scheduled && woke_at > 0 {
             // increment the counter of how many schedules occured
-            metrics.total_scheduled_count.fetch_add(1, SeqCst);
+            deltas.scheduled_count += 1;
 
             // recall that the `woke_at` field is internally represented as
             // nanoseconds-since-instrumentation. here, for accounting purposes,
@@ -2348,22 +8555,75 @@ impl<T: Future> Future for Instrumented<T> {
                 .unwrap_or(u64::MAX);
 
             // add `scheduled_ns` to the Monitor's total
-            metrics
-                .total_scheduled_duration_ns
-                .fetch_add(scheduled_ns, SeqCst);
+            deltas.scheduled_ns += scheduled_ns;
+            deltas.max_scheduled_ns = deltas.max_scheduled_ns.max(scheduled_ns);
+            if let Some(summary) = this.summary.as_deref_mut() {
+                summary.total_scheduled_duration += Duration::from_nanos(scheduled_ns);
+            }
+
+            if let Some(sketches) = &metrics.latency_sketches {
+                let scheduled = Duration::from_nanos(scheduled_ns);
+                sketches.scheduled.lock().unwrap().record(scheduled);
+            }
+
+            #[cfg(feature = "usdt")]
+            if let Some(threshold) = metrics.usdt_long_schedule_threshold {
+                if Duration::from_nanos(scheduled_ns) >= threshold {
+                    crate::usdt::long_schedule(state.id, scheduled_ns);
+                }
+            }
+
+            #[cfg(feature = "tracing")]
+            if let Some(logger) = &metrics.outlier_logger {
+                let scheduled = Duration::from_nanos(scheduled_ns);
+                logger.scheduled(TaskId(state.id), state.name, scheduled, poll_start);
+            }
+
+            if let Some(recorder) = &metrics.trace_recorder {
+                recorder.record_scheduled(state.id, woke_instant, poll_start);
+            }
         }
 
-        // Register the waker
-        state.waker.register(cx.waker());
+        // unless updates are batched, record the idle and schedule before polling the task, so
+        // that they are visible to it
+        if metrics.batching.is_none() {
+            metrics.apply_poll_deltas(&std::mem::take(&mut deltas));
+        }
 
-        // Get the instrumented waker
-        let waker_ref = futures_util::task::waker_ref(state);
-        let mut cx = Context::from_waker(&*waker_ref);
+        // SAFETY: `ManuallyDrop` is a transparent wrapper, so the pinning guarantees of the
+        // wrapper carry over to the task.
+        let task = unsafe { this.task.map_unchecked_mut(|task| &mut **task) };
 
         // Poll the task
-        let inner_poll_start = Instant::now();
-        let ret = Future::poll(this.task, &mut cx);
+        let cpu_start = metrics.thread_cpu_time();
+        let allocations_start = metrics.thread_allocations();
+        let perf_start = metrics.perf_sample();
+        let inner_poll_start;
+        let ret;
+        *this.polling = true;
+        let concurrent_poll = metrics.begin_poll();
+        if metrics.track_wakes {
+            // Remember which thread polled the task, to classify its next wake
+            state.polled_on.store(current_thread_id(), SeqCst);
+
+            // Get the instrumented waker, which forwards wakes to the original
+            let waker = InstrumentedWaker::get(this.waker, state, cx.waker()).as_waker();
+            let mut cx = Context::from_waker(&waker);
+
+            inner_poll_start = Instant::now();
+            ret = Future::poll(task, &mut cx);
+        } else {
+            // nothing needs to know when the task is awoken; poll it with the original waker
+            inner_poll_start = Instant::now();
+            ret = Future::poll(task, cx);
+        }
         let inner_poll_end = Instant::now();
+        drop(concurrent_poll);
+        *this.polling = false;
+        state.count_poll(to_nanos(inner_poll_end - inner_poll_start));
+        let cpu_end = metrics.thread_cpu_time();
+        let allocations_end = metrics.thread_allocations();
+        let perf_end = metrics.perf_sample();
 
         /* idle time starts now */
         *idled_at = (inner_poll_end - instrumented_at)
@@ -2378,22 +8638,391 @@ impl<T: Future> Future for Instrumented<T> {
             .try_into()
             .unwrap_or(u64::MAX);
 
+        let slow = inner_poll_duration >= metrics.slow_poll_threshold();
+        metrics.calibrate_slow_poll_threshold(inner_poll_ns);
+
         let (count_bucket, duration_bucket) = // was this a slow or fast poll?
-            if inner_poll_duration >= metrics.slow_poll_threshold {
-                (&metrics.total_slow_poll_count, &metrics.total_slow_poll_duration)
+            if slow {
+                (&mut deltas.slow_poll_count, &mut deltas.slow_poll_ns)
             } else {
-                (&metrics.total_fast_poll_count, &metrics.total_fast_poll_duration_ns)
+                (&mut deltas.fast_poll_count, &mut deltas.fast_poll_ns)
             };
 
         // update the appropriate bucket
-        count_bucket.fetch_add(1, SeqCst);
-        duration_bucket.fetch_add(inner_poll_ns, SeqCst);
+        *count_bucket += 1;
+        *duration_bucket += inner_poll_ns;
+        if ret.is_pending() {
+            deltas.pending_poll_count += 1;
+        }
+
+        // the probe site is a `nop` unless a tracer enables it
+        #[cfg(feature = "usdt")]
+        if slow {
+            crate::usdt::slow_poll(state.id, inner_poll_ns);
+        }
 
+        metrics.record_poll_deltas(deltas, inner_poll_end);
+        metrics.record_poll_bucket(inner_poll_duration, inner_poll_ns);
+        metrics.record_slow_poll_tiers(inner_poll_duration, inner_poll_ns);
+        // a slow poll likely starved other tasks
+        #[cfg(all(tokio_unstable, tokio_taskdump, feature = "rt"))]
+        if slow && metrics.on_stall_dump.is_some() {
+            let stalled = metrics.stalled_tasks();
+            if !stalled.is_empty() {
+                metrics.dump_stalled_tasks_in_background(stalled);
+            }
+        }
+        if let Some(sketches) = &metrics.latency_sketches {
+            sketches.polls.lock().unwrap().record(inner_poll_duration);
+        }
+
+        if let (Some(cpu_start), Some(cpu_end)) = (cpu_start, cpu_end) {
+            let cpu_ns = to_nanos(cpu_end.saturating_sub(cpu_start));
+            metrics
+                .polls
+                .total_poll_cpu_duration_ns
+                .fetch_add(cpu_ns, SeqCst);
+        }
+        if let (Some((count_start, bytes_start)), Some((count_end, bytes_end))) =
+            (allocations_start, allocations_end)
+        {
+            metrics
+                .polls
+                .total_allocation_count
+                .fetch_add(count_end.wrapping_sub(count_start), SeqCst);
+            metrics
+                .polls
+                .total_allocated_bytes
+                .fetch_add(bytes_end.wrapping_sub(bytes_start), SeqCst);
+        }
+        metrics.record_worker_poll(inner_poll_ns, slow);
+
+        #[cfg(feature = "tracing")]
+        if let Some(logger) = &metrics.outlier_logger {
+            logger.poll(
+                TaskId(state.id),
+                state.name,
+                inner_poll_duration,
+                inner_poll_end,
+            );
+        }
+
+        if let Some(recorder) = &metrics.trace_recorder {
+            recorder.record_poll(state.id, inner_poll_start, inner_poll_end);
+        }
+
+        /* accounting for the slowest polls */
+        // the floor spares the lock for polls too fast to enter a full leaderboard
+        if metrics.slowest_polls_capacity > 0
+            && inner_poll_ns >= metrics.slowest_polls_floor_ns.load(Relaxed)
+        {
+            let perf = perf_start.zip(perf_end).map(|(start, end)| end - start);
+            metrics.record_slow_poll(inner_poll_duration, state, perf);
+        }
+
+        /* accounting for blocking polls */
+        if let Some(blocking_poll_threshold) = metrics.blocking_poll_threshold {
+            if inner_poll_duration >= blocking_poll_threshold {
+                metrics.total_blocking_poll_count.fetch_add(1, SeqCst);
+                if let Some(on_blocking_poll) = &metrics.on_blocking_poll {
+                    on_blocking_poll(inner_poll_duration);
+                }
+            }
+        }
+
+        if let Some(observer) = &metrics.observer {
+            observer.on_poll(TaskId(state.id), inner_poll_duration, ret.is_ready());
+        }
+
+        /* accounting for the metrics of this task alone */
+        *this.poll_count += 1;
+        if let Some(summary) = this.summary.as_deref_mut() {
+            summary.total_poll_duration += inner_poll_duration;
+        }
+        if ret.is_ready() {
+            *this.completed = true;
+            metrics.polls.completed_count.fetch_add(1, SeqCst);
+            metrics
+                .polls
+                .total_completed_poll_count
+                .fetch_add(*this.poll_count, SeqCst);
+            if let Some(summary) = this.summary.as_deref_mut() {
+                summary.poll_count = *this.poll_count;
+                summary.lifetime = inner_poll_end - instrumented_at;
+                if let Some(on_completion) = &metrics.on_completion {
+                    on_completion(summary);
+                }
+            }
+        }
+
+        /* accounting for forced yields */
+        // tokio's resources respond to an exhausted coop budget by waking the task and returning
+        // `Pending`; a task that was woken during a poll that returned `Pending` was likely
+        // forced to yield.
+        if ret.is_pending() && state.woke_at.load(SeqCst) != 0 {
+            metrics.polls.total_budget_yield_count.fetch_add(1, SeqCst);
+        }
+
+        ret
+    }
+}
+
+impl<T: Future> Future for InstrumentedWithSummary<T> {
+    type Output = (T::Output, TaskSummary);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut task = self.project().task;
+        match task.as_mut().poll(cx) {
+            Poll::Ready(output) => {
+                // the summary is always produced for these tasks; see `instrument_with_result`
+                let summary = task.project().summary.take().unwrap_or_default();
+                Poll::Ready((output, *summary))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T: Future> Future for InstrumentedBranch<T> {
+    type Output = T::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if !this.metrics.enabled.load(Relaxed) {
+            return this.branch.poll(cx);
+        }
+
+        let poll_start = Instant::now();
+        let ret = this.branch.poll(cx);
+        let poll_duration = poll_start.elapsed();
+        let poll_ns = to_nanos(poll_duration);
+
+        let counters = this.counters;
+        counters.poll_count.fetch_add(1, SeqCst);
+        counters.poll_duration_ns.fetch_add(poll_ns, SeqCst);
+        if poll_duration >= this.metrics.slow_poll_threshold() {
+            counters.slow_poll_count.fetch_add(1, SeqCst);
+            counters.slow_poll_duration_ns.fetch_add(poll_ns, SeqCst);
+        }
+        ret
+    }
+}
+
+impl<T, C> Future for InstrumentedResult<T, C>
+where
+    T: Future,
+    C: FnMut(&T::Output) -> bool,
+{
+    type Output = T::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let mut task = this.task;
+        let ret = task.as_mut().poll(cx);
+        if let Poll::Ready(output) = &ret {
+            let metrics = &task.state.metrics;
+            if (this.classify)(output) {
+                metrics.succeeded_count.fetch_add(1, SeqCst);
+            } else {
+                metrics.failed_count.fetch_add(1, SeqCst);
+            }
+        }
+        ret
+    }
+}
+
+#[cfg(feature = "rt")]
+impl<T: Future> Future for InstrumentedWithTimeout<T> {
+    type Output = Result<T::Output, tokio::time::error::Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut task = self.project().task;
+        let ret = task.as_mut().poll(cx);
+        if let Poll::Ready(Err(_)) = &ret {
+            let state = &task.state;
+            let elapsed = to_nanos(state.instrumented_at.elapsed());
+            state.metrics.timed_out_count.fetch_add(1, SeqCst);
+            state
+                .metrics
+                .total_timed_out_duration_ns
+                .fetch_add(elapsed, SeqCst);
+        }
         ret
     }
 }
 
 impl State {
+    /// Removes this task from the monitor's registry of live tasks, if any.
+    fn untrack(&self) {
+        if let Some(live_tasks) = &self.metrics.live_tasks {
+            live_tasks.lock().unwrap().remove(&self.id);
+        }
+    }
+
+    /// Records that this task was dropped, in `drop_ns` nanoseconds.
+    fn record_drop(&self, drop_ns: u64) {
+        let metrics = &self.metrics;
+        metrics.total_drop_duration_ns.fetch_add(drop_ns, SeqCst);
+        metrics.dropped_count.fetch_add(1, SeqCst);
+
+        if let Some(observer) = &metrics.observer {
+            observer.on_drop(TaskId(self.id));
+        }
+    }
+
+    /// Allocates `state`, reusing an allocation of its monitor pooled by the current thread, if
+    /// any.
+    fn acquire(state: State) -> Arc<State> {
+        if state.metrics.state_pool.is_none() {
+            return Arc::new(state);
+        }
+        let metrics = &*state.metrics;
+        let pooled = LOCAL_STATE_POOLS
+            .try_with(|pools| {
+                let mut pools = pools.borrow_mut();
+                pools
+                    .iter_mut()
+                    .find(|pool| ptr::eq(pool.metrics.as_ptr(), metrics))
+                    .and_then(|pool| pool.states.pop())
+            })
+            .ok()
+            .flatten();
+        if let Some(mut pooled) = pooled {
+            // pooled states are unique; see `State::release`
+            if let Some(slot) = Arc::get_mut(&mut pooled) {
+                *slot = state;
+                return pooled;
+            }
+        }
+        Arc::new(state)
+    }
+
+    /// Returns the allocation of the state of a dropped task to the current thread's pool of its
+    /// monitor, if any, unless the state is shared; e.g., with a waker that outlives the task.
+    fn release(mut this: Arc<State>) {
+        let state = match Arc::get_mut(&mut this) {
+            Some(state) => state,
+            None => return,
+        };
+        let placeholder = match &state.metrics.state_pool {
+            Some(pool) => pool.placeholder.clone(),
+            None => return,
+        };
+        let metrics = std::mem::replace(&mut state.metrics, placeholder);
+
+        let capacity = match &metrics.state_pool {
+            Some(pool) => pool.capacity,
+            None => return,
+        };
+        let _ = LOCAL_STATE_POOLS.try_with(|pools| {
+            let mut pools = pools.borrow_mut();
+            let i = match pools
+                .iter()
+                .position(|pool| ptr::eq(pool.metrics.as_ptr(), &*metrics))
+            {
+                Some(i) => i,
+                None => {
+                    // discard the pools of monitors whose metrics have since been freed
+                    pools.retain(|pool| pool.metrics.strong_count() > 0);
+                    pools.push(LocalStatePool {
+                        metrics: Arc::downgrade(&metrics),
+                        states: Vec::new(),
+                    });
+                    pools.len() - 1
+                }
+            };
+
+            let states = &mut pools[i].states;
+            if states.len() < capacity {
+                states.push(this);
+            }
+        });
+    }
+
+    /// Determines whether this task is stalled, as of `now`.
+    fn stall(&self, now: Instant) -> Option<StalledTask> {
+        let metrics = &self.metrics;
+        let age = now.saturating_duration_since(self.instrumented_at);
+
+        if let Some(threshold) = metrics.stalled_task_threshold {
+            // tasks awaiting their first poll have been waiting since instrumentation
+            let awaiting_poll = if !self.polled.load(SeqCst) {
+                Some((StallKind::FirstPoll, age))
+            } else {
+                match self.woke_at.load(SeqCst) {
+                    0 => None,
+                    woke_at => Some((
+                        StallKind::Scheduled,
+                        age.saturating_sub(Duration::from_nanos(woke_at)),
+                    )),
+                }
+            };
+
+            if let Some((kind, stalled_for)) = awaiting_poll {
+                if stalled_for >= threshold {
+                    return Some(StalledTask {
+                        kind,
+                        stalled_for,
+                        age,
+                        name: self.name,
+                        location: self.location,
+                    });
+                }
+            }
+        }
+
+        match metrics.incomplete_task_threshold {
+            Some(threshold) if age >= threshold => Some(StalledTask {
+                kind: StallKind::Incomplete,
+                stalled_for: age,
+                age,
+                name: self.name,
+                location: self.location,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Counts a poll of the task, of `duration_ns`, if live tasks are tracked.
+    fn count_poll(&self, duration_ns: u64) {
+        if self.metrics.live_tasks.is_some() {
+            self.poll_count.fetch_add(1, Relaxed);
+            self.poll_duration_ns.fetch_add(duration_ns, Relaxed);
+        }
+    }
+
+    /// Produces an empty summary of this task.
+    fn summary(&self) -> Box<TaskSummary> {
+        Box::new(TaskSummary {
+            id: TaskId(self.id),
+            name: self.name,
+            location: self.location,
+            ..TaskSummary::default()
+        })
+    }
+
+    /// Describes this task as a live task, as of `now`.
+    fn live(&self, now: Instant) -> LiveTask {
+        let state = if !self.polled.load(SeqCst) {
+            LiveTaskState::FirstPoll
+        } else if self.woke_at.load(SeqCst) != 0 {
+            LiveTaskState::Scheduled
+        } else {
+            LiveTaskState::Idle
+        };
+        LiveTask {
+            id: TaskId(self.id),
+            state,
+            age: now.saturating_duration_since(self.instrumented_at),
+            poll_count: self.poll_count.load(Relaxed),
+            total_poll_duration: Duration::from_nanos(self.poll_duration_ns.load(Relaxed)),
+            name: self.name,
+            location: self.location,
+        }
+    }
+}
+
+impl OnWake for State {
     fn on_wake(&self) {
         let woke_at: u64 = match self.instrumented_at.elapsed().as_nanos().try_into() {
             Ok(woke_at) => woke_at,
@@ -2403,20 +9032,112 @@ impl State {
             Err(_) => return,
         };
 
-        // We don't actually care about the result
-        let _ = self.woke_at.compare_exchange(0, woke_at, SeqCst, SeqCst);
+        // Only the first wake since the last poll schedules the task
+        if self
+            .woke_at
+            .compare_exchange(0, woke_at, SeqCst, SeqCst)
+            .is_ok()
+        {
+            match self.polled_on.load(SeqCst) {
+                // the task has not yet been polled
+                0 => {}
+                polled_on if polled_on == current_thread_id() => {
+                    self.metrics
+                        .polls
+                        .total_local_wake_count
+                        .fetch_add(1, SeqCst);
+                }
+                _ => {
+                    self.metrics
+                        .polls
+                        .total_remote_wake_count
+                        .fetch_add(1, SeqCst);
+                }
+            }
+        }
+
+        if let Some(observer) = &self.metrics.observer {
+            observer.on_wake(TaskId(self.id));
+        }
     }
 }
 
-impl ArcWake for State {
-    fn wake_by_ref(arc_self: &Arc<State>) {
-        arc_self.on_wake();
-        arc_self.waker.wake();
+/// The state of an instrumented task that records its wakes.
+pub(crate) trait OnWake: Send + Sync + 'static {
+    /// Records a wake of the task; invoked on the thread that wakes it, before the wake is
+    /// forwarded.
+    fn on_wake(&self);
+}
+
+/// A waker that records the wakes of an instrumented task, and forwards them to the waker with
+/// which the task was polled.
+///
+/// Each waker forwards to the waker of the poll in which it was produced, not necessarily that of
+/// the latest poll; like the runtime's wakers, these are equivalent for the same task.
+pub(crate) struct InstrumentedWaker<S> {
+    state: Arc<S>,
+    waker: Waker,
+}
+
+impl<S: OnWake> InstrumentedWaker<S> {
+    /// Produces the instrumented waker forwarding to `waker`, reusing the one in `cache` if it
+    /// forwards to an equivalent waker, and otherwise replacing it.
+    pub(crate) fn get<'a>(
+        cache: &'a mut Option<Arc<InstrumentedWaker<S>>>,
+        state: &Arc<S>,
+        waker: &Waker,
+    ) -> &'a Arc<InstrumentedWaker<S>> {
+        match cache {
+            Some(cached) if cached.waker.will_wake(waker) => {}
+            _ => {
+                *cache = Some(Arc::new(InstrumentedWaker {
+                    state: state.clone(),
+                    waker: waker.clone(),
+                }))
+            }
+        }
+        cache.as_ref().unwrap()
+    }
+
+    /// Borrows this as a [`Waker`], without cloning it.
+    pub(crate) fn as_waker(self: &Arc<Self>) -> WakerRef<'_> {
+        let raw = RawWaker::new(Arc::as_ptr(self).cast(), Self::vtable());
+        // SAFETY: the waker borrows the reference to the instrumented waker held by `self`, and
+        // does not release it, since it is never dropped.
+        WakerRef::new_unowned(ManuallyDrop::new(unsafe { Waker::from_raw(raw) }))
+    }
+
+    fn vtable() -> &'static RawWakerVTable {
+        &RawWakerVTable::new(
+            Self::clone_raw,
+            Self::wake_raw,
+            Self::wake_by_ref_raw,
+            Self::drop_raw,
+        )
+    }
+
+    unsafe fn clone_raw(data: *const ()) -> RawWaker {
+        Arc::increment_strong_count(data.cast::<Self>());
+        RawWaker::new(data, Self::vtable())
+    }
+
+    unsafe fn wake_raw(data: *const ()) {
+        let this = Arc::from_raw(data.cast::<Self>());
+        this.state.on_wake();
+        match Arc::try_unwrap(this) {
+            Ok(this) => this.waker.wake(),
+            Err(this) => this.waker.wake_by_ref(),
+        }
+    }
+
+    unsafe fn wake_by_ref_raw(data: *const ()) {
+        let this = &*data.cast::<Self>();
+        this.state.on_wake();
+        this.waker.wake_by_ref();
     }
 
-    fn wake(self: Arc<State>) {
-        self.on_wake();
-        self.waker.wake();
+    unsafe fn drop_raw(data: *const ()) {
+        drop(Arc::from_raw(data.cast::<Self>()));
     }
 }
 
@@ -0,0 +1,210 @@
+use std::collections::VecDeque;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering::Relaxed};
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "rt")]
+use tokio::time::{Duration, Instant};
+
+#[cfg(not(feature = "rt"))]
+use std::time::{Duration, Instant};
+
+/// Records the polls and schedules of instrumented tasks, for visualization on a timeline.
+///
+/// A [`TraceRecorder`] retains the most recent events of the tasks instrumented by the
+/// [`TaskMonitor`][crate::TaskMonitor]s it is [attached][crate::TaskMonitorBuilder::with_trace_recorder]
+/// to; once `capacity` events have been recorded, each new event evicts the oldest. The retained
+/// window of events may be exported in the [Chrome trace event format], which can be opened with
+/// `chrome://tracing` or the [Perfetto UI].
+///
+/// Two kinds of events are recorded, on the thread that polled the task:
+/// - `poll`, spanning each poll of a task.
+/// - `scheduled`, spanning the interim between the instant a task is awoken and the instant it is
+///   subsequently polled.
+///
+/// The same recorder may be attached to multiple monitors; the events of each are exported as
+/// those of a distinct process, numbered in the order in which the monitors were built.
+///
+/// [Chrome trace event format]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+/// [Perfetto UI]: https://ui.perfetto.dev
+///
+/// ##### Examples
+/// ```
+/// use tokio_metrics::{TaskMonitor, TraceRecorder};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     // retain the most recent 10,000 events
+///     let recorder = TraceRecorder::new(10_000);
+///     let monitor = TaskMonitor::builder()
+///         .with_trace_recorder(recorder.clone())
+///         .build();
+///
+///     monitor.instrument(async {
+///         tokio::task::yield_now().await;
+///     }).await;
+///
+///     let mut trace = Vec::new();
+///     recorder.write_chrome_trace(&mut trace).unwrap();
+///
+///     let trace: serde_json::Value = serde_json::from_slice(&trace).unwrap();
+///     let events = trace["traceEvents"].as_array().unwrap();
+///     let polls = events.iter().filter(|event| event["name"] == "poll").count();
+///     assert_eq!(polls, 2);
+///
+///     // the events of the first monitor attached to the recorder
+///     assert!(events.iter().all(|event| event["pid"] == 1));
+/// }
+/// ```
+#[derive(Clone)]
+pub struct TraceRecorder {
+    inner: Arc<Inner>,
+
+    /// The id of the monitor this handle is attached to; zero if unattached.
+    monitor_id: u64,
+}
+
+struct Inner {
+    /// The instant from which event timestamps are measured.
+    started_at: Instant,
+
+    /// The id of the next monitor attached to the recorder.
+    next_monitor_id: AtomicU64,
+
+    /// The maximum number of retained events.
+    capacity: usize,
+
+    /// The most recent events.
+    events: Mutex<VecDeque<TraceEvent>>,
+}
+
+#[derive(Clone, Copy)]
+struct TraceEvent {
+    kind: TraceEventKind,
+
+    /// The id of the monitor of the task, unique among the monitors of the recorder.
+    monitor_id: u64,
+
+    /// The id of the task, unique among the tasks of its monitor.
+    task_id: u64,
+
+    /// The id of the thread that recorded the event.
+    thread_id: u64,
+
+    /// The start of the event, relative to `started_at`.
+    start: Duration,
+
+    duration: Duration,
+}
+
+#[derive(Clone, Copy)]
+enum TraceEventKind {
+    Poll,
+    Scheduled,
+}
+
+impl TraceRecorder {
+    /// Constructs a new recorder that retains, at most, the `capacity` most recent events.
+    pub fn new(capacity: usize) -> TraceRecorder {
+        TraceRecorder {
+            inner: Arc::new(Inner {
+                started_at: Instant::now(),
+                next_monitor_id: AtomicU64::new(1),
+                capacity,
+                events: Mutex::new(VecDeque::with_capacity(capacity)),
+            }),
+            monitor_id: 0,
+        }
+    }
+
+    /// Discards all retained events.
+    pub fn clear(&self) {
+        self.inner.events.lock().unwrap().clear();
+    }
+
+    /// Writes the retained events to `writer`, in the Chrome trace event JSON format.
+    ///
+    /// Timestamps are measured in microseconds since the construction of this recorder. Each
+    /// event's `pid` is the id of the monitor of its task, and its `args` note the id of the task,
+    /// which is unique among the tasks instrumented by the same monitor.
+    pub fn write_chrome_trace<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        let events: Vec<TraceEvent> = self.inner.events.lock().unwrap().iter().copied().collect();
+
+        writer.write_all(b"{\"traceEvents\":[")?;
+        for (i, event) in events.iter().enumerate() {
+            if i > 0 {
+                writer.write_all(b",")?;
+            }
+            let name = match event.kind {
+                TraceEventKind::Poll => "poll",
+                TraceEventKind::Scheduled => "scheduled",
+            };
+            write!(
+                writer,
+                "{{\"name\":\"{}\",\"cat\":\"tokio_metrics\",\"ph\":\"X\",\"ts\":{:.3},\"dur\":{:.3},\"pid\":{},\"tid\":{},\"args\":{{\"task\":{}}}}}",
+                name,
+                event.start.as_secs_f64() * 1e6,
+                event.duration.as_secs_f64() * 1e6,
+                event.monitor_id,
+                event.thread_id,
+                event.task_id,
+            )?;
+        }
+        writer.write_all(b"],\"displayTimeUnit\":\"ns\"}")?;
+        writer.flush()
+    }
+
+    /// Produces a handle to this recorder that records the events of a newly built monitor.
+    pub(crate) fn attach(self) -> TraceRecorder {
+        let monitor_id = self.inner.next_monitor_id.fetch_add(1, Relaxed);
+        TraceRecorder {
+            inner: self.inner,
+            monitor_id,
+        }
+    }
+
+    /// Records a poll of the task `task_id` spanning `start` to `end`.
+    pub(crate) fn record_poll(&self, task_id: u64, start: Instant, end: Instant) {
+        self.record(TraceEventKind::Poll, task_id, start, end);
+    }
+
+    /// Records that the task `task_id` was scheduled from `woke_at` until `polled_at`.
+    pub(crate) fn record_scheduled(&self, task_id: u64, woke_at: Instant, polled_at: Instant) {
+        self.record(TraceEventKind::Scheduled, task_id, woke_at, polled_at);
+    }
+
+    fn record(&self, kind: TraceEventKind, task_id: u64, start: Instant, end: Instant) {
+        let inner = &self.inner;
+        if inner.capacity == 0 {
+            return;
+        }
+
+        let event = TraceEvent {
+            kind,
+            monitor_id: self.monitor_id,
+            task_id,
+            thread_id: current_thread_id(),
+            start: start.saturating_duration_since(inner.started_at),
+            duration: end.saturating_duration_since(start),
+        };
+
+        let mut events = inner.events.lock().unwrap();
+        if events.len() == inner.capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+}
+
+/// Produces a small integer uniquely identifying the current thread, for use as a trace `tid`;
+/// never zero, unless the thread is exiting.
+#[inline(always)]
+pub(crate) fn current_thread_id() -> u64 {
+    static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+    thread_local! {
+        static THREAD_ID: u64 = NEXT_THREAD_ID.fetch_add(1, Relaxed);
+    }
+
+    THREAD_ID.try_with(|thread_id| *thread_id).unwrap_or(0)
+}
@@ -0,0 +1,91 @@
+//! USDT probes, in the SystemTap SDT format understood by `bpftrace`, `perf` and DTrace-alike
+//! tooling on Linux.
+//!
+//! Each probe site is a `nop` instruction, described by a note in the `.note.stapsdt` section of
+//! the binary; tracers enable a probe by patching its site. Disabled probes cost only the `nop`.
+//! On targets other than Linux on x86-64 or AArch64, probes compile to nothing.
+
+/// Emits a probe of the provider `tokio_metrics` named `$name`, with two 64-bit arguments.
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+macro_rules! probe {
+    ($name:literal, $arg0:expr, $arg1:expr) => {
+        let (arg0, arg1): (u64, u64) = ($arg0, $arg1);
+        // the arguments are described in the assembler syntax of registers that tracers expect
+        #[cfg(target_arch = "x86_64")]
+        stapsdt!(
+            $name,
+            arg0,
+            arg1,
+            options(att_syntax, nomem, nostack, preserves_flags)
+        );
+        #[cfg(target_arch = "aarch64")]
+        stapsdt!($name, arg0, arg1, options(nomem, nostack, preserves_flags));
+    };
+}
+
+/// Emits the site and note of a probe.
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+macro_rules! stapsdt {
+    ($name:literal, $arg0:ident, $arg1:ident, $($options:tt)*) => {
+        // SAFETY: the probe site is a `nop`; everything else is emitted into other sections.
+        unsafe {
+            std::arch::asm!(
+                "990: nop",
+                ".pushsection .note.stapsdt, \"?\", \"note\"",
+                ".balign 4",
+                ".4byte 992f-991f, 994f-993f, 3",
+                "991: .asciz \"stapsdt\"",
+                "992: .balign 4",
+                "993: .8byte 990b",
+                ".8byte _.stapsdt.base",
+                ".8byte 0",
+                ".asciz \"tokio_metrics\"",
+                concat!(".asciz \"", $name, "\""),
+                ".asciz \"8@{0} 8@{1}\"",
+                "994: .balign 4",
+                ".popsection",
+                ".ifndef _.stapsdt.base",
+                ".pushsection .stapsdt.base, \"aG\", \"progbits\", .stapsdt.base, comdat",
+                ".weak _.stapsdt.base",
+                ".hidden _.stapsdt.base",
+                "_.stapsdt.base: .space 1",
+                ".size _.stapsdt.base, 1",
+                ".popsection",
+                ".endif",
+                in(reg) $arg0,
+                in(reg) $arg1,
+                $($options)*
+            );
+        }
+    };
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+macro_rules! probe {
+    ($name:literal, $arg0:expr, $arg1:expr) => {
+        let _: (u64, u64) = ($arg0, $arg1);
+    };
+}
+
+/// Fires the `slow_poll` probe, with the id of the task and the duration of the poll, in
+/// nanoseconds.
+#[inline(always)]
+pub(crate) fn slow_poll(task_id: u64, duration_ns: u64) {
+    probe!("slow_poll", task_id, duration_ns);
+}
+
+/// Fires the `long_schedule` probe, with the id of the task and the delay between it being
+/// awoken and polled, in nanoseconds.
+#[inline(always)]
+pub(crate) fn long_schedule(task_id: u64, delay_ns: u64) {
+    probe!("long_schedule", task_id, delay_ns);
+}